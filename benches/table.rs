@@ -0,0 +1,114 @@
+//! Frame rendering, filtering and update benchmarks against synthetic feeds
+//! of up to 10,000 aircraft. Run with `cargo bench`; compare runs before and
+//! after changes to the table path.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use adsb_tui::cli::ConfigArgs;
+use adsb_tui::model::ApiResponse;
+use adsb_tui::synthetic::{SyntheticFeed, STRESS_AIRCRAFT};
+use adsb_tui::{commands, config, ui};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+const FEED_SIZES: [usize; 3] = [500, 5_000, STRESS_AIRCRAFT];
+
+fn feed(size: usize) -> ApiResponse {
+    let now = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    SyntheticFeed::new(size).snapshot(0, now)
+}
+
+fn bench_config(layout: &str) -> config::Config {
+    let path = std::env::temp_dir().join("adsb-tui-bench.toml");
+    std::fs::write(
+        &path,
+        "url = \"http://127.0.0.1:1/data/aircraft.json\"\nsite_lat = 40.0\nsite_lon = -74.0\n",
+    )
+    .expect("write bench config");
+    let args = ConfigArgs {
+        config: Some(PathBuf::from(&path)),
+        ..ConfigArgs::default()
+    };
+    let mut config = config::load_config(&args).expect("load bench config");
+    config.layout = layout.to_string();
+    config.smooth_mode = false;
+    config.route_enabled = false;
+    config.favorites_file = String::new();
+    config.traffic_history_file = String::new();
+    config.watchlist_file = String::new();
+    config
+}
+
+fn bench_frames(c: &mut Criterion) {
+    for (name, layout) in [("table", "compact"), ("full", "full")] {
+        let mut group = c.benchmark_group(format!("frame/{name}"));
+        for size in FEED_SIZES {
+            let config = bench_config(layout);
+            let mut app = commands::build_app(&config, config.url.clone()).unwrap();
+            app.apply_update(feed(size));
+            let indices = app.visible_indices();
+            let mut terminal = Terminal::new(TestBackend::new(200, 60)).expect("test terminal");
+            group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+                b.iter(|| {
+                    terminal
+                        .draw(|f| ui::ui(f, &mut app, &indices))
+                        .expect("draw");
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+fn bench_visible_indices(c: &mut Criterion) {
+    let mut group = c.benchmark_group("visible_indices");
+    for size in FEED_SIZES {
+        let config = bench_config("full");
+        let mut app = commands::build_app(&config, config.url.clone()).unwrap();
+        app.apply_update(feed(size));
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter(|| app.visible_indices())
+        });
+    }
+    group.finish();
+}
+
+/// Feed update plus smooth-mode swap, which merges each aircraft with the
+/// previous snapshot.
+fn bench_merge(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update_merge");
+    for size in FEED_SIZES {
+        let mut config = bench_config("full");
+        config.smooth_mode = true;
+        config.smooth_merge = true;
+        let mut app = commands::build_app(&config, config.url.clone()).unwrap();
+        let synthetic = SyntheticFeed::new(size);
+        let snapshots = [synthetic.snapshot(0, 1_000), synthetic.snapshot(1, 1_001)];
+        let start = SystemTime::now();
+        let mut next = 0usize;
+        let mut tick = 0u64;
+        group.bench_with_input(BenchmarkId::from_parameter(size), &size, |b, _| {
+            b.iter_batched(
+                || {
+                    next += 1;
+                    snapshots[next % 2].clone()
+                },
+                |snapshot| {
+                    tick += 1;
+                    app.apply_update(snapshot);
+                    app.maybe_swap_snapshot(start + Duration::from_secs(tick));
+                },
+                BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_frames, bench_visible_indices, bench_merge);
+criterion_main!(benches);
@@ -0,0 +1,267 @@
+//! Offline aircraft database: tar1090-db `aircraft.csv` or `basic-ac-db.json`,
+//! optionally gzipped, read on first use.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+
+use serde_json::Value;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tracing::{info, warn};
+
+use crate::model::Aircraft;
+use crate::storage;
+use crate::tasks::{self, Tasks};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DbEntry {
+    pub registration: Option<Box<str>>,
+    pub type_code: Option<Box<str>>,
+    pub description: Option<Box<str>>,
+    pub owner: Option<Box<str>>,
+    pub year: Option<Box<str>>,
+}
+
+#[derive(Debug, Default)]
+pub struct AircraftDb {
+    entries: HashMap<u32, DbEntry>,
+}
+
+impl AircraftDb {
+    /// Parse CSV or JSON lines, judged line by line. Headers, blank lines
+    /// and lines without a valid hex are skipped.
+    pub fn parse(text: &str) -> Self {
+        let entries = text.lines().filter_map(parse_line).collect();
+        Self { entries }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, hex: &str) -> Option<&DbEntry> {
+        self.entries.get(&parse_hex(hex)?)
+    }
+
+    /// Copy database fields into those the feed left empty; fields the feed
+    /// sent are kept.
+    pub fn fill(&self, ac: &mut Aircraft) {
+        let Some(entry) = ac.hex.as_deref().and_then(|hex| self.get(hex)) else {
+            return;
+        };
+        for (field, value) in [
+            (&mut ac.r, &entry.registration),
+            (&mut ac.t, &entry.type_code),
+            (&mut ac.desc, &entry.description),
+            (&mut ac.own_op, &entry.owner),
+            (&mut ac.year, &entry.year),
+        ] {
+            if is_missing(field) {
+                if let Some(value) = value {
+                    *field = Some(value.to_string());
+                }
+            }
+        }
+    }
+}
+
+fn is_missing(field: &Option<String>) -> bool {
+    field.as_deref().is_none_or(|value| value.trim().is_empty())
+}
+
+pub fn needs_fill(ac: &Aircraft) -> bool {
+    is_missing(&ac.r) || is_missing(&ac.t) || is_missing(&ac.own_op)
+}
+
+fn parse_hex(hex: &str) -> Option<u32> {
+    let hex = hex.trim();
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+fn text(value: &str) -> Option<Box<str>> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.into())
+}
+
+fn parse_line(line: &str) -> Option<(u32, DbEntry)> {
+    let line = line.trim();
+    if line.starts_with('{') {
+        return parse_json_line(line);
+    }
+    let mut fields = line.split(';');
+    let hex = parse_hex(fields.next()?)?;
+    let mut next = || fields.next().and_then(text);
+    let registration = next();
+    let type_code = next();
+    let _flags = next();
+    let description = next();
+    let year = next();
+    let owner = next();
+    Some((
+        hex,
+        DbEntry {
+            registration,
+            type_code,
+            description,
+            owner,
+            year,
+        },
+    ))
+}
+
+fn parse_json_line(line: &str) -> Option<(u32, DbEntry)> {
+    let value: Value = serde_json::from_str(line).ok()?;
+    let field = |key: &str| match value.get(key)? {
+        Value::String(text) => self::text(text),
+        Value::Number(number) => Some(number.to_string().into()),
+        _ => None,
+    };
+    let hex = parse_hex(value.get("icao")?.as_str()?)?;
+    let description = match (field("manufacturer"), field("model")) {
+        (Some(maker), Some(model)) => Some(format!("{maker} {model}").into()),
+        (maker, model) => maker.or(model),
+    };
+    Some((
+        hex,
+        DbEntry {
+            registration: field("reg"),
+            type_code: field("icaotype"),
+            description,
+            owner: field("ownop"),
+            year: field("year"),
+        },
+    ))
+}
+
+/// The database file, read on first use. Until the background load is done
+/// (or if it failed) `get` returns `None`.
+#[derive(Debug)]
+pub struct LazyAircraftDb {
+    requested: AtomicBool,
+    load_tx: UnboundedSender<()>,
+    db: Arc<OnceLock<AircraftDb>>,
+}
+
+impl LazyAircraftDb {
+    pub fn get(&self) -> Option<&AircraftDb> {
+        if let Some(db) = self.db.get() {
+            return Some(db);
+        }
+        if !self.requested.swap(true, Ordering::Relaxed) {
+            let _ = self.load_tx.send(());
+        }
+        None
+    }
+}
+
+pub fn spawn_aircraft_db_loader(tasks: &Tasks, path: PathBuf) -> LazyAircraftDb {
+    let (load_tx, mut load_rx) = unbounded_channel();
+    let db = Arc::new(OnceLock::new());
+    let target = Arc::clone(&db);
+    tasks.spawn("aircraft_db", async move {
+        if load_rx.recv().await.is_none() {
+            return;
+        }
+        let name = path.display().to_string();
+        let loaded = match tasks::blocking(move || storage::load_aircraft_db(&path)).await {
+            Some(Ok(loaded)) => {
+                info!("loaded {} aircraft from {name}", loaded.len());
+                loaded
+            }
+            Some(Err(err)) => {
+                warn!("{err:#}");
+                AircraftDb::default()
+            }
+            None => {
+                warn!("aircraft database load from {name} did not finish");
+                AircraftDb::default()
+            }
+        };
+        let _ = target.set(loaded);
+    });
+    LazyAircraftDb {
+        requested: AtomicBool::new(false),
+        load_tx,
+        db,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{needs_fill, spawn_aircraft_db_loader, AircraftDb};
+    use crate::model::Aircraft;
+    use crate::tasks::Tasks;
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    const SAMPLE: &str = "\
+icao;reg;type;flags;desc;year;ownop
+4ca7b5;EI-DCL;B738;00;BOEING 737-800;2004;Ryanair;
+a00001;N1;;10;;;;
+~ab1234;NOPE;C172;;;;;
+{\"icao\":\"ac6668\",\"reg\":\"N8642E\",\"icaotype\":\"B38M\",\"year\":2019,\"manufacturer\":\"Boeing\",\"model\":\"737 MAX 8\",\"ownop\":\"Southwest Airlines Co\"}
+";
+
+    #[test]
+    fn parses_csv_and_json_lines() {
+        let db = AircraftDb::parse(SAMPLE);
+        assert_eq!(db.len(), 3);
+        let ryanair = db.get("4CA7B5").unwrap();
+        assert_eq!(ryanair.registration.as_deref(), Some("EI-DCL"));
+        assert_eq!(ryanair.description.as_deref(), Some("BOEING 737-800"));
+        assert_eq!(ryanair.owner.as_deref(), Some("Ryanair"));
+        assert_eq!(db.get("a00001").unwrap().type_code, None);
+        let southwest = db.get("ac6668").unwrap();
+        assert_eq!(southwest.type_code.as_deref(), Some("B38M"));
+        assert_eq!(southwest.year.as_deref(), Some("2019"));
+        assert_eq!(southwest.description.as_deref(), Some("Boeing 737 MAX 8"));
+    }
+
+    #[test]
+    fn fills_only_missing_fields() {
+        let db = AircraftDb::parse(SAMPLE);
+        let mut ac = Aircraft {
+            hex: Some("4ca7b5".to_string()),
+            t: Some("B38M".to_string()),
+            r: Some(" ".to_string()),
+            ..Aircraft::default()
+        };
+        assert!(needs_fill(&ac));
+        db.fill(&mut ac);
+        assert_eq!(ac.r.as_deref(), Some("EI-DCL"));
+        assert_eq!(ac.t.as_deref(), Some("B38M"), "feed value kept");
+        assert_eq!(ac.own_op.as_deref(), Some("Ryanair"));
+        assert!(!needs_fill(&ac));
+    }
+
+    #[test]
+    fn loads_on_the_task_runtime_at_first_miss() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("adsb-tui-aircraft-{suffix}.csv"));
+        std::fs::write(&path, SAMPLE).unwrap();
+        let tasks = Tasks::new().unwrap();
+        let lazy = spawn_aircraft_db_loader(&tasks, path.clone());
+        assert!(lazy.get().is_none(), "first miss starts the load");
+        let deadline = Instant::now() + Duration::from_secs(5);
+        let db = loop {
+            if let Some(db) = lazy.get() {
+                break db;
+            }
+            assert!(Instant::now() < deadline, "database never loaded");
+            std::thread::sleep(Duration::from_millis(10));
+        };
+        assert_eq!(db.len(), 3);
+        tasks.shutdown();
+        let _ = std::fs::remove_file(&path);
+    }
+}
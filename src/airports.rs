@@ -0,0 +1,368 @@
+//! OurAirports `airports.csv` lookups.
+
+use std::collections::HashMap;
+
+use crate::units::distance_nm;
+
+const CELL_DEG: f64 = 1.0;
+const NEAREST_MAX_RINGS: i32 = 3;
+const NM_PER_DEG_LAT: f64 = 60.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AirportSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl AirportSize {
+    /// OurAirports `type`; heliports, seaplane bases, balloonports and closed
+    /// fields are not loaded.
+    fn from_kind(kind: &str) -> Option<Self> {
+        match kind {
+            "large_airport" => Some(AirportSize::Large),
+            "medium_airport" => Some(AirportSize::Medium),
+            "small_airport" => Some(AirportSize::Small),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RadarAirports {
+    Off,
+    #[default]
+    Major,
+    All,
+}
+
+impl RadarAirports {
+    pub fn from_str(value: &str) -> Self {
+        Self::parse(value).unwrap_or_default()
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(RadarAirports::Off),
+            "major" => Some(RadarAirports::Major),
+            "all" => Some(RadarAirports::All),
+            _ => None,
+        }
+    }
+
+    pub fn shows(self, size: AirportSize) -> bool {
+        match self {
+            RadarAirports::Off => false,
+            RadarAirports::Major => size >= AirportSize::Medium,
+            RadarAirports::All => true,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Airport {
+    pub code: String,
+    pub iata: Option<String>,
+    pub name: String,
+    pub city: Option<String>,
+    pub country: Option<String>,
+    pub lat: f64,
+    pub lon: f64,
+    pub size: AirportSize,
+}
+
+impl Airport {
+    pub fn short_name(&self) -> String {
+        let name = [" Airport", " Airfield", " Aerodrome"]
+            .iter()
+            .find_map(|suffix| self.name.strip_suffix(suffix))
+            .unwrap_or(&self.name);
+        name.replace("International", "Intl")
+            .replace("Regional", "Rgnl")
+    }
+
+    pub fn describe(&self) -> String {
+        match &self.city {
+            Some(city) if !self.name.contains(city.as_str()) => {
+                format!("{} ({city})", self.name)
+            }
+            _ => self.name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AirportDb {
+    airports: Vec<Airport>,
+    by_code: HashMap<String, usize>,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl AirportDb {
+    /// Parse OurAirports CSV text; columns are found by header name, rows
+    /// with an unknown type or a bad position are skipped.
+    pub fn parse_csv(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines();
+        let header = split_csv_line(lines.next().ok_or("empty airports file")?);
+        let column = |name: &str| header.iter().position(|field| field == name);
+        let (Some(ident), Some(kind), Some(name), Some(lat), Some(lon)) = (
+            column("ident"),
+            column("type"),
+            column("name"),
+            column("latitude_deg"),
+            column("longitude_deg"),
+        ) else {
+            return Err(
+                "missing ident, type, name, latitude_deg or longitude_deg column".to_string(),
+            );
+        };
+        let icao = column("icao_code");
+        let gps = column("gps_code");
+        let iata = column("iata_code");
+        let city = column("municipality");
+        let country = column("iso_country");
+
+        let mut db = AirportDb::default();
+        for line in lines {
+            let fields = split_csv_line(line);
+            let field = |idx: Option<usize>| {
+                idx.and_then(|idx| fields.get(idx))
+                    .map(|value| value.trim())
+                    .filter(|value| !value.is_empty())
+            };
+            let Some(size) = field(Some(kind)).and_then(AirportSize::from_kind) else {
+                continue;
+            };
+            let (Some(lat), Some(lon)) = (
+                field(Some(lat)).and_then(|v| v.parse::<f64>().ok()),
+                field(Some(lon)).and_then(|v| v.parse::<f64>().ok()),
+            ) else {
+                continue;
+            };
+            let Some(ident) = field(Some(ident)) else {
+                continue;
+            };
+            let icao = field(icao).or(field(gps)).unwrap_or(ident);
+            db.insert(
+                Airport {
+                    code: icao.to_ascii_uppercase(),
+                    iata: field(iata).map(str::to_ascii_uppercase),
+                    name: field(Some(name)).unwrap_or(ident).to_string(),
+                    city: field(city).map(str::to_string),
+                    country: field(country).map(str::to_string),
+                    lat,
+                    lon,
+                    size,
+                },
+                [
+                    ident,
+                    icao,
+                    field(gps).unwrap_or_default(),
+                    field(iata).unwrap_or_default(),
+                ],
+            );
+        }
+        Ok(db)
+    }
+
+    fn insert(&mut self, airport: Airport, codes: [&str; 4]) {
+        let idx = self.airports.len();
+        let size = airport.size;
+        self.cells
+            .entry(cell(airport.lat, airport.lon))
+            .or_default()
+            .push(idx);
+        self.airports.push(airport);
+        for code in codes {
+            if code.is_empty() {
+                continue;
+            }
+            // Several small fields can share a code; keep the biggest.
+            let code = code.to_ascii_uppercase();
+            match self.by_code.get(&code) {
+                Some(&other) if self.airports[other].size >= size => {}
+                _ => {
+                    self.by_code.insert(code, idx);
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.airports.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.airports.is_empty()
+    }
+
+    pub fn lookup(&self, code: &str) -> Option<&Airport> {
+        let code = code.trim();
+        if code.is_empty() {
+            return None;
+        }
+        self.by_code
+            .get(&code.to_ascii_uppercase())
+            .map(|&idx| &self.airports[idx])
+    }
+
+    /// Closest airport to a position and its distance in nm, searching up
+    /// to about three degrees around it.
+    pub fn nearest(&self, lat: f64, lon: f64) -> Option<(&Airport, f64)> {
+        let (row, col) = cell(lat, lon);
+        let mut best: Option<(usize, f64)> = None;
+        for ring in 0..=NEAREST_MAX_RINGS {
+            for (dr, dc) in ring_offsets(ring) {
+                let Some(indices) = self.cells.get(&(row + dr, wrap_col(col + dc))) else {
+                    continue;
+                };
+                for &idx in indices {
+                    let airport = &self.airports[idx];
+                    let dist = distance_nm(lat, lon, airport.lat, airport.lon);
+                    if best.is_none_or(|(_, best_dist)| dist < best_dist) {
+                        best = Some((idx, dist));
+                    }
+                }
+            }
+            // Everything closer than the rings searched so far has been seen.
+            let covered = ring as f64 * CELL_DEG * NM_PER_DEG_LAT * lon_scale(lat, ring);
+            if best.is_some_and(|(_, dist)| dist <= covered) {
+                break;
+            }
+        }
+        best.map(|(idx, dist)| (&self.airports[idx], dist))
+    }
+
+    pub fn within(&self, lat: f64, lon: f64, range_nm: f64) -> Vec<(&Airport, f64)> {
+        let (row, col) = cell(lat, lon);
+        let rows = (range_nm / (CELL_DEG * NM_PER_DEG_LAT)).ceil() as i32;
+        let cols = (range_nm / (CELL_DEG * NM_PER_DEG_LAT * lon_scale(lat, rows)))
+            .ceil()
+            .min(180.0 / CELL_DEG) as i32;
+        let mut found = Vec::new();
+        for dr in -rows..=rows {
+            for dc in -cols..=cols {
+                let Some(indices) = self.cells.get(&(row + dr, wrap_col(col + dc))) else {
+                    continue;
+                };
+                for &idx in indices {
+                    let airport = &self.airports[idx];
+                    let dist = distance_nm(lat, lon, airport.lat, airport.lon);
+                    if dist <= range_nm {
+                        found.push((airport, dist));
+                    }
+                }
+            }
+        }
+        found
+    }
+}
+
+fn cell(lat: f64, lon: f64) -> (i32, i32) {
+    (
+        (lat / CELL_DEG).floor() as i32,
+        wrap_col((lon / CELL_DEG).floor() as i32),
+    )
+}
+
+fn wrap_col(col: i32) -> i32 {
+    let cols = (360.0 / CELL_DEG) as i32;
+    (col + cols / 2).rem_euclid(cols) - cols / 2
+}
+
+fn ring_offsets(ring: i32) -> impl Iterator<Item = (i32, i32)> {
+    (-ring..=ring).flat_map(move |dr| {
+        (-ring..=ring)
+            .filter(move |dc| dr.abs() == ring || dc.abs() == ring)
+            .map(move |dc| (dr, dc))
+    })
+}
+
+/// Width of a longitude cell relative to a latitude cell at the far edge of
+/// `rings` cells from `lat`.
+fn lon_scale(lat: f64, rings: i32) -> f64 {
+    let edge = (lat.abs() + rings as f64 * CELL_DEG).min(89.0);
+    edge.to_radians().cos().max(0.05)
+}
+
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_csv_line, AirportDb, AirportSize, RadarAirports};
+
+    const SAMPLE: &str = r#""id","ident","type","name","latitude_deg","longitude_deg","elevation_ft","continent","iso_country","iso_region","municipality","scheduled_service","gps_code","iata_code","local_code"
+3622,"KJFK","large_airport","John F Kennedy International Airport",40.639447,-73.779317,13,"NA","US","US-NY","New York","yes","KJFK","JFK","JFK"
+3697,"KLGA","large_airport","LaGuardia Airport",40.777245,-73.872608,21,"NA","US","US-NY","New York","yes","KLGA","LGA","LGA"
+20543,"N07","small_airport","Lincoln Park Airport",40.947498,-74.314499,182,"NA","US","US-NJ","Lincoln Park","no","KN07",,"N07"
+6523,"00A","heliport","Total RF Heliport",40.070985,-74.933689,11,"NA","US","US-PA","Bensalem","no","K00A",,"00A"
+2434,"EGLL","large_airport","London Heathrow Airport",51.4706,-0.461941,83,"EU","GB","GB-ENG","London","yes","EGLL","LHR",
+"#;
+
+    #[test]
+    fn csv_lines_keep_quoted_commas() {
+        assert_eq!(
+            split_csv_line(r#"1,"Smith, ""Jr"" Field",,x"#),
+            ["1", r#"Smith, "Jr" Field"#, "", "x"]
+        );
+    }
+
+    #[test]
+    fn parses_and_resolves_codes() {
+        let db = AirportDb::parse_csv(SAMPLE).unwrap();
+        assert_eq!(db.len(), 4, "the heliport is skipped");
+        assert_eq!(
+            db.lookup("egll").unwrap().describe(),
+            "London Heathrow Airport"
+        );
+        let jfk = db.lookup("JFK").unwrap();
+        assert_eq!(jfk.code, "KJFK");
+        assert_eq!(
+            jfk.describe(),
+            "John F Kennedy International Airport (New York)"
+        );
+        assert_eq!(jfk.short_name(), "John F Kennedy Intl");
+        assert_eq!(db.lookup("N07").unwrap().code, "KN07");
+        assert!(db.lookup("K00A").is_none());
+        assert!(AirportDb::parse_csv("id,name\n").is_err());
+    }
+
+    #[test]
+    fn finds_nearest_and_airports_in_range() {
+        let db = AirportDb::parse_csv(SAMPLE).unwrap();
+        let (airport, dist) = db.nearest(40.70, -73.80).unwrap();
+        assert_eq!(airport.code, "KJFK");
+        assert!(dist < 5.0);
+        // Across a cell boundary, LGA is still closer than JFK.
+        assert_eq!(db.nearest(41.01, -73.87).unwrap().0.code, "KLGA");
+        assert!(db.nearest(10.0, 10.0).is_none());
+
+        let mut near: Vec<_> = db
+            .within(40.7, -73.9, 30.0)
+            .into_iter()
+            .map(|(airport, _)| airport.code.as_str())
+            .collect();
+        near.sort();
+        assert_eq!(near, ["KJFK", "KLGA", "KN07"]);
+        assert!(!RadarAirports::Major.shows(AirportSize::Small));
+        assert!(RadarAirports::All.shows(AirportSize::Small));
+    }
+}
@@ -1,45 +1,54 @@
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fs;
-use std::path::PathBuf;
-use std::time::{Duration, SystemTime};
-
-use ratatui::layout::Rect;
+use std::hash::Hash;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use ratatui::layout::{Position, Rect};
+use ratatui::style::Color;
 use ratatui::widgets::TableState;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::oneshot::{self, error::TryRecvError};
+use tokio::sync::watch;
 use toml::Value;
 use toml_edit::DocumentMut;
 use tracing::{debug, info, trace, warn};
 
-use crate::config;
+use crate::aircraft_db::{self, LazyAircraftDb};
+use crate::airports::{Airport, AirportDb, RadarAirports};
+use crate::config::{self, Config};
+use crate::events::{EventKind, EventLog};
+use crate::export::ExportKind;
+use crate::filter::Filter;
+use crate::geography::Geography;
+use crate::intern::{normalize_callsign, normalize_hex, AircraftKey, KeyInterner};
+use crate::journal::ReplayStatus;
+use crate::keys::KeyMap;
 use crate::lookup::{LookupKind, LookupRequest};
-use crate::model::{seen_seconds, Aircraft, ApiResponse};
+use crate::model::{
+    message_seconds, position_seconds, seen_seconds, Aircraft, Altitude, ApiResponse, FeedCache,
+    ReceiverStats,
+};
+use crate::notifier::{AlertEvent, AlertKind, AlertKinds};
+use crate::photos::{PhotoCache, PhotoInfo, PhotoMessage};
+use crate::profile::{millis, ProcessUsage, Profiler, Stage, UsageSampler};
+use crate::quiet::QuietHours;
+use crate::radar::{self, RadarHit};
+use crate::roles::RoleRules;
+use crate::secrets;
+use crate::session::SessionLog;
+use crate::sightings::{Sighting, SightingBatch, SightingStore};
+use crate::sort::{SortColumn, SortKey, SortSpec};
 use crate::storage;
+use crate::traffic::{range_sector, TrafficHistory, TrafficMetric, RANGE_SECTORS};
+use crate::ui::{RowBuffer, ThemeOverrides};
+use crate::units::{bearing_deg, distance_mi, distance_nm, kt_to_mph};
 use crate::watchlist::WatchEntry;
-
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum SortMode {
-    LastSeen,
-    Altitude,
-    Speed,
-}
-
-impl SortMode {
-    pub fn next(self) -> Self {
-        match self {
-            SortMode::LastSeen => SortMode::Altitude,
-            SortMode::Altitude => SortMode::Speed,
-            SortMode::Speed => SortMode::LastSeen,
-        }
-    }
-
-    pub fn label(self) -> &'static str {
-        match self {
-            SortMode::LastSeen => "SEEN",
-            SortMode::Altitude => "ALT",
-            SortMode::Speed => "SPD",
-        }
-    }
-}
+use crate::weather::WeatherReport;
+use crate::zones::{Zone, ZoneAlert, ZoneTracker};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum InputMode {
@@ -51,7 +60,15 @@ pub enum InputMode {
     Config,
     Legend,
     Watchlist,
+    History,
+    Zones,
     Lookup,
+    Export,
+    Sort,
+    Detail,
+    Stats,
+    Events,
+    Notifications,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -60,6 +77,10 @@ pub enum LayoutMode {
     Compact,
     Radar,
     Performance,
+    Watch,
+    Profile,
+    Overhead,
+    Replay,
 }
 
 impl LayoutMode {
@@ -69,6 +90,10 @@ impl LayoutMode {
             LayoutMode::Compact => LayoutMode::Full,
             LayoutMode::Radar => LayoutMode::Full,
             LayoutMode::Performance => LayoutMode::Full,
+            LayoutMode::Watch => LayoutMode::Full,
+            LayoutMode::Profile => LayoutMode::Full,
+            LayoutMode::Overhead => LayoutMode::Full,
+            LayoutMode::Replay => LayoutMode::Full,
         }
     }
 
@@ -78,15 +103,27 @@ impl LayoutMode {
             LayoutMode::Compact => "COMPACT",
             LayoutMode::Radar => "RADAR",
             LayoutMode::Performance => "PERF",
+            LayoutMode::Watch => "WATCH",
+            LayoutMode::Profile => "PROFILE",
+            LayoutMode::Overhead => "OVERHEAD",
+            LayoutMode::Replay => "REPLAY",
         }
     }
 
     pub fn from_str(value: &str) -> Self {
-        match value.to_ascii_lowercase().as_str() {
-            "compact" => LayoutMode::Compact,
-            "radar" => LayoutMode::Radar,
-            "perf" | "performance" | "graph" => LayoutMode::Performance,
-            _ => LayoutMode::Full,
+        Self::parse(value).unwrap_or(LayoutMode::Full)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "full" => Some(LayoutMode::Full),
+            "compact" => Some(LayoutMode::Compact),
+            "radar" => Some(LayoutMode::Radar),
+            "perf" | "performance" | "graph" => Some(LayoutMode::Performance),
+            "watch" | "watchlist" | "targets" => Some(LayoutMode::Watch),
+            "profile" | "vertical" | "vsd" => Some(LayoutMode::Profile),
+            "overhead" | "narrow" | "phone" => Some(LayoutMode::Overhead),
+            _ => None,
         }
     }
 }
@@ -99,9 +136,14 @@ pub enum RadarRenderer {
 
 impl RadarRenderer {
     pub fn from_str(value: &str) -> Self {
-        match value.to_ascii_lowercase().as_str() {
-            "ascii" => RadarRenderer::Ascii,
-            _ => RadarRenderer::Canvas,
+        Self::parse(value).unwrap_or(RadarRenderer::Canvas)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "canvas" => Some(RadarRenderer::Canvas),
+            "ascii" => Some(RadarRenderer::Ascii),
+            _ => None,
         }
     }
 }
@@ -115,15 +157,139 @@ pub enum RadarBlip {
 
 impl RadarBlip {
     pub fn from_str(value: &str) -> Self {
-        match value.to_ascii_lowercase().as_str() {
-            "plane" | "airplane" | "aircraft" => RadarBlip::Plane,
-            "block" | "cube" | "solid" => RadarBlip::Block,
-            _ => RadarBlip::Dot,
+        Self::parse(value).unwrap_or(RadarBlip::Dot)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "dot" => Some(RadarBlip::Dot),
+            "plane" | "airplane" | "aircraft" => Some(RadarBlip::Plane),
+            "block" | "cube" | "solid" => Some(RadarBlip::Block),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GroundFilter {
+    All,
+    Airborne,
+    Ground,
+}
+
+impl GroundFilter {
+    pub fn from_str(value: &str) -> Self {
+        Self::parse(value).unwrap_or(GroundFilter::All)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "all" | "any" => Some(GroundFilter::All),
+            "airborne" | "air" => Some(GroundFilter::Airborne),
+            "ground" | "surface" => Some(GroundFilter::Ground),
+            _ => None,
+        }
+    }
+
+    pub fn allows(self, ac: &Aircraft) -> bool {
+        match self {
+            GroundFilter::All => true,
+            GroundFilter::Airborne => !ac.on_ground(),
+            GroundFilter::Ground => ac.on_ground(),
+        }
+    }
+}
+
+const STANDARD_QNH_HPA: f64 = 1013.25;
+const TRAIL_JUMP_MAX_KT: f64 = 1200.0;
+const TRAIL_JUMP_MIN_NM: f64 = 2.0;
+const TRAIL_JUMP_RESEGMENT: u32 = 3;
+const TRAIL_FADE_MAX_POINTS: usize = 600;
+const PINNED_TRAIL_MAX_POINTS: usize = 10_000;
+const FEET_PER_HPA: f64 = 27.0;
+const DERIVED_TRACK_MIN_NM: f64 = 0.05;
+const VS_SMOOTHING_ALPHA: f64 = 0.3;
+const VS_TREND_FPM: f64 = 200.0;
+const MAX_PREDICTION_SECS: f64 = 5.0;
+const RECEIVER_STATS_SAMPLES: usize = 120;
+const SIGNAL_HISTORY_LEN: usize = 120;
+const METRIC_HISTORY_LEN: usize = 120;
+const METRIC_HISTORY_WINDOW: Duration = Duration::from_secs(300);
+const NOTIFICATION_CAP: usize = 200;
+const HISTORY_PAGE: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AltitudeSource {
+    Baro,
+    Geom,
+    Qnh,
+}
+
+impl AltitudeSource {
+    pub fn from_str(value: &str) -> Self {
+        Self::parse(value).unwrap_or(AltitudeSource::Baro)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "baro" | "barometric" => Some(AltitudeSource::Baro),
+            "geom" | "geometric" | "gnss" => Some(AltitudeSource::Geom),
+            "qnh" | "corrected" => Some(AltitudeSource::Qnh),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            AltitudeSource::Baro => "ALT",
+            AltitudeSource::Geom => "GALT",
+            AltitudeSource::Qnh => "QALT",
+        }
+    }
+
+    /// Altitude from the preferred source, falling back to the other one when
+    /// the aircraft does not report it. Surface traffic stays on the ground.
+    pub fn altitude(self, ac: &Aircraft) -> Option<Altitude> {
+        if ac.on_ground() {
+            return Some(Altitude::Ground);
+        }
+        let geom = ac.alt_geom.map(Altitude::Feet);
+        match self {
+            AltitudeSource::Baro => ac.alt_baro.or(geom),
+            AltitudeSource::Geom => geom.or(ac.alt_baro),
+            AltitudeSource::Qnh => match (ac.alt_baro, ac.nav_qnh) {
+                (Some(Altitude::Feet(feet)), Some(qnh)) => Some(Altitude::Feet(
+                    feet + ((qnh - STANDARD_QNH_HPA) * FEET_PER_HPA).round() as i64,
+                )),
+                (baro, _) => baro.or(geom),
+            },
         }
     }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PositionQuality {
+    Show,
+    Dim,
+    Hide,
+}
+
+impl PositionQuality {
+    pub fn from_str(value: &str) -> Self {
+        Self::parse(value).unwrap_or(PositionQuality::Show)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "show" | "plot" => Some(PositionQuality::Show),
+            "dim" | "downgrade" => Some(PositionQuality::Dim),
+            "hide" | "suppress" => Some(PositionQuality::Hide),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum ThemeMode {
     Default,
     ColorBlind,
@@ -142,10 +308,55 @@ pub enum FlagStyle {
 
 impl FlagStyle {
     pub fn from_str(value: &str) -> Self {
-        match value.to_ascii_lowercase().as_str() {
-            "text" | "code" => FlagStyle::Text,
-            "none" | "off" => FlagStyle::None,
-            _ => FlagStyle::Emoji,
+        Self::parse(value).unwrap_or(FlagStyle::Emoji)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "emoji" => Some(FlagStyle::Emoji),
+            "text" | "code" => Some(FlagStyle::Text),
+            "none" | "off" => Some(FlagStyle::None),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColorBy {
+    Freshness,
+    Altitude,
+}
+
+impl ColorBy {
+    pub fn from_str(value: &str) -> Self {
+        Self::parse(value).unwrap_or(ColorBy::Freshness)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "freshness" | "default" => Some(ColorBy::Freshness),
+            "altitude" | "alt" => Some(ColorBy::Altitude),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RouteLabels {
+    Names,
+    Codes,
+}
+
+impl RouteLabels {
+    pub fn from_str(value: &str) -> Self {
+        Self::parse(value).unwrap_or(RouteLabels::Names)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "names" | "name" => Some(RouteLabels::Names),
+            "codes" | "code" => Some(RouteLabels::Codes),
+            _ => None,
         }
     }
 }
@@ -174,13 +385,18 @@ impl ThemeMode {
     }
 
     pub fn from_str(value: &str) -> Self {
-        match value.to_ascii_lowercase().as_str() {
-            "color" | "colorblind" | "cb" => ThemeMode::ColorBlind,
-            "amber" | "gold" => ThemeMode::Amber,
-            "ocean" | "blue" => ThemeMode::Ocean,
-            "matrix" | "green" => ThemeMode::Matrix,
-            "mono" | "monochrome" | "bw" | "grayscale" => ThemeMode::Monochrome,
-            _ => ThemeMode::Default,
+        Self::parse(value).unwrap_or(ThemeMode::Default)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "default" => Some(ThemeMode::Default),
+            "color" | "colorblind" | "cb" => Some(ThemeMode::ColorBlind),
+            "amber" | "gold" => Some(ThemeMode::Amber),
+            "ocean" | "blue" => Some(ThemeMode::Ocean),
+            "matrix" | "green" => Some(ThemeMode::Matrix),
+            "mono" | "monochrome" | "bw" | "grayscale" => Some(ThemeMode::Monochrome),
+            _ => None,
         }
     }
 }
@@ -205,12 +421,14 @@ pub enum AircraftRole {
 pub struct Trend {
     pub alt: TrendDir,
     pub gs: TrendDir,
+    pub vs: Option<i64>,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ColumnId {
     Fav,
     Watch,
+    Pin,
     Flight,
     Reg,
     Type,
@@ -226,6 +444,54 @@ pub enum ColumnId {
     Msgs,
     Hex,
     Flag,
+    Ver,
+    Class,
+    Cat,
+    Vs,
+    Cpa,
+    Src,
+    Source,
+    Apt,
+}
+
+impl ColumnId {
+    pub fn name(self) -> &'static str {
+        match self {
+            ColumnId::Fav => "fav",
+            ColumnId::Watch => "watch",
+            ColumnId::Pin => "pin",
+            ColumnId::Flight => "flight",
+            ColumnId::Reg => "reg",
+            ColumnId::Type => "type",
+            ColumnId::Route => "route",
+            ColumnId::Alt => "alt",
+            ColumnId::Gs => "gs",
+            ColumnId::Trk => "trk",
+            ColumnId::Lat => "lat",
+            ColumnId::Lon => "lon",
+            ColumnId::Dist => "dist",
+            ColumnId::Brg => "brg",
+            ColumnId::Seen => "seen",
+            ColumnId::Msgs => "msgs",
+            ColumnId::Hex => "hex",
+            ColumnId::Flag => "flag",
+            ColumnId::Ver => "ver",
+            ColumnId::Class => "class",
+            ColumnId::Cat => "cat",
+            ColumnId::Vs => "vs",
+            ColumnId::Cpa => "cpa",
+            ColumnId::Src => "src",
+            ColumnId::Source => "source",
+            ColumnId::Apt => "apt",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        default_columns()
+            .into_iter()
+            .map(|col| col.id)
+            .find(|id| id.name().eq_ignore_ascii_case(name))
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -234,15 +500,154 @@ pub struct ColumnConfig {
     pub label: &'static str,
     pub width: u16,
     pub visible: bool,
+    pub fixed_width: Option<u16>,
+}
+
+impl ColumnConfig {
+    pub fn min_width(&self) -> u16 {
+        self.fixed_width.unwrap_or(self.width)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ColumnLayout {
+    pub id: ColumnId,
+    pub visible: bool,
+    pub width: Option<u16>,
+}
+
+const MAX_COLUMN_WIDTH: u16 = 40;
+
+/// Parses `table_columns`: comma separated column names in display order,
+/// each with an optional `:width`, and a leading `-` when hidden.
+pub fn parse_column_layout(text: &str) -> Result<Vec<ColumnLayout>, String> {
+    let mut layout: Vec<ColumnLayout> = Vec::new();
+    for entry in text.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        let (visible, entry) = match entry.strip_prefix('-') {
+            Some(rest) => (false, rest.trim()),
+            None => (true, entry),
+        };
+        let (name, width) = match entry.split_once(':') {
+            Some((name, width)) => {
+                let width = width
+                    .trim()
+                    .parse::<u16>()
+                    .ok()
+                    .filter(|w| (1..=MAX_COLUMN_WIDTH).contains(w))
+                    .ok_or_else(|| format!("`{entry}`: width must be 1 to {MAX_COLUMN_WIDTH}"))?;
+                (name.trim(), Some(width))
+            }
+            None => (entry, None),
+        };
+        let id = ColumnId::from_name(name).ok_or_else(|| format!("unknown column `{name}`"))?;
+        if layout.iter().any(|col| col.id == id) {
+            return Err(format!("column `{name}` is listed twice"));
+        }
+        layout.push(ColumnLayout { id, visible, width });
+    }
+    Ok(layout)
+}
+
+pub fn column_layout_text(columns: &[ColumnConfig]) -> String {
+    columns
+        .iter()
+        .map(|col| {
+            let hidden = if col.visible { "" } else { "-" };
+            match col.fixed_width {
+                Some(width) => format!("{hidden}{}:{width}", col.id.name()),
+                None => format!("{hidden}{}", col.id.name()),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn build_columns(
+    flags_enabled: bool,
+    watchlist_enabled: bool,
+    layout: &[ColumnLayout],
+) -> Vec<ColumnConfig> {
+    let mut rest = default_columns();
+    for col in &mut rest {
+        match col.id {
+            ColumnId::Flag => col.visible = flags_enabled,
+            ColumnId::Watch => col.visible = watchlist_enabled,
+            _ => {}
+        }
+    }
+    let mut columns = Vec::with_capacity(rest.len());
+    for entry in layout {
+        if let Some(pos) = rest.iter().position(|col| col.id == entry.id) {
+            let mut col = rest.remove(pos);
+            col.visible = entry.visible;
+            col.fixed_width = entry.width;
+            columns.push(col);
+        }
+    }
+    columns.append(&mut rest);
+    if !columns.iter().any(|col| col.visible) {
+        if let Some(first) = columns.first_mut() {
+            first.visible = true;
+        }
+    }
+    columns
 }
 
 #[derive(Clone, Copy, Debug)]
 pub struct TrailPoint {
     pub lat: f64,
     pub lon: f64,
+    pub alt: Option<Altitude>,
+    pub at: SystemTime,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct MetricSample {
+    pub alt: Option<i64>,
+    pub gs: Option<f64>,
+    pub at: SystemTime,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct SignalSample {
+    pub rssi: Option<f64>,
+    pub msg_rate: Option<f64>,
     pub at: SystemTime,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DetailTab {
+    Overview,
+    Positions,
+    Signal,
+    Route,
+    Photo,
+}
+
+impl DetailTab {
+    pub const ALL: [DetailTab; 5] = [
+        DetailTab::Overview,
+        DetailTab::Positions,
+        DetailTab::Signal,
+        DetailTab::Route,
+        DetailTab::Photo,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DetailTab::Overview => "Overview",
+            DetailTab::Positions => "Position history",
+            DetailTab::Signal => "Signal",
+            DetailTab::Route => "Route",
+            DetailTab::Photo => "Photo",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|tab| *tab == self).unwrap_or(0)
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub struct SiteLocation {
     pub lat: f64,
@@ -250,6 +655,58 @@ pub struct SiteLocation {
     pub alt_m: f64,
 }
 
+/// Course over ground, as broadcast or derived from the position trail for
+/// targets (MLAT, some TIS-B) that do not send a track.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Course {
+    pub deg: f64,
+    pub derived: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SiteGeometry {
+    pub distance_nm: f64,
+    pub bearing_deg: f64,
+}
+
+/// Cached geometry plus the positions it was computed from, so a stale entry
+/// (site switched, aircraft list replaced) is recomputed instead of trusted.
+#[derive(Clone, Copy, Debug)]
+struct CachedGeometry {
+    site: (f64, f64),
+    position: (f64, f64),
+    geometry: SiteGeometry,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClosestApproach {
+    /// Minutes until the closest point; 0 once the aircraft is moving away.
+    pub minutes: f64,
+    pub distance_mi: f64,
+}
+
+#[derive(Clone, Debug)]
+pub struct NamedSite {
+    pub name: String,
+    pub location: SiteLocation,
+}
+
+impl NamedSite {
+    pub fn from_config(sites: &[config::SiteConfig]) -> Vec<Self> {
+        sites
+            .iter()
+            .map(|site| Self {
+                name: site.name.clone(),
+                location: SiteLocation {
+                    lat: site.lat,
+                    lon: site.lon,
+                    alt_m: site.alt_m.unwrap_or(0.0),
+                },
+            })
+            .collect()
+    }
+}
+
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub struct RadarCenter {
     pub lat: f64,
@@ -268,6 +725,8 @@ pub enum RadarDirection {
 pub struct Notification {
     pub message: String,
     pub at: SystemTime,
+    pub alert: bool,
+    pub read: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -275,15 +734,91 @@ pub struct ConfigItem {
     pub key: String,
     pub value: String,
     pub kind: config::ConfigKind,
+    pub saved: String,
 }
 
-#[derive(Clone, Debug)]
-struct ColumnWidthCache {
-    width: u16,
+impl ConfigItem {
+    pub fn choices(&self) -> Option<&'static [&'static str]> {
+        if self.kind == config::ConfigKind::Bool {
+            return Some(&["true", "false"]);
+        }
+        config::config_choices(&self.key)
+    }
+}
+
+const COLUMN_SAMPLE_ROWS: usize = 50;
+
+/// Column content widths, sampled a few rows per frame. Widths grow as soon
+/// as a wider cell is seen but only shrink once a full pass over the rows
+/// finds the wide content gone, and the layout is only redone when the
+/// columns, table width or content widths change, so the table does not
+/// jitter as rows come and go.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct ColumnWidths {
     cols: Vec<ColumnId>,
-    rows_len: usize,
-    at: SystemTime,
-    widths: Vec<u16>,
+    content: Vec<usize>,
+    pass: Vec<usize>,
+    cursor: usize,
+    generation: u64,
+    layout: Option<(u16, u64, Vec<u16>)>,
+}
+
+impl ColumnWidths {
+    pub(crate) fn sample_range(&mut self, cols: &[ColumnId], rows: usize) -> Range<usize> {
+        if self.cols != cols {
+            *self = Self {
+                cols: cols.to_vec(),
+                content: vec![0; cols.len()],
+                pass: vec![0; cols.len()],
+                generation: self.generation + 1,
+                ..Self::default()
+            };
+        }
+        let start = self.cursor.min(rows);
+        start..(start + COLUMN_SAMPLE_ROWS).min(rows)
+    }
+
+    pub(crate) fn record_sample(&mut self, sample: &[usize], end: usize, rows: usize) {
+        let mut changed = false;
+        for ((pass, content), width) in self.pass.iter_mut().zip(&mut self.content).zip(sample) {
+            *pass = (*pass).max(*width);
+            if *width > *content {
+                *content = *width;
+                changed = true;
+            }
+        }
+        self.cursor = end;
+        if end >= rows {
+            if self.content != self.pass {
+                self.content.clone_from(&self.pass);
+                changed = true;
+            }
+            self.pass.fill(0);
+            self.cursor = 0;
+        }
+        if changed {
+            self.generation += 1;
+        }
+    }
+
+    pub(crate) fn invalidate(&mut self) {
+        self.layout = None;
+    }
+
+    pub(crate) fn layout(
+        &mut self,
+        width: u16,
+        fit: impl FnOnce(&[usize]) -> Vec<u16>,
+    ) -> Vec<u16> {
+        if let Some((cached_width, generation, widths)) = &self.layout {
+            if *cached_width == width && *generation == self.generation {
+                return widths.clone();
+            }
+        }
+        let widths = fit(&self.content);
+        self.layout = Some((width, self.generation, widths.clone()));
+        widths
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -292,6 +827,7 @@ pub struct RouteInfo {
     pub destination: Option<String>,
     pub route: Option<String>,
     pub fetched_at: SystemTime,
+    pub used_at: SystemTime,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -305,8 +841,9 @@ struct AircraftRate {
 
 #[derive(Clone, Copy, Debug, Default)]
 struct Metrics {
-    alt_baro: Option<i64>,
+    alt: Option<i64>,
     gs: Option<f64>,
+    vs: Option<f64>,
 }
 
 #[derive(Clone, Copy, Debug)]
@@ -317,11 +854,131 @@ struct RadarNavPoint {
     center_dist_sq: f64,
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct PerformanceSample {
     pub msg_rate: Option<f64>,
     pub flights: usize,
     pub rssi_avg: Option<f64>,
+    pub usage: ProcessUsage,
+    pub fetch_ms: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PerfWindow {
+    FiveMinutes,
+    ThirtyMinutes,
+    TwoHours,
+}
+
+impl PerfWindow {
+    pub const ALL: [PerfWindow; 3] = [
+        PerfWindow::FiveMinutes,
+        PerfWindow::ThirtyMinutes,
+        PerfWindow::TwoHours,
+    ];
+
+    pub fn from_str(value: &str) -> Self {
+        Self::parse(value).unwrap_or(PerfWindow::FiveMinutes)
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "5m" | "5min" => Some(PerfWindow::FiveMinutes),
+            "30m" | "30min" => Some(PerfWindow::ThirtyMinutes),
+            "2h" | "120m" => Some(PerfWindow::TwoHours),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PerfWindow::FiveMinutes => "5m",
+            PerfWindow::ThirtyMinutes => "30m",
+            PerfWindow::TwoHours => "2h",
+        }
+    }
+
+    pub fn duration(self) -> Duration {
+        match self {
+            PerfWindow::FiveMinutes => Duration::from_secs(5 * 60),
+            PerfWindow::ThirtyMinutes => Duration::from_secs(30 * 60),
+            PerfWindow::TwoHours => Duration::from_secs(2 * 3600),
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|window| *window == self)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PerfChart {
+    MsgRate,
+    Aircraft,
+    Rssi,
+    MaxRange,
+    PositionRate,
+    RouteHits,
+}
+
+impl PerfChart {
+    pub fn from_panel(name: &str) -> Option<Self> {
+        match name {
+            "msg_rate" => Some(PerfChart::MsgRate),
+            "aircraft" => Some(PerfChart::Aircraft),
+            "rssi" => Some(PerfChart::Rssi),
+            "range" => Some(PerfChart::MaxRange),
+            "positions" => Some(PerfChart::PositionRate),
+            "routes" => Some(PerfChart::RouteHits),
+            _ => None,
+        }
+    }
+
+    pub fn title(self) -> &'static str {
+        match self {
+            PerfChart::MsgRate => "MSG RATE",
+            PerfChart::Aircraft => "AIRCRAFT",
+            PerfChart::Rssi => "AVG RSSI",
+            PerfChart::MaxRange => "MAX RANGE",
+            PerfChart::PositionRate => "POSITIONS",
+            PerfChart::RouteHits => "ROUTE CACHE",
+        }
+    }
+
+    pub fn unit(self) -> &'static str {
+        match self {
+            PerfChart::MsgRate | PerfChart::PositionRate => "/s",
+            PerfChart::Aircraft => "",
+            PerfChart::Rssi => " dBFS",
+            PerfChart::MaxRange => " nm",
+            PerfChart::RouteHits => "% hit",
+        }
+    }
+
+    fn value(self, point: &PerfPoint) -> Option<f64> {
+        match self {
+            PerfChart::MsgRate => point.msg_rate,
+            PerfChart::Aircraft => Some(point.aircraft as f64),
+            PerfChart::Rssi => point.rssi_avg,
+            PerfChart::MaxRange => point.max_range_nm,
+            PerfChart::PositionRate => point.position_rate,
+            PerfChart::RouteHits => point.route_hit_pct,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PerfPoint {
+    pub at: SystemTime,
+    pub msg_rate: Option<f64>,
+    pub aircraft: usize,
+    pub rssi_avg: Option<f64>,
+    pub max_range_nm: Option<f64>,
+    pub position_rate: Option<f64>,
+    pub route_hit_pct: Option<f64>,
 }
 
 #[derive(Clone, Debug)]
@@ -333,6 +990,58 @@ pub struct PerformanceSnapshot {
     pub latest_flights: usize,
     pub latest_signal: Option<f64>,
     pub latest_signal_rsi: Option<f64>,
+    pub adsb_versions: [usize; 3],
+    pub receiver: Option<ReceiverStats>,
+    pub receiver_signal: Vec<u64>,
+    pub receiver_cpu: Vec<u64>,
+    pub app_cpu: Vec<u64>,
+    pub fetch_ms: Vec<u64>,
+    pub latest_usage: ProcessUsage,
+    pub latest_fetch_ms: Option<f64>,
+    pub frame_ms: Option<f64>,
+    pub feed_cache: FeedCache,
+}
+
+pub const ALTITUDE_BANDS: [(&str, i64); 7] = [
+    ("GND", 0),
+    ("<5k", 5_000),
+    ("5-10k", 10_000),
+    ("10-20k", 20_000),
+    ("20-30k", 30_000),
+    ("30-40k", 40_000),
+    ("40k+", i64::MAX),
+];
+pub const DISTANCE_RINGS: [(&str, f64); 7] = [
+    ("<10", 10.0),
+    ("10-25", 25.0),
+    ("25-50", 50.0),
+    ("50-100", 100.0),
+    ("100-150", 150.0),
+    ("150-200", 200.0),
+    ("200+", f64::INFINITY),
+];
+pub const RSSI_BANDS: [(&str, f64); 7] = [
+    ("<-30", -30.0),
+    ("-30", -25.0),
+    ("-25", -20.0),
+    ("-20", -15.0),
+    ("-15", -10.0),
+    ("-10", -5.0),
+    ("-5+", f64::INFINITY),
+];
+
+#[derive(Clone, Debug, Default)]
+pub struct TrafficHistograms {
+    pub altitude: [u64; ALTITUDE_BANDS.len()],
+    pub distance: Option<[u64; DISTANCE_RINGS.len()]>,
+    pub rssi: [u64; RSSI_BANDS.len()],
+    pub rssi_percentiles: Option<[f64; 3]>,
+}
+
+#[derive(Clone, Debug)]
+pub struct RangeSectors {
+    pub current: [f64; RANGE_SECTORS],
+    pub max: [f64; RANGE_SECTORS],
 }
 
 pub struct App {
@@ -340,13 +1049,21 @@ pub struct App {
     pub(crate) feed_templates: Vec<String>,
     pub(crate) refresh: Duration,
     pub(crate) data: ApiResponse,
-    raw_data: ApiResponse,
+    /// Newest feed snapshot not yet shown. Swapping moves it into `data`
+    /// rather than copying it, so a snapshot is never cloned on its way to
+    /// the screen.
+    pending: Option<ApiResponse>,
     pub(crate) last_update: Option<SystemTime>,
     pub(crate) last_error: Option<String>,
-    pub(crate) sort: SortMode,
+    pub(crate) sort: SortSpec,
+    pub sort_cursor: usize,
+    sort_opened_with: SortSpec,
     pub(crate) table_state: TableState,
     pub(crate) table_area: Option<Rect>,
     pub(crate) table_header_rows: u16,
+    pub(crate) radar_area: Option<Rect>,
+    pub(crate) radar_hits: Vec<RadarHit>,
+    pub(crate) radar_hover: Option<(u16, u16)>,
     pub(crate) tick: u64,
     pub(crate) start_time: SystemTime,
     pub(crate) stale_secs: f64,
@@ -359,16 +1076,20 @@ pub struct App {
     pub(crate) watchlist_path: Option<PathBuf>,
     pub(crate) watchlist: Vec<WatchEntry>,
     pub(crate) filter: String,
+    pub(crate) filter_query: Result<Filter, String>,
     pub(crate) filter_edit: String,
     pub(crate) input_mode: InputMode,
     previous_input_mode: Option<InputMode>,
     pub(crate) layout_mode: LayoutMode,
+    /// Terminal width below which the overhead layout is drawn instead of
+    /// `layout_mode`; 0 never switches.
+    pub(crate) overhead_width: u16,
+    terminal_width: u16,
     pub(crate) theme_mode: ThemeMode,
     pub(crate) role_enabled: bool,
     pub(crate) role_highlight: bool,
     pub(crate) column_cache_enabled: bool,
-    pub(crate) column_cache_ttl: Duration,
-    column_width_cache: Option<ColumnWidthCache>,
+    pub(crate) column_widths: ColumnWidths,
     pub(crate) config_path: PathBuf,
     pub(crate) config_items: Vec<ConfigItem>,
     pub(crate) config_cursor: usize,
@@ -376,10 +1097,18 @@ pub struct App {
     pub(crate) config_editing: bool,
     pub(crate) config_dirty: bool,
     pub(crate) config_status: Option<(String, SystemTime)>,
+    pub(crate) pending_feed_urls: Option<Vec<String>>,
+    pub replay: Option<ReplayStatus>,
     pub(crate) help_scroll: usize,
     pub(crate) watchlist_cursor: usize,
     pub(crate) trail_len: usize,
+    /// Trail points older than this are dropped and the rest fade with
+    /// age; 0 keeps `trail_len` points at full strength.
+    pub(crate) trail_fade_secs: u64,
+    pub(crate) trail_altitude_colors: bool,
     pub(crate) site: Option<SiteLocation>,
+    pub(crate) sites: Vec<NamedSite>,
+    pub(crate) site_index: Option<usize>,
     pub(crate) radar_center: Option<RadarCenter>,
     pub(crate) demo_mode: bool,
     pub(crate) radar_range_nm: f64,
@@ -388,6 +1117,7 @@ pub struct App {
     pub(crate) radar_labels: bool,
     pub(crate) radar_blip: RadarBlip,
     pub(crate) columns: Vec<ColumnConfig>,
+    columns_opened_with: String,
     pub(crate) column_cursor: usize,
     pub(crate) smooth_mode: bool,
     pub(crate) smooth_merge: bool,
@@ -402,12 +1132,15 @@ pub struct App {
     pub(crate) altitude_trend_arrows: bool,
     pub(crate) track_arrows: bool,
     pub(crate) stats_metrics: [String; 3],
+    pub(crate) perf_panels: [String; 6],
     #[allow(dead_code)]
     pub(crate) flags_enabled: bool,
     pub(crate) flag_style: FlagStyle,
     pub(crate) route_last_poll: Option<SystemTime>,
     pub(crate) route_cache: HashMap<String, RouteInfo>,
     pub(crate) route_last_request: HashMap<String, SystemTime>,
+    pub(crate) route_hits: u64,
+    pub(crate) route_misses: u64,
     pub(crate) route_backoff_until: Option<SystemTime>,
     pub(crate) route_backoff_attempts: u32,
     pub(crate) msg_rate: Option<f64>,
@@ -417,7 +1150,7 @@ pub struct App {
     msg_rate_window: Duration,
     msg_rate_min_secs: f64,
     msg_samples: VecDeque<(SystemTime, u64)>,
-    aircraft_rates: HashMap<String, AircraftRate>,
+    aircraft_rates: HashMap<AircraftKey, AircraftRate>,
     avg_aircraft_rate: Option<f64>,
     total_aircraft_rate: Option<f64>,
     total_aircraft_rate_ema: Option<f64>,
@@ -425,17 +1158,41 @@ pub struct App {
     pub(crate) notify_radius_mi: f64,
     pub(crate) overpass_mi: f64,
     pub(crate) notify_cooldown: Duration,
+    pub(crate) desktop_alerts: AlertKinds,
+    pub(crate) quiet_hours: QuietHours,
+    /// Do-not-disturb set by hand, until the quiet hours next start or end.
+    dnd_override: Option<bool>,
+    dnd_scheduled: bool,
+    desktop_tx: Option<UnboundedSender<AlertEvent>>,
+    hook_tx: Option<UnboundedSender<AlertEvent>>,
     notified_recent: HashMap<String, SystemTime>,
     watch_notified_recent: HashMap<String, SystemTime>,
     pub(crate) notifications: Vec<Notification>,
     pub(crate) last_msg_total: Option<u64>,
     pub(crate) last_msg_time: Option<SystemTime>,
-    pub(crate) seen_times: HashMap<String, SystemTime>,
-    last_metrics: HashMap<String, Metrics>,
-    pub(crate) trend_cache: HashMap<String, Trend>,
-    pub(crate) trail_points: HashMap<String, Vec<TrailPoint>>,
+    pub(crate) seen_times: HashMap<Arc<str>, SystemTime>,
+    last_metrics: HashMap<Arc<str>, Metrics>,
+    pub(crate) trend_cache: HashMap<Arc<str>, Trend>,
+    pub(crate) trail_points: HashMap<Arc<str>, VecDeque<TrailPoint>>,
+    pinned_trails: HashSet<Arc<str>>,
+    history_rx: Option<oneshot::Receiver<Vec<ApiResponse>>>,
+    trail_jump_strikes: HashMap<Arc<str>, u32>,
+    pub(crate) signal_history: HashMap<Arc<str>, VecDeque<SignalSample>>,
+    pub(crate) metric_history: HashMap<Arc<str>, VecDeque<MetricSample>>,
+    aircraft_keys: KeyInterner,
+    pub(crate) trail_jumps_rejected: u64,
+    pub(crate) parse_warnings: u64,
+    pub(crate) feed_cache: FeedCache,
+    geometry: Vec<Option<CachedGeometry>>,
+    /// Bumped per feed update; compared with `swapped_version` so smooth mode
+    /// only swaps (and the runtime only redraws) when there is new data.
+    data_version: u64,
+    swapped_version: u64,
     perf_samples: VecDeque<PerformanceSample>,
     perf_max_samples: usize,
+    perf_points: VecDeque<PerfPoint>,
+    pub(crate) perf_window: PerfWindow,
+    receiver_stats: VecDeque<ReceiverStats>,
     pub(crate) last_export: Option<(String, SystemTime)>,
     pub(crate) route_error: Option<(String, SystemTime)>,
     // Lookup modal state
@@ -443,66 +1200,125 @@ pub struct App {
     pub(crate) lookup_status: Option<String>,
     pub(crate) lookup_results: Option<Vec<Aircraft>>,
     pub(crate) lookup_busy: bool,
+    pub(crate) profile: Option<String>,
+    pub(crate) export_dir: PathBuf,
+    pub(crate) keymap: KeyMap,
+    pub(crate) theme_overrides: ThemeOverrides,
+    pub(crate) ground_filter: GroundFilter,
+    pub(crate) altitude_source: AltitudeSource,
+    pub(crate) low_quality_positions: PositionQuality,
+    pub(crate) position_stale_secs: f64,
+    pub(crate) cpa_notify_mi: f64,
+    pub(crate) cpa_notify_mins: f64,
+    pub(crate) radar_ghosts: bool,
+    pub(crate) reduced_motion: bool,
+    pub(crate) max_tracked_aircraft: usize,
+    pub(crate) route_cache_size: usize,
+    pub(crate) profiler: Profiler,
+    usage: UsageSampler,
+    pub(crate) show_profiler: bool,
+    pub(crate) row_buffer: RowBuffer,
+    pub(crate) traffic_path: Option<PathBuf>,
+    pub(crate) traffic: TrafficHistory,
+    pub(crate) sightings_path: Option<PathBuf>,
+    pub(crate) sightings: SightingStore,
+    sightings_tx: Option<UnboundedSender<SightingBatch>>,
+    sightings_flushed: Option<watch::Receiver<u64>>,
+    /// The selected aircraft's history row, read outside the draw path.
+    selected_sighting: Option<(String, Option<Sighting>)>,
+    pub history_cursor: usize,
+    pub(crate) history_total: usize,
+    history_offset: usize,
+    history_rows: Vec<Sighting>,
+    pub(crate) session: SessionLog,
+    pub(crate) events: EventLog,
+    pub events_cursor: usize,
+    pub notifications_cursor: usize,
+    pub export_cursor: usize,
+    pub detail_tab: DetailTab,
+    pub stats_metric: TrafficMetric,
+    pub(crate) zones_path: Option<PathBuf>,
+    pub(crate) zones: Vec<Zone>,
+    zone_tracker: ZoneTracker,
+    pub zones_cursor: usize,
+    pub(crate) airports: AirportDb,
+    pub(crate) radar_airports: RadarAirports,
+    pub(crate) route_labels: RouteLabels,
+    pub(crate) weather_station: Option<String>,
+    pub(crate) weather: Option<WeatherReport>,
+    pub(crate) weather_error: Option<String>,
+    photo_cache_path: Option<PathBuf>,
+    pub(crate) photos: Option<PhotoCache>,
+    pub(crate) photo_pending: HashSet<String>,
+    pub(crate) photo_error: Option<(String, String)>,
+    pub(crate) color_by: ColorBy,
+    pub(crate) color_alt_column: bool,
+    pub(crate) predict_positions: bool,
+    data_shown_at: Option<Instant>,
+    pub(crate) radar_vectors: bool,
+    pub(crate) radar_vector_secs: u64,
+    pub(crate) radar_compass: bool,
+    pub(crate) radar_range_rose: bool,
+    pub(crate) radar_follow: bool,
+    pub(crate) geography: Geography,
+    aircraft_db: Option<LazyAircraftDb>,
+    role_rules: RoleRules,
+    roles_path: Option<PathBuf>,
+    military_ranges_path: Option<PathBuf>,
+    // The settings last applied; editor saves and reloads are diffed against it.
+    config: Config,
+}
+
+#[derive(Default)]
+pub struct AppData {
+    pub url: String,
+    pub favorites: HashSet<String>,
+    pub watchlist: Vec<WatchEntry>,
+    pub traffic: TrafficHistory,
+    pub sightings: SightingStore,
+    pub zones: Vec<Zone>,
+    pub airports: AirportDb,
+    pub geography: Geography,
+    pub keymap: KeyMap,
 }
 
 impl App {
-    #[allow(clippy::too_many_arguments)]
-    pub fn new(
-        url: String,
-        feed_templates: Vec<String>,
-        refresh: Duration,
-        stale_secs: f64,
-        hide_stale: bool,
-        low_nic: i64,
-        low_nac: i64,
-        favorites: HashSet<String>,
-        filter: String,
-        layout_mode: LayoutMode,
-        theme_mode: ThemeMode,
-        role_enabled: bool,
-        role_highlight: bool,
-        column_cache_enabled: bool,
-        column_cache_ttl: Duration,
-        config_path: PathBuf,
-        trail_len: usize,
-        favorites_path: Option<PathBuf>,
-        site: Option<SiteLocation>,
-        demo_mode: bool,
-        radar_range_nm: f64,
-        radar_aspect: f64,
-        radar_renderer: RadarRenderer,
-        radar_labels: bool,
-        radar_blip: RadarBlip,
-        route_enabled: bool,
-        route_tar1090: bool,
-        route_ttl: Duration,
-        route_refresh: Duration,
-        route_batch: usize,
-        ui_fps: u64,
-        smooth_mode: bool,
-        smooth_merge: bool,
-        rate_window: Duration,
-        rate_min_secs: f64,
-        notify_radius_mi: f64,
-        overpass_mi: f64,
-        notify_cooldown: Duration,
-        altitude_trend_arrows: bool,
-        track_arrows: bool,
-        flags_enabled: bool,
-        flag_style: FlagStyle,
-        stats_metric_1: String,
-        stats_metric_2: String,
-        stats_metric_3: String,
-        watchlist_enabled: bool,
-        watchlist_path: Option<PathBuf>,
-        watchlist: Vec<WatchEntry>,
-    ) -> Self {
-        let mut table_state = TableState::default();
-        table_state.select(Some(0));
-        let ui_interval = if ui_fps == 0 {
-            Duration::from_secs(0)
-        } else {
-            Duration::from_millis((1000.0 / ui_fps.max(1) as f64) as u64)
+    pub fn new(config: &Config, data: AppData) -> Self {
+        let AppData {
+            url,
+            favorites,
+            watchlist,
+            traffic,
+            sightings,
+            zones,
+            airports,
+            geography,
+            keymap,
+        } = data;
+        let refresh = config.refresh;
+        let filter = config.filter.clone();
+        let watchlist_enabled = config.watchlist_enabled || !watchlist.is_empty();
+        let site = match (config.site_lat, config.site_lon) {
+            (Some(lat), Some(lon)) => Some(SiteLocation {
+                lat,
+                lon,
+                alt_m: config.site_alt_m.unwrap_or(0.0),
+            }),
+            _ => None,
+        };
+        let sites = NamedSite::from_config(&config.sites);
+        let weather_station = Some(config.weather_station.trim().to_ascii_uppercase())
+            .filter(|station| !station.is_empty());
+        let (theme_overrides, theme_errors) = ThemeOverrides::parse(&config.theme_overrides);
+        for err in theme_errors {
+            warn!("{err}");
+        }
+        let mut table_state = TableState::default();
+        table_state.select(Some(0));
+        let ui_interval = if config.ui_fps == 0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_millis((1000.0 / config.ui_fps.max(1) as f64) as u64)
         };
         let perf_max_samples = {
             let refresh_secs = refresh.as_secs_f64().max(0.1);
@@ -512,115 +1328,135 @@ impl App {
             lat: site.lat,
             lon: site.lon,
         });
+        let site_index = site_index_of(&sites, site);
         Self {
             url,
-            feed_templates,
+            feed_templates: config::active_url_templates(config),
             refresh,
             data: ApiResponse::default(),
-            raw_data: ApiResponse::default(),
+            pending: None,
             last_update: None,
             last_error: None,
-            sort: SortMode::LastSeen,
+            sort: SortSpec::parse(&config.sort_order).unwrap_or_default(),
+            sort_cursor: 0,
+            sort_opened_with: SortSpec::default(),
             table_state,
             table_area: None,
+            radar_area: None,
+            radar_hits: Vec::new(),
+            radar_hover: None,
             table_header_rows: 1,
             tick: 0,
             start_time: SystemTime::now(),
-            stale_secs,
-            hide_stale,
-            low_nic,
-            low_nac,
+            stale_secs: config.stale_secs as f64,
+            hide_stale: config.hide_stale,
+            low_nic: config.low_nic,
+            low_nac: config.low_nac,
             favorites,
-            favorites_path,
+            favorites_path: config::data_file_path(&config.favorites_file),
             watchlist_enabled,
-            watchlist_path,
+            watchlist_path: config::data_file_path(&config.watchlist_file),
             watchlist,
+            filter_query: Filter::parse(&filter),
             filter,
             filter_edit: String::new(),
             input_mode: InputMode::Normal,
             previous_input_mode: None,
-            layout_mode,
-            theme_mode,
-            role_enabled,
-            role_highlight,
-            column_cache_enabled,
-            column_cache_ttl: if column_cache_ttl.is_zero() {
-                Duration::from_millis(400)
-            } else {
-                column_cache_ttl
-            },
-            column_width_cache: None,
-            config_path,
+            layout_mode: LayoutMode::from_str(&config.layout),
+            overhead_width: config.overhead_width.min(u16::MAX as u64) as u16,
+            terminal_width: u16::MAX,
+            theme_mode: ThemeMode::from_str(&config.theme),
+            role_enabled: config.role_enabled,
+            role_highlight: config.role_highlight,
+            column_cache_enabled: config.column_cache,
+            column_widths: ColumnWidths::default(),
+            config_path: config.config_path.clone(),
             config_items: Vec::new(),
             config_cursor: 0,
             config_edit: String::new(),
             config_editing: false,
             config_dirty: false,
             config_status: None,
+            pending_feed_urls: None,
+            replay: None,
             help_scroll: 0,
             watchlist_cursor: 0,
-            trail_len: trail_len.max(1),
+            trail_len: (config.trail_len as usize).max(1),
+            trail_fade_secs: config.trail_fade_secs,
+            trail_altitude_colors: config.trail_altitude_colors,
             site,
+            sites,
+            site_index,
             radar_center,
-            demo_mode,
-            radar_range_nm: radar_range_nm.max(1.0),
-            radar_aspect: radar_aspect.max(0.2),
-            radar_renderer,
-            radar_labels,
-            radar_blip,
-            columns: {
-                let mut cols = default_columns();
-                if let Some(flag_col) = cols.iter_mut().find(|c| c.id == ColumnId::Flag) {
-                    flag_col.visible = flags_enabled;
-                }
-                if let Some(watch_col) = cols.iter_mut().find(|c| c.id == ColumnId::Watch) {
-                    watch_col.visible = watchlist_enabled;
-                }
-                cols
-            },
+            demo_mode: config.demo_mode,
+            radar_range_nm: config.radar_range_nm.max(1.0),
+            radar_aspect: config.radar_aspect.max(0.2),
+            radar_renderer: RadarRenderer::from_str(&config.radar_renderer),
+            radar_labels: config.radar_labels,
+            radar_blip: RadarBlip::from_str(&config.radar_blip),
+            columns: build_columns(
+                config.flags_enabled,
+                watchlist_enabled,
+                &parse_column_layout(&config.table_columns).unwrap_or_default(),
+            ),
+            columns_opened_with: String::new(),
             column_cursor: 0,
-            smooth_mode,
-            smooth_merge,
+            smooth_mode: config.smooth_mode,
+            smooth_merge: config.smooth_merge,
             ui_interval,
             last_swap: None,
             selection_key: None,
-            route_enabled,
-            route_tar1090,
-            route_ttl,
-            route_refresh,
-            route_batch: route_batch.max(1),
-            altitude_trend_arrows,
-            track_arrows,
-            stats_metrics: [stats_metric_1, stats_metric_2, stats_metric_3],
-            flags_enabled,
-            flag_style,
+            route_enabled: config.route_enabled,
+            route_tar1090: config.route_mode.eq_ignore_ascii_case("tar1090"),
+            route_ttl: Duration::from_secs(config.route_ttl_secs),
+            route_refresh: Duration::from_secs(config.route_refresh_secs),
+            route_batch: (config.route_batch as usize).max(1),
+            altitude_trend_arrows: config.altitude_trend_arrows,
+            track_arrows: config.track_arrows,
+            stats_metrics: [
+                config.stats_metric_1.clone(),
+                config.stats_metric_2.clone(),
+                config.stats_metric_3.clone(),
+            ],
+            perf_panels: [
+                config.perf_panel_1.clone(),
+                config.perf_panel_2.clone(),
+                config.perf_panel_3.clone(),
+                config.perf_panel_4.clone(),
+                config.perf_panel_5.clone(),
+                config.perf_panel_6.clone(),
+            ],
+            flags_enabled: config.flags_enabled,
+            flag_style: FlagStyle::from_str(&config.flag_style),
             route_last_poll: None,
             route_cache: HashMap::new(),
             route_last_request: HashMap::new(),
+            route_hits: 0,
+            route_misses: 0,
             route_backoff_until: None,
             route_backoff_attempts: 0,
             msg_rate: None,
             msg_rate_display: None,
             msg_rate_ema: None,
             msg_rate_last_display: None,
-            msg_rate_window: if rate_window.is_zero() {
+            msg_rate_window: if config.rate_window_ms == 0 {
                 Duration::from_millis(300)
             } else {
-                rate_window
+                Duration::from_millis(config.rate_window_ms)
             },
-            msg_rate_min_secs: rate_min_secs.max(0.05),
+            msg_rate_min_secs: config.rate_min_secs.max(0.05),
             msg_samples: VecDeque::new(),
             aircraft_rates: HashMap::new(),
             avg_aircraft_rate: None,
             total_aircraft_rate: None,
             total_aircraft_rate_ema: None,
             total_aircraft_rate_time: None,
-            notify_radius_mi: notify_radius_mi.max(0.1),
-            overpass_mi: overpass_mi.max(0.05),
-            notify_cooldown: if notify_cooldown.is_zero() {
+            notify_radius_mi: config.notify_radius_mi.max(0.1),
+            overpass_mi: config.overpass_mi.max(0.05),
+            notify_cooldown: if config.notify_cooldown_secs == 0 {
                 Duration::from_secs(120)
             } else {
-                notify_cooldown
+                Duration::from_secs(config.notify_cooldown_secs)
             },
             notified_recent: HashMap::new(),
             watch_notified_recent: HashMap::new(),
@@ -631,47 +1467,152 @@ impl App {
             last_metrics: HashMap::new(),
             trend_cache: HashMap::new(),
             trail_points: HashMap::new(),
+            pinned_trails: HashSet::new(),
+            history_rx: None,
+            trail_jump_strikes: HashMap::new(),
+            signal_history: HashMap::new(),
+            metric_history: HashMap::new(),
+            aircraft_keys: KeyInterner::default(),
+            trail_jumps_rejected: 0,
+            parse_warnings: 0,
+            feed_cache: FeedCache::default(),
+            geometry: Vec::new(),
+            data_version: 0,
+            swapped_version: 0,
             perf_samples: VecDeque::new(),
             perf_max_samples,
+            perf_points: VecDeque::new(),
+            perf_window: PerfWindow::from_str(&config.perf_window),
+            receiver_stats: VecDeque::new(),
             last_export: None,
             route_error: None,
             lookup_input: String::new(),
             lookup_status: None,
             lookup_results: None,
             lookup_busy: false,
+            profile: config.profile.clone(),
+            export_dir: PathBuf::from(&config.export_dir),
+            keymap,
+            theme_overrides,
+            ground_filter: GroundFilter::from_str(&config.ground_filter),
+            altitude_source: AltitudeSource::from_str(&config.altitude_source),
+            low_quality_positions: PositionQuality::from_str(&config.low_quality_positions),
+            position_stale_secs: config.position_stale_secs as f64,
+            cpa_notify_mi: config.cpa_notify_mi.max(0.0),
+            cpa_notify_mins: config.cpa_notify_mins.max(1.0),
+            radar_ghosts: config.radar_ghosts,
+            reduced_motion: config.reduced_motion,
+            max_tracked_aircraft: config.max_tracked_aircraft as usize,
+            route_cache_size: config.route_cache_size as usize,
+            profiler: Profiler::default(),
+            usage: UsageSampler::default(),
+            show_profiler: false,
+            row_buffer: RowBuffer::default(),
+            traffic_path: config::data_file_path(&config.traffic_history_file),
+            traffic,
+            sightings_path: config::data_file_path(&config.sightings_file),
+            sightings,
+            sightings_tx: None,
+            sightings_flushed: None,
+            selected_sighting: None,
+            history_cursor: 0,
+            history_total: 0,
+            history_offset: 0,
+            history_rows: Vec::new(),
+            session: SessionLog::default(),
+            events: EventLog::default(),
+            events_cursor: 0,
+            notifications_cursor: 0,
+            export_cursor: 0,
+            detail_tab: DetailTab::Overview,
+            stats_metric: TrafficMetric::Aircraft,
+            desktop_alerts: AlertKinds::parse(&config.notify_desktop).unwrap_or_else(|err| {
+                warn!("notify_desktop ignored: {err}");
+                AlertKinds::default()
+            }),
+            quiet_hours: QuietHours::parse(&config.quiet_hours).unwrap_or_else(|err| {
+                warn!("quiet_hours ignored: {err}");
+                QuietHours::default()
+            }),
+            dnd_override: None,
+            dnd_scheduled: false,
+            desktop_tx: None,
+            hook_tx: None,
+            zones_path: config::data_file_path(&config.zones_file),
+            zones,
+            zone_tracker: ZoneTracker::default(),
+            zones_cursor: 0,
+            airports,
+            radar_airports: RadarAirports::from_str(&config.radar_airports),
+            route_labels: RouteLabels::from_str(&config.route_labels),
+            weather_station,
+            weather: None,
+            weather_error: None,
+            photo_cache_path: None,
+            photos: None,
+            photo_pending: HashSet::new(),
+            photo_error: None,
+            color_by: ColorBy::from_str(&config.color_by),
+            color_alt_column: config.color_alt_column,
+            predict_positions: config.predict_positions,
+            data_shown_at: None,
+            radar_vectors: config.radar_vectors,
+            radar_vector_secs: config.radar_vector_secs,
+            radar_compass: config.radar_compass,
+            radar_range_rose: config.radar_range_rose,
+            radar_follow: false,
+            geography,
+            aircraft_db: None,
+            role_rules: RoleRules::default(),
+            roles_path: None,
+            military_ranges_path: None,
+            config: config.clone(),
+        }
+    }
+
+    pub fn apply_update(&mut self, mut data: ApiResponse) {
+        self.feed_cache.add(data.cache);
+        if data.not_modified {
+            // The feed is unchanged; the current snapshot still stands.
+            debug!("apply_update not modified");
+            self.last_error = None;
+            return;
         }
-    }
-
-    pub fn apply_update(&mut self, data: ApiResponse) {
         debug!(
             "apply_update aircraft={} messages={:?}",
             data.aircraft.len(),
             data.messages
         );
-        let now_time = data
-            .now
-            .and_then(|n| {
-                let mut value = n;
-                if value > 4_000_000_000 {
-                    value /= 1000;
-                }
-                if value > 0 {
-                    SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(value as u64))
-                } else {
-                    None
-                }
-            })
-            .unwrap_or_else(SystemTime::now);
+        let now_time = snapshot_time(data.now).unwrap_or_else(SystemTime::now);
+        self.poll_history_backfill();
+        self.parse_warnings += data.parse_warnings;
+        self.fill_from_aircraft_db(&mut data);
+        self.aircraft_keys.update(&data.aircraft);
         self.update_rate(&data, now_time);
         self.update_aircraft_rates(&data, now_time);
+        self.update_signal_history(&data, now_time);
         self.update_performance_samples(&data, now_time);
         self.update_seen_times(&data, now_time);
-        self.update_trends(&data);
+        self.update_trends(&data, now_time);
         self.update_trails(&data, now_time);
+        self.update_dnd(SystemTime::now());
         self.update_notifications(&data, now_time);
+        self.update_cpa_notifications(&data, now_time);
         self.update_watchlist_notifications(&data, now_time);
-
-        self.raw_data = data;
+        self.update_emergency_notifications(&data, now_time);
+        self.update_zone_notifications(&data, now_time);
+        self.update_events(&data, now_time);
+        self.enforce_history_cap();
+        self.update_route_cache(&data);
+        let now_secs = now_time
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        let msg_rate = self.msg_rate_display();
+        self.traffic
+            .record(now_secs, &data.aircraft, data.messages, msg_rate);
+
+        self.pending = Some(data);
+        self.data_version = self.data_version.wrapping_add(1);
         if !self.smooth_mode {
             self.swap_snapshot();
         }
@@ -679,6 +1620,25 @@ impl App {
         self.last_error = None;
     }
 
+    /// Only touches the database (starting its load) once an aircraft
+    /// arrives without registration, type or operator.
+    fn fill_from_aircraft_db(&self, data: &mut ApiResponse) {
+        let Some(lazy) = &self.aircraft_db else {
+            return;
+        };
+        let mut missing = data
+            .aircraft
+            .iter_mut()
+            .filter(|ac| aircraft_db::needs_fill(ac))
+            .peekable();
+        if missing.peek().is_none() {
+            return;
+        }
+        if let Some(db) = lazy.get() {
+            missing.for_each(|ac| db.fill(ac));
+        }
+    }
+
     pub fn apply_error(&mut self, msg: String) {
         warn!("apply_error: {msg}");
         self.last_error = Some(msg);
@@ -688,36 +1648,95 @@ impl App {
         self.tick = self.tick.wrapping_add(1);
     }
 
-    pub fn maybe_swap_snapshot(&mut self, now: SystemTime) {
-        if !self.smooth_mode {
-            return;
+    pub fn maybe_swap_snapshot(&mut self, now: SystemTime) -> bool {
+        if !self.smooth_mode || self.swapped_version == self.data_version {
+            return false;
         }
-        if self.ui_interval.as_secs() == 0 && self.ui_interval.subsec_nanos() == 0 {
+        let due = self.ui_interval.is_zero()
+            || self
+                .last_swap
+                .map(|last| {
+                    now.duration_since(last)
+                        .map(|d| d >= self.ui_interval)
+                        .unwrap_or(false)
+                })
+                .unwrap_or(true);
+        if due {
             self.swap_snapshot();
             self.last_swap = Some(now);
-            return;
         }
-        match self.last_swap {
-            Some(last) => {
-                if now
-                    .duration_since(last)
-                    .map(|d| d >= self.ui_interval)
-                    .unwrap_or(false)
-                {
-                    self.swap_snapshot();
-                    self.last_swap = Some(now);
-                }
-            }
-            None => {
-                self.swap_snapshot();
-                self.last_swap = Some(now);
-            }
+        due
+    }
+
+    pub fn open_sort(&mut self) {
+        self.sort_cursor = self
+            .sort
+            .columns()
+            .first()
+            .and_then(|column| SortKey::ALL.iter().position(|key| *key == column.key))
+            .unwrap_or(0);
+        self.sort_opened_with = self.sort.clone();
+        self.input_mode = InputMode::Sort;
+        debug!("open sort");
+    }
+
+    pub fn close_sort(&mut self) {
+        self.input_mode = InputMode::Normal;
+        if self.sort != self.sort_opened_with {
+            self.save_sort_order();
         }
+        debug!("close sort");
+    }
+
+    pub fn next_sort_key(&mut self) {
+        self.sort_cursor = (self.sort_cursor + 1) % SortKey::ALL.len();
+    }
+
+    pub fn previous_sort_key(&mut self) {
+        let len = SortKey::ALL.len();
+        self.sort_cursor = (self.sort_cursor + len - 1) % len;
+    }
+
+    fn sort_key_at_cursor(&self) -> SortKey {
+        SortKey::ALL[self.sort_cursor.min(SortKey::ALL.len() - 1)]
+    }
+
+    pub fn set_primary_sort(&mut self) {
+        self.sort.set_primary(self.sort_key_at_cursor());
+        debug!("sort -> {}", self.sort);
+    }
+
+    pub fn toggle_secondary_sort(&mut self) {
+        self.sort.toggle_secondary(self.sort_key_at_cursor());
+        debug!("sort -> {}", self.sort);
+    }
+
+    pub fn reverse_sort_key(&mut self) {
+        self.sort.reverse(self.sort_key_at_cursor());
+        debug!("sort -> {}", self.sort);
+    }
+
+    fn save_sort_order(&mut self) {
+        let order = self.sort.to_string();
+        self.save_config_setting("sort_order", &order, "SORT");
     }
 
-    pub fn toggle_sort(&mut self) {
-        self.sort = self.sort.next();
-        debug!("sort mode -> {}", self.sort.label());
+    fn save_config_setting(&mut self, key: &str, value: &str, tag: &str) {
+        let written = read_config_doc(&self.config_path).and_then(|mut doc| {
+            set_doc_value(&mut doc, key, Some(toml_edit::value(value)));
+            write_config_file(&self.config_path, &doc.to_string()).map_err(|err| err.to_string())
+        });
+        let message = match written {
+            Ok(()) => {
+                info!("{key} saved {}", self.config_path.display());
+                format!("{tag} saved to {}", self.config_path.display())
+            }
+            Err(err) => {
+                warn!("{key} save failed: {err}");
+                format!("{tag} save failed: {err}")
+            }
+        };
+        self.notify(message, SystemTime::now());
     }
 
     pub fn toggle_theme(&mut self) {
@@ -725,11 +1744,113 @@ impl App {
         debug!("theme -> {}", self.theme_mode.label());
     }
 
+    pub fn set_theme(&mut self, theme_mode: ThemeMode) {
+        self.theme_mode = theme_mode;
+        debug!("theme -> {}", self.theme_mode.label());
+    }
+
     pub fn toggle_layout(&mut self) {
-        self.layout_mode = self.layout_mode.toggle();
+        // While replaying, the layout key cycles back to the replay view.
+        self.layout_mode = match self.layout_mode {
+            LayoutMode::Compact if self.replay.is_some() => LayoutMode::Replay,
+            mode => mode.toggle(),
+        };
         debug!("layout -> {}", self.layout_mode.label());
     }
 
+    pub fn set_desktop_notifier(&mut self, tx: UnboundedSender<AlertEvent>) {
+        self.desktop_tx = Some(tx);
+    }
+
+    pub fn set_sightings_writer(
+        &mut self,
+        tx: UnboundedSender<SightingBatch>,
+        flushed: watch::Receiver<u64>,
+    ) {
+        self.sightings_tx = Some(tx);
+        self.sightings_flushed = Some(flushed);
+    }
+
+    /// Re-reads the selected aircraft's sighting when the selection moved or
+    /// the writer stored a new snapshot; returns whether the cache changed.
+    pub fn refresh_selected_sighting(&mut self, indices: &[usize]) -> bool {
+        let hex = self
+            .table_state
+            .selected()
+            .and_then(|row| indices.get(row))
+            .and_then(|idx| self.data.aircraft.get(*idx))
+            .and_then(|ac| ac.hex.as_deref())
+            .map(normalize_hex)
+            .filter(|hex| !hex.is_empty());
+        let flushed = self.sightings_flushed.as_mut().is_some_and(|rx| {
+            let changed = rx.has_changed().unwrap_or(false);
+            rx.borrow_and_update();
+            changed
+        });
+        let cached = self.selected_sighting.as_ref().map(|(hex, _)| hex);
+        if !flushed && cached == hex.as_ref() {
+            return false;
+        }
+        let previous = self.selected_sighting.take();
+        self.selected_sighting = hex.map(|hex| {
+            let sighting = self.sightings.get(&hex);
+            (hex, sighting)
+        });
+        previous != self.selected_sighting
+    }
+
+    pub(crate) fn selected_sighting(&self, hex: &str) -> Option<&Sighting> {
+        let (cached, sighting) = self.selected_sighting.as_ref()?;
+        if *cached != normalize_hex(hex) {
+            return None;
+        }
+        sighting.as_ref()
+    }
+
+    pub fn set_history_backfill(&mut self, rx: oneshot::Receiver<Vec<ApiResponse>>) {
+        self.history_rx = Some(rx);
+    }
+
+    pub fn set_alert_hooks(&mut self, tx: UnboundedSender<AlertEvent>) {
+        self.hook_tx = Some(tx);
+    }
+
+    pub fn set_aircraft_db(&mut self, db: LazyAircraftDb) {
+        self.aircraft_db = Some(db);
+    }
+
+    pub fn set_role_rules(
+        &mut self,
+        rules: RoleRules,
+        roles_path: Option<PathBuf>,
+        military_ranges_path: Option<PathBuf>,
+    ) {
+        self.role_rules = rules;
+        self.roles_path = roles_path;
+        self.military_ranges_path = military_ranges_path;
+    }
+
+    pub fn set_photo_cache(&mut self, path: PathBuf, cache: PhotoCache) {
+        self.photo_cache_path = Some(path);
+        self.photos = Some(cache);
+    }
+
+    pub fn effective_layout(&self, width: u16) -> LayoutMode {
+        if width < self.overhead_width {
+            LayoutMode::Overhead
+        } else {
+            self.layout_mode
+        }
+    }
+
+    pub fn current_layout(&self) -> LayoutMode {
+        self.effective_layout(self.terminal_width)
+    }
+
+    pub fn set_terminal_width(&mut self, width: u16) {
+        self.terminal_width = width;
+    }
+
     pub fn set_layout(&mut self, layout_mode: LayoutMode) {
         if self.layout_mode != layout_mode {
             self.layout_mode = layout_mode;
@@ -737,6 +1858,14 @@ impl App {
         }
     }
 
+    pub fn toggle_profiler(&mut self) {
+        self.show_profiler = !self.show_profiler;
+        debug!(
+            "profiler overlay -> {}",
+            if self.show_profiler { "on" } else { "off" }
+        );
+    }
+
     pub fn toggle_radar_labels(&mut self) {
         self.radar_labels = !self.radar_labels;
         debug!(
@@ -745,10 +1874,50 @@ impl App {
         );
     }
 
+    pub fn toggle_radar_vectors(&mut self) {
+        self.radar_vectors = !self.radar_vectors;
+        debug!(
+            "radar vectors -> {}",
+            if self.radar_vectors { "on" } else { "off" }
+        );
+    }
+
     pub fn radar_center(&self) -> Option<RadarCenter> {
         self.radar_center
     }
 
+    pub fn toggle_radar_follow(&mut self, indices: &[usize]) {
+        if !self.radar_follow && self.selected_position(indices).is_none() {
+            self.notify(
+                "FOLLOW no selected aircraft with a position".to_string(),
+                SystemTime::now(),
+            );
+            return;
+        }
+        self.radar_follow = !self.radar_follow;
+        debug!(
+            "radar follow -> {}",
+            if self.radar_follow { "on" } else { "off" }
+        );
+    }
+
+    pub fn radar_follow_center(&self, indices: &[usize]) -> Option<RadarCenter> {
+        if !self.radar_follow {
+            return None;
+        }
+        let (lat, lon) = self.selected_position(indices)?;
+        Some(RadarCenter { lat, lon })
+    }
+
+    fn selected_position(&self, indices: &[usize]) -> Option<(f64, f64)> {
+        let idx = self
+            .table_state
+            .selected()
+            .and_then(|row| indices.get(row))?;
+        let ac = self.data.aircraft.get(*idx)?;
+        self.predicted_position(ac).or(ac.lat.zip(ac.lon))
+    }
+
     pub fn zoom_radar(&mut self, factor: f64) -> Option<Vec<String>> {
         if factor <= 0.0 {
             return None;
@@ -757,7 +1926,322 @@ impl App {
         self.refresh_feed_urls()
     }
 
+    fn set_site(&mut self, lat: Option<f64>, lon: Option<f64>, alt_m: Option<f64>) {
+        self.site = match (lat, lon) {
+            (Some(lat), Some(lon)) => Some(SiteLocation {
+                lat,
+                lon,
+                alt_m: alt_m.unwrap_or(0.0),
+            }),
+            _ => None,
+        };
+        self.radar_center = self.site.map(|site| RadarCenter {
+            lat: site.lat,
+            lon: site.lon,
+        });
+        self.site_index = site_index_of(&self.sites, self.site);
+        self.refresh_geometry();
+    }
+
+    pub fn site_name(&self) -> Option<&str> {
+        if self.demo_mode {
+            return None;
+        }
+        self.site_index
+            .and_then(|index| self.sites.get(index))
+            .map(|site| site.name.as_str())
+    }
+
+    pub fn next_site(&mut self) -> Option<Vec<String>> {
+        if self.sites.is_empty() {
+            self.notify("SITE none configured".to_string(), SystemTime::now());
+            return None;
+        }
+        let next = self
+            .site_index
+            .map(|index| (index + 1) % self.sites.len())
+            .unwrap_or(0);
+        self.select_site(next)
+    }
+
+    pub fn select_site_named(&mut self, name: &str) -> Result<Option<Vec<String>>, String> {
+        match self
+            .sites
+            .iter()
+            .position(|site| site.name.eq_ignore_ascii_case(name.trim()))
+        {
+            Some(index) => Ok(self.select_site(index)),
+            None => {
+                let names: Vec<&str> = self.sites.iter().map(|site| site.name.as_str()).collect();
+                Err(format!(
+                    "unknown site {} (available: {})",
+                    name.trim(),
+                    names.join(", ")
+                ))
+            }
+        }
+    }
+
+    fn select_site(&mut self, index: usize) -> Option<Vec<String>> {
+        let site = self.sites.get(index)?.clone();
+        self.set_site(
+            Some(site.location.lat),
+            Some(site.location.lon),
+            Some(site.location.alt_m),
+        );
+        self.site_index = Some(index);
+        info!("site {}", site.name);
+        self.notify(format!("SITE {}", site.name), SystemTime::now());
+        self.refresh_feed_urls()
+    }
+
+    pub fn apply_config_reload(
+        &mut self,
+        next: Config,
+        restart_keys: &[&str],
+    ) -> Option<Vec<String>> {
+        let feed_changed = self.apply_config(next);
+        let message = if restart_keys.is_empty() {
+            "CONFIG reloaded".to_string()
+        } else {
+            format!(
+                "CONFIG reloaded; restart to apply {}",
+                restart_keys.join(", ")
+            )
+        };
+        info!("{message}");
+        self.notify(message, SystemTime::now());
+
+        if feed_changed {
+            self.refresh_feed_urls()
+        } else {
+            None
+        }
+    }
+
+    /// Applies the settings that differ from the last applied config to the running
+    /// app, for both editor saves and file reloads. Returns whether the feed URLs
+    /// need refreshing.
+    fn apply_config(&mut self, next: Config) -> bool {
+        let previous = std::mem::replace(&mut self.config, next.clone());
+        let (previous, next) = (&previous, &next);
+        if previous.stale_secs != next.stale_secs {
+            self.stale_secs = next.stale_secs as f64;
+        }
+        if previous.position_stale_secs != next.position_stale_secs {
+            self.position_stale_secs = next.position_stale_secs as f64;
+        }
+        if previous.hide_stale != next.hide_stale {
+            self.hide_stale = next.hide_stale;
+        }
+        if previous.table_columns != next.table_columns {
+            if let Ok(layout) = parse_column_layout(&next.table_columns) {
+                self.columns = build_columns(self.flags_enabled, self.watchlist_enabled, &layout);
+                self.column_cursor = 0;
+            }
+        }
+        if previous.sort_order != next.sort_order {
+            if let Ok(sort) = SortSpec::parse(&next.sort_order) {
+                self.sort = sort;
+            }
+        }
+        if previous.ground_filter != next.ground_filter {
+            self.ground_filter = GroundFilter::from_str(&next.ground_filter);
+        }
+        if previous.altitude_source != next.altitude_source {
+            self.altitude_source = AltitudeSource::from_str(&next.altitude_source);
+        }
+        if previous.low_nic != next.low_nic {
+            self.low_nic = next.low_nic;
+        }
+        if previous.low_nac != next.low_nac {
+            self.low_nac = next.low_nac;
+        }
+        if previous.low_quality_positions != next.low_quality_positions {
+            self.low_quality_positions = PositionQuality::from_str(&next.low_quality_positions);
+        }
+        if previous.trail_len != next.trail_len {
+            self.trail_len = (next.trail_len as usize).max(1);
+        }
+        if previous.trail_fade_secs != next.trail_fade_secs {
+            self.trail_fade_secs = next.trail_fade_secs;
+        }
+        if previous.trail_altitude_colors != next.trail_altitude_colors {
+            self.trail_altitude_colors = next.trail_altitude_colors;
+        }
+        if previous.overhead_width != next.overhead_width {
+            self.overhead_width = next.overhead_width.min(u16::MAX as u64) as u16;
+        }
+        if previous.layout != next.layout {
+            self.set_layout(LayoutMode::from_str(&next.layout));
+        }
+        if previous.theme != next.theme {
+            self.theme_mode = ThemeMode::from_str(&next.theme);
+        }
+        if previous.radar_aspect != next.radar_aspect {
+            self.radar_aspect = next.radar_aspect.max(0.2);
+        }
+        if previous.radar_renderer != next.radar_renderer {
+            self.radar_renderer = RadarRenderer::from_str(&next.radar_renderer);
+        }
+        if previous.radar_labels != next.radar_labels {
+            self.radar_labels = next.radar_labels;
+        }
+        if previous.radar_ghosts != next.radar_ghosts {
+            self.radar_ghosts = next.radar_ghosts;
+        }
+        if previous.radar_airports != next.radar_airports {
+            self.radar_airports = RadarAirports::from_str(&next.radar_airports);
+        }
+        if previous.reduced_motion != next.reduced_motion {
+            self.reduced_motion = next.reduced_motion;
+        }
+        if previous.max_tracked_aircraft != next.max_tracked_aircraft {
+            self.max_tracked_aircraft = next.max_tracked_aircraft as usize;
+            self.enforce_history_cap();
+        }
+        if previous.route_cache_size != next.route_cache_size {
+            self.route_cache_size = next.route_cache_size as usize;
+            self.enforce_route_cache_cap();
+        }
+        if previous.predict_positions != next.predict_positions {
+            self.predict_positions = next.predict_positions;
+        }
+        if previous.radar_vectors != next.radar_vectors {
+            self.radar_vectors = next.radar_vectors;
+        }
+        if previous.radar_vector_secs != next.radar_vector_secs {
+            self.radar_vector_secs = next.radar_vector_secs;
+        }
+        if previous.radar_compass != next.radar_compass {
+            self.radar_compass = next.radar_compass;
+        }
+        if previous.perf_window != next.perf_window {
+            self.perf_window = PerfWindow::from_str(&next.perf_window);
+        }
+        if previous.radar_range_rose != next.radar_range_rose {
+            self.radar_range_rose = next.radar_range_rose;
+        }
+        if previous.radar_blip != next.radar_blip {
+            self.radar_blip = RadarBlip::from_str(&next.radar_blip);
+        }
+        if previous.altitude_trend_arrows != next.altitude_trend_arrows {
+            self.altitude_trend_arrows = next.altitude_trend_arrows;
+        }
+        if previous.track_arrows != next.track_arrows {
+            self.track_arrows = next.track_arrows;
+        }
+        if previous.flags_enabled != next.flags_enabled {
+            self.flags_enabled = next.flags_enabled;
+        }
+        if previous.flag_style != next.flag_style {
+            self.flag_style = FlagStyle::from_str(&next.flag_style);
+        }
+        if previous.color_by != next.color_by {
+            self.color_by = ColorBy::from_str(&next.color_by);
+        }
+        if previous.color_alt_column != next.color_alt_column {
+            self.color_alt_column = next.color_alt_column;
+        }
+        if previous.route_labels != next.route_labels {
+            self.route_labels = RouteLabels::from_str(&next.route_labels);
+        }
+        if previous.role_enabled != next.role_enabled {
+            self.role_enabled = next.role_enabled;
+        }
+        if previous.role_highlight != next.role_highlight {
+            self.role_highlight = next.role_highlight;
+        }
+        let metrics = [
+            (&previous.stats_metric_1, &next.stats_metric_1),
+            (&previous.stats_metric_2, &next.stats_metric_2),
+            (&previous.stats_metric_3, &next.stats_metric_3),
+        ];
+        for (slot, (old, new)) in metrics.into_iter().enumerate() {
+            if old != new {
+                self.stats_metrics[slot] = new.clone();
+            }
+        }
+        let panels = [
+            (&previous.perf_panel_1, &next.perf_panel_1),
+            (&previous.perf_panel_2, &next.perf_panel_2),
+            (&previous.perf_panel_3, &next.perf_panel_3),
+            (&previous.perf_panel_4, &next.perf_panel_4),
+            (&previous.perf_panel_5, &next.perf_panel_5),
+            (&previous.perf_panel_6, &next.perf_panel_6),
+        ];
+        for (slot, (old, new)) in panels.into_iter().enumerate() {
+            if old != new {
+                self.perf_panels[slot] = new.clone();
+            }
+        }
+        if previous.notify_radius_mi != next.notify_radius_mi {
+            self.notify_radius_mi = next.notify_radius_mi;
+        }
+        if previous.overpass_mi != next.overpass_mi {
+            self.overpass_mi = next.overpass_mi;
+        }
+        if previous.cpa_notify_mi != next.cpa_notify_mi {
+            self.cpa_notify_mi = next.cpa_notify_mi;
+        }
+        if previous.cpa_notify_mins != next.cpa_notify_mins {
+            self.cpa_notify_mins = next.cpa_notify_mins;
+        }
+        if previous.notify_cooldown_secs != next.notify_cooldown_secs {
+            self.notify_cooldown = Duration::from_secs(next.notify_cooldown_secs);
+        }
+        if previous.notify_desktop != next.notify_desktop {
+            match AlertKinds::parse(&next.notify_desktop) {
+                Ok(kinds) => self.desktop_alerts = kinds,
+                Err(err) => warn!("keeping previous notify_desktop: {err}"),
+            }
+        }
+        if previous.quiet_hours != next.quiet_hours {
+            match QuietHours::parse(&next.quiet_hours) {
+                Ok(quiet_hours) => self.quiet_hours = quiet_hours,
+                Err(err) => warn!("keeping previous quiet_hours: {err}"),
+            }
+        }
+        if previous.keys != next.keys {
+            match KeyMap::from_overrides(&next.keys) {
+                Ok(keymap) => self.keymap = keymap,
+                Err(errors) => warn!("keeping previous key bindings: {}", errors.join("; ")),
+            }
+        }
+        if previous.theme_overrides != next.theme_overrides {
+            let (overrides, errors) = ThemeOverrides::parse(&next.theme_overrides);
+            for err in errors {
+                warn!("{err}");
+            }
+            self.theme_overrides = overrides;
+        }
+
+        let mut feed_changed = false;
+        if previous.sites != next.sites {
+            self.sites = NamedSite::from_config(&next.sites);
+            self.site_index = site_index_of(&self.sites, self.site);
+        }
+        if previous.site_lat != next.site_lat
+            || previous.site_lon != next.site_lon
+            || previous.site_alt_m != next.site_alt_m
+        {
+            self.set_site(next.site_lat, next.site_lon, next.site_alt_m);
+            feed_changed = true;
+        }
+        if previous.radar_range_nm != next.radar_range_nm {
+            self.radar_range_nm = next.radar_range_nm.max(1.0);
+            feed_changed = true;
+        }
+        feed_changed
+    }
+
+    pub fn apply_config_reload_error(&mut self, err: String) {
+        self.notify(format!("CONFIG ERR {err}"), SystemTime::now());
+    }
+
     pub fn pan_radar(&mut self, direction: RadarDirection) -> Option<Vec<String>> {
+        // Panning takes the view back from the followed aircraft.
+        self.radar_follow = false;
         let center = self.ensure_radar_center()?;
         let distance_nm = self.radar_range_nm.max(1.0) * 0.25;
         self.radar_center = Some(offset_center(center, direction, distance_nm));
@@ -797,12 +2281,17 @@ impl App {
     }
 
     pub fn open_columns(&mut self) {
+        self.columns_opened_with = column_layout_text(&self.columns);
         self.input_mode = InputMode::Columns;
         debug!("open columns");
     }
 
     pub fn close_columns(&mut self) {
         self.input_mode = InputMode::Normal;
+        let layout = column_layout_text(&self.columns);
+        if layout != self.columns_opened_with {
+            self.save_config_setting("table_columns", &layout, "COLUMNS");
+        }
         debug!("close columns");
     }
 
@@ -851,26 +2340,17 @@ impl App {
         let now = SystemTime::now();
         let Some(path) = self.watchlist_path.as_ref() else {
             warn!("watchlist save skipped: no file path");
-            self.notifications.push(Notification {
-                message: "WATCHLIST no file path".to_string(),
-                at: now,
-            });
+            self.notify("WATCHLIST no file path".to_string(), now);
             return;
         };
         match storage::save_watchlist(path, &self.watchlist) {
             Ok(_) => {
                 info!("watchlist saved {}", path.display());
-                self.notifications.push(Notification {
-                    message: "WATCHLIST saved".to_string(),
-                    at: now,
-                });
+                self.notify("WATCHLIST saved".to_string(), now);
             }
             Err(err) => {
                 warn!("watchlist save failed: {err}");
-                self.notifications.push(Notification {
-                    message: format!("WATCHLIST ERR {err}"),
-                    at: now,
-                });
+                self.notify(format!("WATCHLIST ERR {err}"), now);
             }
         }
     }
@@ -882,10 +2362,10 @@ impl App {
         if let Some(path) = self.watchlist_path.as_ref() {
             if let Ok(created) = storage::ensure_watchlist_file(path) {
                 if created {
-                    self.notifications.push(Notification {
-                        message: format!("WATCHLIST template created {}", path.display()),
-                        at: SystemTime::now(),
-                    });
+                    self.notify(
+                        format!("WATCHLIST template created {}", path.display()),
+                        SystemTime::now(),
+                    );
                 }
             }
         }
@@ -907,10 +2387,7 @@ impl App {
             .iter()
             .any(|existing| watchlist_entry_key(existing) == entry_key)
         {
-            self.notifications.push(Notification {
-                message: "WATCHLIST already exists".to_string(),
-                at: SystemTime::now(),
-            });
+            self.notify("WATCHLIST already exists".to_string(), SystemTime::now());
             return false;
         }
         self.watchlist.push(entry.clone());
@@ -922,10 +2399,7 @@ impl App {
             .as_deref()
             .filter(|s| !s.trim().is_empty())
             .unwrap_or(entry_id.as_str());
-        self.notifications.push(Notification {
-            message: format!("WATCHLIST added {label}"),
-            at: SystemTime::now(),
-        });
+        self.notify(format!("WATCHLIST added {label}"), SystemTime::now());
         true
     }
 
@@ -1012,29 +2486,449 @@ impl App {
         self.watchlist_cursor = next.min(self.watchlist.len().saturating_sub(1));
     }
 
-    pub fn open_config(&mut self) {
-        let config_exists = self.config_path.exists();
-        self.config_items = load_config_items(&self.config_path);
-        self.config_cursor = 0;
-        self.config_editing = false;
-        self.config_edit.clear();
-        self.config_dirty = !config_exists;
-        self.config_status = None;
-        self.input_mode = InputMode::Config;
-        debug!("open config items={}", self.config_items.len());
+    pub fn open_history(&mut self) {
+        self.history_cursor = 0;
+        self.load_history_page();
+        self.input_mode = InputMode::History;
+        debug!("open history");
     }
 
-    pub fn close_config(&mut self) {
-        if self.config_dirty && !self.save_config() {
-            return;
-        }
+    pub fn close_history(&mut self) {
         self.input_mode = InputMode::Normal;
-        self.config_editing = false;
-        self.config_edit.clear();
-        debug!("close config");
+        debug!("close history");
     }
 
-    pub fn next_config_item(&mut self) {
+    pub fn open_events(&mut self) {
+        self.events_cursor = 0;
+        self.input_mode = InputMode::Events;
+        debug!("open events");
+    }
+
+    pub fn close_events(&mut self) {
+        self.input_mode = InputMode::Normal;
+        debug!("close events");
+    }
+
+    pub fn next_event(&mut self) {
+        let len = self.events.len();
+        if len == 0 {
+            return;
+        }
+        self.events_cursor = (self.events_cursor + 1) % len;
+    }
+
+    pub fn previous_event(&mut self) {
+        let len = self.events.len();
+        if len == 0 {
+            return;
+        }
+        if self.events_cursor == 0 {
+            self.events_cursor = len - 1;
+        } else {
+            self.events_cursor -= 1;
+        }
+    }
+
+    pub fn events_page_up(&mut self, window: usize) {
+        self.events_cursor = self.events_cursor.saturating_sub(window);
+    }
+
+    pub fn events_page_down(&mut self, window: usize) {
+        let next = self.events_cursor.saturating_add(window);
+        self.events_cursor = next.min(self.events.len().saturating_sub(1));
+    }
+
+    pub fn open_notifications(&mut self) {
+        self.notifications_cursor = 0;
+        self.input_mode = InputMode::Notifications;
+        debug!("open notifications");
+    }
+
+    pub fn close_notifications(&mut self) {
+        self.input_mode = InputMode::Normal;
+        debug!("close notifications");
+    }
+
+    pub fn next_notification(&mut self) {
+        let len = self.notifications.len();
+        if len == 0 {
+            return;
+        }
+        self.notifications_cursor = (self.notifications_cursor + 1) % len;
+    }
+
+    pub fn previous_notification(&mut self) {
+        let len = self.notifications.len();
+        if len == 0 {
+            return;
+        }
+        if self.notifications_cursor == 0 {
+            self.notifications_cursor = len - 1;
+        } else {
+            self.notifications_cursor -= 1;
+        }
+    }
+
+    pub fn notifications_page_up(&mut self, window: usize) {
+        self.notifications_cursor = self.notifications_cursor.saturating_sub(window);
+    }
+
+    pub fn notifications_page_down(&mut self, window: usize) {
+        let next = self.notifications_cursor.saturating_add(window);
+        self.notifications_cursor = next.min(self.notifications.len().saturating_sub(1));
+    }
+
+    pub fn acknowledge_notification(&mut self) {
+        let Some(idx) = self
+            .notifications
+            .len()
+            .checked_sub(self.notifications_cursor + 1)
+        else {
+            return;
+        };
+        self.notifications[idx].read = true;
+        self.notifications_page_down(1);
+    }
+
+    pub fn acknowledge_all_notifications(&mut self) {
+        for note in &mut self.notifications {
+            note.read = true;
+        }
+    }
+
+    pub fn clear_notifications(&mut self) {
+        self.notifications.clear();
+        self.notifications_cursor = 0;
+        debug!("clear notifications");
+    }
+
+    pub fn open_stats(&mut self) {
+        self.input_mode = InputMode::Stats;
+        debug!("open stats");
+    }
+
+    pub fn close_stats(&mut self) {
+        self.input_mode = InputMode::Normal;
+        debug!("close stats");
+    }
+
+    pub fn next_stats_metric(&mut self) {
+        let next = (self.stats_metric.index() + 1) % TrafficMetric::ALL.len();
+        self.stats_metric = TrafficMetric::ALL[next];
+    }
+
+    pub fn previous_stats_metric(&mut self) {
+        let len = TrafficMetric::ALL.len();
+        self.stats_metric = TrafficMetric::ALL[(self.stats_metric.index() + len - 1) % len];
+    }
+
+    pub fn next_history_item(&mut self) {
+        let len = self.history_total;
+        if len == 0 {
+            return;
+        }
+        self.history_cursor = (self.history_cursor + 1) % len;
+        self.history_cursor_moved();
+    }
+
+    pub fn previous_history_item(&mut self) {
+        let len = self.history_total;
+        if len == 0 {
+            return;
+        }
+        if self.history_cursor == 0 {
+            self.history_cursor = len - 1;
+        } else {
+            self.history_cursor -= 1;
+        }
+        self.history_cursor_moved();
+    }
+
+    pub fn history_page_up(&mut self, window: usize) {
+        self.history_cursor = self.history_cursor.saturating_sub(window);
+        self.history_cursor_moved();
+    }
+
+    pub fn history_page_down(&mut self, window: usize) {
+        let next = self.history_cursor.saturating_add(window);
+        self.history_cursor = next.min(self.history_total.saturating_sub(1));
+        self.history_cursor_moved();
+    }
+
+    pub(crate) fn history_row(&self, index: usize) -> Option<&Sighting> {
+        index
+            .checked_sub(self.history_offset)
+            .and_then(|i| self.history_rows.get(i))
+    }
+
+    fn load_history_page(&mut self) {
+        self.history_total = self.sightings.len();
+        self.history_cursor = self
+            .history_cursor
+            .min(self.history_total.saturating_sub(1));
+        self.history_offset = self.history_cursor.saturating_sub(HISTORY_PAGE / 2);
+        self.history_rows = self.sightings.page(self.history_offset, HISTORY_PAGE);
+    }
+
+    fn history_cursor_moved(&mut self) {
+        let margin = HISTORY_PAGE / 4;
+        let first = self.history_cursor.saturating_sub(margin);
+        let last = (self.history_cursor + margin).min(self.history_total);
+        if first < self.history_offset || last > self.history_offset + self.history_rows.len() {
+            self.load_history_page();
+        }
+    }
+
+    pub fn open_export(&mut self) {
+        self.export_cursor = 0;
+        self.input_mode = InputMode::Export;
+        debug!("open export");
+    }
+
+    pub fn close_export(&mut self) {
+        self.input_mode = InputMode::Normal;
+        debug!("close export");
+    }
+
+    pub fn next_export_kind(&mut self) {
+        self.export_cursor = (self.export_cursor + 1) % ExportKind::ALL.len();
+    }
+
+    pub fn previous_export_kind(&mut self) {
+        let len = ExportKind::ALL.len();
+        self.export_cursor = (self.export_cursor + len - 1) % len;
+    }
+
+    pub fn selected_export_kind(&self) -> ExportKind {
+        ExportKind::ALL[self.export_cursor.min(ExportKind::ALL.len() - 1)]
+    }
+
+    pub fn open_detail(&mut self, indices: &[usize]) {
+        let Some(row) = self
+            .table_state
+            .selected()
+            .filter(|row| *row < indices.len())
+        else {
+            return;
+        };
+        self.detail_tab = DetailTab::Overview;
+        self.input_mode = InputMode::Detail;
+        debug!("open detail row={row}");
+    }
+
+    pub fn close_detail(&mut self) {
+        self.input_mode = InputMode::Normal;
+        debug!("close detail");
+    }
+
+    pub fn next_detail_tab(&mut self) {
+        let next = (self.detail_tab.index() + 1) % DetailTab::ALL.len();
+        self.detail_tab = DetailTab::ALL[next];
+    }
+
+    pub fn previous_detail_tab(&mut self) {
+        let len = DetailTab::ALL.len();
+        self.detail_tab = DetailTab::ALL[(self.detail_tab.index() + len - 1) % len];
+    }
+
+    pub fn open_zones(&mut self) {
+        self.zones_cursor = 0;
+        self.input_mode = InputMode::Zones;
+        debug!("open zones");
+    }
+
+    pub fn close_zones(&mut self) {
+        self.input_mode = InputMode::Normal;
+        debug!("close zones");
+    }
+
+    pub fn next_zone_item(&mut self) {
+        if self.zones.is_empty() {
+            return;
+        }
+        self.zones_cursor = (self.zones_cursor + 1) % self.zones.len();
+    }
+
+    pub fn previous_zone_item(&mut self) {
+        if self.zones.is_empty() {
+            return;
+        }
+        if self.zones_cursor == 0 {
+            self.zones_cursor = self.zones.len() - 1;
+        } else {
+            self.zones_cursor -= 1;
+        }
+    }
+
+    pub fn zones_page_up(&mut self, window: usize) {
+        self.zones_cursor = self.zones_cursor.saturating_sub(window);
+    }
+
+    pub fn zones_page_down(&mut self, window: usize) {
+        let next = self.zones_cursor.saturating_add(window);
+        self.zones_cursor = next.min(self.zones.len().saturating_sub(1));
+    }
+
+    pub fn zone_occupancy(&self, idx: usize) -> usize {
+        self.zone_tracker.count_inside(idx)
+    }
+
+    pub fn add_zone_from_selected(&mut self, indices: &[usize]) -> bool {
+        let selected = self
+            .table_state
+            .selected()
+            .and_then(|row| indices.get(row))
+            .and_then(|idx| self.data.aircraft.get(*idx))
+            .and_then(|ac| ac.lat.zip(ac.lon));
+        let center = selected
+            .or_else(|| self.radar_center().map(|center| (center.lat, center.lon)))
+            .or_else(|| self.site().map(|site| (site.lat, site.lon)));
+        let Some((lat, lon)) = center else {
+            self.notify("ZONES no position to add".to_string(), SystemTime::now());
+            return false;
+        };
+        let name = (1..)
+            .map(|n| format!("Zone {n}"))
+            .find(|name| self.zones.iter().all(|zone| &zone.name != name))
+            .unwrap_or_default();
+        self.zones.push(Zone {
+            name,
+            lat: Some((lat * 10_000.0).round() / 10_000.0),
+            lon: Some((lon * 10_000.0).round() / 10_000.0),
+            radius_nm: Some(5.0),
+            ..Zone::default()
+        });
+        self.zones_cursor = self.zones.len() - 1;
+        self.zones_changed();
+        self.save_zones();
+        true
+    }
+
+    pub fn toggle_zone_enabled_selected(&mut self) -> bool {
+        let Some(zone) = self.zones.get_mut(self.zones_cursor) else {
+            return false;
+        };
+        zone.enabled = Some(!zone.is_enabled());
+        debug!("zone {} enabled={}", zone.name, zone.is_enabled());
+        self.zones_changed();
+        self.save_zones();
+        true
+    }
+
+    pub fn cycle_zone_alert_selected(&mut self) -> bool {
+        let Some(zone) = self.zones.get_mut(self.zones_cursor) else {
+            return false;
+        };
+        zone.alert = Some(zone.alert_mode().next().name().to_string());
+        debug!("zone {} alert={}", zone.name, zone.alert_mode().name());
+        self.save_zones();
+        true
+    }
+
+    pub fn delete_zone_selected(&mut self) -> bool {
+        if self.zones_cursor >= self.zones.len() {
+            return false;
+        }
+        let zone = self.zones.remove(self.zones_cursor);
+        debug!("zone delete {}", zone.name);
+        self.zones_cursor = self.zones_cursor.min(self.zones.len().saturating_sub(1));
+        self.zones_changed();
+        self.save_zones();
+        true
+    }
+
+    pub fn reload_zones(&mut self) {
+        let now = SystemTime::now();
+        let Some(path) = self.zones_path.as_ref() else {
+            self.notify("ZONES no file path".to_string(), now);
+            return;
+        };
+        let message = match storage::load_zones(path) {
+            Ok(zones) => {
+                info!("zones reloaded {} ({})", path.display(), zones.len());
+                let message = format!("ZONES loaded {}", zones.len());
+                self.zones = zones;
+                self.zones_cursor = self.zones_cursor.min(self.zones.len().saturating_sub(1));
+                self.zones_changed();
+                message
+            }
+            Err(err) => {
+                warn!("zones reload failed: {err:#}");
+                format!("ZONES ERR {err}")
+            }
+        };
+        self.notify(message, now);
+    }
+
+    pub fn reload_roles(&mut self) {
+        let result = storage::load_role_rules(
+            self.roles_path.as_deref(),
+            self.military_ranges_path.as_deref(),
+        );
+        let message = match result {
+            Ok(rules) => {
+                info!("role rules reloaded ({})", rules.len());
+                let message = format!("ROLES loaded {} rules", rules.len());
+                self.role_rules = rules;
+                message
+            }
+            Err(err) => {
+                warn!("role rules reload failed: {err:#}");
+                format!("ROLES ERR {err}")
+            }
+        };
+        self.notify(message, SystemTime::now());
+    }
+
+    pub fn save_zones(&mut self) {
+        let now = SystemTime::now();
+        let Some(path) = self.zones_path.as_ref() else {
+            warn!("zones save skipped: no file path");
+            self.notify("ZONES no file path".to_string(), now);
+            return;
+        };
+        let message = match storage::save_zones(path, &self.zones) {
+            Ok(_) => {
+                info!("zones saved {}", path.display());
+                "ZONES saved".to_string()
+            }
+            Err(err) => {
+                warn!("zones save failed: {err}");
+                format!("ZONES ERR {err}")
+            }
+        };
+        self.notify(message, now);
+    }
+
+    /// Zone indices or shapes changed: start tracking afresh so nobody gets
+    /// a spurious entry or exit.
+    fn zones_changed(&mut self) {
+        self.zone_tracker.reset();
+    }
+
+    pub fn open_config(&mut self) {
+        let config_exists = self.config_path.exists();
+        self.config_items = load_config_items(&self.config_path);
+        self.config_cursor = 0;
+        self.config_editing = false;
+        self.config_edit.clear();
+        self.config_dirty = !config_exists;
+        self.config_status = None;
+        self.input_mode = InputMode::Config;
+        debug!("open config items={}", self.config_items.len());
+    }
+
+    pub fn close_config(&mut self) {
+        if self.config_dirty && !self.save_config() {
+            return;
+        }
+        self.input_mode = InputMode::Normal;
+        self.config_editing = false;
+        self.config_edit.clear();
+        debug!("close config");
+    }
+
+    pub fn next_config_item(&mut self) {
         if self.config_items.is_empty() {
             return;
         }
@@ -1053,6 +2947,10 @@ impl App {
     }
 
     pub fn start_config_edit(&mut self) {
+        if self.selected_config_choices().is_some() {
+            self.cycle_config_value(true);
+            return;
+        }
         if let Some(item) = self.config_items.get(self.config_cursor) {
             self.config_edit = item.value.clone();
             self.config_editing = true;
@@ -1060,6 +2958,32 @@ impl App {
         }
     }
 
+    pub fn selected_config_choices(&self) -> Option<&'static [&'static str]> {
+        self.config_items
+            .get(self.config_cursor)
+            .and_then(ConfigItem::choices)
+    }
+
+    pub fn cycle_config_value(&mut self, forward: bool) {
+        let Some(item) = self.config_items.get_mut(self.config_cursor) else {
+            return;
+        };
+        let Some(choices) = item.choices() else {
+            return;
+        };
+        let current = choices
+            .iter()
+            .position(|choice| choice.eq_ignore_ascii_case(item.value.trim()));
+        let next = match current {
+            Some(idx) if forward => (idx + 1) % choices.len(),
+            Some(idx) => (idx + choices.len() - 1) % choices.len(),
+            None => 0,
+        };
+        item.value = choices[next].to_string();
+        self.config_dirty = true;
+        debug!("config cycle key={} value={}", item.key, item.value);
+    }
+
     pub fn cancel_config_edit(&mut self) {
         self.config_editing = false;
         self.config_edit.clear();
@@ -1091,25 +3015,15 @@ impl App {
         if self.config_editing {
             self.apply_config_edit();
         }
-        if let Some(parent) = self
-            .config_path
-            .parent()
-            .filter(|path| !path.as_os_str().is_empty())
-        {
-            if let Err(err) = fs::create_dir_all(parent) {
-                warn!("config save failed: {err}");
-                self.config_status = Some((format!("save failed: {err}"), SystemTime::now()));
-                return false;
-            }
-        }
         let existing = fs::read_to_string(&self.config_path).unwrap_or_default();
         let mut doc = existing
             .parse::<DocumentMut>()
             .unwrap_or_else(|_| DocumentMut::new());
         let mut api_key_skipped = false;
+        let mut settings = Vec::new();
 
         for item in &self.config_items {
-            if item.key == "api_key" {
+            if item.key == "api_key" && !secrets::is_reference(&item.value) {
                 if !item.value.trim().is_empty() {
                     api_key_skipped = true;
                 }
@@ -1117,11 +3031,11 @@ impl App {
                 continue;
             }
             match parse_config_value(item.kind, item.value.trim()) {
-                Ok(Some(value)) => {
-                    doc[item.key.as_str()] = to_edit_value(value);
-                }
-                Ok(None) => {
-                    doc.remove(item.key.as_str());
+                Ok(value) => {
+                    if item.value.trim() != item.saved.trim() {
+                        settings.push((item.key.clone(), value.clone()));
+                    }
+                    set_doc_value(&mut doc, &item.key, value.map(to_edit_value));
                 }
                 Err(err) => {
                     warn!("config save failed: {err}");
@@ -1130,22 +3044,51 @@ impl App {
                 }
             }
         }
+        let bindings: HashMap<String, String> = self
+            .config_items
+            .iter()
+            .filter(|item| !item.value.trim().is_empty())
+            .filter_map(|item| {
+                let action = item.key.strip_prefix("keys.")?;
+                Some((action.to_string(), item.value.trim().to_string()))
+            })
+            .collect();
+        if let Err(errors) = KeyMap::from_overrides(&bindings) {
+            let err = errors.join("; ");
+            warn!("config save failed: {err}");
+            self.config_status = Some((err, SystemTime::now()));
+            return false;
+        }
+        let next = match config::with_settings(&self.config, &settings) {
+            Ok(next) => next,
+            Err(err) => {
+                let err = format!("{err:#}");
+                warn!("config save failed: {err}");
+                self.config_status = Some((err, SystemTime::now()));
+                return false;
+            }
+        };
 
-        let text = doc.to_string();
-        if let Err(err) = fs::write(&self.config_path, text) {
+        if let Err(err) = write_config_file(&self.config_path, &doc.to_string()) {
             warn!("config save failed: {err}");
             self.config_status = Some((format!("save failed: {err}"), SystemTime::now()));
             return false;
         } else {
-            #[cfg(unix)]
-            {
-                use std::os::unix::fs::PermissionsExt;
-                let _ = fs::set_permissions(&self.config_path, fs::Permissions::from_mode(0o600));
-            }
             info!("config saved {}", self.config_path.display());
-            let mut message = format!("saved {} (restart to apply)", self.config_path.display());
+            for item in &mut self.config_items {
+                item.saved = item.value.clone();
+            }
+            let restart_keys = config::restart_required_keys(&self.config, &next);
+            if self.apply_config(next) {
+                self.pending_feed_urls = self.refresh_feed_urls();
+            }
+            debug!("config save applied live; restart keys={:?}", restart_keys);
+            let mut message = format!("saved {}", self.config_path.display());
+            if !restart_keys.is_empty() {
+                message.push_str(&format!("; restart to apply {}", restart_keys.join(", ")));
+            }
             if api_key_skipped {
-                message.push_str("; api_key not saved");
+                message.push_str("; api_key not saved (use keyring:NAME or env:NAME)");
             }
             self.config_status = Some((message, SystemTime::now()));
             self.config_dirty = false;
@@ -1191,6 +3134,31 @@ impl App {
         }
     }
 
+    pub fn move_column(&mut self, delta: isize) {
+        let len = self.columns.len();
+        let target = self.column_cursor as isize + delta;
+        if len == 0 || target < 0 || target >= len as isize {
+            return;
+        }
+        self.columns.swap(self.column_cursor, target as usize);
+        self.column_cursor = target as usize;
+    }
+
+    pub fn adjust_column_width(&mut self, delta: i16) {
+        if let Some(col) = self.columns.get_mut(self.column_cursor) {
+            let width = col.min_width() as i16 + delta;
+            col.fixed_width = Some(width.clamp(1, MAX_COLUMN_WIDTH as i16) as u16);
+            self.column_widths.invalidate();
+        }
+    }
+
+    pub fn clear_column_width(&mut self) {
+        if let Some(col) = self.columns.get_mut(self.column_cursor) {
+            col.fixed_width = None;
+            self.column_widths.invalidate();
+        }
+    }
+
     pub fn toggle_column(&mut self) {
         if self.columns.is_empty() {
             return;
@@ -1213,67 +3181,57 @@ impl App {
         self.column_cursor
     }
 
-    pub fn column_cache_lookup(
-        &self,
-        width: u16,
-        cols: &[ColumnId],
-        rows_len: usize,
-        now: SystemTime,
-    ) -> Option<Vec<u16>> {
-        if !self.column_cache_enabled {
+    pub fn set_table_area(&mut self, area: Rect, header_rows: u16) {
+        self.table_area = Some(area);
+        self.table_header_rows = header_rows.max(1);
+    }
+
+    pub fn table_row_at(&self, y: u16) -> Option<usize> {
+        let area = self.table_area?;
+        if area.height < 3 {
             return None;
         }
-        let cache = self.column_width_cache.as_ref()?;
-        if cache.width != width || cache.rows_len != rows_len || cache.cols != cols {
-            return None;
-        }
-        if now
-            .duration_since(cache.at)
-            .map(|d| d > self.column_cache_ttl)
-            .unwrap_or(true)
-        {
+        let top = area.y + 1;
+        let data_top = top.saturating_add(self.table_header_rows);
+        let data_bottom = area.y + area.height.saturating_sub(1);
+        if y < data_top || y >= data_bottom {
             return None;
         }
-        Some(cache.widths.clone())
+        Some((y - data_top) as usize)
     }
 
-    pub fn column_cache_store(
-        &mut self,
-        width: u16,
-        cols: Vec<ColumnId>,
-        rows_len: usize,
-        widths: Vec<u16>,
-        now: SystemTime,
-    ) {
-        if !self.column_cache_enabled {
-            return;
-        }
-        self.column_width_cache = Some(ColumnWidthCache {
-            width,
-            cols,
-            rows_len,
-            at: now,
-            widths,
-        });
+    pub fn set_radar_hits(&mut self, area: Rect, hits: Vec<RadarHit>) {
+        self.radar_area = Some(area);
+        self.radar_hits = hits;
     }
 
-    pub fn set_table_area(&mut self, area: Rect, header_rows: u16) {
-        self.table_area = Some(area);
-        self.table_header_rows = header_rows.max(1);
+    pub fn clear_radar_hits(&mut self) {
+        self.radar_area = None;
+        self.radar_hits.clear();
     }
 
-    pub fn table_row_at(&self, y: u16) -> Option<usize> {
-        let area = self.table_area?;
-        if area.height < 3 {
-            return None;
-        }
-        let top = area.y + 1;
-        let data_top = top.saturating_add(self.table_header_rows);
-        let data_bottom = area.y + area.height.saturating_sub(1);
-        if y < data_top || y >= data_bottom {
+    pub fn radar_contains(&self, col: u16, row: u16) -> bool {
+        self.radar_area
+            .is_some_and(|area| area.contains(Position::new(col, row)))
+    }
+
+    pub fn radar_aircraft_at(&self, col: u16, row: u16) -> Option<usize> {
+        if !self.radar_contains(col, row) {
             return None;
         }
-        Some((y - data_top) as usize)
+        radar::hit_at(&self.radar_hits, col, row)
+    }
+
+    pub fn select_radar_aircraft_at(&mut self, col: u16, row: u16, indices: &[usize]) -> bool {
+        let Some(idx) = self.radar_aircraft_at(col, row) else {
+            return false;
+        };
+        let Some(row) = indices.iter().position(|visible| *visible == idx) else {
+            return false;
+        };
+        self.table_state.select(Some(row));
+        self.update_selection_key(indices);
+        true
     }
 
     pub fn select_row(&mut self, row: usize, visible_len: usize) {
@@ -1369,19 +3327,16 @@ impl App {
         if let Some(key) = &self.selection_key {
             if let Some(pos) = indices.iter().position(|idx| {
                 if let Some(ac) = self.data.aircraft.get(*idx) {
-                    let stale = seen_seconds(ac)
-                        .map(|s| s > self.stale_secs)
-                        .unwrap_or(false);
-                    if stale {
+                    if self.message_stale(ac) {
                         return false;
                     }
                     if let Some(hex) = ac.hex.as_deref() {
-                        if normalize_hex(hex) == *key {
+                        if self.aircraft_keys.hex(hex) == key.as_str() {
                             return true;
                         }
                     }
                     if let Some(flight) = ac.flight.as_deref() {
-                        if normalize_callsign(flight) == *key {
+                        if self.aircraft_keys.callsign(flight) == key.as_str() {
                             return true;
                         }
                     }
@@ -1395,16 +3350,32 @@ impl App {
         }
     }
 
+    pub fn select_hex(&mut self, hex: &str, indices: &[usize]) -> bool {
+        let key = normalize_hex(hex);
+        let Some(row) = indices.iter().position(|idx| {
+            self.data
+                .aircraft
+                .get(*idx)
+                .and_then(|ac| ac.hex.as_deref())
+                .is_some_and(|value| self.aircraft_keys.hex(value) == key)
+        }) else {
+            return false;
+        };
+        self.table_state.select(Some(row));
+        self.selection_key = Some(key);
+        true
+    }
+
     pub fn update_selection_key(&mut self, indices: &[usize]) {
         if let Some(selected) = self.table_state.selected() {
             if let Some(idx) = indices.get(selected) {
                 if let Some(ac) = self.data.aircraft.get(*idx) {
                     if let Some(hex) = ac.hex.as_deref() {
-                        self.selection_key = Some(normalize_hex(hex));
+                        self.selection_key = Some(self.aircraft_keys.hex(hex).into_owned());
                         return;
                     }
                     if let Some(flight) = ac.flight.as_deref() {
-                        self.selection_key = Some(normalize_callsign(flight));
+                        self.selection_key = Some(self.aircraft_keys.callsign(flight).into_owned());
                     }
                 }
             }
@@ -1419,7 +3390,8 @@ impl App {
     }
 
     fn radar_navigation_center(&self, indices: &[usize]) -> Option<RadarCenter> {
-        self.radar_center
+        self.radar_follow_center(indices)
+            .or(self.radar_center)
             .or_else(|| self.aircraft_centroid_for(indices))
     }
 
@@ -1484,21 +3456,30 @@ impl App {
     }
 
     pub fn visible_indices(&self) -> Vec<usize> {
+        self.profiler
+            .time(Stage::Visible, || self.collect_visible_indices())
+    }
+
+    fn collect_visible_indices(&self) -> Vec<usize> {
         let mut indices: Vec<usize> = self
             .data
             .aircraft
             .iter()
             .enumerate()
-            .filter(|(_, ac)| {
-                if self.hide_stale {
-                    let stale = seen_seconds(ac)
-                        .map(|s| s > self.stale_secs)
-                        .unwrap_or(false);
-                    if stale {
-                        return false;
-                    }
+            .filter(|(i, ac)| {
+                if self.layout_mode == LayoutMode::Watch
+                    && !self.is_favorite(ac)
+                    && !self.is_watchlisted(ac)
+                {
+                    return false;
+                }
+                if !self.ground_filter.allows(ac) {
+                    return false;
+                }
+                if self.hide_stale && self.message_stale(ac) {
+                    return false;
                 }
-                self.matches_filter(ac)
+                self.matches_filter(*i, ac)
             })
             .map(|(i, _)| i)
             .collect();
@@ -1513,32 +3494,157 @@ impl App {
                 return b_fav.cmp(&a_fav);
             }
 
-            match self.sort {
-                SortMode::LastSeen => {
-                    let a_seen = seen_seconds(a_ac);
-                    let b_seen = seen_seconds(b_ac);
-                    a_seen.partial_cmp(&b_seen).unwrap_or(Ordering::Equal)
-                }
-                SortMode::Altitude => {
-                    let a_alt = a_ac.alt_baro.unwrap_or(-1);
-                    let b_alt = b_ac.alt_baro.unwrap_or(-1);
-                    b_alt.cmp(&a_alt)
-                }
-                SortMode::Speed => {
-                    let a_spd = a_ac.gs.unwrap_or(-1.0);
-                    let b_spd = b_ac.gs.unwrap_or(-1.0);
-                    b_spd.partial_cmp(&a_spd).unwrap_or(Ordering::Equal)
-                }
-            }
+            self.sort
+                .columns()
+                .iter()
+                .map(|column| self.compare_sort_column(*column, a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
         });
 
         indices
     }
 
+    fn compare_sort_column(&self, column: SortColumn, a: usize, b: usize) -> Ordering {
+        let (a_ac, b_ac) = (&self.data.aircraft[a], &self.data.aircraft[b]);
+        let text = |value: fn(&Aircraft) -> Option<&str>| {
+            order_by(
+                sort_text(value(a_ac)),
+                sort_text(value(b_ac)),
+                column.descending,
+                |a, b| a.cmp(b),
+            )
+        };
+        let (a_value, b_value) = match column.key {
+            SortKey::Callsign => return text(|ac| ac.flight.as_deref()),
+            SortKey::Registration => return text(|ac| ac.r.as_deref()),
+            SortKey::Type => return text(|ac| ac.t.as_deref()),
+            SortKey::Hex => return text(|ac| ac.hex.as_deref()),
+            SortKey::Seen => (seen_seconds(a_ac), seen_seconds(b_ac)),
+            SortKey::Altitude => (
+                self.altitude_ft(a_ac).map(|ft| ft as f64),
+                self.altitude_ft(b_ac).map(|ft| ft as f64),
+            ),
+            SortKey::Speed => (a_ac.gs, b_ac.gs),
+            SortKey::Distance => (
+                self.site_geometry(a).map(|geometry| geometry.distance_nm),
+                self.site_geometry(b).map(|geometry| geometry.distance_nm),
+            ),
+            SortKey::Messages => (
+                a_ac.messages.map(|count| count as f64),
+                b_ac.messages.map(|count| count as f64),
+            ),
+            SortKey::Rssi => (a_ac.rssi, b_ac.rssi),
+        };
+        order_by(a_value, b_value, column.descending, f64::total_cmp)
+    }
+
+    pub fn altitude_for(&self, ac: &Aircraft) -> Option<Altitude> {
+        self.altitude_source.altitude(ac)
+    }
+
+    pub fn altitude_ft(&self, ac: &Aircraft) -> Option<i64> {
+        self.altitude_for(ac).map(Altitude::feet)
+    }
+
+    pub fn message_stale(&self, ac: &Aircraft) -> bool {
+        message_seconds(ac)
+            .map(|s| s > self.stale_secs)
+            .unwrap_or(false)
+    }
+
+    /// Whether the aircraft's last position is older than
+    /// `position_stale_secs`, even if other messages are still arriving, or
+    /// the feed has already moved it to `lastPosition`.
+    pub fn position_stale(&self, ac: &Aircraft) -> bool {
+        if ac.last_position.is_some() {
+            return true;
+        }
+        ac.lat.is_some()
+            && ac.lon.is_some()
+            && position_seconds(ac)
+                .map(|s| s > self.position_stale_secs)
+                .unwrap_or(false)
+    }
+
+    pub fn course_for(&self, ac: &Aircraft) -> Option<Course> {
+        if let Some(deg) = ac.track {
+            return Some(Course {
+                deg,
+                derived: false,
+            });
+        }
+        let trail = self.trail_for(ac)?;
+        let last = trail.back()?;
+        let from = trail.iter().rev().skip(1).find(|point| {
+            distance_nm(point.lat, point.lon, last.lat, last.lon) >= DERIVED_TRACK_MIN_NM
+        })?;
+        Some(Course {
+            deg: bearing_deg(from.lat, from.lon, last.lat, last.lon),
+            derived: true,
+        })
+    }
+
+    /// Where the aircraft should be now, dead-reckoned from its last position,
+    /// ground speed and track, when `predict_positions` is on. Extrapolation
+    /// stops `MAX_PREDICTION_SECS` after the position was reported.
+    pub fn predicted_position(&self, ac: &Aircraft) -> Option<(f64, f64)> {
+        if !self.predict_positions {
+            return None;
+        }
+        let (lat, lon, gs, track) = (ac.lat?, ac.lon?, ac.gs?, ac.track?);
+        if gs <= 0.0 {
+            return None;
+        }
+        let wall = self.data_shown_at?.elapsed().as_secs_f64();
+        // Replay time runs at the playback speed and stands still when paused.
+        let elapsed = match &self.replay {
+            Some(status) if status.paused => 0.0,
+            Some(status) => wall * status.speed,
+            None => wall,
+        };
+        let age = (position_seconds(ac).unwrap_or(0.0).max(0.0) + elapsed).min(MAX_PREDICTION_SECS);
+        let distance_nm = gs * age / 3600.0;
+        let track = track.to_radians();
+        Some((
+            clamp_lat(lat + distance_nm * track.cos() / 60.0),
+            wrap_lon(lon + longitude_step(lat, distance_nm * track.sin())),
+        ))
+    }
+
+    pub fn ghost_position(&self, ac: &Aircraft) -> Option<(f64, f64, Option<f64>)> {
+        if let Some(last) = ac.last_position {
+            return Some((last.lat?, last.lon?, last.seen_pos));
+        }
+        if self.position_stale(ac) {
+            return Some((ac.lat?, ac.lon?, position_seconds(ac)));
+        }
+        None
+    }
+
+    pub fn low_quality_position(&self, ac: &Aircraft) -> bool {
+        ac.lat.is_some()
+            && ac.lon.is_some()
+            && (ac.nic.unwrap_or(99) < self.low_nic || ac.nac_p.unwrap_or(99) < self.low_nac)
+    }
+
+    pub fn adsb_version_counts(&self) -> [usize; 3] {
+        let mut counts = [0; 3];
+        for ac in &self.data.aircraft {
+            if let Some(version @ 0..=2) = ac.version {
+                counts[version as usize] += 1;
+            }
+        }
+        counts
+    }
+
     pub fn is_favorite(&self, ac: &Aircraft) -> bool {
         ac.hex
             .as_deref()
-            .map(|hex| self.favorites.contains(&normalize_hex(hex)))
+            .map(|hex| {
+                self.favorites
+                    .contains(self.aircraft_keys.hex(hex).as_ref())
+            })
             .unwrap_or(false)
     }
 
@@ -1558,6 +3664,48 @@ impl App {
         false
     }
 
+    pub fn is_trail_pinned(&self, ac: &Aircraft) -> bool {
+        ac.hex
+            .as_deref()
+            .map(|hex| {
+                self.pinned_trails
+                    .contains(self.aircraft_keys.hex(hex).as_ref())
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn toggle_trail_pin_selected(&mut self, indices: &[usize]) -> bool {
+        let selected = self.table_state.selected().and_then(|row| indices.get(row));
+        let Some(ac) = selected.and_then(|idx| self.data.aircraft.get(*idx)) else {
+            return false;
+        };
+        let Some(hex) = ac.hex.as_deref() else {
+            return false;
+        };
+        let label = ac
+            .flight
+            .as_deref()
+            .map(str::trim)
+            .filter(|flight| !flight.is_empty())
+            .unwrap_or(hex)
+            .to_string();
+        let key = self.aircraft_keys.hex_key(hex);
+        let state = if self.pinned_trails.insert(key.clone()) {
+            "pinned"
+        } else {
+            self.pinned_trails.remove(&key);
+            "unpinned"
+        };
+        self.notify(format!("TRAIL {state} {label}"), SystemTime::now());
+        true
+    }
+
+    pub fn clear_trails(&mut self) {
+        self.trail_points.clear();
+        self.trail_jump_strikes.clear();
+        self.notify("TRAILS cleared".to_string(), SystemTime::now());
+    }
+
     pub fn favorites_path(&self) -> Option<&PathBuf> {
         self.favorites_path.as_ref()
     }
@@ -1592,6 +3740,10 @@ impl App {
         best
     }
 
+    pub fn aircraft(&self) -> &[Aircraft] {
+        &self.data.aircraft
+    }
+
     pub fn site(&self) -> Option<SiteLocation> {
         if self.demo_mode {
             None
@@ -1602,20 +3754,28 @@ impl App {
 
     pub fn route_for(&self, ac: &Aircraft) -> Option<&RouteInfo> {
         if let Some(callsign) = ac.flight.as_deref() {
-            let key = normalize_callsign(callsign);
-            if let Some(info) = self.route_cache.get(&key) {
+            let key = self.aircraft_keys.callsign(callsign);
+            if let Some(info) = self.route_cache.get(key.as_ref()) {
                 return Some(info);
             }
         }
         if let Some(hex) = ac.hex.as_deref() {
-            let key = normalize_hex(hex);
-            if let Some(info) = self.route_cache.get(&key) {
+            let key = self.aircraft_keys.hex(hex);
+            if let Some(info) = self.route_cache.get(key.as_ref()) {
                 return Some(info);
             }
         }
         None
     }
 
+    pub fn airport(&self, code: &str) -> Option<&Airport> {
+        self.airports.lookup(code)
+    }
+
+    pub fn nearest_airport(&self, ac: &Aircraft) -> Option<(&Airport, f64)> {
+        self.airports.nearest(ac.lat?, ac.lon?)
+    }
+
     pub fn route_enabled(&self) -> bool {
         self.route_enabled
     }
@@ -1625,10 +3785,10 @@ impl App {
     }
 
     pub fn route_pending(&self, callsign: &str, now: SystemTime) -> bool {
-        let key = normalize_callsign(callsign);
+        let key = self.aircraft_keys.callsign(callsign);
         let window = self.route_pending_window();
         self.route_last_request
-            .get(&key)
+            .get(key.as_ref())
             .and_then(|last| now.duration_since(*last).ok())
             .map(|delta| delta <= window)
             .unwrap_or(false)
@@ -1661,6 +3821,8 @@ impl App {
         let mut msg_rate = Vec::with_capacity(self.perf_samples.len());
         let mut flights = Vec::with_capacity(self.perf_samples.len());
         let mut signal = Vec::with_capacity(self.perf_samples.len());
+        let mut app_cpu = Vec::with_capacity(self.perf_samples.len());
+        let mut fetch_ms = Vec::with_capacity(self.perf_samples.len());
         let to_rate = |value: Option<f64>| -> u64 {
             match value {
                 Some(v) if v.is_finite() && v > 0.0 => v.round() as u64,
@@ -1682,6 +3844,8 @@ impl App {
             msg_rate.push(to_rate(sample.msg_rate));
             flights.push(sample.flights as u64);
             signal.push(to_signal(sample.rssi_avg));
+            app_cpu.push(to_rate(sample.usage.cpu_percent));
+            fetch_ms.push(to_rate(sample.fetch_ms));
         }
 
         if msg_rate.is_empty() {
@@ -1695,6 +3859,24 @@ impl App {
             None => (None, 0, None),
         };
         let latest_signal_rsi = rsi_from_series(&signal, 14);
+        let latest_usage = self
+            .perf_samples
+            .back()
+            .map(|sample| sample.usage)
+            .unwrap_or_default();
+        let latest_fetch_ms = self.perf_samples.back().and_then(|sample| sample.fetch_ms);
+        let frame = self.profiler.stats(Stage::Frame);
+        let frame_ms = (frame.count > 0).then(|| millis(frame.avg));
+        let receiver_signal = self
+            .receiver_stats
+            .iter()
+            .map(|stats| to_signal(stats.signal))
+            .collect();
+        let receiver_cpu = self
+            .receiver_stats
+            .iter()
+            .map(|stats| to_rate(stats.cpu_percent()))
+            .collect();
 
         PerformanceSnapshot {
             msg_rate,
@@ -1704,14 +3886,176 @@ impl App {
             latest_flights,
             latest_signal,
             latest_signal_rsi,
+            adsb_versions: self.adsb_version_counts(),
+            receiver: self.receiver_stats.back().copied(),
+            receiver_signal,
+            receiver_cpu,
+            app_cpu,
+            fetch_ms,
+            latest_usage,
+            latest_fetch_ms,
+            frame_ms,
+            feed_cache: self.feed_cache,
+        }
+    }
+
+    pub fn traffic_histograms(&self, indices: &[usize]) -> TrafficHistograms {
+        let mut altitude = [0; ALTITUDE_BANDS.len()];
+        let mut distance = self.site().map(|_| [0; DISTANCE_RINGS.len()]);
+        let mut rssi = [0; RSSI_BANDS.len()];
+        let mut levels = Vec::new();
+        for &idx in indices {
+            let Some(ac) = self.data.aircraft.get(idx) else {
+                continue;
+            };
+            let band = match self.altitude_for(ac) {
+                Some(Altitude::Ground) => Some(0),
+                Some(Altitude::Feet(ft)) => ALTITUDE_BANDS
+                    .iter()
+                    .skip(1)
+                    .position(|(_, upper)| ft < *upper)
+                    .map(|pos| pos + 1),
+                None => None,
+            };
+            if let Some(band) = band {
+                altitude[band] += 1;
+            }
+            if let (Some(rings), Some(geometry)) = (distance.as_mut(), self.site_geometry(idx)) {
+                if let Some(ring) = DISTANCE_RINGS
+                    .iter()
+                    .position(|(_, upper)| geometry.distance_nm < *upper)
+                {
+                    rings[ring] += 1;
+                }
+            }
+            if let Some(level) = ac.rssi.filter(|level| level.is_finite()) {
+                if let Some(band) = RSSI_BANDS.iter().position(|(_, upper)| level < *upper) {
+                    rssi[band] += 1;
+                }
+                levels.push(level);
+            }
+        }
+        levels.sort_by(f64::total_cmp);
+        let rssi_percentiles = (!levels.is_empty()).then(|| {
+            // Nearest-rank percentile.
+            [10, 50, 90].map(|pct| {
+                let rank = (pct * levels.len()).div_ceil(100).max(1);
+                levels[rank - 1]
+            })
+        });
+        TrafficHistograms {
+            altitude,
+            distance,
+            rssi,
+            rssi_percentiles,
+        }
+    }
+
+    pub fn range_sectors(&self) -> Option<RangeSectors> {
+        let site = self.site()?;
+        let mut current = [0.0; RANGE_SECTORS];
+        for idx in 0..self.data.aircraft.len() {
+            if let Some(geometry) = self.site_geometry(idx) {
+                let sector = &mut current[range_sector(geometry.bearing_deg)];
+                *sector = f64::max(*sector, geometry.distance_nm);
+            }
+        }
+        let mut max = [0.0; RANGE_SECTORS];
+        if let Some(saved) = self.traffic.max_range((site.lat, site.lon)) {
+            max.copy_from_slice(saved);
+        }
+        Some(RangeSectors { current, max })
+    }
+
+    pub(crate) fn photo_for(&self, hex: &str) -> Option<&PhotoInfo> {
+        let now_secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        self.photos.as_ref()?.get(hex, now_secs)
+    }
+
+    pub fn photo_request(&mut self, indices: &[usize]) -> Option<String> {
+        if self.input_mode != InputMode::Detail
+            || self.detail_tab != DetailTab::Photo
+            || self.photos.is_none()
+        {
+            return None;
+        }
+        let idx = *self
+            .table_state
+            .selected()
+            .and_then(|row| indices.get(row))?;
+        let hex = normalize_hex(self.data.aircraft[idx].hex.as_deref()?);
+        if hex.is_empty() {
+            return None;
+        }
+        let failed = self
+            .photo_error
+            .as_ref()
+            .is_some_and(|(failed, _)| *failed == hex);
+        if self.photo_for(&hex).is_some() {
+            if let Some(cache) = self.photos.as_mut() {
+                cache.touch(&hex);
+            }
+            return None;
+        }
+        if failed || self.photo_pending.contains(&hex) {
+            return None;
+        }
+        self.photo_pending.insert(hex.clone());
+        Some(hex)
+    }
+
+    pub fn apply_photo(&mut self, message: PhotoMessage) {
+        match message {
+            PhotoMessage::Result { hex, info } => {
+                self.photo_pending.remove(&hex);
+                self.photo_error = None;
+                let Some(cache) = self.photos.as_mut() else {
+                    return;
+                };
+                cache.insert(&hex, info);
+                if let Some(path) = &self.photo_cache_path {
+                    if let Err(err) = storage::save_photo_cache(path, cache) {
+                        warn!("photo cache save failed: {err}");
+                    }
+                }
+            }
+            PhotoMessage::Error { hex, message } => {
+                self.photo_pending.remove(&hex);
+                self.photo_error = Some((hex, message));
+            }
+        }
+    }
+
+    pub fn apply_weather(&mut self, result: Result<WeatherReport, String>) {
+        match result {
+            Ok(report) => {
+                self.weather = Some(report);
+                self.weather_error = None;
+            }
+            Err(err) => self.weather_error = Some(err),
+        }
+    }
+
+    pub fn apply_receiver_stats(&mut self, stats: ReceiverStats) {
+        self.receiver_stats.push_back(stats);
+        while self.receiver_stats.len() > RECEIVER_STATS_SAMPLES {
+            self.receiver_stats.pop_front();
         }
     }
 
     pub fn classify_aircraft(&self, ac: &Aircraft) -> AircraftRole {
+        self.role_for(ac).0
+    }
+
+    pub fn role_for(&self, ac: &Aircraft) -> (AircraftRole, Option<Color>) {
         if !self.role_enabled {
-            AircraftRole::Unknown
-        } else {
-            classify_aircraft(ac)
+            return (AircraftRole::Unknown, None);
+        }
+        match self.role_rules.classify(ac) {
+            Some(rule) => (rule.role, rule.color),
+            None => (AircraftRole::Commercial, None),
         }
     }
 
@@ -1749,9 +4093,11 @@ impl App {
                     destination: route.destination,
                     route: route.route,
                     fetched_at: now,
+                    used_at: now,
                 },
             );
         }
+        self.enforce_route_cache_cap();
         self.route_error = None;
         self.route_backoff_until = None;
         self.route_backoff_attempts = 0;
@@ -1783,15 +4129,9 @@ impl App {
                 continue;
             }
             let key = normalize_callsign(callsign.as_str());
-            if !(self.route_ttl.as_secs() == 0 && self.route_ttl.subsec_nanos() == 0) {
-                if let Some(info) = self.route_cache.get(&key) {
-                    if now
-                        .duration_since(info.fetched_at)
-                        .map(|d| d < self.route_ttl)
-                        .unwrap_or(true)
-                    {
-                        continue;
-                    }
+            if let Some(info) = self.route_cache.get(&key) {
+                if route_fresh(info, self.route_ttl, now) {
+                    continue;
                 }
             }
             if !(self.route_refresh.as_secs() == 0 && self.route_refresh.subsec_nanos() == 0) {
@@ -1812,9 +4152,68 @@ impl App {
                 lon: ac.lon.unwrap_or(0.0),
             });
         }
+        if !requests.is_empty() {
+            self.enforce_route_cache_cap();
+        }
         requests
     }
 
+    fn update_route_cache(&mut self, data: &ApiResponse) {
+        if !self.route_enabled {
+            return;
+        }
+        // Route stamps use the wall clock, not the feed's `now`.
+        let now = SystemTime::now();
+        for ac in &data.aircraft {
+            let Some(callsign) = ac.flight.as_deref() else {
+                continue;
+            };
+            let key = self.aircraft_keys.callsign(callsign);
+            if key.is_empty() {
+                continue;
+            }
+            match self.route_cache.get_mut(key.as_ref()) {
+                Some(info) => {
+                    info.used_at = now;
+                    if route_fresh(info, self.route_ttl, now) {
+                        self.route_hits += 1;
+                    } else {
+                        self.route_misses += 1;
+                    }
+                }
+                None => self.route_misses += 1,
+            }
+        }
+
+        let before = self.route_cache.len();
+        if !self.route_ttl.is_zero() {
+            let ttl = self.route_ttl;
+            self.route_cache
+                .retain(|_, info| !older_than(info.used_at, now, ttl));
+        }
+        let window = self.route_refresh.max(self.route_pending_window());
+        self.route_last_request
+            .retain(|_, at| !older_than(*at, now, window));
+        let expired = before - self.route_cache.len();
+        if expired > 0 {
+            debug!("route cache expired {expired} unused entries");
+        }
+    }
+
+    pub fn route_hit_rate(&self) -> Option<f64> {
+        let total = self.route_hits + self.route_misses;
+        (total > 0).then(|| self.route_hits as f64 / total as f64)
+    }
+
+    fn enforce_route_cache_cap(&mut self) {
+        let cap = self.route_cache_size;
+        let evicted = evict_oldest(&mut self.route_cache, cap, |info| info.used_at).len();
+        evict_oldest(&mut self.route_last_request, cap, |at| *at);
+        if evicted > 0 {
+            debug!("route cache evicted {evicted} entries");
+        }
+    }
+
     fn route_pending_window(&self) -> Duration {
         if self.route_refresh.is_zero() {
             Duration::from_secs(10)
@@ -1849,9 +4248,9 @@ impl App {
     }
 
     pub fn apply_filter(&mut self) {
-        self.filter = self.filter_edit.trim().to_string();
+        let edit = std::mem::take(&mut self.filter_edit);
+        self.set_filter(&edit);
         self.input_mode = InputMode::Normal;
-        debug!("filter applied len={}", self.filter.len());
     }
 
     pub fn cancel_filter(&mut self) {
@@ -1861,8 +4260,16 @@ impl App {
     }
 
     pub fn clear_filter(&mut self) {
-        self.filter.clear();
-        debug!("filter cleared");
+        self.set_filter("");
+    }
+
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filter = filter.trim().to_string();
+        self.filter_query = Filter::parse(&self.filter);
+        match &self.filter_query {
+            Ok(_) => debug!("filter set len={}", self.filter.len()),
+            Err(err) => debug!("filter invalid: {err}"),
+        }
     }
 
     pub fn push_filter_char(&mut self, ch: char) {
@@ -1936,31 +4343,90 @@ impl App {
 
     pub fn trend_for(&self, ac: &Aircraft) -> Trend {
         if let Some(hex) = ac.hex.as_deref() {
-            let key = normalize_hex(hex);
-            if let Some(trend) = self.trend_cache.get(&key) {
+            let key = self.aircraft_keys.hex(hex);
+            if let Some(trend) = self.trend_cache.get(key.as_ref()) {
                 return *trend;
             }
         }
         Trend {
             alt: TrendDir::Unknown,
             gs: TrendDir::Unknown,
+            vs: None,
         }
     }
 
-    pub fn trail_for(&self, ac: &Aircraft) -> Option<&[TrailPoint]> {
-        let key = ac.hex.as_deref().map(normalize_hex)?;
-        self.trail_points.get(&key).map(|v| v.as_slice())
+    pub fn trail_for(&self, ac: &Aircraft) -> Option<&VecDeque<TrailPoint>> {
+        let key = self.aircraft_keys.hex(ac.hex.as_deref()?);
+        self.trail_points.get(key.as_ref())
+    }
+
+    pub fn metric_history_for(&self, ac: &Aircraft) -> Option<&VecDeque<MetricSample>> {
+        let key = self.aircraft_keys.hex(ac.hex.as_deref()?);
+        self.metric_history.get(key.as_ref())
+    }
+
+    pub fn signal_history_for(&self, ac: &Aircraft) -> Option<&VecDeque<SignalSample>> {
+        let key = self.aircraft_keys.hex(ac.hex.as_deref()?);
+        self.signal_history.get(key.as_ref())
     }
 
     pub fn set_last_export(&mut self, filename: String) {
         self.last_export = Some((filename, SystemTime::now()));
     }
 
-    pub fn latest_notification(&self) -> Option<&Notification> {
-        self.notifications.last()
+    pub fn latest_status(&self) -> Option<&Notification> {
+        self.notifications.iter().rev().find(|note| !note.alert)
+    }
+
+    pub fn dnd_active(&self) -> bool {
+        self.dnd_override.unwrap_or(self.dnd_scheduled)
     }
 
-    fn update_performance_samples(&mut self, data: &ApiResponse, _now_time: SystemTime) {
+    pub fn toggle_dnd(&mut self) {
+        let on = !self.dnd_active();
+        self.dnd_override = Some(on);
+        let state = if on { "on" } else { "off" };
+        self.notify(format!("DND {state}"), SystemTime::now());
+    }
+
+    /// Follow `quiet_hours` by the wall clock, also while the feed is
+    /// stalled; a manual toggle lasts until they next start or end. Returns
+    /// whether DND changed.
+    pub fn update_dnd(&mut self, now: SystemTime) -> bool {
+        let local: chrono::DateTime<chrono::Local> = now.into();
+        let scheduled = self.quiet_hours.contains(local.time());
+        if scheduled == self.dnd_scheduled {
+            return false;
+        }
+        let was_active = self.dnd_active();
+        self.dnd_scheduled = scheduled;
+        self.dnd_override = None;
+        self.dnd_active() != was_active
+    }
+
+    pub fn unread_alerts(&self) -> usize {
+        self.notifications.iter().filter(|note| !note.read).count()
+    }
+
+    fn notify(&mut self, message: String, at: SystemTime) {
+        self.push_notification(Notification {
+            message,
+            at,
+            alert: false,
+            read: true,
+        });
+    }
+
+    fn push_notification(&mut self, note: Notification) {
+        self.notifications.push(note);
+        if self.notifications.len() > NOTIFICATION_CAP {
+            let excess = self.notifications.len() - NOTIFICATION_CAP;
+            self.notifications.drain(0..excess);
+            self.notifications_cursor = self.notifications_cursor.min(NOTIFICATION_CAP - 1);
+        }
+    }
+
+    fn update_performance_samples(&mut self, data: &ApiResponse, now_time: SystemTime) {
         let flights = data.aircraft.len();
         let mut rssi_sum = 0.0;
         let mut rssi_count = 0usize;
@@ -1979,24 +4445,95 @@ impl App {
             msg_rate: self.msg_rate_display(),
             flights,
             rssi_avg,
+            usage: self.usage.sample(Instant::now()),
+            fetch_ms: data.fetch_time.map(millis),
         };
         self.perf_samples.push_back(sample);
         while self.perf_samples.len() > self.perf_max_samples {
             self.perf_samples.pop_front();
         }
-    }
 
-    fn update_rate(&mut self, data: &ApiResponse, now_time: SystemTime) {
-        if let Some(messages) = data.messages {
-            if let Some(last_total) = self.last_msg_total {
-                if messages < last_total {
-                    self.msg_samples.clear();
-                    self.msg_rate_ema = None;
-                    self.msg_rate_display = None;
+        let elapsed = self
+            .perf_points
+            .back()
+            .and_then(|last| now_time.duration_since(last.at).ok())
+            .map(|d| d.as_secs_f64())
+            .filter(|secs| *secs > 0.0);
+        let position_rate = elapsed.map(|secs| {
+            let fresh = data
+                .aircraft
+                .iter()
+                .filter(|ac| ac.seen_pos.is_some_and(|seen| seen <= secs))
+                .count();
+            fresh as f64 / secs
+        });
+        let max_range_nm = self.site().and_then(|site| {
+            data.aircraft
+                .iter()
+                .filter_map(|ac| Some(distance_nm(site.lat, site.lon, ac.lat?, ac.lon?)))
+                .filter(|nm| nm.is_finite())
+                .reduce(f64::max)
+        });
+        let (mut named, mut routed) = (0usize, 0usize);
+        for ac in &data.aircraft {
+            if ac.flight.as_deref().is_some_and(|f| !f.trim().is_empty()) {
+                named += 1;
+                if self.route_for(ac).is_some() {
+                    routed += 1;
                 }
-                if messages == last_total {
-                    self.apply_rate_decay(now_time);
-                    return;
+            }
+        }
+        self.perf_points.push_back(PerfPoint {
+            at: now_time,
+            msg_rate: sample.msg_rate,
+            aircraft: flights,
+            rssi_avg,
+            max_range_nm,
+            position_rate,
+            route_hit_pct: (named > 0).then(|| routed as f64 * 100.0 / named as f64),
+        });
+        let longest = PerfWindow::TwoHours.duration();
+        while self.perf_points.front().is_some_and(|first| {
+            now_time
+                .duration_since(first.at)
+                .is_ok_and(|age| age > longest)
+        }) {
+            self.perf_points.pop_front();
+        }
+    }
+
+    pub fn perf_series(&self, chart: PerfChart, now: SystemTime) -> Vec<(f64, f64)> {
+        let window = self.perf_window.duration().as_secs_f64();
+        self.perf_points
+            .iter()
+            .filter_map(|point| {
+                let age = now
+                    .duration_since(point.at)
+                    .map_or(0.0, |d| d.as_secs_f64());
+                let value = chart.value(point)?;
+                (age <= window && value.is_finite()).then_some((-age, value))
+            })
+            .collect()
+    }
+
+    pub fn cycle_perf_window(&mut self, forward: bool) {
+        let len = PerfWindow::ALL.len();
+        let step = if forward { 1 } else { len - 1 };
+        self.perf_window = PerfWindow::ALL[(self.perf_window.index() + step) % len];
+        debug!("perf window {}", self.perf_window.name());
+    }
+
+    fn update_rate(&mut self, data: &ApiResponse, now_time: SystemTime) {
+        if let Some(messages) = data.messages {
+            if let Some(last_total) = self.last_msg_total {
+                if messages < last_total {
+                    self.msg_samples.clear();
+                    self.msg_rate_ema = None;
+                    self.msg_rate_display = None;
+                }
+                if messages == last_total {
+                    self.apply_rate_decay(now_time);
+                    return;
                 }
             }
 
@@ -2107,11 +4644,7 @@ impl App {
         let max_age = self.msg_rate_window + self.msg_rate_window;
 
         for ac in &data.aircraft {
-            let key = if let Some(hex) = ac.hex.as_deref() {
-                format!("hex:{}", normalize_hex(hex))
-            } else if let Some(flight) = ac.flight.as_deref() {
-                format!("flt:{}", normalize_callsign(flight))
-            } else {
+            let Some(key) = self.aircraft_keys.key(ac) else {
                 continue;
             };
             present.insert(key.clone());
@@ -2230,59 +4763,237 @@ impl App {
         }
     }
 
+    /// Runs after `update_aircraft_rates` so each sample has this update's
+    /// message rate.
+    fn update_signal_history(&mut self, data: &ApiResponse, now_time: SystemTime) {
+        for ac in &data.aircraft {
+            let Some(hex) = ac.hex.as_deref() else {
+                continue;
+            };
+            let key = self.aircraft_keys.hex_key(hex);
+            let msg_rate = self
+                .aircraft_rates
+                .get(&AircraftKey::Hex(key.clone()))
+                .and_then(|rate| rate.rate);
+            let rssi = ac.rssi.filter(|rssi| rssi.is_finite());
+            if rssi.is_none() && msg_rate.is_none() {
+                continue;
+            }
+            let entry = self
+                .signal_history
+                .entry(key)
+                .or_insert_with(|| VecDeque::with_capacity(SIGNAL_HISTORY_LEN));
+            if entry.len() >= SIGNAL_HISTORY_LEN {
+                entry.pop_front();
+            }
+            entry.push_back(SignalSample {
+                rssi,
+                msg_rate,
+                at: now_time,
+            });
+        }
+    }
+
+    fn poll_history_backfill(&mut self) {
+        let Some(rx) = self.history_rx.as_mut() else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(snapshots) => {
+                self.history_rx = None;
+                self.apply_history(snapshots);
+            }
+            Err(TryRecvError::Empty) => {}
+            Err(TryRecvError::Closed) => self.history_rx = None,
+        }
+    }
+
+    /// Fill trails and seen times from history snapshots, oldest first, so
+    /// the radar shows tracks right after startup. The history goes before
+    /// any trail the live feed has started meanwhile.
+    pub(crate) fn apply_history(&mut self, snapshots: Vec<ApiResponse>) {
+        let live = std::mem::take(&mut self.trail_points);
+        let strikes = std::mem::take(&mut self.trail_jump_strikes);
+        let mut applied = 0usize;
+        for data in &snapshots {
+            let Some(at) = snapshot_time(data.now) else {
+                continue;
+            };
+            self.update_trails(data, at);
+            for hex in data.aircraft.iter().filter_map(|ac| ac.hex.as_deref()) {
+                let seen = self
+                    .seen_times
+                    .entry(self.aircraft_keys.hex_key(hex))
+                    .or_insert(at);
+                *seen = (*seen).max(at);
+            }
+            applied += 1;
+        }
+        self.trail_jump_strikes = strikes;
+        for (key, points) in live {
+            let max_len = self.trail_cap(&key);
+            let trail = self.trail_points.entry(key).or_default();
+            if let Some(first) = points.front() {
+                trail.retain(|point| point.at < first.at);
+            }
+            trail.extend(points);
+            while trail.len() > max_len {
+                trail.pop_front();
+            }
+        }
+        self.enforce_history_cap();
+        info!(
+            "history backfill: {applied} snapshots, {} trails",
+            self.trail_points.len()
+        );
+    }
+
     fn update_seen_times(&mut self, data: &ApiResponse, now_time: SystemTime) {
         for ac in &data.aircraft {
             if let Some(hex) = ac.hex.as_deref() {
-                self.seen_times.insert(normalize_hex(hex), now_time);
+                self.seen_times
+                    .insert(self.aircraft_keys.hex_key(hex), now_time);
             }
         }
     }
 
-    fn update_trends(&mut self, data: &ApiResponse) {
+    fn update_trends(&mut self, data: &ApiResponse, now_time: SystemTime) {
         for ac in &data.aircraft {
             if let Some(hex) = ac.hex.as_deref() {
-                let key = normalize_hex(hex);
+                let key = self.aircraft_keys.hex_key(hex);
                 let prev = self.last_metrics.get(&key).copied().unwrap_or_default();
+                let rate = match self.altitude_source {
+                    AltitudeSource::Geom => ac.geom_rate.or(ac.baro_rate),
+                    _ => ac.baro_rate.or(ac.geom_rate),
+                };
                 let current = Metrics {
-                    alt_baro: ac.alt_baro,
+                    alt: self.altitude_ft(ac),
                     gs: ac.gs,
+                    vs: smooth_vertical_rate(prev.vs, rate),
+                };
+                // Prefer the smoothed vertical rate so a single noisy
+                // altitude sample does not flip the arrow.
+                let alt = match current.vs {
+                    Some(vs) => vertical_rate_trend(vs),
+                    None => compare_i64(prev.alt, current.alt),
                 };
                 let trend = Trend {
-                    alt: compare_i64(prev.alt_baro, current.alt_baro),
+                    alt,
                     gs: compare_f64(prev.gs, current.gs),
+                    vs: current.vs.map(|vs| vs.round() as i64),
                 };
                 self.trend_cache.insert(key.clone(), trend);
+                if current.alt.is_some() || current.gs.is_some() {
+                    let history = self
+                        .metric_history
+                        .entry(key.clone())
+                        .or_insert_with(|| VecDeque::with_capacity(METRIC_HISTORY_LEN));
+                    let cutoff = now_time.checked_sub(METRIC_HISTORY_WINDOW);
+                    while history.len() >= METRIC_HISTORY_LEN
+                        || history
+                            .front()
+                            .zip(cutoff)
+                            .is_some_and(|(oldest, cutoff)| oldest.at < cutoff)
+                    {
+                        history.pop_front();
+                    }
+                    history.push_back(MetricSample {
+                        alt: current.alt,
+                        gs: current.gs,
+                        at: now_time,
+                    });
+                }
                 self.last_metrics.insert(key, current);
             }
         }
     }
 
+    fn trail_cap(&self, key: &str) -> usize {
+        if self.pinned_trails.contains(key) {
+            PINNED_TRAIL_MAX_POINTS
+        } else if self.trail_fade_secs > 0 {
+            TRAIL_FADE_MAX_POINTS
+        } else {
+            self.trail_len.max(1)
+        }
+    }
+
     fn update_trails(&mut self, data: &ApiResponse, now_time: SystemTime) {
-        let max_len = self.trail_len.max(1);
+        let cutoff = (self.trail_fade_secs > 0)
+            .then(|| now_time.checked_sub(Duration::from_secs(self.trail_fade_secs)))
+            .flatten();
         for ac in &data.aircraft {
+            if self.low_quality_positions != PositionQuality::Show && self.low_quality_position(ac)
+            {
+                continue;
+            }
             if let (Some(hex), Some(lat), Some(lon)) = (ac.hex.as_deref(), ac.lat, ac.lon) {
-                let key = normalize_hex(hex);
-                let entry = self.trail_points.entry(key).or_default();
-                if let Some(last) = entry.last().copied() {
+                let key = self.aircraft_keys.hex_key(hex);
+                let alt = self.altitude_for(ac);
+                let max_len = self.trail_cap(&key);
+                let pinned = self.pinned_trails.contains(&key);
+                let entry = self
+                    .trail_points
+                    .entry(key.clone())
+                    .or_insert_with(|| VecDeque::with_capacity(max_len.min(self.trail_len)));
+                if let Some(cutoff) = cutoff.filter(|_| !pinned) {
+                    while entry.front().is_some_and(|point| point.at < cutoff) {
+                        entry.pop_front();
+                    }
+                }
+                if let Some(last) = entry.back().copied() {
                     let last_lat = last.lat;
                     let last_lon = last.lon;
                     if (last_lat - lat).abs() < 0.00001 && (last_lon - lon).abs() < 0.00001 {
                         continue;
                     }
+                    if implausible_jump(last, lat, lon, now_time) {
+                        // A lone bad fix is dropped; a position that keeps
+                        // reappearing is real, so the trail restarts there.
+                        let strikes = self.trail_jump_strikes.entry(key.clone()).or_insert(0);
+                        *strikes += 1;
+                        if *strikes < TRAIL_JUMP_RESEGMENT {
+                            self.trail_jumps_rejected += 1;
+                            continue;
+                        }
+                        entry.clear();
+                    }
                 }
-                entry.push(TrailPoint {
+                self.trail_jump_strikes.remove(&key);
+                // Oldest points fall off the front; the loop also trims
+                // trails left longer by a smaller reloaded `trail_len`.
+                while entry.len() >= max_len {
+                    entry.pop_front();
+                }
+                entry.push_back(TrailPoint {
                     lat,
                     lon,
+                    alt,
                     at: now_time,
                 });
-                if entry.len() > max_len {
-                    let excess = entry.len() - max_len;
-                    entry.drain(0..excess);
-                }
             }
         }
     }
 
+    fn enforce_history_cap(&mut self) {
+        let cap = self.max_tracked_aircraft;
+        let evicted = evict_oldest(&mut self.seen_times, cap, |at| *at);
+        for key in &evicted {
+            self.last_metrics.remove(key);
+            self.trend_cache.remove(key);
+            self.trail_points.remove(key);
+            self.trail_jump_strikes.remove(key);
+            self.signal_history.remove(key);
+            self.metric_history.remove(key);
+        }
+        evict_oldest(&mut self.aircraft_rates, cap, |rate| rate.last_time);
+        evict_oldest(&mut self.notified_recent, cap, |at| *at);
+        evict_oldest(&mut self.watch_notified_recent, cap, |at| *at);
+        if !evicted.is_empty() {
+            debug!("history evicted {} aircraft", evicted.len());
+        }
+    }
+
     fn update_notifications(&mut self, data: &ApiResponse, now: SystemTime) {
         let Some(site) = self.site() else {
             return;
@@ -2304,6 +5015,9 @@ impl App {
             let (Some(lat), Some(lon)) = (ac.lat, ac.lon) else {
                 continue;
             };
+            if self.position_stale(ac) {
+                continue;
+            }
             let dist_mi = distance_mi(site.lat, site.lon, lat, lon);
             if dist_mi > radius {
                 continue;
@@ -2329,19 +5043,248 @@ impl App {
 
             let callsign = ac.flight.as_deref().unwrap_or("--").trim();
             let reg = ac.r.as_deref().unwrap_or("--");
-            let prefix = if dist_mi <= self.overpass_mi {
-                "OVER"
+            let (prefix, kind) = if dist_mi <= self.overpass_mi {
+                ("OVER", AlertKind::Over)
             } else {
-                "NEAR"
+                ("NEAR", AlertKind::Near)
             };
-            let message = format!("{prefix} {callsign} {reg} {dist_mi:.1}mi");
-            debug!("notify {message}");
-            self.notifications.push(Notification { message, at: now });
+            let alt = match self.altitude_for(ac) {
+                Some(Altitude::Ground) => " GND".to_string(),
+                Some(alt) => format!(" {alt}ft"),
+                None => String::new(),
+            };
+            let message = format!("{prefix} {callsign} {reg} {dist_mi:.1}mi{alt}");
+            self.push_alert(kind, ac, Some(dist_mi), message, now);
         }
+    }
 
-        if self.notifications.len() > 10 {
-            let excess = self.notifications.len() - 10;
-            self.notifications.drain(0..excess);
+    fn push_alert(
+        &mut self,
+        kind: AlertKind,
+        ac: &Aircraft,
+        distance_mi: Option<f64>,
+        message: String,
+        now: SystemTime,
+    ) {
+        debug!("notify {message}");
+        let quiet = self.dnd_active();
+        if quiet {
+            info!("quiet hours: {message} not sent");
+        }
+        let desktop = self
+            .desktop_tx
+            .as_ref()
+            .filter(|_| !quiet && self.desktop_alerts.enabled(kind));
+        let hooks = self.hook_tx.as_ref().filter(|_| !quiet);
+        if desktop.is_some() || hooks.is_some() {
+            let text = |value: Option<&str>| value.map(str::trim).unwrap_or_default().to_string();
+            let event = AlertEvent {
+                kind,
+                message: message.clone(),
+                hex: text(ac.hex.as_deref()),
+                callsign: text(ac.flight.as_deref()),
+                reg: text(ac.r.as_deref()),
+                squawk: text(ac.squawk.as_deref()),
+                distance_mi,
+                alt_ft: self.altitude_ft(ac),
+                at: now
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .map_or(0, |d| d.as_secs() as i64),
+            };
+            if let Some(tx) = desktop {
+                let _ = tx.send(event.clone());
+            }
+            if let Some(tx) = hooks {
+                let _ = tx.send(event);
+            }
+        }
+        let event = match kind {
+            AlertKind::Emergency => Some(EventKind::Emergency),
+            AlertKind::Watch => Some(EventKind::Watch),
+            _ => None,
+        };
+        if let Some(event) = event {
+            self.events.push(event, unix_secs(now), message.clone());
+        }
+        self.push_notification(Notification {
+            message,
+            at: now,
+            alert: true,
+            read: false,
+        });
+    }
+
+    fn update_events(&mut self, data: &ApiResponse, now: SystemTime) {
+        let at = unix_secs(now);
+        for ac in &data.aircraft {
+            if self.classify_aircraft(ac) != AircraftRole::Military {
+                continue;
+            }
+            let Some(hex) = ac.hex.as_deref() else {
+                continue;
+            };
+            if self.events.first_military(hex) {
+                let callsign = ac.flight.as_deref().unwrap_or("--").trim();
+                let reg = ac.r.as_deref().unwrap_or("--");
+                let ac_type = ac.t.as_deref().unwrap_or("--");
+                let message = format!("MIL {callsign} {reg} {ac_type} {}", hex.trim());
+                self.events.push(EventKind::Military, at, message);
+            }
+        }
+        let types = data.aircraft.iter().filter_map(|ac| ac.t.as_deref());
+        for ac_type in self.events.new_types(types) {
+            let ac = data.aircraft.iter().find(|ac| {
+                ac.t.as_deref()
+                    .is_some_and(|t| t.trim().eq_ignore_ascii_case(&ac_type))
+            });
+            let callsign = ac.and_then(|ac| ac.flight.as_deref()).unwrap_or("--");
+            let reg = ac.and_then(|ac| ac.r.as_deref()).unwrap_or("--");
+            let message = format!("TYPE {ac_type} {} {reg}", callsign.trim());
+            self.events.push(EventKind::NewType, at, message);
+        }
+    }
+
+    fn site_distance_mi(&self, ac: &Aircraft) -> Option<f64> {
+        let site = self.site()?;
+        Some(distance_mi(site.lat, site.lon, ac.lat?, ac.lon?))
+    }
+
+    fn update_emergency_notifications(&mut self, data: &ApiResponse, now: SystemTime) {
+        for ac in &data.aircraft {
+            let Some(code) = ac.emergency_code() else {
+                continue;
+            };
+            let key = if let Some(hex) = ac.hex.as_deref() {
+                format!("emg:hex:{}", normalize_hex(hex))
+            } else if let Some(flight) = ac.flight.as_deref() {
+                format!("emg:flt:{}", normalize_callsign(flight))
+            } else {
+                continue;
+            };
+            let should_notify = match self.notified_recent.get(&key) {
+                Some(last) => now
+                    .duration_since(*last)
+                    .map(|d| d >= self.notify_cooldown)
+                    .unwrap_or(true),
+                None => true,
+            };
+            if !should_notify {
+                continue;
+            }
+            self.notified_recent.insert(key, now);
+
+            let callsign = ac.flight.as_deref().unwrap_or("--").trim();
+            let reg = ac.r.as_deref().unwrap_or("--");
+            let squawk = ac.squawk.as_deref().unwrap_or("--");
+            let distance = self.site_distance_mi(ac);
+            let message = format!(
+                "EMERG {callsign} {reg} {} SQK {squawk}",
+                code.to_ascii_uppercase()
+            );
+            self.push_alert(AlertKind::Emergency, ac, distance, message, now);
+        }
+    }
+
+    fn update_zone_notifications(&mut self, data: &ApiResponse, now: SystemTime) {
+        if self.zones.is_empty() {
+            return;
+        }
+        let positions: Vec<(&str, Option<(f64, f64)>)> = data
+            .aircraft
+            .iter()
+            .filter_map(|ac| {
+                let position = ac.lat.zip(ac.lon).filter(|_| !self.position_stale(ac));
+                Some((ac.hex.as_deref()?, position))
+            })
+            .collect();
+        let crossings = self.zone_tracker.update(&self.zones, positions);
+
+        for crossing in crossings {
+            let Some(ac) = data.aircraft.iter().find(|ac| {
+                ac.hex
+                    .as_deref()
+                    .is_some_and(|hex| hex.trim().eq_ignore_ascii_case(&crossing.hex))
+            }) else {
+                continue;
+            };
+            let zone = &self.zones[crossing.zone];
+            match zone.alert_mode() {
+                ZoneAlert::Off => continue,
+                ZoneAlert::Watchlist if !self.is_watchlisted(ac) && !self.is_favorite(ac) => {
+                    continue
+                }
+                _ => {}
+            }
+            let direction = if crossing.entered { "IN" } else { "OUT" };
+            let key = format!("zone:{}:{direction}:{}", zone.name, crossing.hex);
+            let should_notify = match self.notified_recent.get(&key) {
+                Some(last) => now
+                    .duration_since(*last)
+                    .map(|d| d >= self.notify_cooldown)
+                    .unwrap_or(true),
+                None => true,
+            };
+            if !should_notify {
+                continue;
+            }
+            self.notified_recent.insert(key, now);
+
+            let callsign = ac.flight.as_deref().unwrap_or("--").trim();
+            let reg = ac.r.as_deref().unwrap_or("--");
+            let message = format!("ZONE {direction} {} {callsign} {reg}", zone.name);
+            let distance = self.site_distance_mi(ac);
+            self.push_alert(AlertKind::Zone, ac, distance, message, now);
+        }
+    }
+
+    fn update_cpa_notifications(&mut self, data: &ApiResponse, now: SystemTime) {
+        let Some(site) = self.site() else {
+            return;
+        };
+        if self.cpa_notify_mi <= 0.0 {
+            return;
+        }
+
+        for ac in &data.aircraft {
+            if ac.on_ground() || self.position_stale(ac) {
+                continue;
+            }
+            let Some(cpa) = closest_approach(site, ac) else {
+                continue;
+            };
+            if cpa.minutes <= 0.0
+                || cpa.minutes > self.cpa_notify_mins
+                || cpa.distance_mi > self.cpa_notify_mi
+            {
+                continue;
+            }
+            let key = if let Some(hex) = ac.hex.as_deref() {
+                format!("cpa:hex:{}", normalize_hex(hex))
+            } else if let Some(flight) = ac.flight.as_deref() {
+                format!("cpa:flt:{}", normalize_callsign(flight))
+            } else {
+                continue;
+            };
+            let should_notify = match self.notified_recent.get(&key) {
+                Some(last) => now
+                    .duration_since(*last)
+                    .map(|d| d >= self.notify_cooldown)
+                    .unwrap_or(true),
+                None => true,
+            };
+            if !should_notify {
+                continue;
+            }
+            self.notified_recent.insert(key, now);
+
+            let callsign = ac.flight.as_deref().unwrap_or("--").trim();
+            let reg = ac.r.as_deref().unwrap_or("--");
+            let message = format!(
+                "CPA {callsign} {reg} {:.1}mi in {:.0}min",
+                cpa.distance_mi, cpa.minutes
+            );
+            let distance = self.site_distance_mi(ac);
+            self.push_alert(AlertKind::Cpa, ac, distance, message, now);
         }
     }
 
@@ -2394,53 +5337,129 @@ impl App {
                 .filter(|s| !s.trim().is_empty())
                 .unwrap_or(entry_id.as_str());
             let message = format!("WATCH {label} {callsign} {reg}");
-            debug!("notify {message}");
-            self.notifications.push(Notification { message, at: now });
-        }
-
-        if self.notifications.len() > 10 {
-            let excess = self.notifications.len() - 10;
-            self.notifications.drain(0..excess);
+            let distance = self.site_distance_mi(ac);
+            self.push_alert(AlertKind::Watch, ac, distance, message, now);
         }
     }
 
     fn swap_snapshot(&mut self) {
-        let mut next = self.raw_data.clone();
+        let Some(mut next) = self.pending.take() else {
+            return;
+        };
         if self.smooth_merge {
-            merge_api_response(&mut next, &self.data);
+            merge_api_response(&mut next, &self.data, &self.aircraft_keys);
         }
         self.data = next;
+        self.data_shown_at = Some(Instant::now());
+        self.swapped_version = self.data_version;
+        self.refresh_geometry();
+        self.record_sightings();
+    }
+
+    fn record_sightings(&mut self) {
+        let now_secs = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() as i64);
+        let distances: Vec<Option<f64>> = self
+            .geometry
+            .iter()
+            .map(|cached| cached.as_ref().map(|c| c.geometry.distance_nm))
+            .collect();
+        if let Some(tx) = &self.sightings_tx {
+            let batch = SightingBatch::new(
+                now_secs,
+                self.data.aircraft.iter().zip(distances.iter().copied()),
+            );
+            if !batch.is_empty() {
+                let _ = tx.send(batch);
+            }
+        }
+        if self.input_mode == InputMode::History {
+            self.load_history_page();
+        }
+        self.session
+            .record(now_secs, self.data.aircraft.iter().zip(distances));
     }
 
-    fn matches_filter(&self, ac: &Aircraft) -> bool {
-        let filter = self.filter.trim();
-        if filter.is_empty() {
-            return true;
+    fn refresh_geometry(&mut self) {
+        let site = self.site();
+        self.geometry = self
+            .data
+            .aircraft
+            .iter()
+            .map(|ac| {
+                let site = site?;
+                let position = (ac.lat?, ac.lon?);
+                Some(CachedGeometry {
+                    site: (site.lat, site.lon),
+                    position,
+                    geometry: compute_site_geometry(site, position.0, position.1),
+                })
+            })
+            .collect();
+        if let Some(site) = site {
+            let record = |traffic: &TrafficHistory| {
+                traffic
+                    .max_range((site.lat, site.lon))
+                    .map(|sectors| sectors.iter().copied().fold(0.0, f64::max))
+            };
+            let previous = record(&self.traffic).filter(|nm| *nm > 0.0);
+            for cached in self.geometry.iter().flatten() {
+                self.traffic.record_range(
+                    (site.lat, site.lon),
+                    cached.geometry.bearing_deg,
+                    cached.geometry.distance_nm,
+                );
+            }
+            if let (Some(previous), Some(current)) = (previous, record(&self.traffic)) {
+                if current > previous {
+                    self.log_range_record(current);
+                }
+            }
         }
-        let needle = filter.to_lowercase();
-        let haystacks = [
-            ac.flight.as_deref(),
-            ac.r.as_deref(),
-            ac.t.as_deref(),
-            ac.desc.as_deref(),
-            ac.own_op.as_deref(),
-            ac.hex.as_deref(),
-        ];
+    }
 
-        haystacks.iter().any(|value| {
-            value
-                .map(|v| v.to_lowercase().contains(&needle))
-                .unwrap_or(false)
-        })
+    fn log_range_record(&mut self, distance_nm: f64) {
+        let Some((idx, geometry)) = self.geometry.iter().enumerate().find_map(|(idx, cached)| {
+            let geometry = cached.as_ref()?.geometry;
+            (geometry.distance_nm == distance_nm).then_some((idx, geometry))
+        }) else {
+            return;
+        };
+        let ac = &self.data.aircraft[idx];
+        let callsign = ac.flight.as_deref().unwrap_or("--").trim();
+        let reg = ac.r.as_deref().unwrap_or("--");
+        let message = format!(
+            "RANGE {callsign} {reg} {distance_nm:.1}nm {:.0}°",
+            geometry.bearing_deg
+        );
+        self.events
+            .push(EventKind::Range, unix_secs(SystemTime::now()), message);
     }
-}
 
-fn normalize_hex(value: &str) -> String {
-    value.trim().to_ascii_lowercase()
-}
+    pub fn site_geometry(&self, idx: usize) -> Option<SiteGeometry> {
+        let site = self.site()?;
+        let ac = self.data.aircraft.get(idx)?;
+        let (lat, lon) = (ac.lat?, ac.lon?);
+        match self.geometry.get(idx).copied().flatten() {
+            Some(cached)
+                if cached.site == (site.lat, site.lon) && cached.position == (lat, lon) =>
+            {
+                Some(cached.geometry)
+            }
+            _ => Some(compute_site_geometry(site, lat, lon)),
+        }
+    }
 
-fn normalize_callsign(value: &str) -> String {
-    value.trim().to_ascii_lowercase()
+    /// An invalid filter expression hides everything until it is fixed.
+    fn matches_filter(&self, idx: usize, ac: &Aircraft) -> bool {
+        match &self.filter_query {
+            Ok(filter) => filter.matches(ac, &|| {
+                self.site_geometry(idx).map(|geometry| geometry.distance_nm)
+            }),
+            Err(_) => false,
+        }
+    }
 }
 
 fn normalize_text(value: &str) -> String {
@@ -2480,6 +5499,24 @@ fn rsi_from_series(values: &[u64], period: usize) -> Option<f64> {
     Some(100.0 - (100.0 / (1.0 + rs)))
 }
 
+/// A snapshot's `now` (seconds, or milliseconds from some feeds) as a time.
+fn snapshot_time(now: Option<i64>) -> Option<SystemTime> {
+    let mut value = now?;
+    if value > 4_000_000_000 {
+        value /= 1000;
+    }
+    if value > 0 {
+        SystemTime::UNIX_EPOCH.checked_add(Duration::from_secs(value as u64))
+    } else {
+        None
+    }
+}
+
+fn unix_secs(time: SystemTime) -> i64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
 fn is_rate_limited_message(message: &str) -> bool {
     let msg = message.to_ascii_lowercase();
     msg.contains(" 429")
@@ -2488,123 +5525,6 @@ fn is_rate_limited_message(message: &str) -> bool {
         || msg.contains("rate limit")
 }
 
-fn classify_aircraft(ac: &Aircraft) -> AircraftRole {
-    let callsign = ac
-        .flight
-        .as_deref()
-        .unwrap_or("")
-        .trim()
-        .to_ascii_uppercase();
-    let owner = ac.own_op.as_deref().unwrap_or("").to_ascii_lowercase();
-    let ac_type = ac.t.as_deref().unwrap_or("").to_ascii_uppercase();
-    let desc = ac.desc.as_deref().unwrap_or("").to_ascii_lowercase();
-    let category = ac.category.as_deref().unwrap_or("").to_ascii_lowercase();
-
-    if matches_prefix(&callsign, &military_callsign_prefixes()) {
-        return AircraftRole::Military;
-    }
-
-    if matches_prefix(&callsign, &government_callsign_prefixes()) {
-        return AircraftRole::Government;
-    }
-
-    if contains_any(&owner, &military_owner_keywords())
-        || contains_any(&desc, &military_owner_keywords())
-        || likely_military_type(&ac_type)
-        || category.contains("mil")
-    {
-        return AircraftRole::Military;
-    }
-
-    if contains_any(&owner, &government_owner_keywords())
-        || contains_any(&desc, &government_owner_keywords())
-        || matches_prefix(&callsign, &government_callsign_prefixes())
-    {
-        return AircraftRole::Government;
-    }
-
-    AircraftRole::Commercial
-}
-
-fn matches_prefix(value: &str, prefixes: &[&str]) -> bool {
-    if value.is_empty() {
-        return false;
-    }
-    prefixes
-        .iter()
-        .any(|p| value.starts_with(p) && value.len() >= p.len())
-}
-
-fn contains_any(value: &str, needles: &[&str]) -> bool {
-    let lower = value.to_ascii_lowercase();
-    needles.iter().any(|n| lower.contains(n))
-}
-
-fn likely_military_type(ac_type: &str) -> bool {
-    let prefixes = [
-        "C17", "C-17", "C130", "C-130", "K35", "KC10", "KC-10", "KC46", "E3", "E-3", "E6", "E-6",
-        "P8", "P-8", "P3", "P-3", "B52", "B-52", "F1", "F2", "F3", "F4", "F5", "F6", "F14", "F15",
-        "F16", "F18", "F22", "F35",
-    ];
-    let upper = ac_type.to_ascii_uppercase();
-    prefixes.iter().any(|p| upper.starts_with(p))
-}
-
-fn military_callsign_prefixes() -> Vec<&'static str> {
-    vec![
-        "RCH", "MC", "MAF", "NAF", "BAF", "GAF", "LAGR", "TEXAN", "PAT", "SAM", "SPAR", "ASF",
-        "CFC", "CAF", "HK", "VV", "VM", "AF", "SEN", "CNV", "JENA", "KING",
-    ]
-}
-
-fn government_callsign_prefixes() -> Vec<&'static str> {
-    vec![
-        "NATION", "GOV", "GVT", "POL", "RIDER", "EAG", "EAGLE", "COAST", "CST",
-    ]
-}
-
-fn military_owner_keywords() -> Vec<&'static str> {
-    vec![
-        "air force",
-        "navy",
-        "marine",
-        "marines",
-        "army",
-        "usaf",
-        "usn",
-        "usmc",
-        "uscg",
-        "raf",
-        "rcaf",
-        "luftwaffe",
-        "bundeswehr",
-        "aeronautica",
-        "military",
-        "defence",
-        "defense",
-        "armée",
-        "ejército",
-    ]
-}
-
-fn government_owner_keywords() -> Vec<&'static str> {
-    vec![
-        "government",
-        "gov",
-        "border",
-        "customs",
-        "police",
-        "gendarmerie",
-        "coast guard",
-        "homeland",
-        "cbp",
-        "state",
-        "federal",
-        "department",
-        "ministry",
-    ]
-}
-
 fn parse_retry_after_hint(message: &str) -> Option<u64> {
     message
         .split(|c: char| [' ', '(', ')'].contains(&c))
@@ -2637,7 +5557,7 @@ fn match_text(needle: &str, haystack: &str, mode: &str) -> bool {
     }
 }
 
-fn parse_lookup_input(input: &str) -> Option<LookupKind> {
+pub(crate) fn parse_lookup_input(input: &str) -> Option<LookupKind> {
     let trimmed = input.trim();
     if trimmed.is_empty() {
         return None;
@@ -2732,11 +5652,18 @@ fn watch_entry_matches(entry: &WatchEntry, ac: &Aircraft, route: Option<&RouteIn
             .as_deref()
             .map(|owner| match_text(&normalize_text(value), &normalize_text(owner), mode))
             .unwrap_or(false),
-        "category" => ac
-            .category
-            .as_deref()
-            .map(|cat| match_text(&normalize_text(value), &normalize_text(cat), mode))
-            .unwrap_or(false),
+        "category" => {
+            let value = normalize_text(value);
+            let code = ac
+                .category
+                .as_deref()
+                .map(|cat| match_text(&value, &normalize_text(cat), mode))
+                .unwrap_or(false);
+            code || ac
+                .category_class()
+                .map(|class| match_text(&value, class.name(), mode))
+                .unwrap_or(false)
+        }
         "route" => {
             let Some(info) = route else { return false };
             let mut route_text = String::new();
@@ -2846,6 +5773,24 @@ fn compare_i64(prev: Option<i64>, current: Option<i64>) -> TrendDir {
     }
 }
 
+fn smooth_vertical_rate(prev: Option<f64>, rate: Option<i64>) -> Option<f64> {
+    let rate = rate? as f64;
+    Some(match prev {
+        Some(prev) => prev + VS_SMOOTHING_ALPHA * (rate - prev),
+        None => rate,
+    })
+}
+
+fn vertical_rate_trend(vs: f64) -> TrendDir {
+    if vs >= VS_TREND_FPM {
+        TrendDir::Up
+    } else if vs <= -VS_TREND_FPM {
+        TrendDir::Down
+    } else {
+        TrendDir::Flat
+    }
+}
+
 fn compare_f64(prev: Option<f64>, current: Option<f64>) -> TrendDir {
     match (prev, current) {
         (Some(p), Some(c)) if c > p => TrendDir::Up,
@@ -2855,32 +5800,59 @@ fn compare_f64(prev: Option<f64>, current: Option<f64>) -> TrendDir {
     }
 }
 
-fn distance_mi(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
-    let r_mi = 3958.8_f64;
-    let dlat = (lat2 - lat1).to_radians();
-    let dlon = (lon2 - lon1).to_radians();
-    let lat1 = lat1.to_radians();
-    let lat2 = lat2.to_radians();
-    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
-    let c = 2.0 * a.sqrt().atan2((1.0 - a).sqrt());
-    r_mi * c
+fn implausible_jump(last: TrailPoint, lat: f64, lon: f64, now: SystemTime) -> bool {
+    let dist = distance_nm(last.lat, last.lon, lat, lon);
+    if dist < TRAIL_JUMP_MIN_NM {
+        return false;
+    }
+    let secs = now
+        .duration_since(last.at)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+        .max(1.0);
+    dist / (secs / 3600.0) > TRAIL_JUMP_MAX_KT
 }
 
-fn distance_nm(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
-    distance_mi(lat1, lon1, lat2, lon2) / 1.15078
+/// Closest approach to `site` assuming the aircraft holds its ground speed
+/// and track, on a flat-earth projection around the site.
+pub fn closest_approach(site: SiteLocation, ac: &Aircraft) -> Option<ClosestApproach> {
+    let (lat, lon, gs, track) = (ac.lat?, ac.lon?, ac.gs?, ac.track?);
+    let speed_mph = kt_to_mph(gs);
+    if speed_mph <= 0.0 {
+        return None;
+    }
+    let dist = distance_mi(site.lat, site.lon, lat, lon);
+    let brg = bearing_deg(site.lat, site.lon, lat, lon).to_radians();
+    let (x, y) = (dist * brg.sin(), dist * brg.cos());
+    let track = track.to_radians();
+    let (vx, vy) = (speed_mph * track.sin(), speed_mph * track.cos());
+    let hours = -(x * vx + y * vy) / (speed_mph * speed_mph);
+    if hours <= 0.0 {
+        return Some(ClosestApproach {
+            minutes: 0.0,
+            distance_mi: dist,
+        });
+    }
+    Some(ClosestApproach {
+        minutes: hours * 60.0,
+        distance_mi: (x + vx * hours).hypot(y + vy * hours),
+    })
 }
 
-fn bearing_deg(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
-    let lat1 = lat1.to_radians();
-    let lat2 = lat2.to_radians();
-    let dlon = (lon2 - lon1).to_radians();
-    let y = dlon.sin() * lat2.cos();
-    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
-    let mut deg = y.atan2(x).to_degrees();
-    if deg < 0.0 {
-        deg += 360.0;
+fn compute_site_geometry(site: SiteLocation, lat: f64, lon: f64) -> SiteGeometry {
+    SiteGeometry {
+        distance_nm: distance_nm(site.lat, site.lon, lat, lon),
+        bearing_deg: bearing_deg(site.lat, site.lon, lat, lon),
     }
-    deg
+}
+
+fn site_index_of(sites: &[NamedSite], site: Option<SiteLocation>) -> Option<usize> {
+    let site = site?;
+    sites.iter().position(|named| {
+        named.location.lat == site.lat
+            && named.location.lon == site.lon
+            && named.location.alt_m == site.alt_m
+    })
 }
 
 fn offset_center(center: RadarCenter, direction: RadarDirection, distance_nm: f64) -> RadarCenter {
@@ -2937,7 +5909,50 @@ fn radar_candidate_distance(current: RadarNavPoint, candidate: RadarNavPoint) ->
     dx * dx + dy * dy
 }
 
-fn merge_api_response(target: &mut ApiResponse, prev: &ApiResponse) {
+/// Remove the oldest entries until `map` holds at most `cap`, returning the
+/// evicted keys. A cap of zero leaves the map unbounded.
+fn evict_oldest<K, V>(
+    map: &mut HashMap<K, V>,
+    cap: usize,
+    stamp: impl Fn(&V) -> SystemTime,
+) -> Vec<K>
+where
+    K: Clone + Eq + Hash,
+{
+    if cap == 0 || map.len() <= cap {
+        return Vec::new();
+    }
+    let excess = map.len() - cap;
+    let mut entries: Vec<(SystemTime, K)> = map
+        .iter()
+        .map(|(key, value)| (stamp(value), key.clone()))
+        .collect();
+    entries.select_nth_unstable_by_key(excess - 1, |(at, _)| *at);
+    entries.truncate(excess);
+    entries
+        .into_iter()
+        .map(|(_, key)| {
+            map.remove(&key);
+            key
+        })
+        .collect()
+}
+
+/// Whether a cached route is recent enough not to be requested again; with
+/// a zero `ttl` routes are always requested again.
+fn route_fresh(info: &RouteInfo, ttl: Duration, now: SystemTime) -> bool {
+    !ttl.is_zero()
+        && now
+            .duration_since(info.fetched_at)
+            .map(|d| d < ttl)
+            .unwrap_or(true)
+}
+
+fn older_than(at: SystemTime, now: SystemTime, age: Duration) -> bool {
+    now.duration_since(at).is_ok_and(|d| d > age)
+}
+
+fn merge_api_response(target: &mut ApiResponse, prev: &ApiResponse, keys: &KeyInterner) {
     if target.now.is_none() {
         target.now = prev.now;
     }
@@ -2945,25 +5960,15 @@ fn merge_api_response(target: &mut ApiResponse, prev: &ApiResponse) {
         target.messages = prev.messages;
     }
 
-    let mut prev_map = HashMap::new();
+    let mut prev_map = HashMap::with_capacity(prev.aircraft.len());
     for ac in &prev.aircraft {
-        if let Some(hex) = ac.hex.as_deref() {
-            prev_map.insert(format!("hex:{}", normalize_hex(hex)), ac);
-        } else if let Some(flight) = ac.flight.as_deref() {
-            prev_map.insert(format!("flt:{}", normalize_callsign(flight)), ac);
+        if let Some(key) = keys.key(ac) {
+            prev_map.insert(key, ac);
         }
     }
 
     for ac in &mut target.aircraft {
-        let key = if let Some(hex) = ac.hex.as_deref() {
-            Some(format!("hex:{}", normalize_hex(hex)))
-        } else {
-            ac.flight
-                .as_deref()
-                .map(|flight| format!("flt:{}", normalize_callsign(flight)))
-        };
-
-        let Some(key) = key else { continue };
+        let Some(key) = keys.key(ac) else { continue };
         let Some(prev_ac) = prev_map.get(&key) else {
             continue;
         };
@@ -2978,14 +5983,29 @@ fn merge_api_response(target: &mut ApiResponse, prev: &ApiResponse) {
         fill_string(&mut ac.year, &prev_ac.year);
         fill_string(&mut ac.category, &prev_ac.category);
         fill_string(&mut ac.sil_type, &prev_ac.sil_type);
+        fill_string(&mut ac.squawk, &prev_ac.squawk);
+        fill_string(&mut ac.emergency, &prev_ac.emergency);
+        if ac.nav_modes.is_none() {
+            ac.nav_modes = prev_ac.nav_modes.clone();
+        }
 
         fill_copy(&mut ac.alt_baro, prev_ac.alt_baro);
         fill_copy(&mut ac.alt_geom, prev_ac.alt_geom);
         fill_copy(&mut ac.gs, prev_ac.gs);
         fill_copy(&mut ac.track, prev_ac.track);
         fill_copy(&mut ac.baro_rate, prev_ac.baro_rate);
+        fill_copy(&mut ac.geom_rate, prev_ac.geom_rate);
+        fill_copy(&mut ac.track_rate, prev_ac.track_rate);
+        fill_copy(&mut ac.roll, prev_ac.roll);
+        fill_copy(&mut ac.true_heading, prev_ac.true_heading);
+        fill_copy(&mut ac.mag_heading, prev_ac.mag_heading);
+        fill_copy(&mut ac.ias, prev_ac.ias);
+        fill_copy(&mut ac.tas, prev_ac.tas);
+        fill_copy(&mut ac.mach, prev_ac.mach);
         fill_copy(&mut ac.nav_qnh, prev_ac.nav_qnh);
         fill_copy(&mut ac.nav_altitude_mcp, prev_ac.nav_altitude_mcp);
+        fill_copy(&mut ac.nav_altitude_fms, prev_ac.nav_altitude_fms);
+        fill_copy(&mut ac.nav_heading, prev_ac.nav_heading);
         fill_copy(&mut ac.lat, prev_ac.lat);
         fill_copy(&mut ac.lon, prev_ac.lon);
         fill_copy(&mut ac.nic, prev_ac.nic);
@@ -2996,11 +6016,17 @@ fn merge_api_response(target: &mut ApiResponse, prev: &ApiResponse) {
         fill_copy(&mut ac.nac_p, prev_ac.nac_p);
         fill_copy(&mut ac.nac_v, prev_ac.nac_v);
         fill_copy(&mut ac.sil, prev_ac.sil);
+        fill_copy(&mut ac.gva, prev_ac.gva);
+        fill_copy(&mut ac.sda, prev_ac.sda);
         fill_copy(&mut ac.alert, prev_ac.alert);
         fill_copy(&mut ac.spi, prev_ac.spi);
         fill_copy(&mut ac.messages, prev_ac.messages);
         fill_copy(&mut ac.seen, prev_ac.seen);
         fill_copy(&mut ac.rssi, prev_ac.rssi);
+        fill_copy(&mut ac.wd, prev_ac.wd);
+        fill_copy(&mut ac.ws, prev_ac.ws);
+        fill_copy(&mut ac.oat, prev_ac.oat);
+        fill_copy(&mut ac.tat, prev_ac.tat);
     }
 }
 
@@ -3032,102 +6058,182 @@ fn default_columns() -> Vec<ColumnConfig> {
             label: "FLAG",
             width: 2,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Fav,
             label: "*",
             width: 1,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Watch,
             label: "W",
             width: 1,
             visible: true,
+            fixed_width: None,
+        },
+        ColumnConfig {
+            id: ColumnId::Pin,
+            label: "T",
+            width: 1,
+            visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Flight,
             label: "FLIGHT",
             width: 8,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Reg,
             label: "REG",
             width: 8,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Type,
             label: "TYPE",
             width: 5,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Route,
             label: "ROUTE",
             width: 9,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Alt,
             label: "ALT",
             width: 7,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Gs,
             label: "GS",
             width: 6,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Trk,
             label: "TRK",
             width: 5,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Lat,
             label: "LAT",
             width: 9,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Lon,
             label: "LON",
             width: 9,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Dist,
             label: "DIST",
             width: 6,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Brg,
             label: "BRG",
             width: 5,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Seen,
             label: "SEEN",
             width: 6,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Msgs,
             label: "MSGS",
             width: 6,
             visible: true,
+            fixed_width: None,
         },
         ColumnConfig {
             id: ColumnId::Hex,
             label: "HEX",
             width: 6,
             visible: true,
+            fixed_width: None,
+        },
+        ColumnConfig {
+            id: ColumnId::Ver,
+            label: "VER",
+            width: 3,
+            visible: false,
+            fixed_width: None,
+        },
+        ColumnConfig {
+            id: ColumnId::Class,
+            label: "CLASS",
+            width: 7,
+            visible: false,
+            fixed_width: None,
+        },
+        ColumnConfig {
+            id: ColumnId::Cat,
+            label: "CAT",
+            width: 4,
+            visible: false,
+            fixed_width: None,
+        },
+        ColumnConfig {
+            id: ColumnId::Vs,
+            label: "V/S",
+            width: 7,
+            visible: false,
+            fixed_width: None,
+        },
+        ColumnConfig {
+            id: ColumnId::Cpa,
+            label: "CPA",
+            width: 8,
+            visible: false,
+            fixed_width: None,
+        },
+        ColumnConfig {
+            id: ColumnId::Src,
+            label: "SRC",
+            width: 5,
+            visible: false,
+            fixed_width: None,
+        },
+        ColumnConfig {
+            id: ColumnId::Source,
+            label: "SOURCE",
+            width: 6,
+            visible: false,
+            fixed_width: None,
+        },
+        ColumnConfig {
+            id: ColumnId::Apt,
+            label: "APT",
+            width: 10,
+            visible: false,
+            fixed_width: None,
         },
     ]
 }
@@ -3145,7 +6251,20 @@ fn load_config_items(path: &PathBuf) -> Vec<ConfigItem> {
             index.insert(item.key.clone(), idx);
         }
         let mut extras = Vec::new();
+        let mut entries = Vec::new();
         for (key, value) in map {
+            match value {
+                // `[keys]` is edited as flat `keys.*` items; other tables (profiles) are left alone.
+                Value::Table(section) if key == "keys" => entries.extend(
+                    section
+                        .iter()
+                        .map(|(name, value)| (format!("keys.{name}"), value)),
+                ),
+                Value::Table(_) => {}
+                _ => entries.push((key.clone(), value)),
+            }
+        }
+        for (key, value) in entries {
             let kind = match value {
                 Value::String(_) => config::ConfigKind::Str,
                 Value::Integer(_) => config::ConfigKind::Int,
@@ -3154,14 +6273,16 @@ fn load_config_items(path: &PathBuf) -> Vec<ConfigItem> {
                 _ => config::ConfigKind::Str,
             };
             let value = toml_value_to_string(value).unwrap_or_else(|| value.to_string());
-            if let Some(idx) = index.get(key) {
+            if let Some(idx) = index.get(&key) {
                 if let Some(item) = items.get_mut(*idx) {
+                    item.saved = value.clone();
                     item.value = value;
                     item.kind = kind;
                 }
             } else {
                 extras.push(ConfigItem {
-                    key: key.to_string(),
+                    key,
+                    saved: value.clone(),
                     value,
                     kind,
                 });
@@ -3183,6 +6304,7 @@ fn default_config_items() -> Vec<ConfigItem> {
             key: spec.key.to_string(),
             value: spec.default_string(),
             kind: spec.kind,
+            saved: spec.default_string(),
         })
         .collect()
 }
@@ -3225,6 +6347,78 @@ fn parse_config_value(kind: config::ConfigKind, raw: &str) -> Result<Option<Valu
     }
 }
 
+/// A missing file is an empty document; one that cannot be read or parsed is
+/// an error, so it is never overwritten with just the setting being saved.
+fn read_config_doc(path: &Path) -> Result<DocumentMut, String> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err.to_string()),
+    };
+    text.parse::<DocumentMut>().map_err(|err| err.to_string())
+}
+
+fn write_config_file(path: &Path, text: &str) -> std::io::Result<()> {
+    if let Some(parent) = path.parent().filter(|path| !path.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, text)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(path, fs::Permissions::from_mode(0o600));
+    }
+    Ok(())
+}
+
+/// Orders two sort values; a missing value goes last in either direction.
+fn order_by<T>(
+    a: Option<T>,
+    b: Option<T>,
+    descending: bool,
+    cmp: impl Fn(&T, &T) -> Ordering,
+) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => cmp(&b, &a),
+        (Some(a), Some(b)) => cmp(&a, &b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn sort_text(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|value| !value.is_empty())
+}
+
+fn set_doc_value(doc: &mut DocumentMut, key: &str, value: Option<toml_edit::Item>) {
+    let Some((section, name)) = key.split_once('.') else {
+        match value {
+            Some(value) => doc[key] = value,
+            None => {
+                doc.remove(key);
+            }
+        }
+        return;
+    };
+    match value {
+        Some(value) => {
+            if !doc.get(section).is_some_and(|item| item.is_table_like()) {
+                doc[section] = toml_edit::table();
+            }
+            doc[section][name] = value;
+        }
+        None => {
+            if let Some(table) = doc
+                .get_mut(section)
+                .and_then(|item| item.as_table_like_mut())
+            {
+                table.remove(name);
+            }
+        }
+    }
+}
+
 fn to_edit_value(value: Value) -> toml_edit::Item {
     match value {
         Value::String(s) => toml_edit::value(s),
@@ -3257,15 +6451,28 @@ fn toml_value_to_edit(value: Value) -> Option<toml_edit::Value> {
 #[cfg(test)]
 mod tests {
     use super::{
-        compare_f64, compare_i64, distance_mi, load_config_items, parse_config_value,
-        watch_entry_matches, AircraftRole, App, InputMode, PerformanceSample, RadarBlip,
-        RadarCenter, RadarDirection, RadarRenderer, RouteInfo, TrendDir, WatchEntry,
+        build_columns, closest_approach, column_layout_text, compare_f64, compare_i64,
+        load_config_items, parse_column_layout, parse_config_value, watch_entry_matches,
+        AircraftRole, AltitudeSource, App, AppData, ColumnId, ColumnLayout, ColumnWidths,
+        DetailTab, GroundFilter, InputMode, NamedSite, PerfChart, PerfWindow, PerformanceSample,
+        RadarBlip, RadarCenter, RadarDirection, RadarRenderer, RouteInfo, SiteLocation, TrendDir,
+        WatchEntry, MAX_PREDICTION_SECS, NOTIFICATION_CAP,
     };
-    use crate::config::ConfigKind;
-    use crate::model::Aircraft;
-    use std::collections::{HashSet, VecDeque};
+    use crate::config::{Config, ConfigKind, SiteConfig};
+    use crate::events::EventKind;
+    use crate::export::ExportKind;
+    use crate::keys::{Action, Key};
+    use crate::military::MilitaryRanges;
+    use crate::model::{Aircraft, Altitude, ApiResponse, FeedCache, ReceiverStats};
+    use crate::notifier::AlertKinds;
+    use crate::photos::{PhotoCache, PhotoInfo, PhotoMessage};
+    use crate::radar::RadarHit;
+    use crate::roles::RoleRules;
+    use crate::sort::SortSpec;
+    use ratatui::layout::Rect;
+    use std::collections::VecDeque;
     use std::path::PathBuf;
-    use std::time::{Duration, SystemTime};
+    use std::time::{Duration, Instant, SystemTime};
 
     fn sample_aircraft() -> Aircraft {
         Aircraft {
@@ -3288,56 +6495,91 @@ mod tests {
         }
     }
 
+    fn test_config(role_enabled: bool, role_highlight: bool) -> Config {
+        let mut config = crate::config::base_config();
+        config.refresh = Duration::from_secs(1);
+        config.stale_secs = 60;
+        config.hide_stale = false;
+        config.low_nic = 5;
+        config.low_nac = 8;
+        config.overhead_width = 0;
+        config.role_enabled = role_enabled;
+        config.role_highlight = role_highlight;
+        config.column_cache = true;
+        config.trail_len = 3;
+        config.trail_fade_secs = 0;
+        config.trail_altitude_colors = false;
+        config.favorites_file = String::new();
+        config.radar_range_nm = 200.0;
+        config.radar_aspect = 1.0;
+        config.radar_renderer = "canvas".to_string();
+        config.radar_labels = false;
+        config.radar_blip = "dot".to_string();
+        config.route_enabled = false;
+        config.route_ttl_secs = 1;
+        config.route_refresh_secs = 1;
+        config.route_batch = 1;
+        config.ui_fps = 10;
+        config.smooth_mode = true;
+        config.smooth_merge = true;
+        config.rate_window_ms = 300;
+        config.rate_min_secs = 0.2;
+        config.notify_radius_mi = 10.0;
+        config.overpass_mi = 0.5;
+        config.notify_cooldown_secs = 60;
+        config.altitude_trend_arrows = true;
+        config.track_arrows = true;
+        config.flags_enabled = true;
+        config.flag_style = "emoji".to_string();
+        config.stats_metric_1 = "msg_rate_total".to_string();
+        config.stats_metric_2 = "kbps_total".to_string();
+        config.stats_metric_3 = "msg_rate_avg".to_string();
+        config.perf_panel_1 = "messages".to_string();
+        config.perf_panel_2 = "flights".to_string();
+        config.perf_panel_3 = "signal".to_string();
+        config.perf_panel_4 = "app".to_string();
+        config.perf_panel_5 = "histograms".to_string();
+        config.perf_panel_6 = "traffic".to_string();
+        config.watchlist_enabled = true;
+        config.watchlist_file = "adsb-watchlist.toml".to_string();
+        config.export_dir = "exports".to_string();
+        config.ground_filter = "all".to_string();
+        config.altitude_source = "baro".to_string();
+        config.low_quality_positions = "show".to_string();
+        config.position_stale_secs = 60;
+        config.cpa_notify_mi = 0.0;
+        config.cpa_notify_mins = 10.0;
+        config.radar_ghosts = false;
+        config.reduced_motion = false;
+        config.max_tracked_aircraft = 4096;
+        config.route_cache_size = 2048;
+        config.traffic_history_file = String::new();
+        config.sightings_file = String::new();
+        config.notify_desktop = String::new();
+        config.quiet_hours = String::new();
+        config.zones_file = String::new();
+        config.radar_airports = String::new();
+        config.route_labels = "names".to_string();
+        config.weather_station = String::new();
+        config.color_by = "freshness".to_string();
+        config.color_alt_column = true;
+        config.predict_positions = false;
+        config.radar_vectors = false;
+        config.radar_vector_secs = 60;
+        config.radar_compass = true;
+        config.sort_order = "seen".to_string();
+        config.radar_range_rose = false;
+        config.perf_window = "5m".to_string();
+        config
+    }
+
     fn make_app(role_enabled: bool, role_highlight: bool) -> App {
         App::new(
-            "http://example".to_string(),
-            Vec::new(),
-            Duration::from_secs(1),
-            60.0,
-            false,
-            5,
-            8,
-            HashSet::new(),
-            String::new(),
-            crate::app::LayoutMode::Full,
-            crate::app::ThemeMode::Default,
-            role_enabled,
-            role_highlight,
-            true,
-            Duration::from_millis(400),
-            PathBuf::from("adsb-tui.toml"),
-            3,
-            None,
-            None,
-            false,
-            200.0,
-            1.0,
-            crate::app::RadarRenderer::Canvas,
-            false,
-            crate::app::RadarBlip::Dot,
-            false,
-            false,
-            Duration::from_secs(1),
-            Duration::from_secs(1),
-            1,
-            10,
-            true,
-            true,
-            Duration::from_millis(300),
-            0.2,
-            10.0,
-            0.5,
-            Duration::from_secs(60),
-            true,
-            true,
-            true,
-            crate::app::FlagStyle::Emoji,
-            "msg_rate_total".to_string(),
-            "kbps_total".to_string(),
-            "msg_rate_avg".to_string(),
-            true,
-            Some(PathBuf::from("adsb-watchlist.toml")),
-            Vec::new(),
+            &test_config(role_enabled, role_highlight),
+            AppData {
+                url: "http://example".to_string(),
+                ..AppData::default()
+            },
         )
     }
 
@@ -3349,6 +6591,7 @@ mod tests {
             destination: Some("KMIA".to_string()),
             route: None,
             fetched_at: SystemTime::now(),
+            used_at: SystemTime::now(),
         };
 
         let entry = WatchEntry {
@@ -3378,184 +6621,1386 @@ mod tests {
             mode: Some("contains".to_string()),
             ..entry.clone()
         };
-        assert!(watch_entry_matches(&entry, &ac, None));
-
-        let entry = WatchEntry {
-            match_type: "type".to_string(),
-            value: "B38M".to_string(),
-            mode: Some("exact".to_string()),
-            ..entry.clone()
+        assert!(watch_entry_matches(&entry, &ac, None));
+
+        let entry = WatchEntry {
+            match_type: "type".to_string(),
+            value: "B38M".to_string(),
+            mode: Some("exact".to_string()),
+            ..entry.clone()
+        };
+        assert!(watch_entry_matches(&entry, &ac, None));
+
+        let entry = WatchEntry {
+            match_type: "owner".to_string(),
+            value: "SOUTHWEST".to_string(),
+            mode: Some("contains".to_string()),
+            ..entry.clone()
+        };
+        assert!(watch_entry_matches(&entry, &ac, None));
+
+        let entry = WatchEntry {
+            match_type: "category".to_string(),
+            value: "A3".to_string(),
+            mode: Some("exact".to_string()),
+            ..entry.clone()
+        };
+        assert!(watch_entry_matches(&entry, &ac, None));
+
+        let entry = WatchEntry {
+            value: "Medium".to_string(),
+            ..entry.clone()
+        };
+        assert!(watch_entry_matches(&entry, &ac, None));
+
+        let entry = WatchEntry {
+            value: "rotorcraft".to_string(),
+            ..entry.clone()
+        };
+        assert!(!watch_entry_matches(&entry, &ac, None));
+
+        let entry = WatchEntry {
+            match_type: "route".to_string(),
+            value: "KJFK-KMIA".to_string(),
+            mode: Some("exact".to_string()),
+            ..entry.clone()
+        };
+        assert!(watch_entry_matches(&entry, &ac, Some(&route)));
+
+        let entry = WatchEntry {
+            match_type: "unknown".to_string(),
+            value: "X".to_string(),
+            mode: Some("exact".to_string()),
+            ..entry.clone()
+        };
+        assert!(!watch_entry_matches(&entry, &ac, None));
+    }
+
+    #[test]
+    fn watchlist_priority_pick() {
+        let watchlist = vec![
+            WatchEntry {
+                id: Some("low".to_string()),
+                label: None,
+                match_type: "hex".to_string(),
+                value: "ac6668".to_string(),
+                enabled: Some(true),
+                notify: Some(true),
+                priority: Some(1),
+                mode: Some("exact".to_string()),
+                color: None,
+            },
+            WatchEntry {
+                id: Some("high".to_string()),
+                label: None,
+                match_type: "hex".to_string(),
+                value: "ac6668".to_string(),
+                enabled: Some(true),
+                notify: Some(true),
+                priority: Some(5),
+                mode: Some("exact".to_string()),
+                color: None,
+            },
+        ];
+
+        let app = App::new(
+            &test_config(true, true),
+            AppData {
+                url: "http://example".to_string(),
+                watchlist,
+                ..AppData::default()
+            },
+        );
+
+        let ac = sample_aircraft();
+        let entry = app.watch_entry_for(&ac).unwrap();
+        assert_eq!(entry.entry_id(), "high");
+    }
+
+    #[test]
+    fn watch_layout_limits_to_watchlist_and_favorites() {
+        let mut app = make_app(true, true);
+        app.data.aircraft = vec![
+            sample_aircraft(),
+            Aircraft {
+                hex: Some("a00001".to_string()),
+                ..Aircraft::default()
+            },
+            Aircraft {
+                hex: Some("a00002".to_string()),
+                ..Aircraft::default()
+            },
+        ];
+        app.favorites.insert("a00002".to_string());
+        app.watchlist = vec![WatchEntry {
+            id: None,
+            label: None,
+            match_type: "callsign".to_string(),
+            value: "SWA".to_string(),
+            enabled: Some(true),
+            notify: Some(false),
+            priority: None,
+            mode: Some("prefix".to_string()),
+            color: None,
+        }];
+
+        assert_eq!(app.visible_indices().len(), 3);
+
+        app.set_layout(crate::app::LayoutMode::Watch);
+        let mut indices = app.visible_indices();
+        indices.sort();
+        assert_eq!(indices, vec![0, 2]);
+    }
+
+    #[test]
+    fn message_and_position_staleness_are_separate() {
+        let mut app = make_app(true, true);
+        app.hide_stale = true;
+        app.stale_secs = 30.0;
+        app.position_stale_secs = 20.0;
+        app.data.aircraft = vec![
+            Aircraft {
+                hex: Some("a00001".to_string()),
+                lat: Some(51.5),
+                lon: Some(-0.1),
+                seen: Some(1.0),
+                seen_pos: Some(45.0),
+                ..Aircraft::default()
+            },
+            Aircraft {
+                hex: Some("a00002".to_string()),
+                seen: Some(40.0),
+                ..Aircraft::default()
+            },
+        ];
+
+        assert_eq!(app.visible_indices(), vec![0]);
+        assert!(!app.message_stale(&app.data.aircraft[0]));
+        assert!(app.position_stale(&app.data.aircraft[0]));
+        assert!(app.message_stale(&app.data.aircraft[1]));
+        assert!(!app.position_stale(&app.data.aircraft[1]));
+    }
+
+    #[test]
+    fn predicted_position_dead_reckons_and_clamps() {
+        let mut app = make_app(true, true);
+        let moving = |seen_pos: f64| Aircraft {
+            hex: Some("a00001".to_string()),
+            lat: Some(0.0),
+            lon: Some(0.0),
+            gs: Some(360.0),
+            track: Some(90.0),
+            seen_pos: Some(seen_pos),
+            ..Aircraft::default()
+        };
+        app.data_shown_at = Some(Instant::now());
+        assert_eq!(app.predicted_position(&moving(2.0)), None);
+
+        app.predict_positions = true;
+        // 360 kt is 0.1 nm a second, due east along the equator.
+        let (lat, lon) = app.predicted_position(&moving(2.0)).unwrap();
+        assert!(lat.abs() < 1e-9);
+        assert!((lon * 60.0 - 0.2).abs() < 0.01);
+        let (_, lon) = app.predicted_position(&moving(60.0)).unwrap();
+        assert!((lon * 60.0 - 0.1 * MAX_PREDICTION_SECS).abs() < 1e-6);
+
+        let parked = Aircraft {
+            gs: Some(0.0),
+            ..moving(2.0)
+        };
+        assert_eq!(app.predicted_position(&parked), None);
+    }
+
+    #[test]
+    fn ground_filter_splits_surface_and_airborne_traffic() {
+        let mut app = make_app(true, true);
+        app.data.aircraft = vec![
+            Aircraft {
+                hex: Some("a00001".to_string()),
+                alt_baro: Some(Altitude::Ground),
+                ..Aircraft::default()
+            },
+            Aircraft {
+                hex: Some("a00002".to_string()),
+                alt_baro: Some(Altitude::Feet(3500)),
+                ..Aircraft::default()
+            },
+            Aircraft {
+                hex: Some("a00003".to_string()),
+                ..Aircraft::default()
+            },
+        ];
+
+        assert_eq!(app.visible_indices().len(), 3);
+        app.ground_filter = GroundFilter::Ground;
+        assert_eq!(app.visible_indices(), vec![0]);
+        app.ground_filter = GroundFilter::Airborne;
+        let mut indices = app.visible_indices();
+        indices.sort();
+        assert_eq!(indices, vec![1, 2]);
+        assert_eq!(GroundFilter::parse("surface"), Some(GroundFilter::Ground));
+        assert_eq!(GroundFilter::parse("taxi"), None);
+    }
+
+    #[test]
+    fn altitude_source_picks_preferred_altitude() {
+        let ac = Aircraft {
+            alt_baro: Some(Altitude::Feet(5000)),
+            alt_geom: Some(5300),
+            nav_qnh: Some(1023.25),
+            ..Aircraft::default()
+        };
+        assert_eq!(
+            AltitudeSource::Baro.altitude(&ac),
+            Some(Altitude::Feet(5000))
+        );
+        assert_eq!(
+            AltitudeSource::Geom.altitude(&ac),
+            Some(Altitude::Feet(5300))
+        );
+        assert_eq!(
+            AltitudeSource::Qnh.altitude(&ac),
+            Some(Altitude::Feet(5270))
+        );
+
+        let geom_only = Aircraft {
+            alt_geom: Some(1200),
+            ..Aircraft::default()
+        };
+        assert_eq!(
+            AltitudeSource::Baro.altitude(&geom_only),
+            Some(Altitude::Feet(1200))
+        );
+        let surface = Aircraft {
+            alt_baro: Some(Altitude::Ground),
+            alt_geom: Some(75),
+            ..Aircraft::default()
+        };
+        assert_eq!(
+            AltitudeSource::Geom.altitude(&surface),
+            Some(Altitude::Ground)
+        );
+    }
+
+    #[test]
+    fn next_site_cycles_named_sites() {
+        let mut app = make_app(true, true);
+        assert!(app.next_site().is_none());
+        app.sites = NamedSite::from_config(&[
+            SiteConfig {
+                name: "home".to_string(),
+                lat: 26.5,
+                lon: -80.1,
+                alt_m: None,
+            },
+            SiteConfig {
+                name: "work".to_string(),
+                lat: 40.7,
+                lon: -74.0,
+                alt_m: Some(12.0),
+            },
+        ]);
+        app.set_site(Some(26.5), Some(-80.1), None);
+        assert_eq!(app.site_name(), Some("home"));
+
+        app.next_site();
+        assert_eq!(app.site_name(), Some("work"));
+        let site = app.site().unwrap();
+        assert_eq!((site.lat, site.lon, site.alt_m), (40.7, -74.0, 12.0));
+        assert_eq!(app.radar_center().unwrap().lat, 40.7);
+        assert_eq!(app.notifications.last().unwrap().message, "SITE work");
+
+        app.next_site();
+        assert_eq!(app.site_name(), Some("home"));
+    }
+
+    #[test]
+    fn config_reload_applies_only_changed_keys() {
+        let path = std::env::temp_dir().join(format!(
+            "adsb-tui-app-reload-{}.toml",
+            SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_nanos())
+                .unwrap_or(0)
+        ));
+        std::fs::write(&path, "").unwrap();
+        let args = crate::cli::ConfigArgs {
+            config: Some(path.clone()),
+            ..Default::default()
+        };
+        let previous = crate::config::load_config(&args).unwrap();
+        let _ = std::fs::remove_file(&path);
+        let mut next = previous.clone();
+        next.stale_secs = 15;
+        next.theme = "amber".to_string();
+        next.stats_metric_2 = "uptime".to_string();
+        next.perf_panel_4 = "none".to_string();
+        next.notify_desktop = "near overhead".to_string();
+
+        let mut app = App::new(&previous, AppData::default());
+        app.theme_mode = crate::app::ThemeMode::Ocean;
+        app.low_nic = 2;
+        app.desktop_alerts = AlertKinds::parse("watch").unwrap();
+        app.apply_config_reload(next, &["refresh"]);
+
+        assert_eq!(app.stale_secs, 15.0);
+        assert_eq!(app.theme_mode, crate::app::ThemeMode::Amber);
+        assert_eq!(app.stats_metrics[1], "uptime");
+        assert_eq!(app.perf_panels[3], "none");
+        assert_eq!(app.low_nic, 2);
+        assert_eq!(app.desktop_alerts, AlertKinds::parse("watch").unwrap());
+        let note = app.notifications.last().unwrap();
+        assert_eq!(note.message, "CONFIG reloaded; restart to apply refresh");
+    }
+
+    #[test]
+    fn config_pickers_cycle_valid_values() {
+        let mut app = make_app(true, true);
+        app.config_items = load_config_items(&PathBuf::from("/nonexistent/adsb-tui.toml"));
+        let select = |app: &mut App, key: &str| {
+            app.config_cursor = app
+                .config_items
+                .iter()
+                .position(|item| item.key == key)
+                .unwrap();
+        };
+
+        select(&mut app, "theme");
+        app.cycle_config_value(true);
+        assert_eq!(app.config_items[app.config_cursor].value, "color");
+        app.cycle_config_value(false);
+        app.cycle_config_value(false);
+        assert_eq!(app.config_items[app.config_cursor].value, "mono");
+        assert!(app.config_dirty);
+
+        select(&mut app, "radar_labels");
+        let before = app.config_items[app.config_cursor].value.clone();
+        app.start_config_edit();
+        assert!(!app.config_editing);
+        assert_ne!(app.config_items[app.config_cursor].value, before);
+
+        select(&mut app, "stale_secs");
+        assert!(app.selected_config_choices().is_none());
+        app.cycle_config_value(true);
+        assert_eq!(app.config_items[app.config_cursor].value, "60");
+        app.start_config_edit();
+        assert!(app.config_editing);
+    }
+
+    #[test]
+    fn config_editor_save_applies_changed_keys_live() {
+        let path = write_temp_config("stale_secs = 60\ntheme = \"ocean\"\n");
+        let mut app = make_app(true, true);
+        app.config_path = path.clone();
+        app.open_config();
+        app.theme_mode = crate::app::ThemeMode::Matrix;
+        for (key, value) in [
+            ("stale_secs", "20"),
+            ("layout", "radar"),
+            ("site_lat", "26.5"),
+            ("site_lon", "-80.1"),
+            ("refresh_secs", "9"),
+        ] {
+            let item = app
+                .config_items
+                .iter_mut()
+                .find(|item| item.key == key)
+                .unwrap();
+            item.value = value.to_string();
+        }
+
+        assert!(app.save_config());
+
+        assert_eq!(app.stale_secs, 20.0);
+        assert_eq!(app.layout_mode, crate::app::LayoutMode::Radar);
+        assert_eq!(app.theme_mode, crate::app::ThemeMode::Matrix);
+        let site = app.site.expect("site applied");
+        assert_eq!((site.lat, site.lon), (26.5, -80.1));
+        let (status, _) = app.config_status.clone().unwrap();
+        assert!(status.ends_with("; restart to apply refresh"));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn config_editor_round_trips_keys_section() {
+        let path = write_temp_config("[keys]\nquit = \"x\"\n\n[profile.vps]\nrefresh_secs = 5\n");
+        let mut app = make_app(true, true);
+        app.config_path = path.clone();
+        app.open_config();
+        assert!(!app.config_items.iter().any(|item| item.key == "profile"));
+        let quit = app
+            .config_items
+            .iter()
+            .find(|item| item.key == "keys.quit")
+            .unwrap();
+        assert_eq!(quit.value, "x");
+        let sort = app
+            .config_items
+            .iter_mut()
+            .find(|item| item.key == "keys.sort")
+            .unwrap();
+        sort.value = "o".to_string();
+
+        assert!(app.save_config());
+
+        assert_eq!(app.keymap.action(Key::Char('o')), Some(Action::Sort));
+        assert_eq!(app.keymap.action(Key::Char('s')), None);
+        let saved: toml::Value = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["keys"]["quit"].as_str(), Some("x"));
+        assert_eq!(saved["keys"]["sort"].as_str(), Some("o"));
+        assert_eq!(
+            saved["profile"]["vps"]["refresh_secs"].as_integer(),
+            Some(5)
+        );
+
+        let theme = app
+            .config_items
+            .iter_mut()
+            .find(|item| item.key == "keys.theme")
+            .unwrap();
+        theme.value = "o".to_string();
+        assert!(!app.save_config());
+        let (status, _) = app.config_status.clone().unwrap();
+        assert_eq!(status, "key `o` is bound to both keys.sort and keys.theme");
+        assert_eq!(app.keymap.action(Key::Char('o')), Some(Action::Sort));
+        let saved: toml::Value = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["keys"]["theme"].as_str(), Some("t"));
+
+        for (key, value) in [("keys.sort", "t"), ("keys.theme", "o")] {
+            let item = app
+                .config_items
+                .iter_mut()
+                .find(|item| item.key == key)
+                .unwrap();
+            item.value = value.to_string();
+        }
+        assert!(app.save_config());
+        assert_eq!(app.keymap.action(Key::Char('t')), Some(Action::Sort));
+        assert_eq!(app.keymap.action(Key::Char('o')), Some(Action::Theme));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn config_editor_keeps_secret_references_only() {
+        let path = write_temp_config("api_key = \"env:ADSB_FEED_KEY\"\n");
+        let mut app = make_app(true, true);
+        app.config_path = path.clone();
+        app.open_config();
+        assert!(app.save_config());
+        let saved: toml::Value = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["api_key"].as_str(), Some("env:ADSB_FEED_KEY"));
+
+        let api_key = app
+            .config_items
+            .iter_mut()
+            .find(|item| item.key == "api_key")
+            .unwrap();
+        api_key.value = "literal-secret".to_string();
+        assert!(app.save_config());
+        let saved: toml::Value = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(saved.get("api_key").is_none());
+        assert!(app
+            .config_status
+            .as_ref()
+            .is_some_and(|(message, _)| message.contains("api_key not saved")));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn role_disabled_masks_classification() {
+        let mut ac = sample_aircraft();
+        ac.flight = Some("RCH123".to_string()); // military prefix
+
+        let app = make_app(false, true);
+        assert!(matches!(app.classify_aircraft(&ac), AircraftRole::Unknown));
+    }
+
+    #[test]
+    fn performance_snapshot_scales_signal_strength() {
+        let mut app = make_app(true, true);
+        let mut samples = VecDeque::new();
+        for i in 0..15 {
+            let rssi = -50.0 + (i as f64) * (50.0 / 14.0);
+            samples.push_back(PerformanceSample {
+                msg_rate: Some(12.0 + i as f64),
+                flights: 3 + i as usize,
+                rssi_avg: Some(rssi),
+                ..PerformanceSample::default()
+            });
+        }
+        app.perf_samples = samples;
+
+        let snapshot = app.performance_snapshot();
+        assert_eq!(snapshot.msg_rate.last().copied(), Some(26));
+        assert_eq!(snapshot.flights.last().copied(), Some(17));
+        assert_eq!(snapshot.signal.len(), 15);
+        assert_eq!(snapshot.signal.first().copied(), Some(0));
+        assert_eq!(snapshot.signal.last().copied(), Some(100));
+        let rsi = snapshot.latest_signal_rsi.unwrap_or_default();
+        assert!(rsi >= 99.0);
+    }
+
+    #[test]
+    fn perf_series_follow_the_selected_window() {
+        let mut app = make_app(true, true);
+        let aircraft = |seen_pos: f64| Aircraft {
+            hex: Some("abc123".to_string()),
+            flight: Some("BAW12".to_string()),
+            lat: Some(0.5),
+            lon: Some(0.0),
+            seen_pos: Some(seen_pos),
+            rssi: Some(-12.0),
+            ..Aircraft::default()
+        };
+        for (now, seen_pos) in [(0, 0.5), (600, 3.0), (1_200, 700.0), (1_202, 1.0)] {
+            app.apply_update(ApiResponse {
+                now: Some(1_000_000 + now),
+                aircraft: vec![aircraft(seen_pos)],
+                ..ApiResponse::default()
+            });
+        }
+        let now = app.last_update.unwrap();
+
+        let counts = app.perf_series(PerfChart::Aircraft, now);
+        assert_eq!(counts, [(-2.0, 1.0), (0.0, 1.0)]);
+        let positions = app.perf_series(PerfChart::PositionRate, now);
+        assert_eq!(positions, [(-2.0, 0.0), (0.0, 0.5)]);
+        let routes = app.perf_series(PerfChart::RouteHits, now);
+        assert_eq!(routes.last().map(|(_, pct)| *pct), Some(0.0));
+
+        app.cycle_perf_window(true);
+        assert_eq!(app.perf_window, PerfWindow::ThirtyMinutes);
+        assert_eq!(app.perf_series(PerfChart::Rssi, now).len(), 4);
+        app.cycle_perf_window(false);
+        app.cycle_perf_window(false);
+        assert_eq!(app.perf_window, PerfWindow::TwoHours);
+        assert_eq!(PerfWindow::parse("30M"), Some(PerfWindow::ThirtyMinutes));
+        assert_eq!(PerfWindow::from_str("1d"), PerfWindow::FiveMinutes);
+    }
+
+    #[test]
+    fn performance_snapshot_reports_app_usage() {
+        let mut app = make_app(true, true);
+        let snapshot = app.performance_snapshot();
+        assert_eq!(snapshot.latest_fetch_ms, None);
+        assert_eq!(snapshot.frame_ms, None);
+
+        app.apply_update(ApiResponse {
+            now: Some(1_000),
+            fetch_time: Some(Duration::from_millis(42)),
+            ..ApiResponse::default()
+        });
+        app.profiler
+            .record(crate::profile::Stage::Frame, Duration::from_millis(8));
+        let snapshot = app.performance_snapshot();
+        assert_eq!(snapshot.fetch_ms.last().copied(), Some(42));
+        assert_eq!(snapshot.latest_fetch_ms, Some(42.0));
+        assert_eq!(snapshot.frame_ms, Some(8.0));
+        if cfg!(target_os = "linux") {
+            assert!(snapshot.latest_usage.rss_bytes.is_some_and(|rss| rss > 0));
+        }
+    }
+
+    #[test]
+    fn not_modified_polls_keep_the_snapshot_and_count_as_cache_hits() {
+        let mut app = make_app(true, true);
+        app.apply_update(ApiResponse {
+            now: Some(1_000),
+            aircraft: vec![Aircraft {
+                hex: Some("abc123".to_string()),
+                ..Aircraft::default()
+            }],
+            cache: FeedCache {
+                requests: 1,
+                not_modified: 0,
+            },
+            ..ApiResponse::default()
+        });
+        let version = app.data_version;
+        app.apply_error("timeout".to_string());
+        app.apply_update(ApiResponse {
+            not_modified: true,
+            cache: FeedCache {
+                requests: 1,
+                not_modified: 1,
+            },
+            ..ApiResponse::default()
+        });
+
+        assert_eq!(app.data_version, version);
+        assert_eq!(app.last_error, None);
+        let snapshot = app.performance_snapshot();
+        assert_eq!(snapshot.feed_cache.hit_percent(), Some(50.0));
+    }
+
+    #[test]
+    fn performance_snapshot_keeps_receiver_stats_history() {
+        let mut app = make_app(true, true);
+        assert!(app.performance_snapshot().receiver.is_none());
+
+        for i in 0..130 {
+            app.apply_receiver_stats(ReceiverStats {
+                period_secs: 60.0,
+                signal: Some(-25.0 + i as f64 / 10.0),
+                cpu_ms: Some(12_000),
+                ..ReceiverStats::default()
+            });
+        }
+        let snapshot = app.performance_snapshot();
+        assert_eq!(snapshot.receiver_signal.len(), 120);
+        assert_eq!(snapshot.receiver_cpu.last().copied(), Some(20));
+        assert_eq!(
+            snapshot.receiver.and_then(|stats| stats.signal),
+            Some(-12.1)
+        );
+    }
+
+    #[test]
+    fn quit_confirmation_restores_previous_mode() {
+        let mut app = make_app(true, true);
+        app.input_mode = InputMode::Help;
+
+        app.open_quit_confirm();
+        assert_eq!(app.input_mode, InputMode::QuitConfirm);
+
+        app.close_quit_confirm();
+        assert_eq!(app.input_mode, InputMode::Help);
+    }
+
+    #[test]
+    fn role_enabled_classifies_military() {
+        let mut ac = sample_aircraft();
+        ac.flight = Some("RCH123".to_string());
+
+        let app = make_app(true, true);
+        assert!(matches!(app.classify_aircraft(&ac), AircraftRole::Military));
+    }
+
+    #[test]
+    fn military_hex_range_classifies_without_callsign() {
+        let mut ac = sample_aircraft();
+        ac.flight = None;
+        ac.hex = Some("ae1234".to_string());
+
+        let mut app = make_app(true, true);
+        assert!(matches!(app.classify_aircraft(&ac), AircraftRole::Military));
+        let ranges = MilitaryRanges::parse("43Cxxx").unwrap();
+        app.set_role_rules(RoleRules::builtin(&ranges), None, None);
+        assert!(matches!(
+            app.classify_aircraft(&ac),
+            AircraftRole::Commercial
+        ));
+    }
+
+    #[test]
+    fn trail_drops_implausible_jumps_then_resegments() {
+        let mut app = make_app(true, true);
+        app.trail_len = 10;
+        let feed = |now: i64, lat: f64| ApiResponse {
+            now: Some(now),
+            aircraft: vec![positioned_aircraft("a00001", lat, 0.0)],
+            ..ApiResponse::default()
+        };
+
+        app.apply_update(feed(1_000, 40.0));
+        app.apply_update(feed(1_010, 40.01));
+        // ~300 nm in 10 s.
+        app.apply_update(feed(1_020, 45.0));
+        let ac = positioned_aircraft("a00001", 0.0, 0.0);
+        assert_eq!(app.trail_for(&ac).unwrap().len(), 2);
+        assert_eq!(app.trail_jumps_rejected, 1);
+
+        app.apply_update(feed(1_030, 45.01));
+        app.apply_update(feed(1_040, 45.02));
+        let trail = app.trail_for(&ac).unwrap();
+        assert_eq!(trail.len(), 1);
+        assert_eq!(trail[0].lat, 45.02);
+        assert_eq!(app.trail_jumps_rejected, 2);
+    }
+
+    #[test]
+    fn history_backfill_goes_before_live_trails() {
+        let mut app = make_app(true, true);
+        app.trail_len = 4;
+        let ac = positioned_aircraft("a00001", 0.0, 0.0);
+        let snapshot = |now: i64, hex: &str, lat: f64| ApiResponse {
+            now: Some(now),
+            aircraft: vec![positioned_aircraft(hex, lat, 0.0)],
+            ..ApiResponse::default()
+        };
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        app.set_history_backfill(rx);
+        app.apply_update(snapshot(1_000, "a00001", 40.03));
+
+        tx.send(vec![
+            snapshot(970, "a00001", 40.0),
+            snapshot(980, "a00001", 40.01),
+            snapshot(990, "a00001", 40.02),
+            snapshot(990, "a00002", 41.0),
+        ])
+        .unwrap();
+        app.apply_update(snapshot(1_010, "a00001", 40.04));
+        let lats: Vec<f64> = app.trail_for(&ac).unwrap().iter().map(|p| p.lat).collect();
+        assert_eq!(lats, vec![40.01, 40.02, 40.03, 40.04]);
+        assert!(app.seen_times.contains_key("a00002"));
+        assert_eq!(app.trail_points["a00002"].len(), 1);
+    }
+
+    #[test]
+    fn trail_keeps_newest_points_within_trail_len() {
+        let mut app = make_app(true, true);
+        app.trail_len = 3;
+        let ac = positioned_aircraft("a00001", 0.0, 0.0);
+        for step in 0..5 {
+            app.apply_update(ApiResponse {
+                now: Some(1_000 + step * 10),
+                aircraft: vec![positioned_aircraft(
+                    "a00001",
+                    40.0 + step as f64 * 0.01,
+                    0.0,
+                )],
+                ..ApiResponse::default()
+            });
+        }
+        let lats: Vec<f64> = app.trail_for(&ac).unwrap().iter().map(|p| p.lat).collect();
+        assert_eq!(lats, vec![40.02, 40.03, 40.04]);
+
+        // A smaller reloaded trail_len trims on the next point.
+        app.trail_len = 2;
+        app.apply_update(ApiResponse {
+            now: Some(1_050),
+            aircraft: vec![positioned_aircraft("a00001", 40.05, 0.0)],
+            ..ApiResponse::default()
+        });
+        let lats: Vec<f64> = app.trail_for(&ac).unwrap().iter().map(|p| p.lat).collect();
+        assert_eq!(lats, vec![40.04, 40.05]);
+    }
+
+    #[test]
+    fn pinned_trails_outgrow_trail_len_until_cleared() {
+        let mut app = make_app(true, true);
+        app.trail_len = 2;
+        app.trail_fade_secs = 15;
+        let pinned = positioned_aircraft("a00001", 0.0, 0.0);
+        let other = positioned_aircraft("a00002", 0.0, 0.0);
+        let update = |app: &mut App, step: i64| {
+            let lat = 40.0 + step as f64 * 0.01;
+            app.apply_update(ApiResponse {
+                now: Some(1_000 + step * 10),
+                aircraft: vec![
+                    positioned_aircraft("a00001", lat, 0.0),
+                    positioned_aircraft("a00002", lat, 1.0),
+                ],
+                ..ApiResponse::default()
+            });
+        };
+        update(&mut app, 0);
+        app.data.aircraft = vec![pinned.clone(), other.clone()];
+        app.table_state.select(Some(0));
+        assert!(app.toggle_trail_pin_selected(&[0, 1]));
+        for step in 1..5 {
+            update(&mut app, step);
+        }
+        assert!(app.is_trail_pinned(&pinned));
+        assert!(!app.is_trail_pinned(&other));
+        assert_eq!(app.trail_for(&pinned).unwrap().len(), 5);
+        // Unpinned trails still age out after `trail_fade_secs`.
+        assert_eq!(app.trail_for(&other).unwrap().len(), 2);
+
+        app.clear_trails();
+        assert!(app.trail_for(&pinned).is_none());
+        update(&mut app, 5);
+        assert!(app.is_trail_pinned(&pinned));
+        assert_eq!(app.trail_for(&pinned).unwrap().len(), 1);
+
+        assert!(app.toggle_trail_pin_selected(&[0, 1]));
+        assert!(!app.is_trail_pinned(&pinned));
+    }
+
+    #[test]
+    fn history_modal_pages_through_the_sighting_store() {
+        let dir = std::env::temp_dir().join(format!("adsb-tui-history-{}", std::process::id()));
+        let path = dir.join("sightings.db");
+        let _ = std::fs::remove_dir_all(&dir);
+        let tasks = crate::tasks::Tasks::new().unwrap();
+        let mut app = make_app(true, true);
+        app.sightings = crate::sightings::SightingStore::open(&path, 0).unwrap();
+        let (tx, flushed) = crate::sightings::spawn_sightings_writer(&tasks, path.clone());
+        app.set_sightings_writer(tx, flushed);
+        app.data.aircraft = (0..100)
+            .map(|i| positioned_aircraft(&format!("a{i:05}"), 0.0, 0.0))
+            .collect();
+        app.refresh_geometry();
+        app.record_sightings();
+        let started = Instant::now();
+        while app.sightings.len() < 100 && started.elapsed() < Duration::from_secs(5) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        app.open_history();
+        assert_eq!(app.history_total, 100);
+        assert_eq!(app.history_rows.len(), super::HISTORY_PAGE);
+        app.history_page_down(80);
+        assert_eq!(app.history_cursor, 80);
+        assert_eq!(app.history_row(80).unwrap().hex, "a00080");
+        assert_eq!(app.history_row(99).unwrap().hex, "a00099");
+        assert!(app.history_row(0).is_none());
+
+        app.table_state.select(Some(7));
+        let indices: Vec<usize> = (0..100).collect();
+        assert!(app.refresh_selected_sighting(&indices));
+        assert_eq!(app.selected_sighting("A00007").unwrap().hex, "a00007");
+        assert!(app.selected_sighting("a00008").is_none());
+        assert!(!app.refresh_selected_sighting(&indices));
+        drop(app);
+        tasks.shutdown();
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn altitude_trend_follows_smoothed_vertical_rate() {
+        let mut app = make_app(true, true);
+        let feed = |alt: i64, rate: i64| ApiResponse {
+            aircraft: vec![Aircraft {
+                hex: Some("a00001".to_string()),
+                alt_baro: Some(Altitude::Feet(alt)),
+                baro_rate: Some(rate),
+                ..Aircraft::default()
+            }],
+            ..ApiResponse::default()
+        };
+        let ac = feed(0, 0).aircraft.remove(0);
+
+        app.apply_update(feed(10_000, 1_500));
+        app.apply_update(feed(10_100, 1_500));
+        assert_eq!(app.trend_for(&ac).alt, TrendDir::Up);
+        assert_eq!(app.trend_for(&ac).vs, Some(1_500));
+
+        // One jittery sample dips the altitude but not the sustained climb.
+        app.apply_update(feed(10_050, -300));
+        let trend = app.trend_for(&ac);
+        assert_eq!(trend.alt, TrendDir::Up);
+        assert_eq!(trend.vs, Some(960));
+    }
+
+    #[test]
+    fn closest_approach_ahead_and_behind() {
+        let site = SiteLocation {
+            lat: 0.0,
+            lon: 0.0,
+            alt_m: 0.0,
+        };
+        let mut ac = Aircraft {
+            lat: Some(0.02),
+            lon: Some(-0.5),
+            gs: Some(300.0),
+            track: Some(90.0),
+            ..Aircraft::default()
+        };
+        let cpa = closest_approach(site, &ac).unwrap();
+        assert!((cpa.distance_mi - 1.38).abs() < 0.05, "{cpa:?}");
+        assert!((cpa.minutes - 6.0).abs() < 0.2, "{cpa:?}");
+
+        ac.track = Some(270.0);
+        let cpa = closest_approach(site, &ac).unwrap();
+        assert_eq!(cpa.minutes, 0.0);
+        ac.gs = None;
+        assert!(closest_approach(site, &ac).is_none());
+    }
+
+    #[test]
+    fn cpa_alert_fires_inside_window() {
+        let mut app = make_app(true, true);
+        app.set_site(Some(0.0), Some(0.0), None);
+        app.cpa_notify_mi = 2.0;
+        let feed = |track: f64| ApiResponse {
+            aircraft: vec![Aircraft {
+                hex: Some("a00001".to_string()),
+                flight: Some("TEST1".to_string()),
+                lat: Some(0.02),
+                lon: Some(-0.5),
+                gs: Some(300.0),
+                track: Some(track),
+                ..Aircraft::default()
+            }],
+            ..ApiResponse::default()
+        };
+
+        app.apply_update(feed(270.0));
+        assert!(app
+            .notifications
+            .iter()
+            .all(|n| !n.message.starts_with("CPA")));
+        app.apply_update(feed(90.0));
+        let message = &app.notifications.last().unwrap().message;
+        assert_eq!(message, "CPA TEST1 -- 1.4mi in 6min");
+    }
+
+    #[test]
+    fn emergency_alert_fires_once_and_reaches_hooks() {
+        let mut app = make_app(true, true);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        app.set_alert_hooks(tx);
+        let feed = ApiResponse {
+            aircraft: vec![Aircraft {
+                hex: Some("A00002".to_string()),
+                flight: Some("MEDIC1 ".to_string()),
+                squawk: Some("7700".to_string()),
+                ..Aircraft::default()
+            }],
+            ..ApiResponse::default()
+        };
+
+        app.apply_update(feed.clone());
+        app.apply_update(feed);
+        let emergencies: Vec<_> = app
+            .notifications
+            .iter()
+            .filter(|n| n.message.starts_with("EMERG"))
+            .collect();
+        assert_eq!(emergencies.len(), 1);
+        assert_eq!(emergencies[0].message, "EMERG MEDIC1 -- GENERAL SQK 7700");
+
+        let event = rx.try_recv().unwrap();
+        assert_eq!(event.kind, crate::notifier::AlertKind::Emergency);
+        assert_eq!(
+            (event.hex.as_str(), event.callsign.as_str()),
+            ("A00002", "MEDIC1")
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn event_log_records_military_emergencies_and_new_types() {
+        let mut app = make_app(true, true);
+        let aircraft = |hex: &str, flight: &str, t: &str, squawk: &str| Aircraft {
+            hex: Some(hex.to_string()),
+            flight: Some(flight.to_string()),
+            t: Some(t.to_string()),
+            squawk: Some(squawk.to_string()),
+            ..Aircraft::default()
+        };
+        let feed = |aircraft: Vec<Aircraft>| ApiResponse {
+            aircraft,
+            ..ApiResponse::default()
+        };
+
+        app.apply_update(feed(vec![aircraft("a00001", "UAL1", "B738", "1200")]));
+        assert!(app.events.is_empty(), "first snapshot seeds the types");
+
+        let update = feed(vec![
+            aircraft("a00001", "UAL1", "B738", "1200"),
+            aircraft("ae1234", "RCH123", "C17", "7700"),
+        ]);
+        app.apply_update(update.clone());
+        app.apply_update(update);
+        let kinds: Vec<_> = app.events.events().map(|event| event.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                EventKind::Emergency,
+                EventKind::Military,
+                EventKind::NewType
+            ]
+        );
+        assert!(app.events.to_text().contains("TYPE C17 RCH123"));
+    }
+
+    #[test]
+    fn dnd_keeps_alerts_from_hooks_but_lists_them() {
+        let mut app = make_app(true, true);
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        app.set_alert_hooks(tx);
+        let feed = |hex: &str| ApiResponse {
+            aircraft: vec![Aircraft {
+                hex: Some(hex.to_string()),
+                squawk: Some("7700".to_string()),
+                ..Aircraft::default()
+            }],
+            ..ApiResponse::default()
+        };
+
+        app.toggle_dnd();
+        assert!(app.dnd_active());
+        app.apply_update(feed("a00001"));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(app.unread_alerts(), 1);
+
+        app.toggle_dnd();
+        app.apply_update(feed("a00002"));
+        assert!(rx.try_recv().is_ok());
+
+        app.quiet_hours = crate::quiet::QuietHours::parse("00:00-12:00, 12:00-00:00").unwrap();
+        assert!(!app.dnd_active(), "switched off by hand");
+        assert!(app.update_dnd(SystemTime::now()), "no feed update needed");
+        assert!(app.dnd_active(), "quiet hours starting clear the toggle");
+        assert!(!app.update_dnd(SystemTime::now()));
+        app.apply_update(feed("a00003"));
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn alerts_stay_unread_until_acknowledged() {
+        let mut app = make_app(true, true);
+        app.apply_update(ApiResponse {
+            aircraft: vec![Aircraft {
+                hex: Some("a00002".to_string()),
+                squawk: Some("7700".to_string()),
+                ..Aircraft::default()
+            }],
+            ..ApiResponse::default()
+        });
+        app.notify("WATCHLIST saved".to_string(), SystemTime::now());
+        assert_eq!(app.unread_alerts(), 1);
+        assert_eq!(app.latest_status().unwrap().message, "WATCHLIST saved");
+
+        app.open_notifications();
+        assert_eq!(app.input_mode, InputMode::Notifications);
+        app.acknowledge_notification();
+        assert_eq!(app.unread_alerts(), 1, "newest first: the status line");
+        app.acknowledge_notification();
+        assert_eq!(app.unread_alerts(), 0);
+
+        for i in 0..NOTIFICATION_CAP {
+            app.notify(format!("SITE {i}"), SystemTime::now());
+        }
+        assert_eq!(app.notifications.len(), NOTIFICATION_CAP);
+        app.clear_notifications();
+        assert!(app.notifications.is_empty());
+        app.close_notifications();
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn zone_alerts_fire_on_entry_and_exit() {
+        let mut app = make_app(true, true);
+        app.zones = vec![crate::zones::Zone {
+            name: "Field".to_string(),
+            lat: Some(40.0),
+            lon: Some(-74.0),
+            radius_nm: Some(2.0),
+            ..crate::zones::Zone::default()
+        }];
+        let feed = |lat: f64| ApiResponse {
+            aircraft: vec![Aircraft {
+                hex: Some("A00003".to_string()),
+                flight: Some("N123AB".to_string()),
+                lat: Some(lat),
+                lon: Some(-74.0),
+                ..Aircraft::default()
+            }],
+            ..ApiResponse::default()
+        };
+        let zone_alerts = |app: &App| -> Vec<String> {
+            app.notifications
+                .iter()
+                .filter(|n| n.message.starts_with("ZONE"))
+                .map(|n| n.message.clone())
+                .collect()
         };
-        assert!(watch_entry_matches(&entry, &ac, None));
 
-        let entry = WatchEntry {
-            match_type: "owner".to_string(),
-            value: "SOUTHWEST".to_string(),
-            mode: Some("contains".to_string()),
-            ..entry.clone()
-        };
-        assert!(watch_entry_matches(&entry, &ac, None));
+        app.apply_update(feed(40.5));
+        app.apply_update(feed(40.01));
+        app.apply_update(feed(40.5));
+        assert_eq!(
+            zone_alerts(&app),
+            ["ZONE IN Field N123AB --", "ZONE OUT Field N123AB --"]
+        );
+        assert_eq!(app.zone_occupancy(0), 0);
 
-        let entry = WatchEntry {
-            match_type: "category".to_string(),
-            value: "A3".to_string(),
-            mode: Some("exact".to_string()),
-            ..entry.clone()
-        };
-        assert!(watch_entry_matches(&entry, &ac, None));
+        app.zones[0].alert = Some("watchlist".to_string());
+        app.apply_update(feed(40.01));
+        assert_eq!(zone_alerts(&app).len(), 2);
+        assert_eq!(app.zone_occupancy(0), 1);
+    }
 
-        let entry = WatchEntry {
-            match_type: "route".to_string(),
-            value: "KJFK-KMIA".to_string(),
-            mode: Some("exact".to_string()),
-            ..entry.clone()
+    #[test]
+    fn course_is_derived_from_trail_without_track() {
+        let mut app = make_app(true, true);
+        let feed = |now: i64, lon: f64| ApiResponse {
+            now: Some(now),
+            aircraft: vec![positioned_aircraft("a00001", 40.0, lon)],
+            ..ApiResponse::default()
         };
-        assert!(watch_entry_matches(&entry, &ac, Some(&route)));
+        let mut ac = positioned_aircraft("a00001", 40.0, 0.0);
 
-        let entry = WatchEntry {
-            match_type: "unknown".to_string(),
-            value: "X".to_string(),
-            mode: Some("exact".to_string()),
-            ..entry.clone()
-        };
-        assert!(!watch_entry_matches(&entry, &ac, None));
+        app.apply_update(feed(1_000, 0.0));
+        assert_eq!(app.course_for(&ac), None);
+        app.apply_update(feed(1_010, 0.01));
+        let course = app.course_for(&ac).unwrap();
+        assert!(course.derived);
+        assert!((course.deg - 90.0).abs() < 0.1, "{course:?}");
+
+        ac.track = Some(270.0);
+        let course = app.course_for(&ac).unwrap();
+        assert!(!course.derived);
+        assert_eq!(course.deg, 270.0);
     }
 
     #[test]
-    fn watchlist_priority_pick() {
-        let watchlist = vec![
-            WatchEntry {
-                id: Some("low".to_string()),
-                label: None,
-                match_type: "hex".to_string(),
-                value: "ac6668".to_string(),
-                enabled: Some(true),
-                notify: Some(true),
-                priority: Some(1),
-                mode: Some("exact".to_string()),
-                color: None,
-            },
-            WatchEntry {
-                id: Some("high".to_string()),
-                label: None,
-                match_type: "hex".to_string(),
-                value: "ac6668".to_string(),
-                enabled: Some(true),
-                notify: Some(true),
-                priority: Some(5),
-                mode: Some("exact".to_string()),
-                color: None,
-            },
+    fn traffic_histograms_count_altitude_bands_and_distance_rings() {
+        let mut app = make_app(true, true);
+        let with_alt = |hex: &str, lon: f64, alt: Option<Altitude>| Aircraft {
+            alt_baro: alt,
+            ..positioned_aircraft(hex, 0.0, lon)
+        };
+        app.data.aircraft = vec![
+            with_alt("a00001", 0.1, Some(Altitude::Ground)),
+            with_alt("a00002", 0.5, Some(Altitude::Feet(3_000))),
+            with_alt("a00003", 1.0, Some(Altitude::Feet(35_000))),
+            with_alt("a00004", 4.0, Some(Altitude::Feet(45_000))),
+            with_alt("a00005", 0.2, None),
         ];
+        let indices = [0, 1, 2, 3, 4];
 
-        let app = App::new(
-            "http://example".to_string(),
-            Vec::new(),
-            Duration::from_secs(1),
-            60.0,
-            false,
-            5,
-            8,
-            HashSet::new(),
-            String::new(),
-            crate::app::LayoutMode::Full,
-            crate::app::ThemeMode::Default,
-            true,
-            true,
-            true,
-            Duration::from_millis(400),
-            PathBuf::from("adsb-tui.toml"),
-            3,
-            None,
-            None,
-            false,
-            200.0,
-            1.0,
-            crate::app::RadarRenderer::Canvas,
-            false,
-            crate::app::RadarBlip::Dot,
-            false,
-            false,
-            Duration::from_secs(1),
-            Duration::from_secs(1),
-            1,
-            10,
-            true,
-            true,
-            Duration::from_millis(300),
-            0.2,
-            10.0,
-            0.5,
-            Duration::from_secs(60),
-            true,
-            true,
-            true,
-            crate::app::FlagStyle::Emoji,
-            "msg_rate_total".to_string(),
-            "kbps_total".to_string(),
-            "msg_rate_avg".to_string(),
-            true,
-            Some(PathBuf::from("adsb-watchlist.toml")),
-            watchlist,
-        );
+        let histograms = app.traffic_histograms(&indices);
+        assert_eq!(histograms.altitude, [1, 1, 0, 0, 0, 1, 1]);
+        assert!(histograms.distance.is_none());
 
-        let ac = sample_aircraft();
-        let entry = app.watch_entry_for(&ac).unwrap();
-        assert_eq!(entry.entry_id(), "high");
+        app.set_site(Some(0.0), Some(0.0), None);
+        let histograms = app.traffic_histograms(&indices[..4]);
+        assert_eq!(histograms.distance, Some([1, 0, 1, 1, 0, 0, 1]));
     }
 
     #[test]
-    fn role_disabled_masks_classification() {
-        let mut ac = sample_aircraft();
-        ac.flight = Some("RCH123".to_string()); // military prefix
+    fn traffic_histograms_bucket_rssi_with_percentiles() {
+        let mut app = make_app(true, true);
+        app.data.aircraft = (0..10)
+            .map(|i| Aircraft {
+                rssi: Some(-36.0 + 3.5 * i as f64),
+                ..positioned_aircraft(&format!("a0000{i}"), 0.0, 0.1)
+            })
+            .collect();
+        app.data
+            .aircraft
+            .push(positioned_aircraft("a00010", 0.0, 0.1));
+        let indices: Vec<usize> = (0..app.data.aircraft.len()).collect();
 
-        let app = make_app(false, true);
-        assert!(matches!(app.classify_aircraft(&ac), AircraftRole::Unknown));
+        let histograms = app.traffic_histograms(&indices);
+        assert_eq!(histograms.rssi, [2, 2, 1, 1, 2, 1, 1]);
+        assert_eq!(histograms.rssi_percentiles, Some([-36.0, -22.0, -8.0]));
+
+        assert_eq!(app.traffic_histograms(&[10]).rssi_percentiles, None);
     }
 
     #[test]
-    fn performance_snapshot_scales_signal_strength() {
+    fn range_sectors_track_current_and_maximum_range() {
         let mut app = make_app(true, true);
-        let mut samples = VecDeque::new();
-        for i in 0..15 {
-            let rssi = -50.0 + (i as f64) * (50.0 / 14.0);
-            samples.push_back(PerformanceSample {
-                msg_rate: Some(12.0 + i as f64),
-                flights: 3 + i as usize,
-                rssi_avg: Some(rssi),
-            });
-        }
-        app.perf_samples = samples;
+        assert!(app.range_sectors().is_none());
+        app.set_site(Some(0.0), Some(0.0), None);
+        app.apply_update(ApiResponse {
+            now: Some(1_000),
+            aircraft: vec![
+                positioned_aircraft("a00001", 0.0, 1.0),
+                positioned_aircraft("a00002", 1.0, 0.0),
+            ],
+            ..ApiResponse::default()
+        });
+        app.swap_snapshot();
+        app.apply_update(ApiResponse {
+            now: Some(1_001),
+            aircraft: vec![positioned_aircraft("a00001", 0.0, 0.5)],
+            ..ApiResponse::default()
+        });
+        app.swap_snapshot();
 
-        let snapshot = app.performance_snapshot();
-        assert_eq!(snapshot.msg_rate.last().copied(), Some(26));
-        assert_eq!(snapshot.flights.last().copied(), Some(17));
-        assert_eq!(snapshot.signal.len(), 15);
-        assert_eq!(snapshot.signal.first().copied(), Some(0));
-        assert_eq!(snapshot.signal.last().copied(), Some(100));
-        let rsi = snapshot.latest_signal_rsi.unwrap_or_default();
-        assert!(rsi >= 99.0);
+        let range = app.range_sectors().unwrap();
+        assert!((range.current[9] - 30.02).abs() < 0.05);
+        assert_eq!(range.current[0], 0.0);
+        assert!((range.max[9] - 60.04).abs() < 0.05);
+        assert!((range.max[0] - 60.04).abs() < 0.05);
     }
 
     #[test]
-    fn quit_confirmation_restores_previous_mode() {
+    fn site_geometry_is_cached_per_update() {
         let mut app = make_app(true, true);
-        app.input_mode = InputMode::Help;
+        app.set_site(Some(0.0), Some(0.0), None);
+        app.apply_update(ApiResponse {
+            now: Some(1_000),
+            aircraft: vec![positioned_aircraft("a00001", 0.0, 1.0)],
+            ..ApiResponse::default()
+        });
+        app.swap_snapshot();
+        assert_eq!(app.geometry.len(), 1);
+        let geometry = app.site_geometry(0).unwrap();
+        assert!((geometry.distance_nm - 60.04).abs() < 0.05);
+        assert!((geometry.bearing_deg - 90.0).abs() < 1e-9);
 
-        app.open_quit_confirm();
-        assert_eq!(app.input_mode, InputMode::QuitConfirm);
+        app.set_site(Some(0.0), Some(2.0), None);
+        let geometry = app.site_geometry(0).unwrap();
+        assert!((geometry.bearing_deg - 270.0).abs() < 1e-9);
 
-        app.close_quit_confirm();
-        assert_eq!(app.input_mode, InputMode::Help);
+        // Aircraft swapped in without an update fall back to computing.
+        app.data.aircraft = vec![positioned_aircraft("a00002", 1.0, 2.0)];
+        let geometry = app.site_geometry(0).unwrap();
+        assert!(geometry.bearing_deg.abs() < 1e-9);
+        assert_eq!(app.site_geometry(1), None);
     }
 
     #[test]
-    fn role_enabled_classifies_military() {
-        let mut ac = sample_aircraft();
-        ac.flight = Some("RCH123".to_string());
+    fn smooth_mode_swaps_pending_snapshot_once() {
+        let mut app = make_app(true, true);
+        app.smooth_mode = true;
+        app.smooth_merge = true;
+        app.ui_interval = Duration::ZERO;
+        let now = SystemTime::now();
+        let feed = |flight: Option<&str>, gs: f64| ApiResponse {
+            aircraft: vec![Aircraft {
+                hex: Some("a00001".to_string()),
+                flight: flight.map(str::to_string),
+                gs: Some(gs),
+                ..Aircraft::default()
+            }],
+            ..ApiResponse::default()
+        };
 
-        let app = make_app(true, true);
-        assert!(matches!(app.classify_aircraft(&ac), AircraftRole::Military));
+        app.apply_update(feed(Some("UAL1"), 400.0));
+        assert!(app.data.aircraft.is_empty());
+        assert!(app.maybe_swap_snapshot(now));
+        assert_eq!(app.data.aircraft[0].gs, Some(400.0));
+
+        app.apply_update(feed(None, 410.0));
+        assert!(app.maybe_swap_snapshot(now));
+        assert!(!app.maybe_swap_snapshot(now));
+        let ac = &app.data.aircraft[0];
+        assert_eq!(ac.gs, Some(410.0));
+        assert_eq!(ac.flight.as_deref(), Some("UAL1"));
+    }
+
+    #[test]
+    fn column_widths_grow_at_once_and_shrink_after_full_pass() {
+        let cols = [ColumnId::Flight, ColumnId::Alt];
+        let mut widths = ColumnWidths::default();
+        let mut fits = 0;
+        let mut layout = |widths: &mut ColumnWidths| {
+            widths.layout(80, |content| {
+                fits += 1;
+                content.iter().map(|w| *w as u16).collect()
+            })
+        };
+
+        // 120 rows take three frames of 50 to sample.
+        let range = widths.sample_range(&cols, 120);
+        assert_eq!(range, 0..50);
+        widths.record_sample(&[8, 5], range.end, 120);
+        assert_eq!(layout(&mut widths), vec![8, 5]);
+        assert_eq!(layout(&mut widths), vec![8, 5]);
+
+        let range = widths.sample_range(&cols, 120);
+        widths.record_sample(&[6, 7], range.end, 120);
+        assert_eq!(layout(&mut widths), vec![8, 7]);
+        let range = widths.sample_range(&cols, 120);
+        assert_eq!(range, 100..120);
+        widths.record_sample(&[4, 4], range.end, 120);
+        assert_eq!(layout(&mut widths), vec![8, 7]);
+
+        // The wide callsign is gone: kept until the next pass completes.
+        for _ in 0..2 {
+            let range = widths.sample_range(&cols, 120);
+            widths.record_sample(&[5, 7], range.end, 120);
+            assert_eq!(layout(&mut widths), vec![8, 7]);
+        }
+        let range = widths.sample_range(&cols, 120);
+        widths.record_sample(&[5, 7], range.end, 120);
+        assert_eq!(layout(&mut widths), vec![5, 7]);
+        assert_eq!(fits, 3);
+    }
+
+    #[test]
+    fn history_cap_evicts_least_recently_seen() {
+        let mut app = make_app(true, true);
+        app.max_tracked_aircraft = 2;
+        let feed = |now: i64, hexes: &[&str]| ApiResponse {
+            now: Some(now),
+            aircraft: hexes
+                .iter()
+                .map(|hex| positioned_aircraft(hex, 40.0, -74.0))
+                .collect(),
+            ..ApiResponse::default()
+        };
+        app.apply_update(feed(1_000, &["a00001", "a00002"]));
+        app.apply_update(feed(1_010, &["a00002", "a00003"]));
+
+        assert_eq!(app.seen_times.len(), 2);
+        assert!(!app.seen_times.contains_key("a00001"));
+        assert!(!app.trail_points.contains_key("a00001"));
+        assert!(!app.trend_cache.contains_key("a00001"));
+        assert!(app.trail_points.contains_key("a00003"));
+
+        app.route_cache_size = 1;
+        app.apply_routes(vec![crate::routes::RouteResult {
+            callsign: "UAL1".to_string(),
+            origin: None,
+            destination: None,
+            route: None,
+        }]);
+        app.apply_routes(vec![crate::routes::RouteResult {
+            callsign: "UAL2".to_string(),
+            origin: None,
+            destination: None,
+            route: None,
+        }]);
+        assert_eq!(app.route_cache.len(), 1);
+    }
+
+    #[test]
+    fn route_cache_evicts_least_recently_used_and_counts_hits() {
+        let mut app = make_app(true, true);
+        app.route_enabled = true;
+        app.route_ttl = Duration::from_secs(3600);
+        app.route_cache_size = 2;
+        let route = |callsign: &str| crate::routes::RouteResult {
+            callsign: callsign.to_string(),
+            origin: None,
+            destination: None,
+            route: Some("KJFK-KLAX".to_string()),
+        };
+        let feed = |callsigns: &[&str]| ApiResponse {
+            aircraft: callsigns
+                .iter()
+                .map(|callsign| Aircraft {
+                    flight: Some(callsign.to_string()),
+                    ..Aircraft::default()
+                })
+                .collect(),
+            ..ApiResponse::default()
+        };
+
+        app.apply_routes(vec![route("UAL1"), route("UAL2")]);
+        let old = SystemTime::now() - Duration::from_secs(60);
+        for info in app.route_cache.values_mut() {
+            info.used_at = old;
+        }
+        // UAL1 is in use; UAL3 has no route yet.
+        app.apply_update(feed(&["UAL1", "UAL3"]));
+        assert_eq!((app.route_hits, app.route_misses), (1, 1));
+        assert_eq!(app.route_hit_rate(), Some(0.5));
+
+        app.apply_routes(vec![route("UAL3")]);
+        assert!(app.route_cache.contains_key("ual1"));
+        assert!(app.route_cache.contains_key("ual3"));
+        assert!(!app.route_cache.contains_key("ual2"));
+
+        // Routes unused for longer than the TTL expire.
+        app.route_ttl = Duration::from_secs(30);
+        app.route_cache.get_mut("ual3").unwrap().used_at = old;
+        app.apply_update(feed(&["UAL1"]));
+        assert!(app.route_cache.contains_key("ual1"));
+        assert!(!app.route_cache.contains_key("ual3"));
+    }
+
+    #[test]
+    fn adsb_version_counts_break_down_by_version() {
+        let mut app = make_app(true, true);
+        app.data.aircraft = [Some(2), Some(2), Some(1), Some(0), None, Some(7)]
+            .into_iter()
+            .map(|version| Aircraft {
+                version,
+                ..Aircraft::default()
+            })
+            .collect();
+        assert_eq!(app.adsb_version_counts(), [1, 1, 2]);
+        assert_eq!(app.performance_snapshot().adsb_versions, [1, 1, 2]);
     }
 
     #[test]
@@ -3621,12 +8066,6 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
-    #[test]
-    fn distance_same_point_is_zero() {
-        let dist = distance_mi(26.0, -80.0, 26.0, -80.0);
-        assert!(dist.abs() < 0.0001);
-    }
-
     #[test]
     fn radar_zoom_updates_range_and_feed_url() {
         let mut app = make_app(true, true);
@@ -3676,6 +8115,32 @@ mod tests {
         assert_eq!(app.radar_center.unwrap().lat, 89.999999);
     }
 
+    #[test]
+    fn select_hex_and_site_by_name() {
+        let mut app = make_app(true, true);
+        app.data.aircraft = vec![
+            positioned_aircraft("a1b2c3", 0.0, 0.0),
+            positioned_aircraft("ac6668", 0.1, 0.0),
+        ];
+        let indices: Vec<usize> = (0..app.data.aircraft.len()).collect();
+        assert!(app.select_hex(" AC6668", &indices));
+        assert_eq!(app.table_state.selected(), Some(1));
+        assert_eq!(app.selection_key.as_deref(), Some("ac6668"));
+        assert!(!app.select_hex("ffffff", &indices));
+        assert_eq!(app.table_state.selected(), Some(1));
+
+        app.sites = NamedSite::from_config(&[SiteConfig {
+            name: "Home".to_string(),
+            lat: 26.5,
+            lon: -80.1,
+            alt_m: None,
+        }]);
+        assert!(app.select_site_named("home").is_ok());
+        assert_eq!(app.site_name(), Some("Home"));
+        let err = app.select_site_named("cabin").unwrap_err();
+        assert_eq!(err, "unknown site cabin (available: Home)");
+    }
+
     #[test]
     fn radar_direction_selection_picks_nearest_cardinal_targets() {
         let mut app = make_app(true, true);
@@ -3737,6 +8202,340 @@ mod tests {
         assert_eq!(app.table_state.selected(), Some(1));
     }
 
+    #[test]
+    fn radar_click_selects_visible_aircraft() {
+        let mut app = make_app(true, true);
+        app.data.aircraft = vec![
+            positioned_aircraft("a", 0.0, 0.0),
+            positioned_aircraft("b", 0.0, 0.1),
+        ];
+        app.set_radar_hits(
+            Rect::new(0, 0, 20, 10),
+            vec![
+                RadarHit {
+                    col: 5,
+                    row: 4,
+                    idx: 0,
+                },
+                RadarHit {
+                    col: 12,
+                    row: 4,
+                    idx: 1,
+                },
+            ],
+        );
+
+        assert!(app.select_radar_aircraft_at(12, 4, &[0, 1]));
+        assert_eq!(app.table_state.selected(), Some(1));
+        // Filtered out of the table: nothing to select.
+        assert!(!app.select_radar_aircraft_at(5, 4, &[1]));
+        assert!(!app.select_radar_aircraft_at(25, 4, &[0, 1]));
+
+        app.clear_radar_hits();
+        assert!(!app.radar_contains(5, 4));
+    }
+
+    #[test]
+    fn radar_follow_needs_a_positioned_selection() {
+        let mut app = make_app(true, true);
+        app.data.aircraft = vec![sample_aircraft(), positioned_aircraft("b", 10.0, 20.0)];
+        let indices = [0, 1];
+        app.table_state.select(Some(0));
+        app.toggle_radar_follow(&indices);
+        assert!(!app.radar_follow);
+        assert!(app
+            .notifications
+            .last()
+            .unwrap()
+            .message
+            .starts_with("FOLLOW"));
+
+        app.table_state.select(Some(1));
+        app.toggle_radar_follow(&indices);
+        let center = app.radar_follow_center(&indices).unwrap();
+        assert_eq!((center.lat, center.lon), (10.0, 20.0));
+
+        // Losing the position falls back to the usual center; panning stops.
+        app.table_state.select(Some(0));
+        assert!(app.radar_follow_center(&indices).is_none());
+        app.pan_radar(RadarDirection::North);
+        assert!(!app.radar_follow);
+    }
+
+    #[test]
+    fn session_and_trails_feed_the_export_menu() {
+        let mut app = make_app(true, true);
+        let mut ac = positioned_aircraft("abc123", 10.0, 20.0);
+        ac.flight = Some("TEST1".to_string());
+        app.apply_update(ApiResponse {
+            aircraft: vec![ac],
+            ..ApiResponse::default()
+        });
+        app.swap_snapshot();
+        assert_eq!(app.session.len(), 1);
+        let session = crate::export::session_csv_text(&app);
+        assert!(session.lines().nth(1).unwrap().starts_with("abc123,TEST1,"));
+        let trails = crate::export::trails_csv_text(&app);
+        assert!(trails.lines().nth(1).unwrap().starts_with("abc123,TEST1,"));
+        assert!(trails.ends_with(",10.00000,20.00000"));
+
+        app.open_export();
+        assert_eq!(app.input_mode, InputMode::Export);
+        app.previous_export_kind();
+        assert_eq!(app.selected_export_kind(), ExportKind::Events);
+        app.next_export_kind();
+        assert_eq!(app.selected_export_kind(), ExportKind::TableCsv);
+        app.close_export();
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn detail_popup_cycles_tabs_and_records_signal() {
+        let mut app = make_app(true, true);
+        let feed = |now: i64, messages: u64, rssi: f64| ApiResponse {
+            now: Some(now),
+            aircraft: vec![Aircraft {
+                messages: Some(messages),
+                rssi: Some(rssi),
+                ..positioned_aircraft("a00001", 40.0, 1.0)
+            }],
+            ..ApiResponse::default()
+        };
+        app.apply_update(feed(1_000, 100, -20.0));
+        app.apply_update(feed(1_010, 150, -18.5));
+        app.swap_snapshot();
+
+        let history = app.signal_history_for(&app.data.aircraft[0]).unwrap();
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].rssi, Some(-20.0));
+        assert_eq!(history[0].msg_rate, None, "no rate before a second update");
+        assert_eq!(history[1].msg_rate, Some(5.0));
+
+        app.table_state.select(None);
+        app.open_detail(&[0]);
+        assert_eq!(app.input_mode, InputMode::Normal, "nothing selected");
+        app.table_state.select(Some(0));
+        app.open_detail(&[0]);
+        assert_eq!(app.input_mode, InputMode::Detail);
+        assert_eq!(app.detail_tab, DetailTab::Overview);
+        app.previous_detail_tab();
+        assert_eq!(app.detail_tab, DetailTab::Photo);
+        app.next_detail_tab();
+        app.next_detail_tab();
+        assert_eq!(app.detail_tab, DetailTab::Positions);
+        app.close_detail();
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn photo_tab_looks_up_each_aircraft_once() {
+        let mut app = make_app(true, true);
+        app.apply_update(ApiResponse {
+            now: Some(1_000),
+            aircraft: vec![positioned_aircraft("A00001", 40.0, 1.0)],
+            ..ApiResponse::default()
+        });
+        app.swap_snapshot();
+        app.table_state.select(Some(0));
+        app.open_detail(&[0]);
+        app.detail_tab = DetailTab::Photo;
+        assert_eq!(app.photo_request(&[0]), None, "lookups are off");
+
+        app.photos = Some(PhotoCache::new(Duration::from_secs(3_600)));
+        assert_eq!(app.photo_request(&[0]).as_deref(), Some("a00001"));
+        assert_eq!(app.photo_request(&[0]), None, "already pending");
+        app.apply_photo(PhotoMessage::Error {
+            hex: "a00001".to_string(),
+            message: "HTTP 503".to_string(),
+        });
+        assert_eq!(app.photo_request(&[0]), None, "no retry loop on errors");
+
+        app.apply_photo(PhotoMessage::Result {
+            hex: "a00001".to_string(),
+            info: PhotoInfo {
+                link: Some("https://www.planespotters.net/photo/1".to_string()),
+                fetched_at: chrono::Utc::now().timestamp(),
+                ..PhotoInfo::default()
+            },
+        });
+        assert!(app.photo_for("A00001").is_some_and(PhotoInfo::has_photo));
+        assert_eq!(app.photo_error, None);
+        assert_eq!(app.photo_request(&[0]), None, "cached");
+    }
+
+    #[test]
+    fn metric_history_keeps_the_last_five_minutes() {
+        let mut app = make_app(true, true);
+        let feed = |now: i64, alt: i64| ApiResponse {
+            now: Some(now),
+            aircraft: vec![Aircraft {
+                alt_baro: Some(Altitude::Feet(alt)),
+                gs: Some(250.0),
+                ..positioned_aircraft("a00001", 40.0, 1.0)
+            }],
+            ..ApiResponse::default()
+        };
+        for (step, alt) in [3_000, 4_000, 5_000, 6_000].into_iter().enumerate() {
+            app.apply_update(feed(1_000 + step as i64 * 120, alt));
+        }
+        app.swap_snapshot();
+
+        let history = app.metric_history_for(&app.data.aircraft[0]).unwrap();
+        let alts: Vec<Option<i64>> = history.iter().map(|sample| sample.alt).collect();
+        assert_eq!(alts, [Some(4_000), Some(5_000), Some(6_000)]);
+        assert!(history.iter().all(|sample| sample.gs == Some(250.0)));
+    }
+
+    #[test]
+    fn geojson_has_points_then_trails() {
+        let mut app = make_app(true, true);
+        app.trail_len = 10;
+        let feed = |now: i64, lat: f64| ApiResponse {
+            now: Some(now),
+            aircraft: vec![
+                Aircraft {
+                    squawk: Some("7000".to_string()),
+                    alt_baro: Some(Altitude::Ground),
+                    ..positioned_aircraft("a00001", lat, 1.0)
+                },
+                sample_aircraft(),
+            ],
+            ..ApiResponse::default()
+        };
+        app.apply_update(feed(1_000, 40.0));
+        app.apply_update(feed(1_010, 40.01));
+        app.swap_snapshot();
+
+        let collection = crate::export::geojson(&app, &[0, 1]);
+        assert_eq!(collection["type"], "FeatureCollection");
+        let features = collection["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2, "no position, no feature");
+        assert_eq!(features[0]["geometry"]["coordinates"][1], 40.01);
+        assert_eq!(features[0]["properties"]["squawk"], "7000");
+        assert_eq!(features[0]["properties"]["ground"], true);
+        assert_eq!(features[1]["geometry"]["type"], "LineString");
+        assert_eq!(features[1]["geometry"]["coordinates"][0][0], 1.0);
+    }
+
+    #[test]
+    fn sort_columns_break_ties_and_save_on_close() {
+        let path = write_temp_config("theme = \"ocean\"\n");
+        let mut app = make_app(true, true);
+        app.config_path = path.clone();
+        let plane = |hex: &str, alt: Option<i64>, flight: &str| Aircraft {
+            hex: Some(hex.to_string()),
+            flight: Some(flight.to_string()),
+            alt_baro: alt.map(Altitude::Feet),
+            ..Aircraft::default()
+        };
+        app.data.aircraft = vec![
+            plane("a", Some(5_000), "BBB"),
+            plane("b", None, "AAA"),
+            plane("c", Some(9_000), "CCC"),
+            plane("d", Some(5_000), "AAA"),
+        ];
+        app.sort = SortSpec::parse("alt, callsign").unwrap();
+        assert_eq!(app.visible_indices(), vec![2, 3, 0, 1], "no altitude last");
+
+        app.open_sort();
+        assert_eq!(app.input_mode, InputMode::Sort);
+        app.reverse_sort_key();
+        assert_eq!(app.sort.label(), "ALT↑ CALLSIGN↑");
+        assert_eq!(app.visible_indices(), vec![3, 0, 2, 1]);
+        app.close_sort();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("theme = \"ocean\""));
+        assert!(saved.contains("sort_order = \"alt asc, callsign asc\""));
+
+        let broken = "theme = \"ocean\"\nsite_lat = \n";
+        std::fs::write(&path, broken).unwrap();
+        app.open_sort();
+        app.reverse_sort_key();
+        app.close_sort();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), broken);
+        let status = &app.latest_status().unwrap().message;
+        assert!(status.starts_with("SORT save failed:"), "{status}");
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn column_layout_parses_and_round_trips() {
+        let layout = parse_column_layout("flight:10, -reg, ALT").unwrap();
+        assert_eq!(
+            layout[0],
+            ColumnLayout {
+                id: ColumnId::Flight,
+                visible: true,
+                width: Some(10),
+            }
+        );
+        assert!(!layout[1].visible);
+        let columns = build_columns(true, false, &layout);
+        let ids: Vec<ColumnId> = columns.iter().take(4).map(|col| col.id).collect();
+        assert_eq!(
+            ids,
+            [
+                ColumnId::Flight,
+                ColumnId::Reg,
+                ColumnId::Alt,
+                ColumnId::Flag
+            ]
+        );
+        assert_eq!(columns.len(), 26);
+        let text = column_layout_text(&columns);
+        assert!(text.starts_with("flight:10, -reg, alt, flag, fav, -watch, pin, type"));
+        assert_eq!(parse_column_layout(&text).unwrap().len(), 26);
+
+        assert!(parse_column_layout("").unwrap().is_empty());
+        assert!(parse_column_layout("wingspan").is_err());
+        assert!(parse_column_layout("alt:0").is_err());
+        assert!(parse_column_layout("alt, -alt").is_err());
+    }
+
+    #[test]
+    fn columns_menu_reorders_resizes_and_saves() {
+        let path = write_temp_config("");
+        let mut app = make_app(true, true);
+        app.config_path = path.clone();
+        app.open_columns();
+        app.move_column(-1);
+        assert_eq!(app.column_cursor(), 0, "already at the top");
+        app.move_column(1);
+        assert_eq!(app.column_cursor(), 1);
+        assert_eq!(app.columns()[0].id, ColumnId::Fav);
+        assert_eq!(app.columns()[1].id, ColumnId::Flag);
+        app.adjust_column_width(-1);
+        app.adjust_column_width(-1);
+        assert_eq!(app.columns()[1].fixed_width, Some(1));
+        app.close_columns();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(saved.contains("table_columns = \"fav, flag:1, watch, pin, flight,"));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn narrow_terminals_get_the_overhead_layout() {
+        let mut app = make_app(true, true);
+        app.overhead_width = 70;
+        app.set_layout(crate::app::LayoutMode::Radar);
+        assert_eq!(
+            app.current_layout(),
+            crate::app::LayoutMode::Radar,
+            "nothing drawn yet"
+        );
+        app.set_terminal_width(60);
+        assert_eq!(app.current_layout(), crate::app::LayoutMode::Overhead);
+        app.set_terminal_width(70);
+        assert_eq!(app.current_layout(), crate::app::LayoutMode::Radar);
+
+        app.overhead_width = 0;
+        assert_eq!(app.effective_layout(40), crate::app::LayoutMode::Radar);
+        app.set_layout(crate::app::LayoutMode::from_str("phone"));
+        assert_eq!(app.effective_layout(200), crate::app::LayoutMode::Overhead);
+    }
+
     #[test]
     fn radar_blip_parses() {
         assert_eq!(RadarBlip::from_str("dot"), RadarBlip::Dot);
@@ -0,0 +1,850 @@
+//! Beast binary input (port 30005): Mode S frames decoded locally into
+//! `ApiResponse` snapshots.
+
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, info, warn};
+
+use crate::model::{Aircraft, Altitude, ApiResponse, ReceiverStats};
+use crate::tasks::{blocking, Tasks};
+use crate::units::distance_nm;
+
+pub const BEAST_SCHEME: &str = "beast://";
+pub const DEFAULT_BEAST_PORT: u16 = 30005;
+
+const ESCAPE: u8 = 0x1a;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+const TRACK_TIMEOUT_SECS: f64 = 60.0;
+/// Even/odd CPR frames further apart than this are not paired.
+const CPR_PAIR_SECS: f64 = 10.0;
+/// Positions decoded against a reference must land within this distance.
+const LOCAL_CPR_MAX_NM: f64 = 180.0;
+/// Slowest and fastest snapshot rates, whatever `refresh_secs` says.
+const MIN_SNAPSHOT_INTERVAL: Duration = Duration::from_millis(200);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+const STATS_INTERVAL: Duration = Duration::from_secs(60);
+
+pub fn beast_address(url: &str) -> Option<String> {
+    let trimmed = url.trim();
+    let scheme = trimmed.get(..BEAST_SCHEME.len())?;
+    if !scheme.eq_ignore_ascii_case(BEAST_SCHEME) {
+        return None;
+    }
+    let host = trimmed[BEAST_SCHEME.len()..].trim_end_matches('/');
+    if host.is_empty() {
+        return None;
+    }
+    let has_port = match host.rsplit_once(':') {
+        // A bracketed IPv6 address has colons of its own.
+        Some((name, port)) => {
+            (!name.contains(':') || name.ends_with(']')) && port.parse::<u16>().is_ok()
+        }
+        None => false,
+    };
+    Some(if has_port {
+        host.to_string()
+    } else {
+        format!("{host}:{DEFAULT_BEAST_PORT}")
+    })
+}
+
+pub fn probe(address: &str) -> Result<(), String> {
+    connect(address).map(|_| ())
+}
+
+/// Read Beast frames from `address` and send a snapshot every `refresh`,
+/// reconnecting with backoff when the connection drops. `site` lets surface
+/// positions decode before an aircraft has a reference position. Message
+/// counts per downlink format go to `stats` once a minute.
+pub fn spawn_beast_reader(
+    tasks: &Tasks,
+    address: String,
+    refresh: Duration,
+    site: Option<(f64, f64)>,
+    tx: UnboundedSender<Result<ApiResponse, String>>,
+    stats: UnboundedSender<Result<ReceiverStats, String>>,
+) {
+    let interval = refresh.clamp(MIN_SNAPSHOT_INTERVAL, Duration::from_secs(5));
+    tasks.spawn("beast", async move {
+        info!("beast reader started for {address}");
+        // The socket reads block, so the whole session lives on the
+        // blocking pool; it ends once the display drops the receiver.
+        blocking(move || run_reader(&address, interval, site, &tx, &stats)).await;
+    });
+}
+
+fn run_reader(
+    address: &str,
+    interval: Duration,
+    site: Option<(f64, f64)>,
+    tx: &UnboundedSender<Result<ApiResponse, String>>,
+    stats: &UnboundedSender<Result<ReceiverStats, String>>,
+) {
+    let started = Instant::now();
+    let mut tracker = Tracker::new(site);
+    let mut failures = 0u32;
+    loop {
+        let error = match connect(address) {
+            Ok(stream) => {
+                info!("beast connected to {address}");
+                failures = 0;
+                match read_session(stream, interval, started, &mut tracker, tx, stats) {
+                    Ok(()) => return,
+                    Err(err) => err,
+                }
+            }
+            Err(err) => err,
+        };
+        failures = failures.saturating_add(1);
+        warn!("beast {address}: {error}");
+        if tx.send(Err(format!("Beast {address}: {error}"))).is_err() {
+            return;
+        }
+        let delay = Duration::from_secs(1 << failures.min(5)).min(MAX_RECONNECT_DELAY);
+        thread::sleep(delay);
+        if tx.is_closed() {
+            return;
+        }
+    }
+}
+
+fn connect(address: &str) -> Result<TcpStream, String> {
+    let addrs = address
+        .to_socket_addrs()
+        .map_err(|err| format!("resolve failed: {err}"))?;
+    let mut last_error = "no address".to_string();
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(err) => last_error = err.to_string(),
+        }
+    }
+    Err(last_error)
+}
+
+fn read_session(
+    mut stream: TcpStream,
+    interval: Duration,
+    started: Instant,
+    tracker: &mut Tracker,
+    tx: &UnboundedSender<Result<ApiResponse, String>>,
+    stats: &UnboundedSender<Result<ReceiverStats, String>>,
+) -> Result<(), String> {
+    stream
+        .set_read_timeout(Some(interval))
+        .map_err(|err| err.to_string())?;
+    let mut framer = BeastFramer::default();
+    let mut buf = [0u8; 16 * 1024];
+    let mut sent_at = Instant::now();
+    let mut stats_at = Instant::now();
+    loop {
+        match stream.read(&mut buf) {
+            Ok(0) => return Err("connection closed".to_string()),
+            Ok(read) => {
+                framer.push(&buf[..read]);
+                let now = started.elapsed().as_secs_f64();
+                while let Some(frame) = framer.next_frame() {
+                    tracker.handle(&frame, now);
+                }
+            }
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {}
+            Err(err) => return Err(err.to_string()),
+        }
+        if sent_at.elapsed() >= interval {
+            sent_at = Instant::now();
+            let snapshot = tracker.snapshot(started.elapsed().as_secs_f64(), unix_now());
+            if tx.send(Ok(snapshot)).is_err() {
+                debug!("receiver dropped, exiting beast reader");
+                return Ok(());
+            }
+        }
+        if stats_at.elapsed() >= STATS_INTERVAL {
+            let period = stats_at.elapsed();
+            stats_at = Instant::now();
+            // Headless runs have no PERF view to read these.
+            let _ = stats.send(Ok(tracker.take_stats(period)));
+        }
+    }
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+/// One Beast frame: a Mode S message with its receive signal level
+/// (0-255). Mode A/C frames are dropped by the framer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BeastFrame {
+    pub signal: u8,
+    pub data: Vec<u8>,
+}
+
+/// Splits the escaped Beast byte stream into frames: `0x1a`, a type byte
+/// (`'2'` short, `'3'` long Mode S), a 6-byte timestamp, a signal byte and
+/// the message, with literal `0x1a` bytes doubled.
+#[derive(Debug, Default)]
+pub struct BeastFramer {
+    buf: Vec<u8>,
+}
+
+impl BeastFramer {
+    pub fn push(&mut self, bytes: &[u8]) {
+        self.buf.extend_from_slice(bytes);
+    }
+
+    pub fn next_frame(&mut self) -> Option<BeastFrame> {
+        loop {
+            let Some(start) = self.buf.iter().position(|&b| b == ESCAPE) else {
+                self.buf.clear();
+                return None;
+            };
+            self.buf.drain(..start);
+            let &kind = self.buf.get(1)?;
+            let payload_len = match kind {
+                b'1' => 2,
+                b'2' => 7,
+                b'3' => 14,
+                _ => {
+                    // Not a frame start (or a doubled escape): resync.
+                    self.buf.drain(..1);
+                    continue;
+                }
+            };
+            let wanted = 6 + 1 + payload_len;
+            let mut body = Vec::with_capacity(wanted);
+            let mut pos = 2;
+            while body.len() < wanted {
+                let &byte = self.buf.get(pos)?;
+                if byte == ESCAPE {
+                    match self.buf.get(pos + 1) {
+                        None => return None,
+                        Some(&ESCAPE) => pos += 1,
+                        // An unescaped frame start: this frame is broken.
+                        Some(_) => break,
+                    }
+                }
+                body.push(byte);
+                pos += 1;
+            }
+            if body.len() < wanted {
+                self.buf.drain(..pos);
+                continue;
+            }
+            self.buf.drain(..pos);
+            if kind == b'1' {
+                continue;
+            }
+            return Some(BeastFrame {
+                signal: body[6],
+                data: body[7..].to_vec(),
+            });
+        }
+    }
+}
+
+/// Mode S CRC-24 syndrome of a whole message (parity included): zero for an
+/// intact DF17/18, the transmitting address for DF4/5/20/21.
+pub fn crc_syndrome(msg: &[u8]) -> u32 {
+    let Some(split) = msg.len().checked_sub(3) else {
+        return u32::MAX;
+    };
+    let mut crc: u32 = 0;
+    for &byte in &msg[..split] {
+        crc ^= u32::from(byte) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x100_0000 != 0 {
+                crc ^= 0x1ff_f409;
+            }
+        }
+    }
+    let parity = u32::from_be_bytes([0, msg[split], msg[split + 1], msg[split + 2]]);
+    (crc & 0xff_ffff) ^ parity
+}
+
+/// `len` bits starting at 1-based bit `first` of `data`, as in the Mode S
+/// and ADS-B specifications.
+fn bits(data: &[u8], first: usize, len: usize) -> u64 {
+    (first..first + len).fold(0, |acc, bit| {
+        let byte = data[(bit - 1) / 8];
+        (acc << 1) | u64::from((byte >> (7 - (bit - 1) % 8)) & 1)
+    })
+}
+
+const CALLSIGN_CHARS: &[u8; 64] =
+    b"#ABCDEFGHIJKLMNOPQRSTUVWXYZ##### ###############0123456789######";
+
+fn callsign(data: &[u8], first: usize) -> Option<String> {
+    let text: String = (0..8)
+        .map(|i| CALLSIGN_CHARS[bits(data, first + i * 6, 6) as usize] as char)
+        .collect();
+    let text = text.trim_end().to_string();
+    (!text.is_empty() && !text.contains('#')).then_some(text)
+}
+
+/// 13-bit altitude code of DF0/4/16/20; only the 25 ft (Q bit) encoding is
+/// decoded.
+pub fn decode_ac13(code: u32) -> Option<i64> {
+    let metric = code & 0x40 != 0;
+    let q = code & 0x10 != 0;
+    if code == 0 || metric || !q {
+        return None;
+    }
+    let n = ((code & 0x1f80) >> 2) | ((code & 0x20) >> 1) | (code & 0x0f);
+    Some(i64::from(n) * 25 - 1000)
+}
+
+fn decode_ac12(code: u32) -> Option<i64> {
+    if code == 0 || code & 0x10 == 0 {
+        return None;
+    }
+    let n = ((code & 0x0fe0) >> 1) | (code & 0x0f);
+    Some(i64::from(n) * 25 - 1000)
+}
+
+pub fn decode_id13(code: u32) -> String {
+    let bit = |n: u32| (code >> n) & 1;
+    let a = bit(11) | bit(9) << 1 | bit(7) << 2;
+    let b = bit(5) | bit(3) << 1 | bit(1) << 2;
+    let c = bit(12) | bit(10) << 1 | bit(8) << 2;
+    let d = bit(4) | bit(2) << 1 | bit(0) << 2;
+    format!("{a}{b}{c}{d}")
+}
+
+fn surface_speed(movement: u64) -> Option<f64> {
+    let mov = movement as f64;
+    match movement {
+        1 => Some(0.0),
+        2..=8 => Some(0.125 * (mov - 1.0)),
+        9..=12 => Some(1.0 + 0.25 * (mov - 9.0)),
+        13..=38 => Some(2.0 + 0.5 * (mov - 13.0)),
+        39..=93 => Some(15.0 + (mov - 39.0)),
+        94..=108 => Some(70.0 + 2.0 * (mov - 94.0)),
+        109..=123 => Some(100.0 + 5.0 * (mov - 109.0)),
+        124 => Some(175.0),
+        _ => None,
+    }
+}
+
+fn cpr_nl(lat: f64) -> i32 {
+    let lat = lat.abs();
+    if lat < 1e-9 {
+        return 59;
+    }
+    if (lat - 87.0).abs() < 1e-9 {
+        return 2;
+    }
+    if lat > 87.0 {
+        return 1;
+    }
+    let nz = 15.0;
+    let a = 1.0 - (std::f64::consts::PI / (2.0 * nz)).cos();
+    let b = (std::f64::consts::PI / 180.0 * lat).cos().powi(2);
+    (2.0 * std::f64::consts::PI / (1.0 - a / b).acos()).floor() as i32
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct CprFrame {
+    lat: f64,
+    lon: f64,
+    at: f64,
+}
+
+/// Globally unambiguous airborne position from an even/odd pair; the newer
+/// frame decides which latitude zone is used.
+fn cpr_global(even: CprFrame, odd: CprFrame) -> Option<(f64, f64)> {
+    let dlat_even = 360.0 / 60.0;
+    let dlat_odd = 360.0 / 59.0;
+    let j = (59.0 * even.lat - 60.0 * odd.lat + 0.5).floor();
+    let wrap = |lat: f64| if lat >= 270.0 { lat - 360.0 } else { lat };
+    let lat_even = wrap(dlat_even * (j.rem_euclid(60.0) + even.lat));
+    let lat_odd = wrap(dlat_odd * (j.rem_euclid(59.0) + odd.lat));
+    if cpr_nl(lat_even) != cpr_nl(lat_odd) {
+        return None;
+    }
+    let odd_newer = odd.at > even.at;
+    let (lat, nl, lon_cpr) = if odd_newer {
+        (lat_odd, cpr_nl(lat_odd) - 1, odd.lon)
+    } else {
+        (lat_even, cpr_nl(lat_even), even.lon)
+    };
+    let ni = f64::from(nl.max(1));
+    let nl_even = f64::from(cpr_nl(lat_even));
+    let m = (even.lon * (nl_even - 1.0) - odd.lon * nl_even + 0.5).floor();
+    let mut lon = (360.0 / ni) * (m.rem_euclid(ni) + lon_cpr);
+    if lon >= 180.0 {
+        lon -= 360.0;
+    }
+    valid_position(lat, lon)
+}
+
+/// Position from a single frame decoded against a nearby reference. Surface
+/// frames use 90° zones instead of 360°.
+fn cpr_local(
+    frame: CprFrame,
+    odd: bool,
+    surface: bool,
+    reference: (f64, f64),
+) -> Option<(f64, f64)> {
+    let span = if surface { 90.0 } else { 360.0 };
+    let i = if odd { 1.0 } else { 0.0 };
+    let (ref_lat, ref_lon) = reference;
+    let dlat = span / (60.0 - i);
+    let j = (ref_lat / dlat).floor() + (0.5 + ref_lat.rem_euclid(dlat) / dlat - frame.lat).floor();
+    let lat = dlat * (j + frame.lat);
+    let ni = (f64::from(cpr_nl(lat)) - i).max(1.0);
+    let dlon = span / ni;
+    let m = (ref_lon / dlon).floor() + (0.5 + ref_lon.rem_euclid(dlon) / dlon - frame.lon).floor();
+    let lon = dlon * (m + frame.lon);
+    let (lat, lon) = valid_position(lat, lon)?;
+    (distance_nm(ref_lat, ref_lon, lat, lon) <= LOCAL_CPR_MAX_NM).then_some((lat, lon))
+}
+
+fn valid_position(lat: f64, lon: f64) -> Option<(f64, f64)> {
+    let lon = if lon > 180.0 { lon - 360.0 } else { lon };
+    (lat.abs() <= 90.0 && lon.abs() <= 180.0).then_some((lat, lon))
+}
+
+#[derive(Debug, Default)]
+struct Track {
+    /// DF17 hex, `~`-prefixed for non-ICAO DF18 addresses.
+    hex: String,
+    kind: Option<&'static str>,
+    flight: Option<String>,
+    category: Option<String>,
+    alt_baro: Option<Altitude>,
+    alt_geom: Option<i64>,
+    gs: Option<f64>,
+    track: Option<f64>,
+    baro_rate: Option<i64>,
+    geom_rate: Option<i64>,
+    squawk: Option<String>,
+    version: Option<i64>,
+    even: Option<CprFrame>,
+    odd: Option<CprFrame>,
+    position: Option<(f64, f64)>,
+    position_at: f64,
+    seen_at: f64,
+    messages: u64,
+    signal: [f64; 8],
+    signal_count: usize,
+}
+
+impl Track {
+    fn record_signal(&mut self, level: u8) {
+        let power = (f64::from(level) / 255.0).powi(2);
+        self.signal[self.signal_count % self.signal.len()] = power;
+        self.signal_count += 1;
+    }
+
+    fn rssi(&self) -> Option<f64> {
+        let samples = self.signal_count.min(self.signal.len());
+        if samples == 0 {
+            return None;
+        }
+        let mean = self.signal[..samples].iter().sum::<f64>() / samples as f64;
+        Some((10.0 * mean.max(1e-5).log10() * 10.0).round() / 10.0)
+    }
+
+    fn to_aircraft(&self, now: f64) -> Aircraft {
+        let (lat, lon) = self.position.unzip();
+        Aircraft {
+            hex: Some(self.hex.clone()),
+            kind: self.kind.map(str::to_string),
+            flight: self.flight.clone(),
+            alt_baro: self.alt_baro,
+            alt_geom: self.alt_geom,
+            gs: self.gs,
+            track: self.track,
+            baro_rate: self.baro_rate,
+            geom_rate: self.geom_rate,
+            squawk: self.squawk.clone(),
+            category: self.category.clone(),
+            lat,
+            lon,
+            seen_pos: self.position.map(|_| round_tenth(now - self.position_at)),
+            version: self.version,
+            messages: Some(self.messages),
+            seen: Some(round_tenth(now - self.seen_at)),
+            rssi: self.rssi(),
+            ..Aircraft::default()
+        }
+    }
+}
+
+fn round_tenth(value: f64) -> f64 {
+    (value.max(0.0) * 10.0).round() / 10.0
+}
+
+#[derive(Debug)]
+pub struct Tracker {
+    site: Option<(f64, f64)>,
+    tracks: HashMap<u32, Track>,
+    messages: u64,
+    messages_by_df: [u64; 32],
+}
+
+impl Tracker {
+    pub fn new(site: Option<(f64, f64)>) -> Self {
+        Self {
+            site,
+            tracks: HashMap::new(),
+            messages: 0,
+            messages_by_df: [0; 32],
+        }
+    }
+
+    pub fn take_stats(&mut self, period: Duration) -> ReceiverStats {
+        ReceiverStats {
+            period_secs: period.as_secs_f64(),
+            messages_by_df: Some(std::mem::take(&mut self.messages_by_df)),
+            ..ReceiverStats::default()
+        }
+    }
+
+    pub fn handle(&mut self, frame: &BeastFrame, now: f64) {
+        let data = &frame.data;
+        let df = data[0] >> 3;
+        let long = data.len() == 14;
+        let address = match (df, long) {
+            (17 | 18, true) => {
+                if crc_syndrome(data) != 0 {
+                    return;
+                }
+                bits(data, 9, 24) as u32
+            }
+            (11, false) => {
+                // Interrogator codes overlay the low 7 parity bits.
+                if crc_syndrome(data) & !0x7f != 0 {
+                    return;
+                }
+                bits(data, 9, 24) as u32
+            }
+            (4 | 5, false) | (20 | 21, true) => {
+                // Address/parity: only trust addresses already heard in
+                // DF11/17/18, otherwise noise creates phantom aircraft.
+                let address = crc_syndrome(data);
+                if !self.tracks.contains_key(&address) {
+                    return;
+                }
+                address
+            }
+            _ => return,
+        };
+        if df == 18 && bits(data, 6, 3) > 1 {
+            // TIS-B/ADS-R fine formats carry no aircraft of our own.
+            return;
+        }
+        self.messages += 1;
+        self.messages_by_df[df as usize] += 1;
+        let track = self.tracks.entry(address).or_insert_with(|| Track {
+            hex: format!("{address:06x}"),
+            ..Track::default()
+        });
+        track.seen_at = now;
+        track.messages += 1;
+        track.record_signal(frame.signal);
+        match df {
+            17 | 18 => {
+                if df == 18 {
+                    if bits(data, 6, 3) == 1 {
+                        track.hex = format!("~{address:06x}");
+                        track.kind = Some("adsb_other");
+                    } else {
+                        track.kind = Some("adsb_icao_nt");
+                    }
+                } else {
+                    track.kind = Some("adsb_icao");
+                }
+                decode_extended_squitter(track, &data[4..11], now, self.site);
+            }
+            4 | 20 => {
+                if let Some(alt) = decode_ac13(bits(data, 20, 13) as u32) {
+                    track.alt_baro = Some(Altitude::Feet(alt));
+                }
+                if df == 20 {
+                    decode_comm_b(track, &data[4..11]);
+                }
+            }
+            5 | 21 => {
+                track.squawk = Some(decode_id13(bits(data, 20, 13) as u32));
+                if df == 21 {
+                    decode_comm_b(track, &data[4..11]);
+                }
+            }
+            _ => {}
+        }
+        if track.kind.is_none() {
+            track.kind = Some("mode_s");
+        }
+    }
+
+    pub fn snapshot(&mut self, now: f64, unix_now: i64) -> ApiResponse {
+        self.tracks
+            .retain(|_, track| now - track.seen_at <= TRACK_TIMEOUT_SECS);
+        let mut aircraft: Vec<Aircraft> = self
+            .tracks
+            .values()
+            .map(|track| track.to_aircraft(now))
+            .collect();
+        aircraft.sort_by(|a, b| a.hex.cmp(&b.hex));
+        ApiResponse {
+            now: Some(unix_now),
+            messages: Some(self.messages),
+            aircraft,
+            ..ApiResponse::default()
+        }
+    }
+}
+
+/// Comm-B register 2,0 (aircraft identification) is the only one decoded.
+fn decode_comm_b(track: &mut Track, mb: &[u8]) {
+    if mb[0] == 0x20 {
+        if let Some(flight) = callsign(mb, 9) {
+            track.flight = Some(flight);
+        }
+    }
+}
+
+fn decode_extended_squitter(track: &mut Track, me: &[u8], now: f64, site: Option<(f64, f64)>) {
+    let type_code = bits(me, 1, 5);
+    match type_code {
+        1..=4 => {
+            let class = [b'D', b'C', b'B', b'A'][type_code as usize - 1] as char;
+            track.category = Some(format!("{class}{}", bits(me, 6, 3)));
+            if let Some(flight) = callsign(me, 9) {
+                track.flight = Some(flight);
+            }
+        }
+        5..=8 => {
+            track.alt_baro = Some(Altitude::Ground);
+            track.gs = surface_speed(bits(me, 6, 7));
+            if bits(me, 13, 1) == 1 {
+                track.track = Some(bits(me, 14, 7) as f64 * 360.0 / 128.0);
+            }
+            decode_position(track, me, now, true, site);
+        }
+        9..=18 | 20..=22 => {
+            let alt = decode_ac12(bits(me, 9, 12) as u32);
+            if type_code <= 18 {
+                if let Some(alt) = alt {
+                    track.alt_baro = Some(Altitude::Feet(alt));
+                }
+            } else {
+                track.alt_geom = alt.or(track.alt_geom);
+            }
+            decode_position(track, me, now, false, site);
+        }
+        19 => decode_velocity(track, me),
+        31 => {
+            track.version = Some(bits(me, 41, 3) as i64);
+        }
+        _ => {}
+    }
+}
+
+fn decode_position(
+    track: &mut Track,
+    me: &[u8],
+    now: f64,
+    surface: bool,
+    site: Option<(f64, f64)>,
+) {
+    let odd = bits(me, 22, 1) == 1;
+    let frame = CprFrame {
+        lat: bits(me, 23, 17) as f64 / 131_072.0,
+        lon: bits(me, 40, 17) as f64 / 131_072.0,
+        at: now,
+    };
+    if odd {
+        track.odd = Some(frame);
+    } else {
+        track.even = Some(frame);
+    }
+    // A recent position is the best reference; surface frames otherwise
+    // need the site since they have no global decode here.
+    let reference = track
+        .position
+        .filter(|_| now - track.position_at <= TRACK_TIMEOUT_SECS)
+        .or(if surface { site } else { None });
+    let decoded = match (reference, track.even, track.odd) {
+        (Some(reference), _, _) => cpr_local(frame, odd, surface, reference),
+        (None, Some(even), Some(odd_frame))
+            if !surface && (even.at - odd_frame.at).abs() <= CPR_PAIR_SECS =>
+        {
+            cpr_global(even, odd_frame)
+        }
+        _ => None,
+    };
+    if let Some(position) = decoded {
+        track.position = Some(position);
+        track.position_at = now;
+    }
+}
+
+fn decode_velocity(track: &mut Track, me: &[u8]) {
+    let subtype = bits(me, 6, 3);
+    if matches!(subtype, 1 | 2) {
+        let scale = if subtype == 2 { 4.0 } else { 1.0 };
+        let east_west = bits(me, 15, 10);
+        let north_south = bits(me, 26, 10);
+        if east_west > 0 && north_south > 0 {
+            let sign = |negative: u64| if negative == 1 { -1.0 } else { 1.0 };
+            let vx = sign(bits(me, 14, 1)) * (east_west - 1) as f64 * scale;
+            let vy = sign(bits(me, 25, 1)) * (north_south - 1) as f64 * scale;
+            track.gs = Some((vx.hypot(vy) * 10.0).round() / 10.0);
+            let heading = vx.atan2(vy).to_degrees().rem_euclid(360.0);
+            track.track = Some((heading * 100.0).round() / 100.0);
+        }
+    }
+    if matches!(subtype, 1..=4) {
+        let raw = bits(me, 38, 9);
+        if raw > 0 {
+            let sign = if bits(me, 37, 1) == 1 { -1 } else { 1 };
+            let rate = sign * (raw as i64 - 1) * 64;
+            if bits(me, 36, 1) == 1 {
+                track.baro_rate = Some(rate);
+            } else {
+                track.geom_rate = Some(rate);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        beast_address, cpr_global, crc_syndrome, decode_ac13, decode_id13, BeastFrame, BeastFramer,
+        CprFrame, Tracker,
+    };
+    use crate::model::Altitude;
+    use std::time::Duration;
+
+    fn hex(message: &str) -> Vec<u8> {
+        (0..message.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&message[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn frame(message: &str) -> BeastFrame {
+        BeastFrame {
+            signal: 128,
+            data: hex(message),
+        }
+    }
+
+    #[test]
+    fn parses_beast_urls() {
+        assert_eq!(
+            beast_address("beast://192.168.1.20").as_deref(),
+            Some("192.168.1.20:30005")
+        );
+        assert_eq!(
+            beast_address("BEAST://pi.local:30105/").as_deref(),
+            Some("pi.local:30105")
+        );
+        assert_eq!(
+            beast_address("beast://[::1]").as_deref(),
+            Some("[::1]:30005")
+        );
+        assert_eq!(beast_address("http://pi.local/data/aircraft.json"), None);
+        assert_eq!(beast_address("beast://"), None);
+    }
+
+    #[test]
+    fn framer_unescapes_and_resyncs() {
+        let message = hex("8D4840D6202CC371C32CE0576098");
+        let mut stream = vec![0x00, 0x1a, b'1', 0, 0, 0, 0, 0, 0, 9, 0x12, 0x34];
+        stream.extend([0x1a, b'3', 0, 0, 0x1a, 0x1a, 0, 0, 0, 0x1a, 0x1a]);
+        stream.extend(&message);
+        let mut framer = BeastFramer::default();
+        // Split mid-frame to exercise buffering.
+        framer.push(&stream[..20]);
+        assert_eq!(framer.next_frame(), None);
+        framer.push(&stream[20..]);
+        let frame = framer.next_frame().unwrap();
+        assert_eq!(frame.signal, 0x1a);
+        assert_eq!(frame.data, message);
+        assert_eq!(framer.next_frame(), None);
+    }
+
+    #[test]
+    fn crc_and_field_decoders() {
+        assert_eq!(crc_syndrome(&hex("8D4840D6202CC371C32CE0576098")), 0);
+        assert_ne!(crc_syndrome(&hex("8D4840D6202CC371C32CE0576099")), 0);
+        // 38,000 ft: N = 1560 with the Q bit set.
+        assert_eq!(decode_ac13(0x1838), Some(38_000));
+        assert_eq!(decode_ac13(0x1838 | 0x40), None);
+        // 7500: A=7, B=5, C=0, D=0.
+        let code = (1 << 11) | (1 << 9) | (1 << 7) | (1 << 5) | (1 << 1);
+        assert_eq!(decode_id13(code), "7500");
+    }
+
+    #[test]
+    fn decodes_identification_position_and_velocity() {
+        let mut tracker = Tracker::new(None);
+        tracker.handle(&frame("8D4840D6202CC371C32CE0576098"), 0.0);
+        tracker.handle(&frame("8D40621D58C386435CC412692AD6"), 1.0);
+        tracker.handle(&frame("8D40621D58C382D690C8AC2863A7"), 2.0);
+        tracker.handle(&frame("8D485020994409940838175B284F"), 3.0);
+        // Corrupted copy is rejected by the CRC.
+        tracker.handle(&frame("8D485020994409940838175B2840"), 3.0);
+        let snapshot = tracker.snapshot(4.0, 1_700_000_000);
+        assert_eq!(snapshot.messages, Some(4));
+        assert_eq!(snapshot.aircraft.len(), 3);
+        let stats = tracker.take_stats(Duration::from_secs(60));
+        assert_eq!(stats.df_breakdown().unwrap()[0], ("DF17", 4));
+        assert_eq!(
+            tracker.take_stats(Duration::from_secs(60)).df_breakdown(),
+            None
+        );
+
+        let klm = &snapshot.aircraft[1];
+        assert_eq!(klm.hex.as_deref(), Some("4840d6"));
+        assert_eq!(klm.flight.as_deref(), Some("KLM1023"));
+        assert_eq!(klm.category.as_deref(), Some("A0"));
+
+        let positioned = &snapshot.aircraft[0];
+        assert_eq!(positioned.hex.as_deref(), Some("40621d"));
+        assert_eq!(positioned.alt_baro, Some(Altitude::Feet(38_000)));
+        assert!((positioned.lat.unwrap() - 52.2572).abs() < 1e-3);
+        assert!((positioned.lon.unwrap() - 3.9194).abs() < 1e-3);
+        assert_eq!(positioned.seen_pos, Some(2.0));
+
+        let moving = &snapshot.aircraft[2];
+        assert!((moving.gs.unwrap() - 159.2).abs() < 0.1);
+        assert!((moving.track.unwrap() - 182.88).abs() < 0.01);
+        assert_eq!(moving.geom_rate.or(moving.baro_rate), Some(-832));
+        assert!(moving.rssi.unwrap() < 0.0);
+
+        // Nothing heard for over a minute: dropped.
+        assert!(tracker.snapshot(70.0, 1_700_000_066).aircraft.is_empty());
+    }
+
+    #[test]
+    fn global_cpr_uses_newest_frame() {
+        let even = CprFrame {
+            lat: 93_000.0 / 131_072.0,
+            lon: 51_372.0 / 131_072.0,
+            at: 1.0,
+        };
+        let odd = CprFrame {
+            lat: 74_158.0 / 131_072.0,
+            lon: 50_194.0 / 131_072.0,
+            at: 0.0,
+        };
+        let (lat, lon) = cpr_global(even, odd).unwrap();
+        assert!((lat - 52.2572).abs() < 1e-3);
+        assert!((lon - 3.9194).abs() < 1e-3);
+        let (lat, _) = cpr_global(even, CprFrame { at: 2.0, ..odd }).unwrap();
+        assert!((lat - 52.2657).abs() < 1e-3);
+    }
+}
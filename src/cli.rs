@@ -0,0 +1,904 @@
+use clap::builder::{PossibleValue, StringValueParser, TypedValueParser};
+use clap::{Args, Parser, Subcommand, ValueEnum, ValueHint};
+use clap_complete::Shell;
+use std::ffi::OsStr;
+use std::path::PathBuf;
+
+use crate::config::config_choices;
+use crate::synthetic::STRESS_AIRCRAFT;
+
+const AFTER_HELP: &str = "\
+Environment: ADSB_* variables override the config file (e.g. ADSB_URL, ADSB_REFRESH)
+Environment: ADSB_ALT_TREND toggles altitude trend arrows
+Environment: ADSB_COLUMN_CACHE toggles column width cache
+Environment: ADSB_TRACK_ARROWS toggles track direction arrows
+Environment: ADSB_FLAG_STYLE sets flag rendering mode
+Environment: ADSB_DEMO_MODE toggles demo mode
+Environment: ADSB_STATS_METRIC_1/2/3 control stats metrics
+Environment: ADSB_PERF_PANEL_1..6 choose the PERF layout panels
+Environment: ADSB_URLS lists receivers; ADSB_MERGE_RECEIVERS merges them
+Keys: q quit | up/down move | s sort | / filter | f favorite | m columns | ? help
+      t theme | l layout | R radar | v watch | b labels | L site | +/- zoom
+      Shift+arrows pan
+      e export csv | E export json
+      C config editor (defaults; remap in the [keys] config section)";
+
+#[derive(Debug, Parser)]
+#[command(name = "adsb-tui", version, about, after_help = AFTER_HELP)]
+pub struct Cli {
+    #[command(flatten)]
+    pub config: ConfigArgs,
+    /// Validate the configuration and exit (same as `validate-config`)
+    #[arg(long)]
+    pub check: bool,
+    /// Print the merged configuration with the source of each value and exit
+    #[arg(long)]
+    pub print_config: bool,
+    /// Run without the terminal UI, printing alerts and feed status to stdout
+    #[arg(long)]
+    pub headless: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Start the interactive terminal UI (default)
+    Run,
+    /// Fetch one snapshot and write it to the exports directory
+    Export(ExportArgs),
+    /// Fetch once and print the filtered, sorted aircraft to stdout
+    Snapshot(SnapshotArgs),
+    /// Query the lookup API and print matching aircraft
+    Lookup(LookupArgs),
+    /// Check the configuration and feeds, print a report and exit
+    ValidateConfig(ValidateArgs),
+    /// Poll the feed and append every snapshot to a journal file
+    Record(RecordArgs),
+    /// Play a recorded journal back through the terminal UI
+    Replay(ReplayArgs),
+    /// Run the terminal UI on a large synthetic feed (see F12 profiler)
+    Stress(StressArgs),
+    /// Print a shell completion script to stdout
+    Completions(CompletionsArgs),
+    /// Store or remove secrets in the system keyring
+    #[command(subcommand)]
+    Secret(SecretCommand),
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum SecretCommand {
+    /// Read a secret from stdin and store it as keyring:NAME
+    Set {
+        /// Entry name, referenced in the config as `keyring:NAME`
+        name: String,
+    },
+    /// Remove the keyring:NAME entry
+    Delete { name: String },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Geojson,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ExportArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = ExportFormat::Csv)]
+    pub format: ExportFormat,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SnapshotFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SnapshotSort {
+    Seen,
+    Alt,
+    Spd,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct SnapshotArgs {
+    /// Output format
+    #[arg(long, value_enum, default_value_t = SnapshotFormat::Table)]
+    pub format: SnapshotFormat,
+    /// Sort order (favorites first, as in the UI)
+    #[arg(long, value_enum, default_value_t = SnapshotSort::Seen)]
+    pub sort: SnapshotSort,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct LookupArgs {
+    /// Query, e.g. `hex a1b2c3`, `callsign SWA123`, `point 40.6 -73.8 25`, `mil`
+    #[arg(required = true, num_args = 1.., allow_hyphen_values = true)]
+    pub query: Vec<String>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct CompletionsArgs {
+    /// Target shell
+    #[arg(value_enum)]
+    pub shell: Shell,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct ValidateArgs {
+    /// Skip the feed reachability checks
+    #[arg(long)]
+    pub offline: bool,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct RecordArgs {
+    /// Journal file to append to (JSON lines; zstd-compressed if it ends in .zst)
+    pub path: PathBuf,
+    /// Stop after this many snapshots
+    #[arg(long)]
+    pub count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct ReplayArgs {
+    /// Journal file written by `record`
+    pub path: PathBuf,
+    /// Playback speed multiplier
+    #[arg(long, default_value_t = 1.0)]
+    pub speed: f64,
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct StressArgs {
+    /// Aircraft per snapshot
+    #[arg(long, default_value_t = STRESS_AIRCRAFT)]
+    pub aircraft: usize,
+}
+
+#[derive(Debug, Clone, Default, Args)]
+pub struct ConfigArgs {
+    /// Config file path
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+    /// Apply the [profile.NAME] section of the config file
+    #[arg(long, global = true, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Feed URL
+    #[arg(long, global = true, help_heading = "Feed")]
+    pub url: Option<String>,
+    /// Feed URL template ({lat}, {lon}, {range_nm}, {range}, {zoom})
+    #[arg(long, global = true, help_heading = "Feed")]
+    pub url_template: Option<String>,
+    /// Refresh interval in seconds
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Feed")]
+    pub refresh: Option<u64>,
+    /// Accept invalid TLS certificates
+    #[arg(long, global = true, help_heading = "Feed")]
+    pub insecure: bool,
+    /// Allow plain http:// feed URLs
+    #[arg(long, global = true, help_heading = "Feed")]
+    pub allow_http: bool,
+    /// Poll every `urls` receiver at once and merge them into one view
+    #[arg(long, global = true, help_heading = "Feed")]
+    pub merge_receivers: bool,
+    /// Allow --insecure to be used
+    #[arg(long, global = true, help_heading = "Feed")]
+    pub allow_insecure: bool,
+    /// Receiver stats.json URL ("off" disables; default derives it from the feed URL)
+    #[arg(long, global = true, value_name = "URL", help_heading = "Feed")]
+    pub stats_url: Option<String>,
+    /// Fill trails from the last N minutes of tar1090 history at startup (0 = off)
+    #[arg(long, global = true, value_name = "MINUTES", help_heading = "Feed")]
+    pub history_backfill_minutes: Option<u64>,
+    /// API key sent with feed and lookup requests
+    #[arg(long, global = true, value_name = "KEY", help_heading = "Feed")]
+    pub api_key: Option<String>,
+    /// Header name used for the API key
+    #[arg(long, global = true, value_name = "NAME", help_heading = "Feed")]
+    pub api_key_header: Option<String>,
+    /// .env file read for `env:NAME` secret references
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Feed")]
+    pub env_file: Option<String>,
+
+    /// Seconds before an aircraft is considered stale
+    #[arg(long, global = true, value_name = "SECONDS", help_heading = "Data")]
+    pub stale: Option<u64>,
+    /// Seconds before a position is too old to plot on the radar
+    #[arg(long, global = true, value_name = "SECONDS", help_heading = "Data")]
+    pub position_stale: Option<u64>,
+    /// Hide aircraft older than --stale
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "show_stale",
+        help_heading = "Data"
+    )]
+    pub hide_stale: bool,
+    /// Show stale aircraft (default)
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "hide_stale",
+        help_heading = "Data"
+    )]
+    pub show_stale: bool,
+    /// Limit the table and radar to airborne or surface traffic
+    #[arg(long, global = true, value_parser = ConfigChoices("ground_filter"), help_heading = "Data")]
+    pub ground_filter: Option<String>,
+    /// Altitude shown in the ALT column and used for sorting and alerts
+    #[arg(long, global = true, value_parser = ConfigChoices("altitude_source"), help_heading = "Data")]
+    pub altitude_source: Option<String>,
+    /// NIC threshold for low-integrity alerts
+    #[arg(long, global = true, value_name = "N", help_heading = "Data")]
+    pub low_nic: Option<i64>,
+    /// NACp threshold for low-accuracy alerts
+    #[arg(long, global = true, value_name = "N", help_heading = "Data")]
+    pub low_nac: Option<i64>,
+    /// How the radar treats positions below --low-nic/--low-nac
+    #[arg(long, global = true, value_parser = ConfigChoices("low_quality_positions"), help_heading = "Data")]
+    pub low_quality_positions: Option<String>,
+    /// Trail length in points
+    #[arg(long, global = true, value_name = "N", help_heading = "Data")]
+    pub trail: Option<u64>,
+    /// Hide trail points older than this many seconds and fade the rest (0 = off)
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Data")]
+    pub trail_fade_secs: Option<u64>,
+    /// Color trail points by the altitude they were reported at
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_trail_altitude_colors",
+        help_heading = "Radar"
+    )]
+    pub trail_altitude_colors: bool,
+    /// Draw trails in the dim color (default)
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "trail_altitude_colors",
+        help_heading = "Radar"
+    )]
+    pub no_trail_altitude_colors: bool,
+    /// Aircraft kept in trend/trail/rate history (0 = unlimited)
+    #[arg(long, global = true, value_name = "N", help_heading = "Data")]
+    pub max_tracked: Option<u64>,
+    /// Initial table filter
+    #[arg(long, global = true, value_name = "TEXT", help_heading = "Data")]
+    pub filter: Option<String>,
+    /// Table sort columns, e.g. "alt desc, callsign"
+    #[arg(long, global = true, value_name = "SPEC", help_heading = "Data")]
+    pub sort_order: Option<String>,
+    /// Table columns in order, e.g. "flight:10, -reg, alt" (- hides, :N fixes the width)
+    #[arg(long, global = true, value_name = "SPEC", help_heading = "Display")]
+    pub table_columns: Option<String>,
+    /// Favorite hex (repeatable)
+    #[arg(
+        long = "favorite",
+        global = true,
+        value_name = "HEX",
+        help_heading = "Data"
+    )]
+    pub favorites: Vec<String>,
+    /// Favorites file path
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Data")]
+    pub favorites_file: Option<String>,
+    /// Enable watchlist matching
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_watchlist",
+        help_heading = "Data"
+    )]
+    pub watchlist: bool,
+    /// Disable watchlist matching
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "watchlist",
+        help_heading = "Data"
+    )]
+    pub no_watchlist: bool,
+    /// Watchlist file path
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Data")]
+    pub watchlist_file: Option<String>,
+    /// Hourly traffic and range outline history file (empty disables it)
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Data")]
+    pub traffic_history_file: Option<String>,
+    /// Sighting history file: per-aircraft visits, altitudes and range (empty disables it)
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Data")]
+    pub sightings_file: Option<String>,
+    /// Geofence zones file: circles and polygons with entry/exit alerts (empty disables it)
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Data")]
+    pub zones_file: Option<String>,
+    /// OurAirports airports.csv for route names, the nearest-airport column and radar markers
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Data")]
+    pub airports_file: Option<String>,
+    /// GeoJSON coastlines and borders drawn on the canvas radar
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Data")]
+    pub geography_file: Option<String>,
+    /// tar1090-db aircraft.csv(.gz) or basic-ac-db JSON for registration, type and operator
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Data")]
+    pub aircraft_db_file: Option<String>,
+    /// Military ICAO address ranges, one per line; replaces the built-in list
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Data")]
+    pub military_ranges_file: Option<String>,
+    /// TOML role rules (callsigns, hex ranges, operators, types to role and color)
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Data")]
+    pub roles_file: Option<String>,
+    /// Directory for CSV/JSON exports
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::DirPath, help_heading = "Data")]
+    pub export_dir: Option<String>,
+
+    /// Enable logging
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_log",
+        help_heading = "Logging"
+    )]
+    pub log: bool,
+    /// Disable logging
+    #[arg(long, global = true, overrides_with = "log", help_heading = "Logging")]
+    pub no_log: bool,
+    /// Log level (error, warn, info, debug, trace)
+    #[arg(long, global = true, value_name = "LEVEL", help_heading = "Logging")]
+    pub log_level: Option<String>,
+    /// Log file path (empty logs to stderr)
+    #[arg(long, global = true, value_name = "PATH", value_hint = ValueHint::FilePath, help_heading = "Logging")]
+    pub log_file: Option<String>,
+    /// Control socket: a UNIX socket path or tcp:127.0.0.1:PORT
+    #[arg(long, global = true, value_name = "ADDRESS", help_heading = "Logging")]
+    pub control_socket: Option<String>,
+
+    /// Table layout
+    #[arg(long, global = true, value_parser = ConfigChoices("layout"), help_heading = "Display")]
+    pub layout: Option<String>,
+    /// Use the overhead layout below this terminal width (0 = never)
+    #[arg(long, global = true, value_name = "COLS", help_heading = "Display")]
+    pub overhead_width: Option<u64>,
+    /// Color theme
+    #[arg(long, global = true, value_parser = ConfigChoices("theme"), help_heading = "Display")]
+    pub theme: Option<String>,
+    /// Country flag rendering
+    #[arg(long, global = true, value_parser = ConfigChoices("flag_style"), help_heading = "Display")]
+    pub flag_style: Option<String>,
+    /// Radar blip and table row coloring: recent messages, or an altitude gradient
+    #[arg(long, global = true, value_parser = ConfigChoices("color_by"), help_heading = "Display")]
+    pub color_by: Option<String>,
+    /// Color the ALT column by altitude too when color_by is "altitude" (default)
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_color_alt_column",
+        help_heading = "Display"
+    )]
+    pub color_alt_column: bool,
+    /// Leave the ALT column in the row color
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "color_alt_column",
+        help_heading = "Display"
+    )]
+    pub no_color_alt_column: bool,
+    /// Enable demo mode
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_demo_mode",
+        help_heading = "Display"
+    )]
+    pub demo_mode: bool,
+    /// Disable demo mode
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "demo_mode",
+        help_heading = "Display"
+    )]
+    pub no_demo_mode: bool,
+    /// UI redraw rate
+    #[arg(long, global = true, value_name = "FPS", help_heading = "Display")]
+    pub ui_fps: Option<u64>,
+    /// Swap snapshots at the UI rate
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_smooth",
+        help_heading = "Display"
+    )]
+    pub smooth: bool,
+    /// Apply every update immediately
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "smooth",
+        help_heading = "Display"
+    )]
+    pub no_smooth: bool,
+    /// Keep last known values when a snapshot omits them
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_smooth_merge",
+        help_heading = "Display"
+    )]
+    pub smooth_merge: bool,
+    /// Disable snapshot merging
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "smooth_merge",
+        help_heading = "Display"
+    )]
+    pub no_smooth_merge: bool,
+    /// Stop the spinner, sweeps and blinking so idle frames are not redrawn
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_reduced_motion",
+        help_heading = "Display"
+    )]
+    pub reduced_motion: bool,
+    /// Animate the spinner, sweeps and sync indicator (default)
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "reduced_motion",
+        help_heading = "Display"
+    )]
+    pub no_reduced_motion: bool,
+    /// Show altitude trend arrows
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_alt_arrows",
+        help_heading = "Display"
+    )]
+    pub alt_arrows: bool,
+    /// Hide altitude trend arrows
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "alt_arrows",
+        help_heading = "Display"
+    )]
+    pub no_alt_arrows: bool,
+    /// Show track direction arrows
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_track_arrows",
+        help_heading = "Display"
+    )]
+    pub track_arrows: bool,
+    /// Hide track direction arrows
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "track_arrows",
+        help_heading = "Display"
+    )]
+    pub no_track_arrows: bool,
+    /// Cache column widths
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_column_cache",
+        help_heading = "Display"
+    )]
+    pub column_cache: bool,
+    /// Recompute column widths every frame
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "column_cache",
+        help_heading = "Display"
+    )]
+    pub no_column_cache: bool,
+    /// Message rate smoothing window
+    #[arg(long, global = true, value_name = "MS", help_heading = "Display")]
+    pub rate_window_ms: Option<u64>,
+    /// Minimum interval for rate samples
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Display")]
+    pub rate_min_secs: Option<f64>,
+    /// First configurable stats metric
+    #[arg(long, global = true, value_name = "METRIC", value_parser = ConfigChoices("stats_metric_1"), help_heading = "Display")]
+    pub stats_metric_1: Option<String>,
+    /// Second configurable stats metric
+    #[arg(long, global = true, value_name = "METRIC", value_parser = ConfigChoices("stats_metric_2"), help_heading = "Display")]
+    pub stats_metric_2: Option<String>,
+    /// Third configurable stats metric
+    #[arg(long, global = true, value_name = "METRIC", value_parser = ConfigChoices("stats_metric_3"), help_heading = "Display")]
+    pub stats_metric_3: Option<String>,
+    /// First PERF layout panel (none hides the row)
+    #[arg(long, global = true, value_name = "PANEL", value_parser = ConfigChoices("perf_panel_1"), help_heading = "Display")]
+    pub perf_panel_1: Option<String>,
+    /// Second PERF layout panel (none hides the row)
+    #[arg(long, global = true, value_name = "PANEL", value_parser = ConfigChoices("perf_panel_2"), help_heading = "Display")]
+    pub perf_panel_2: Option<String>,
+    /// Third PERF layout panel (none hides the row)
+    #[arg(long, global = true, value_name = "PANEL", value_parser = ConfigChoices("perf_panel_3"), help_heading = "Display")]
+    pub perf_panel_3: Option<String>,
+    /// Fourth PERF layout panel (none hides the row)
+    #[arg(long, global = true, value_name = "PANEL", value_parser = ConfigChoices("perf_panel_4"), help_heading = "Display")]
+    pub perf_panel_4: Option<String>,
+    /// Fifth PERF layout panel (none hides the row)
+    #[arg(long, global = true, value_name = "PANEL", value_parser = ConfigChoices("perf_panel_5"), help_heading = "Display")]
+    pub perf_panel_5: Option<String>,
+    /// Sixth PERF layout panel (none hides the row)
+    #[arg(long, global = true, value_name = "PANEL", value_parser = ConfigChoices("perf_panel_6"), help_heading = "Display")]
+    pub perf_panel_6: Option<String>,
+    /// Time span of the PERF axis charts
+    #[arg(long, global = true, value_name = "WINDOW", value_parser = ConfigChoices("perf_window"), help_heading = "Display")]
+    pub perf_window: Option<String>,
+
+    /// Radar range in nautical miles
+    #[arg(long, global = true, value_name = "NM", help_heading = "Radar")]
+    pub radar_range_nm: Option<f64>,
+    /// Radar horizontal aspect correction
+    #[arg(long, global = true, value_name = "RATIO", help_heading = "Radar")]
+    pub radar_aspect: Option<f64>,
+    /// Radar renderer
+    #[arg(long, global = true, value_parser = ConfigChoices("radar_renderer"), help_heading = "Radar")]
+    pub radar_renderer: Option<String>,
+    /// Radar blip glyph
+    #[arg(long, global = true, value_parser = ConfigChoices("radar_blip"), help_heading = "Radar")]
+    pub radar_blip: Option<String>,
+    /// Airport markers on the radar
+    #[arg(long, global = true, value_parser = ConfigChoices("radar_airports"), help_heading = "Radar")]
+    pub radar_airports: Option<String>,
+    /// Show radar blip labels
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_radar_labels",
+        help_heading = "Radar"
+    )]
+    pub radar_labels: bool,
+    /// Hide radar blip labels
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "radar_labels",
+        help_heading = "Radar"
+    )]
+    pub no_radar_labels: bool,
+    /// Plot aircraft whose position timed out as hollow ghost blips
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_radar_ghosts",
+        help_heading = "Radar"
+    )]
+    pub radar_ghosts: bool,
+    /// Leave aircraft without a live position off the radar (default)
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "radar_ghosts",
+        help_heading = "Radar"
+    )]
+    pub no_radar_ghosts: bool,
+    /// Dead-reckon radar blips from speed and track between polls
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_predict_positions",
+        help_heading = "Radar"
+    )]
+    pub predict_positions: bool,
+    /// Plot radar blips where the feed last reported them (default)
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "predict_positions",
+        help_heading = "Radar"
+    )]
+    pub no_predict_positions: bool,
+    /// Draw a leader line ahead of each radar blip along its track
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_radar_vectors",
+        help_heading = "Radar"
+    )]
+    pub radar_vectors: bool,
+    /// Plot radar blips without leader lines (default)
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "radar_vectors",
+        help_heading = "Radar"
+    )]
+    pub no_radar_vectors: bool,
+    /// Seconds of travel a radar leader line shows
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Radar")]
+    pub radar_vector_secs: Option<u64>,
+    /// Label the radar range rings and mark N/E/S/W (default)
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_radar_compass",
+        help_heading = "Radar"
+    )]
+    pub radar_compass: bool,
+    /// Draw plain range rings without labels or compass marks
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "radar_compass",
+        help_heading = "Radar"
+    )]
+    pub no_radar_compass: bool,
+    /// Outline the farthest range seen in each direction on the radar
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "no_radar_range_rose",
+        help_heading = "Radar"
+    )]
+    pub radar_range_rose: bool,
+    /// Hide the radar range outline (default)
+    #[arg(
+        long,
+        global = true,
+        overrides_with = "radar_range_rose",
+        help_heading = "Radar"
+    )]
+    pub no_radar_range_rose: bool,
+
+    /// Receiver latitude
+    #[arg(
+        long,
+        global = true,
+        value_name = "LAT",
+        allow_negative_numbers = true,
+        help_heading = "Site"
+    )]
+    pub site_lat: Option<f64>,
+    /// Receiver longitude
+    #[arg(
+        long,
+        global = true,
+        value_name = "LON",
+        allow_negative_numbers = true,
+        help_heading = "Site"
+    )]
+    pub site_lon: Option<f64>,
+    /// Receiver altitude in meters
+    #[arg(
+        long,
+        global = true,
+        value_name = "METERS",
+        allow_negative_numbers = true,
+        help_heading = "Site"
+    )]
+    pub site_alt_m: Option<f64>,
+    /// Start at the named [[sites]] entry
+    #[arg(long, global = true, value_name = "NAME", help_heading = "Site")]
+    pub site: Option<String>,
+    /// Proximity alert radius in miles
+    #[arg(long, global = true, value_name = "MI", help_heading = "Site")]
+    pub notify_mi: Option<f64>,
+    /// Overpass alert radius in miles
+    #[arg(long, global = true, value_name = "MI", help_heading = "Site")]
+    pub overpass_mi: Option<f64>,
+    /// Alert when an aircraft will pass within this many miles (0 = off)
+    #[arg(long, global = true, value_name = "MI", help_heading = "Site")]
+    pub cpa_mi: Option<f64>,
+    /// Look-ahead window in minutes for closest-approach alerts
+    #[arg(long, global = true, value_name = "MIN", help_heading = "Site")]
+    pub cpa_mins: Option<f64>,
+    /// Seconds between repeat alerts per aircraft
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Site")]
+    pub notify_cooldown: Option<u64>,
+    /// Alerts also shown as desktop notifications: near, over, cpa, watch or all
+    #[arg(long, global = true, value_name = "EVENTS", help_heading = "Site")]
+    pub notify_desktop: Option<String>,
+    /// Local time windows when alerts skip the desktop and hooks, e.g. 23:00-07:00
+    #[arg(long, global = true, value_name = "WINDOWS", help_heading = "Site")]
+    pub quiet_hours: Option<String>,
+    /// POST a JSON payload here when a hook_events alert fires
+    #[arg(long, global = true, value_name = "URL", help_heading = "Site")]
+    pub hook_url: Option<String>,
+    /// Publish the hook payload to this mqtt://host[:port]/topic when a hook_events alert fires
+    #[arg(long, global = true, value_name = "URL", help_heading = "Site")]
+    pub hook_mqtt: Option<String>,
+    /// Shell command run when a hook_events alert fires (payload on stdin)
+    #[arg(long, global = true, value_name = "CMD", help_heading = "Site")]
+    pub hook_command: Option<String>,
+    /// Hook payload template with {event}, {hex}, {callsign}, {distance_mi}, ... (empty = built-in JSON)
+    #[arg(long, global = true, value_name = "TEMPLATE", help_heading = "Site")]
+    pub hook_payload: Option<String>,
+    /// Alerts that trigger hooks: near, over, cpa, watch, emergency or all
+    #[arg(long, global = true, value_name = "EVENTS", help_heading = "Site")]
+    pub hook_events: Option<String>,
+    /// Seconds between hook calls per aircraft and alert type
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Site")]
+    pub hook_cooldown: Option<u64>,
+
+    /// ICAO station for the WEATHER panel's METAR/TAF (empty disables it)
+    #[arg(long, global = true, value_name = "ICAO", help_heading = "Weather")]
+    pub weather_station: Option<String>,
+    /// Seconds between weather fetches
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Weather")]
+    pub weather_refresh: Option<u64>,
+    /// NOAA text service base URL for METAR/TAF files
+    #[arg(long, global = true, value_name = "URL", help_heading = "Weather")]
+    pub weather_base: Option<String>,
+
+    /// planespotters.net photo API base URL (empty disables the Photo tab)
+    #[arg(long, global = true, value_name = "URL", help_heading = "Photos")]
+    pub photo_base: Option<String>,
+    /// Seconds to keep cached photo metadata
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Photos")]
+    pub photo_ttl: Option<u64>,
+
+    /// Route/lookup API base URL
+    #[arg(long, global = true, value_name = "URL", help_heading = "Routes")]
+    pub route_base: Option<String>,
+    /// Route cache lifetime
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Routes")]
+    pub route_ttl: Option<u64>,
+    /// Route request interval
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Routes")]
+    pub route_refresh: Option<u64>,
+    /// Callsigns per route request
+    #[arg(long, global = true, value_name = "N", help_heading = "Routes")]
+    pub route_batch: Option<u64>,
+    /// Routes kept in the cache (0 = unlimited)
+    #[arg(long, global = true, value_name = "N", help_heading = "Routes")]
+    pub route_cache_size: Option<u64>,
+    /// Route request timeout
+    #[arg(long, global = true, value_name = "SECS", help_heading = "Routes")]
+    pub route_timeout: Option<u64>,
+    /// Disable route lookups
+    #[arg(long, global = true, help_heading = "Routes")]
+    pub route_disable: bool,
+    /// Route lookup backend
+    #[arg(long, global = true, value_parser = ConfigChoices("route_mode"), help_heading = "Routes")]
+    pub route_mode: Option<String>,
+    /// Route airports in the details panel: names from airports_file, or codes
+    #[arg(long, global = true, value_parser = ConfigChoices("route_labels"), help_heading = "Routes")]
+    pub route_labels: Option<String>,
+    /// tar1090 routes.json path
+    #[arg(long, global = true, value_name = "PATH", help_heading = "Routes")]
+    pub route_path: Option<String>,
+}
+
+/// Accepts any string (aliases and unknown values keep their config fallbacks)
+/// while advertising the canonical choices to `--help` and shell completions.
+#[derive(Clone, Copy, Debug)]
+struct ConfigChoices(&'static str);
+
+impl TypedValueParser for ConfigChoices {
+    type Value = String;
+
+    fn parse_ref(
+        &self,
+        cmd: &clap::Command,
+        arg: Option<&clap::Arg>,
+        value: &OsStr,
+    ) -> Result<Self::Value, clap::Error> {
+        StringValueParser::new().parse_ref(cmd, arg, value)
+    }
+
+    fn possible_values(&self) -> Option<Box<dyn Iterator<Item = PossibleValue> + '_>> {
+        let choices = config_choices(self.0)?;
+        Some(Box::new(choices.iter().map(PossibleValue::new)))
+    }
+}
+
+pub fn flag_pair(on: bool, off: bool) -> Option<bool> {
+    if on {
+        Some(true)
+    } else if off {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{flag_pair, Cli, Command, ExportFormat, SnapshotFormat, SnapshotSort};
+    use clap::{CommandFactory, Parser};
+    use clap_complete::{generate, Shell};
+
+    #[test]
+    fn cli_definition_is_valid() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn global_options_work_after_subcommand() {
+        let cli = Cli::try_parse_from([
+            "adsb-tui",
+            "export",
+            "--format",
+            "json",
+            "--site-lat",
+            "-33.9",
+            "--url",
+            "http://feed.test/data.json",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Export(args)) => assert_eq!(args.format, ExportFormat::Json),
+            other => panic!("unexpected command {other:?}"),
+        }
+        assert_eq!(cli.config.site_lat, Some(-33.9));
+        assert_eq!(
+            cli.config.url.as_deref(),
+            Some("http://feed.test/data.json")
+        );
+    }
+
+    #[test]
+    fn snapshot_defaults_to_table_sorted_by_seen() {
+        let cli = Cli::try_parse_from(["adsb-tui", "snapshot"]).unwrap();
+        match cli.command {
+            Some(Command::Snapshot(args)) => {
+                assert_eq!(args.format, SnapshotFormat::Table);
+                assert_eq!(args.sort, SnapshotSort::Seen);
+            }
+            other => panic!("unexpected command {other:?}"),
+        }
+        let cli = Cli::try_parse_from([
+            "adsb-tui", "snapshot", "--format", "csv", "--sort", "alt", "--filter", "UAL",
+        ])
+        .unwrap();
+        match cli.command {
+            Some(Command::Snapshot(args)) => {
+                assert_eq!(args.format, SnapshotFormat::Csv);
+                assert_eq!(args.sort, SnapshotSort::Alt);
+            }
+            other => panic!("unexpected command {other:?}"),
+        }
+        assert_eq!(cli.config.filter.as_deref(), Some("UAL"));
+    }
+
+    #[test]
+    fn paired_flags_last_one_wins() {
+        let cli = Cli::try_parse_from(["adsb-tui", "--log", "--no-log"]).unwrap();
+        assert_eq!(flag_pair(cli.config.log, cli.config.no_log), Some(false));
+        let cli = Cli::try_parse_from(["adsb-tui", "--no-log", "--log"]).unwrap();
+        assert_eq!(flag_pair(cli.config.log, cli.config.no_log), Some(true));
+        let cli = Cli::try_parse_from(["adsb-tui"]).unwrap();
+        assert_eq!(flag_pair(cli.config.log, cli.config.no_log), None);
+        assert!(cli.command.is_none());
+    }
+
+    #[test]
+    fn unknown_arguments_are_rejected() {
+        assert!(Cli::try_parse_from(["adsb-tui", "--bogus"]).is_err());
+    }
+
+    #[test]
+    fn choice_flags_accept_aliases() {
+        let cli =
+            Cli::try_parse_from(["adsb-tui", "--theme", "colorblind", "--layout", "perf"]).unwrap();
+        assert_eq!(cli.config.theme.as_deref(), Some("colorblind"));
+        assert_eq!(cli.config.layout.as_deref(), Some("perf"));
+    }
+
+    #[test]
+    fn completions_cover_flags_and_choices() {
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish] {
+            let mut out = Vec::new();
+            generate(shell, &mut Cli::command(), "adsb-tui", &mut out);
+            let script = String::from_utf8(out).unwrap();
+            assert!(script.contains("radar-renderer"), "{shell}");
+            assert!(script.contains("validate-config"), "{shell}");
+            assert!(script.contains("tar1090"), "{shell}");
+        }
+    }
+}
@@ -0,0 +1,824 @@
+use anyhow::{anyhow, Result};
+use clap::CommandFactory;
+use crossterm::event::{self, Event, KeyCode, KeyModifiers};
+use crossterm::terminal;
+use std::collections::HashSet;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::{oneshot, watch};
+use tracing::{debug, info, warn};
+
+use crate::aircraft_db::spawn_aircraft_db_loader;
+use crate::airports::AirportDb;
+use crate::app::{App, AppData, LayoutMode};
+use crate::beast::{self, spawn_beast_reader};
+use crate::cli::{
+    Cli, CompletionsArgs, ConfigArgs, ExportArgs, ExportFormat, LookupArgs, RecordArgs, ReplayArgs,
+    SecretCommand, SnapshotArgs, SnapshotFormat, SnapshotSort, StressArgs, ValidateArgs,
+};
+use crate::config::{self, Config};
+use crate::control::{self, ControlRequest};
+use crate::export;
+use crate::geography::Geography;
+use crate::hooks::{spawn_alert_hooks, HookSettings};
+use crate::journal::{self, JournalEntry, JournalWriter, ReplayStatus};
+use crate::keys::KeyMap;
+use crate::lookup::{self, spawn_lookup_fetcher};
+use crate::model::{seen_seconds, Aircraft, ApiResponse, ReceiverStats};
+use crate::mqtt::{spawn_mqtt_reader, MqttTarget};
+use crate::net::{self, spawn_fetcher, spawn_merged_fetcher, spawn_stats_fetcher};
+use crate::notifier::{self, AlertKinds};
+use crate::photos::{spawn_photo_fetcher, PhotoCache};
+use crate::reload::ConfigWatcher;
+use crate::roles::RoleRules;
+use crate::routes::spawn_route_fetcher;
+use crate::runtime::{
+    init_terminal, restore_terminal, run_app, run_headless, LookupChannels, PhotoChannels,
+    ReplayChannels, RouteChannels,
+};
+use crate::secrets;
+use crate::sightings::{self, SightingStore};
+use crate::sort::{SortKey, SortSpec};
+use crate::storage::{
+    ensure_watchlist_file, ensure_zones_file, load_airports, load_favorites, load_geography,
+    load_photo_cache, load_role_rules, load_traffic_history, load_watchlist, load_zones,
+};
+use crate::synthetic::{self, SyntheticFeed};
+use crate::tasks::Tasks;
+use crate::traffic::TrafficHistory;
+use crate::validate;
+use crate::watchlist::WatchEntry;
+use crate::weather::{spawn_weather_fetcher, WeatherReport};
+use crate::zones::Zone;
+
+pub fn run(config: Config, config_args: ConfigArgs, headless: bool) -> Result<()> {
+    let tasks = Tasks::new()?;
+    let (tx, rx) = unbounded_channel();
+    let (api_key, api_key_header) = api_credentials(&config);
+    let feed_urls = config::initial_fetch_urls(&config)?;
+    let display_url = feed_urls
+        .first()
+        .cloned()
+        .unwrap_or_else(|| config.url.clone());
+    let (feed_update_tx, feed_update_rx) = unbounded_channel();
+    // Beast input has no stats.json; the reader counts messages itself.
+    let mut beast_stats = None;
+
+    if config.merge_receivers {
+        require_json_feed(&feed_urls, "merge_receivers")?;
+        spawn_merged_fetcher(
+            &tasks,
+            feed_urls,
+            config.refresh,
+            config.insecure,
+            api_key,
+            api_key_header,
+            feed_update_rx,
+            tx,
+        );
+    } else {
+        let first = feed_urls.first().map(String::as_str).unwrap_or_default();
+        match beast::beast_address(first) {
+            Some(address) => {
+                let site = config.site_lat.zip(config.site_lon);
+                let (stats_tx, stats_rx) = unbounded_channel();
+                spawn_beast_reader(&tasks, address, config.refresh, site, tx, stats_tx);
+                beast_stats = Some(stats_rx);
+            }
+            None if MqttTarget::is_mqtt_url(first) => {
+                let target = MqttTarget::parse(first).map_err(|err| anyhow!("feed url: {err}"))?;
+                spawn_mqtt_reader(&tasks, target, tx);
+            }
+            None => spawn_fetcher(
+                &tasks,
+                feed_urls,
+                config.refresh,
+                config.insecure,
+                api_key,
+                api_key_header,
+                feed_update_rx,
+                tx,
+            ),
+        }
+    }
+
+    let mut app = build_app(&config, display_url)?;
+    if let Some(base) = config::history_base_url(&config) {
+        let (history_tx, history_rx) = oneshot::channel();
+        net::spawn_history_backfill(
+            &tasks,
+            base,
+            config.history_backfill_minutes,
+            config.insecure,
+            history_tx,
+        );
+        app.set_history_backfill(history_rx);
+    }
+    let aircraft_db = Path::new(config.aircraft_db_file.trim());
+    if !aircraft_db.as_os_str().is_empty() && aircraft_db.exists() {
+        app.set_aircraft_db(spawn_aircraft_db_loader(&tasks, aircraft_db.to_path_buf()));
+    }
+    app.set_desktop_notifier(notifier::spawn_notifier(&tasks));
+    if let Some(path) = app
+        .sightings_path
+        .clone()
+        .filter(|_| app.sightings.is_enabled())
+    {
+        let (tx, flushed) = sightings::spawn_sightings_writer(&tasks, path);
+        app.set_sightings_writer(tx, flushed);
+    }
+    let hooks = HookSettings {
+        url: config.hook_url.clone(),
+        command: config.hook_command.clone(),
+        mqtt: config.hook_mqtt.clone(),
+        payload: config.hook_payload.clone(),
+        events: AlertKinds::from_str(&config.hook_events),
+        cooldown: Duration::from_secs(config.hook_cooldown_secs),
+        insecure: config.insecure,
+    };
+    if hooks.is_enabled() {
+        app.set_alert_hooks(spawn_alert_hooks(&tasks, hooks));
+    }
+    if headless {
+        let routes = route_channels(&tasks, &config);
+        let control = control_channel(&tasks, &config)?;
+        let watcher = ConfigWatcher::new(config_args, config);
+        let result = run_headless(
+            &tasks,
+            app,
+            rx,
+            routes,
+            Some(feed_update_tx),
+            Some(watcher),
+            control,
+        );
+        tasks.shutdown();
+        return result;
+    }
+
+    // Receiver stats only feed the PERF view, so headless mode skips them.
+    let stats = beast_stats.or_else(|| {
+        config::stats_url(&config).map(|url| {
+            let (stats_tx, stats_rx) = unbounded_channel();
+            spawn_stats_fetcher(&tasks, url, config.insecure, stats_tx);
+            stats_rx
+        })
+    });
+    run_tui(
+        tasks,
+        config,
+        config_args,
+        app,
+        rx,
+        stats,
+        None,
+        Some(feed_update_tx),
+    )
+}
+
+pub fn replay(mut config: Config, config_args: ConfigArgs, args: ReplayArgs) -> Result<()> {
+    // Recorded traffic must not end up in the live site's history.
+    config.traffic_history_file.clear();
+    config.sightings_file.clear();
+    let journal = journal::Journal::open(&args.path)?;
+    if journal.is_empty() {
+        return Err(anyhow!("Journal is empty: {}", args.path.display()));
+    }
+    let tasks = Tasks::new()?;
+    let (tx, rx) = unbounded_channel();
+    let (control_tx, control_rx) = unbounded_channel();
+    let (status_tx, status_rx) = watch::channel(ReplayStatus::default());
+    journal::spawn_replay(&tasks, journal, args.speed, control_rx, status_tx, tx);
+    let mut app = build_app(&config, format!("replay:{}", args.path.display()))?;
+    app.replay = Some(*status_rx.borrow());
+    app.set_layout(LayoutMode::Replay);
+    let replay = ReplayChannels {
+        control_tx,
+        status_rx,
+    };
+    run_tui(
+        tasks,
+        config,
+        config_args,
+        app,
+        rx,
+        None,
+        Some(replay),
+        None,
+    )
+}
+
+pub fn stress(mut config: Config, config_args: ConfigArgs, args: StressArgs) -> Result<()> {
+    config.traffic_history_file.clear();
+    config.sightings_file.clear();
+    let mut feed = SyntheticFeed::new(args.aircraft);
+    if let (Some(lat), Some(lon)) = (config.site_lat, config.site_lon) {
+        feed = feed.with_center(lat, lon);
+    }
+    let tasks = Tasks::new()?;
+    let (tx, rx) = unbounded_channel();
+    synthetic::spawn_synthetic_feed(&tasks, feed, config.refresh, tx);
+
+    let app = build_app(&config, format!("synthetic:{}", args.aircraft))?;
+    run_tui(tasks, config, config_args, app, rx, None, None, None)
+}
+
+pub fn export(mut config: Config, args: ExportArgs) -> Result<()> {
+    config.smooth_mode = false;
+    let data = fetch_snapshot(&config)?;
+    let mut app = build_app(&config, config.url.clone())?;
+    app.apply_update(data);
+    let indices = app.visible_indices();
+    let path = match args.format {
+        ExportFormat::Csv => export::export_csv(&app, &indices)?,
+        ExportFormat::Json => export::export_json(&app)?,
+        ExportFormat::Geojson => export::export_geojson(&app, &indices)?,
+    };
+    info!("export {}", path);
+    println!("{path}");
+    Ok(())
+}
+
+pub fn snapshot(mut config: Config, args: SnapshotArgs) -> Result<()> {
+    config.smooth_mode = false;
+    let data = fetch_snapshot(&config)?;
+    let mut app = build_app(&config, config.url.clone())?;
+    app.sort = SortSpec::single(match args.sort {
+        SnapshotSort::Seen => SortKey::Seen,
+        SnapshotSort::Alt => SortKey::Altitude,
+        SnapshotSort::Spd => SortKey::Speed,
+    });
+    app.apply_update(data);
+    let indices = app.visible_indices();
+    let mut out = io::stdout().lock();
+    let written = match args.format {
+        SnapshotFormat::Table => {
+            let aircraft: Vec<Aircraft> = indices
+                .iter()
+                .map(|idx| app.data.aircraft[*idx].clone())
+                .collect();
+            write_aircraft_table(&mut out, &aircraft)
+        }
+        SnapshotFormat::Json => {
+            let payload = export::filtered_response(&app, &indices);
+            writeln!(out, "{}", serde_json::to_string_pretty(&payload)?)
+        }
+        SnapshotFormat::Csv => writeln!(out, "{}", export::csv_text(&app, &indices)),
+    };
+    match written {
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        other => Ok(other?),
+    }
+}
+
+pub fn lookup(config: Config, args: LookupArgs) -> Result<()> {
+    let query = args.query.join(" ");
+    let kind = crate::app::parse_lookup_input(&query)
+        .ok_or_else(|| anyhow!("Invalid lookup query: {query}"))?;
+    let (api_key, api_key_header) = api_credentials(&config);
+    let data = lookup::lookup_once(
+        &config.route_base,
+        config.insecure,
+        api_key.as_deref(),
+        api_key_header.as_deref(),
+        &kind,
+    )
+    .map_err(|err| anyhow!("Lookup failed: {err}"))?;
+    write_aircraft_table(&mut io::stdout().lock(), &data.aircraft)?;
+    Ok(())
+}
+
+pub fn validate_config(config_args: &ConfigArgs, args: &ValidateArgs) -> Result<()> {
+    let report = validate::validate(config_args, !args.offline);
+    if report.found {
+        println!("config {}", report.path.display());
+    } else {
+        println!("config defaults ({} not found)", report.path.display());
+    }
+    for error in &report.errors {
+        println!("  error: {error}");
+    }
+    for warning in &report.warnings {
+        println!("  warning: {warning}");
+    }
+    for note in &report.notes {
+        println!("  {note}");
+    }
+    if !report.errors.is_empty() {
+        return Err(anyhow!(
+            "{} error(s), {} warning(s)",
+            report.errors.len(),
+            report.warnings.len()
+        ));
+    }
+    println!("config OK ({} warning(s))", report.warnings.len());
+    Ok(())
+}
+
+pub fn print_config(args: &ConfigArgs) -> Result<()> {
+    let (config, layers) = config::explain_config(args)?;
+    print!("{}", config::render_effective_config(&config, &layers));
+    Ok(())
+}
+
+pub fn completions(args: &CompletionsArgs) {
+    let mut cmd = Cli::command();
+    let name = cmd.get_name().to_string();
+    clap_complete::generate(args.shell, &mut cmd, name, &mut io::stdout());
+}
+
+pub fn secret(command: &SecretCommand) -> Result<()> {
+    match command {
+        SecretCommand::Set { name } => {
+            let value = read_secret(name)?;
+            if value.is_empty() {
+                return Err(anyhow!("empty secret, nothing stored"));
+            }
+            secrets::store_keyring(name, &value)?;
+            println!("stored keyring:{name}");
+        }
+        SecretCommand::Delete { name } => {
+            secrets::delete_keyring(name)?;
+            println!("deleted keyring:{name}");
+        }
+    }
+    Ok(())
+}
+
+// Reads one line from stdin; on a terminal the input is not echoed.
+fn read_secret(name: &str) -> Result<String> {
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        let mut line = String::new();
+        stdin.read_line(&mut line)?;
+        return Ok(line.trim_end_matches(['\r', '\n']).to_string());
+    }
+    eprint!("secret for keyring:{name}: ");
+    terminal::enable_raw_mode()?;
+    let mut value = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) => match key.code {
+                KeyCode::Enter => break Ok(()),
+                KeyCode::Esc => break Err(anyhow!("cancelled")),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(anyhow!("cancelled"))
+                }
+                KeyCode::Char(ch) => value.push(ch),
+                KeyCode::Backspace => {
+                    value.pop();
+                }
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(err) => break Err(err.into()),
+        }
+    };
+    terminal::disable_raw_mode()?;
+    eprintln!();
+    result.map(|_| value)
+}
+
+pub fn record(config: Config, args: RecordArgs) -> Result<()> {
+    let (api_key, api_key_header) = api_credentials(&config);
+    let urls = config::initial_fetch_urls(&config)?;
+    require_json_feed(&urls, "record")?;
+    let client = net::build_client(config.insecure)?;
+    let mut journal = JournalWriter::open(&args.path)?;
+    let interval = if config.refresh.is_zero() {
+        Duration::from_millis(200)
+    } else {
+        config.refresh
+    };
+
+    info!("recording to {}", args.path.display());
+    let mut recorded = 0u64;
+    while args.count.is_none_or(|count| recorded < count) {
+        match net::fetch_first(
+            &client,
+            &urls,
+            api_key.as_deref(),
+            api_key_header.as_deref(),
+        ) {
+            Ok(data) => {
+                let entry = JournalEntry {
+                    at_ms: now_ms(),
+                    data,
+                };
+                journal.append(&entry)?;
+                recorded += 1;
+                eprintln!(
+                    "recorded {} snapshot(s), {} aircraft",
+                    recorded,
+                    entry.data.aircraft.len()
+                );
+            }
+            Err(err) => {
+                warn!("record fetch failed: {err}");
+                eprintln!("fetch failed: {err}");
+            }
+        }
+        thread::sleep(interval);
+    }
+    journal.finish()
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_tui(
+    tasks: Tasks,
+    config: Config,
+    config_args: ConfigArgs,
+    mut app: App,
+    rx: UnboundedReceiver<Result<ApiResponse, String>>,
+    stats: Option<UnboundedReceiver<Result<ReceiverStats, String>>>,
+    replay: Option<ReplayChannels>,
+    feed_updates: Option<UnboundedSender<Vec<String>>>,
+) -> Result<()> {
+    let (api_key, api_key_header) = api_credentials(&config);
+    let control = control_channel(&tasks, &config)?;
+    let mut terminal = init_terminal()?;
+    let route_channels = route_channels(&tasks, &config);
+    // Live weather means nothing against a recorded session.
+    let weather = if replay.is_none() {
+        weather_channel(&tasks, &config)
+    } else {
+        None
+    };
+    let photos = photo_channels(&tasks, &config, &mut app);
+
+    let lookup_channels = {
+        let (lookup_req_tx, lookup_req_rx) = unbounded_channel();
+        let (lookup_res_tx, lookup_res_rx) = unbounded_channel();
+        spawn_lookup_fetcher(
+            &tasks,
+            config.route_base.clone(),
+            config.insecure,
+            api_key,
+            api_key_header,
+            lookup_req_rx,
+            lookup_res_tx,
+        );
+        LookupChannels {
+            req_tx: lookup_req_tx,
+            res_rx: lookup_res_rx,
+        }
+    };
+
+    let res = run_app(
+        &mut terminal,
+        app,
+        rx,
+        route_channels,
+        Some(lookup_channels),
+        stats,
+        weather,
+        photos,
+        replay,
+        feed_updates,
+        Some(ConfigWatcher::new(config_args, config)),
+        control,
+    );
+    restore_terminal(&mut terminal)?;
+    tasks.shutdown();
+
+    if let Err(err) = res {
+        warn!("runtime error: {err}");
+        eprintln!("{err}");
+    }
+    Ok(())
+}
+
+fn control_channel(tasks: &Tasks, config: &Config) -> Result<Option<Receiver<ControlRequest>>> {
+    if config.control_socket.trim().is_empty() {
+        return Ok(None);
+    }
+    let (control_tx, control_rx) = mpsc::channel();
+    control::spawn_control_listener(tasks, &config.control_socket, control_tx)?;
+    Ok(Some(control_rx))
+}
+
+fn route_channels(tasks: &Tasks, config: &Config) -> Option<RouteChannels> {
+    if !config.route_enabled {
+        return None;
+    }
+    let (route_req_tx, route_req_rx) = unbounded_channel();
+    let (route_res_tx, route_res_rx) = unbounded_channel();
+    spawn_route_fetcher(
+        tasks,
+        config.route_base.clone(),
+        config.route_mode.clone(),
+        config.route_path.clone(),
+        config.insecure,
+        Duration::from_secs(config.route_timeout_secs.max(2)),
+        route_res_tx,
+        route_req_rx,
+    );
+    Some(RouteChannels {
+        req_tx: route_req_tx,
+        res_rx: route_res_rx,
+    })
+}
+
+fn weather_channel(
+    tasks: &Tasks,
+    config: &Config,
+) -> Option<UnboundedReceiver<Result<WeatherReport, String>>> {
+    let station = config.weather_station.trim().to_ascii_uppercase();
+    if station.is_empty() {
+        return None;
+    }
+    let (weather_tx, weather_rx) = unbounded_channel();
+    spawn_weather_fetcher(
+        tasks,
+        config.weather_base.clone(),
+        station,
+        Duration::from_secs(config.weather_refresh_secs.max(60)),
+        config.insecure,
+        weather_tx,
+    );
+    Some(weather_rx)
+}
+
+fn photo_channels(tasks: &Tasks, config: &Config, app: &mut App) -> Option<PhotoChannels> {
+    if config.photo_base.trim().is_empty() {
+        return None;
+    }
+    let path = config.cache_dir.join("photos.json");
+    let ttl = Duration::from_secs(config.photo_ttl_secs);
+    let now_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    let cache = load_photo_cache(&path, ttl, now_secs).unwrap_or_else(|err| {
+        warn!("photo cache load failed: {err}");
+        PhotoCache::new(ttl)
+    });
+    app.set_photo_cache(path, cache);
+    let (photo_req_tx, photo_req_rx) = unbounded_channel();
+    let (photo_res_tx, photo_res_rx) = unbounded_channel();
+    spawn_photo_fetcher(
+        tasks,
+        config.photo_base.clone(),
+        config.insecure,
+        photo_req_rx,
+        photo_res_tx,
+    );
+    Some(PhotoChannels {
+        req_tx: photo_req_tx,
+        res_rx: photo_res_rx,
+    })
+}
+
+pub fn build_app(config: &Config, display_url: String) -> Result<App> {
+    let keymap = KeyMap::from_overrides(&config.keys)
+        .map_err(|errors| anyhow!("[keys] {}", errors.join("; ")))?;
+
+    let mut favorites: HashSet<String> = config
+        .favorites
+        .iter()
+        .map(|v| v.trim().to_ascii_lowercase())
+        .filter(|v| !v.is_empty())
+        .collect();
+
+    let favorites_path = config::data_file_path(&config.favorites_file);
+    let watchlist_path = config::data_file_path(&config.watchlist_file);
+    let traffic_path = config::data_file_path(&config.traffic_history_file);
+    let sightings_path = config::data_file_path(&config.sightings_file);
+    let zones_path = config::data_file_path(&config.zones_file);
+    let airports_path = config::data_file_path(&config.airports_file);
+    let geography_path = config::data_file_path(&config.geography_file);
+    let military_path = config::data_file_path(&config.military_ranges_file);
+    let roles_path = config::data_file_path(&config.roles_file);
+    let now_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+
+    // The files may sit on slow storage; read them side by side.
+    let (file_favs, watchlist, traffic, sightings, zones, airports, geography, roles) =
+        thread::scope(|scope| {
+            let favs = scope.spawn(|| favorites_path.as_deref().map(load_favorites));
+            let traffic = scope.spawn(|| traffic_path.as_deref().map(load_traffic_history));
+            let sightings = scope.spawn(|| {
+                sightings_path
+                    .as_deref()
+                    .map(|path| SightingStore::open(path, now_secs))
+            });
+            let zones = scope.spawn(|| zones_path.as_deref().map(read_zones));
+            let airports = scope.spawn(|| airports_path.as_deref().map(read_airports));
+            let geography = scope.spawn(|| geography_path.as_deref().map(read_geography));
+            let roles =
+                scope.spawn(|| read_role_rules(roles_path.as_deref(), military_path.as_deref()));
+            let watchlist = watchlist_path
+                .as_deref()
+                .map(|path| read_watchlist(path, config.watchlist_enabled))
+                .unwrap_or_default();
+            (
+                favs.join().ok().flatten(),
+                watchlist,
+                traffic.join().ok().flatten(),
+                sightings.join().ok().flatten(),
+                zones.join().ok().flatten().unwrap_or_default(),
+                airports.join().ok().flatten().unwrap_or_default(),
+                geography.join().ok().flatten().unwrap_or_default(),
+                roles.join().unwrap_or_default(),
+            )
+        });
+    if let Some(Ok(file_favs)) = file_favs {
+        favorites.extend(file_favs);
+    }
+    let traffic = match traffic {
+        Some(Ok(history)) => history,
+        Some(Err(err)) => {
+            warn!("{err:#}");
+            TrafficHistory::default()
+        }
+        None => TrafficHistory::default(),
+    };
+    let sightings = match sightings {
+        Some(Ok(log)) => log,
+        Some(Err(err)) => {
+            warn!("{err:#}");
+            SightingStore::default()
+        }
+        None => SightingStore::default(),
+    };
+    debug!("building app for {}", secrets::redact_url(&display_url));
+
+    let data = AppData {
+        url: display_url,
+        favorites,
+        watchlist,
+        traffic,
+        sightings,
+        zones,
+        airports,
+        geography,
+        keymap,
+    };
+    let mut app = App::new(config, data);
+    app.set_role_rules(roles, roles_path, military_path);
+    Ok(app)
+}
+
+fn read_watchlist(path: &Path, enabled: bool) -> Vec<WatchEntry> {
+    if let Ok(created) = ensure_watchlist_file(path) {
+        if created {
+            info!("watchlist template created at {}", path.display());
+        }
+    }
+    match load_watchlist(path) {
+        Ok(entries) => entries,
+        Err(_) => {
+            if enabled {
+                warn!("failed to load watchlist from {}", path.display());
+            }
+            Vec::new()
+        }
+    }
+}
+
+fn read_zones(path: &Path) -> Vec<Zone> {
+    if let Ok(true) = ensure_zones_file(path) {
+        info!("zones template created at {}", path.display());
+    }
+    match load_zones(path) {
+        Ok(zones) => zones,
+        Err(err) => {
+            warn!("{err:#}");
+            Vec::new()
+        }
+    }
+}
+
+fn read_airports(path: &Path) -> AirportDb {
+    match load_airports(path) {
+        Ok(airports) => {
+            if !airports.is_empty() {
+                info!("loaded {} airports from {}", airports.len(), path.display());
+            }
+            airports
+        }
+        Err(err) => {
+            warn!("{err:#}");
+            AirportDb::default()
+        }
+    }
+}
+
+fn read_role_rules(roles_path: Option<&Path>, military_path: Option<&Path>) -> RoleRules {
+    match load_role_rules(roles_path, military_path) {
+        Ok(rules) => {
+            info!("loaded {} role rules", rules.len());
+            rules
+        }
+        Err(err) => {
+            warn!("{err:#}");
+            RoleRules::default()
+        }
+    }
+}
+
+fn read_geography(path: &Path) -> Geography {
+    match load_geography(path) {
+        Ok(geography) => {
+            if !geography.is_empty() {
+                info!(
+                    "loaded {} outlines from {}",
+                    geography.len(),
+                    path.display()
+                );
+            }
+            geography
+        }
+        Err(err) => {
+            warn!("{err:#}");
+            Geography::default()
+        }
+    }
+}
+
+/// Beast input only drives the live display on its own; one-shot commands
+/// and merged receivers need JSON endpoints.
+fn require_json_feed(urls: &[String], command: &str) -> Result<()> {
+    if urls
+        .iter()
+        .any(|url| beast::beast_address(url).is_some() || MqttTarget::is_mqtt_url(url))
+    {
+        return Err(anyhow!(
+            "{command} needs aircraft.json feed URLs; beast:// and mqtt:// input are only supported as the feed of run"
+        ));
+    }
+    Ok(())
+}
+
+fn fetch_snapshot(config: &Config) -> Result<ApiResponse> {
+    let (api_key, api_key_header) = api_credentials(config);
+    let urls = config::initial_fetch_urls(config)?;
+    require_json_feed(&urls, "this command")?;
+    let client = net::build_client(config.insecure)?;
+    net::fetch_first(
+        &client,
+        &urls,
+        api_key.as_deref(),
+        api_key_header.as_deref(),
+    )
+    .map_err(|err| anyhow!("Fetch failed: {err}"))
+}
+
+pub(crate) fn api_credentials(config: &Config) -> (Option<String>, Option<String>) {
+    let api_key = if config.api_key.trim().is_empty() {
+        None
+    } else {
+        Some(config.api_key.clone())
+    };
+    let api_key_header = if config.api_key_header.trim().is_empty() {
+        None
+    } else {
+        Some(config.api_key_header.clone())
+    };
+    (api_key, api_key_header)
+}
+
+fn write_aircraft_table(out: &mut impl Write, aircraft: &[Aircraft]) -> io::Result<()> {
+    writeln!(
+        out,
+        "{:<7} {:<8} {:<8} {:<5} {:>6} {:>4} {:>9} {:>10} {:>5}",
+        "HEX", "FLIGHT", "REG", "TYPE", "ALT", "GS", "LAT", "LON", "SEEN"
+    )?;
+    for ac in aircraft {
+        writeln!(
+            out,
+            "{:<7} {:<8} {:<8} {:<5} {:>6} {:>4} {:>9} {:>10} {:>5}",
+            ac.hex.as_deref().unwrap_or("--"),
+            ac.flight.as_deref().map(str::trim).unwrap_or("--"),
+            ac.r.as_deref().unwrap_or("--"),
+            ac.t.as_deref().unwrap_or("--"),
+            ac.alt_baro
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "--".to_string()),
+            ac.gs
+                .map(|v| format!("{v:.0}"))
+                .unwrap_or_else(|| "--".to_string()),
+            ac.lat
+                .map(|v| format!("{v:.4}"))
+                .unwrap_or_else(|| "--".to_string()),
+            ac.lon
+                .map(|v| format!("{v:.4}"))
+                .unwrap_or_else(|| "--".to_string()),
+            seen_seconds(ac)
+                .map(|v| format!("{v:.0}s"))
+                .unwrap_or_else(|| "--".to_string()),
+        )?;
+    }
+    writeln!(out, "{} aircraft", aircraft.len())
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
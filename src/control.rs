@@ -0,0 +1,397 @@
+use anyhow::{anyhow, Context, Result};
+use std::net::SocketAddr;
+#[cfg(unix)]
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::time::Duration;
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader,
+};
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use tokio::task::JoinSet;
+use tracing::{debug, info, warn};
+
+use crate::app::{LayoutMode, ThemeMode};
+use crate::tasks::Tasks;
+
+const REPLY_TIMEOUT: Duration = Duration::from_secs(2);
+const ACCEPT_RETRY: Duration = Duration::from_millis(200);
+const MAX_LINE_BYTES: usize = 4096;
+
+pub const HELP: &str =
+    "commands: filter TEXT | clear-filter | select HEX | export csv|json|geojson | \
+layout NAME | theme NAME | zoom in|out | site NAME|next | status | help";
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ControlCommand {
+    Filter(String),
+    Select(String),
+    ExportCsv,
+    ExportJson,
+    ExportGeoJson,
+    Layout(LayoutMode),
+    Theme(ThemeMode),
+    Zoom(f64),
+    Site(Option<String>),
+    Status,
+}
+
+pub struct ControlRequest {
+    pub command: ControlCommand,
+    pub reply: oneshot::Sender<String>,
+}
+
+pub fn parse_command(line: &str) -> Result<ControlCommand, String> {
+    let line = line.trim();
+    let (verb, rest) = line.split_once(' ').unwrap_or((line, ""));
+    let rest = rest.trim();
+    let command = match verb.to_ascii_lowercase().as_str() {
+        "filter" => ControlCommand::Filter(rest.to_string()),
+        "clear-filter" => ControlCommand::Filter(String::new()),
+        "select" if !rest.is_empty() => ControlCommand::Select(rest.to_string()),
+        "export" => match rest.to_ascii_lowercase().as_str() {
+            "csv" => ControlCommand::ExportCsv,
+            "json" => ControlCommand::ExportJson,
+            "geojson" => ControlCommand::ExportGeoJson,
+            _ => return Err("export needs csv, json or geojson".to_string()),
+        },
+        "layout" => ControlCommand::Layout(
+            LayoutMode::parse(rest).ok_or_else(|| format!("unknown layout `{rest}`"))?,
+        ),
+        "theme" => ControlCommand::Theme(
+            ThemeMode::parse(rest).ok_or_else(|| format!("unknown theme `{rest}`"))?,
+        ),
+        "zoom" => match rest.to_ascii_lowercase().as_str() {
+            "in" | "+" => ControlCommand::Zoom(0.8),
+            "out" | "-" => ControlCommand::Zoom(1.25),
+            _ => return Err("zoom needs in or out".to_string()),
+        },
+        "site" if rest.eq_ignore_ascii_case("next") => ControlCommand::Site(None),
+        "site" if !rest.is_empty() => ControlCommand::Site(Some(rest.to_string())),
+        "status" => ControlCommand::Status,
+        _ => return Err(format!("unknown command `{line}`; {HELP}")),
+    };
+    Ok(command)
+}
+
+/// Starts listening on `address`: `tcp:HOST:PORT` (loopback only) or a UNIX
+/// socket path. Each line received is answered with one `ok ...` or
+/// `error ...` line. The listener and its connections stop with `tasks`.
+pub fn spawn_control_listener(
+    tasks: &Tasks,
+    address: &str,
+    tx: Sender<ControlRequest>,
+) -> Result<()> {
+    let address = address.trim();
+    if let Some(addr) = address.strip_prefix("tcp:") {
+        let addr: SocketAddr = addr
+            .parse()
+            .with_context(|| format!("Invalid control_socket address {addr}"))?;
+        if !addr.ip().is_loopback() {
+            return Err(anyhow!(
+                "control_socket must listen on a loopback address, got {addr}"
+            ));
+        }
+        let listener = std::net::TcpListener::bind(addr)
+            .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+            .with_context(|| format!("Failed to bind control socket {addr}"))?;
+        info!("control socket listening on tcp:{addr}");
+        tasks.spawn("control", async move {
+            let listener = match TcpListener::from_std(listener) {
+                Ok(listener) => listener,
+                Err(err) => {
+                    warn!("control socket failed: {err}");
+                    return;
+                }
+            };
+            let mut connections = JoinSet::new();
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let (reader, writer) = stream.into_split();
+                        connections.spawn(serve(BufReader::new(reader), writer, tx.clone()));
+                    }
+                    Err(err) => {
+                        warn!("control accept failed: {err}");
+                        tokio::time::sleep(ACCEPT_RETRY).await;
+                    }
+                }
+                while connections.try_join_next().is_some() {}
+            }
+        });
+        return Ok(());
+    }
+    spawn_unix_listener(tasks, address, tx)
+}
+
+#[cfg(unix)]
+fn spawn_unix_listener(tasks: &Tasks, path: &str, tx: Sender<ControlRequest>) -> Result<()> {
+    use std::os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt};
+    use tokio::net::UnixListener;
+
+    let path = PathBuf::from(path);
+    // A socket left behind by a previous run would make bind fail; anything
+    // else at that path is not ours to delete.
+    let replace_stale = || -> Result<()> {
+        match std::fs::symlink_metadata(&path) {
+            Ok(meta) if meta.file_type().is_socket() => {
+                std::fs::remove_file(&path).with_context(|| {
+                    format!("Failed to remove stale control socket {}", path.display())
+                })
+            }
+            Ok(_) => Err(anyhow!(
+                "control_socket {} exists and is not a socket; refusing to replace it",
+                path.display()
+            )),
+            Err(_) => Ok(()),
+        }
+    };
+    replace_stale()?;
+
+    // Bind inside a private directory and only move the socket into place
+    // once it is 0600, so it is never reachable with looser permissions.
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let private_dir = parent.join(format!(".adsb-control-{}", std::process::id()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)
+        .with_context(|| format!("Failed to create {}", private_dir.display()))?;
+    let staged = private_dir.join("control.sock");
+    let bound = std::os::unix::net::UnixListener::bind(&staged)
+        .with_context(|| format!("Failed to bind control socket {}", path.display()))
+        .and_then(|listener| {
+            std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o600))
+                .with_context(|| format!("Failed to restrict control socket {}", path.display()))?;
+            listener.set_nonblocking(true)?;
+            replace_stale()?;
+            std::fs::rename(&staged, &path)
+                .with_context(|| format!("Failed to bind control socket {}", path.display()))?;
+            Ok(listener)
+        });
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&private_dir);
+    let listener = bound?;
+    info!("control socket listening on {}", path.display());
+
+    tasks.spawn("control", async move {
+        let _socket = SocketFile(path);
+        let listener = match UnixListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(err) => {
+                warn!("control socket failed: {err}");
+                return;
+            }
+        };
+        let mut connections = JoinSet::new();
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let (reader, writer) = stream.into_split();
+                    connections.spawn(serve(BufReader::new(reader), writer, tx.clone()));
+                }
+                Err(err) => {
+                    warn!("control accept failed: {err}");
+                    tokio::time::sleep(ACCEPT_RETRY).await;
+                }
+            }
+            while connections.try_join_next().is_some() {}
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn spawn_unix_listener(_tasks: &Tasks, path: &str, _tx: Sender<ControlRequest>) -> Result<()> {
+    Err(anyhow!(
+        "UNIX control sockets are not supported here; use control_socket = \"tcp:127.0.0.1:PORT\" instead of {path}"
+    ))
+}
+
+#[cfg(unix)]
+struct SocketFile(PathBuf);
+
+#[cfg(unix)]
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        match std::fs::remove_file(&self.0) {
+            Ok(()) => debug!("control socket removed {}", self.0.display()),
+            Err(err) => debug!("control socket {} not removed: {err}", self.0.display()),
+        }
+    }
+}
+
+async fn serve(
+    mut reader: impl AsyncBufRead + Unpin,
+    mut writer: impl AsyncWrite + Unpin,
+    tx: Sender<ControlRequest>,
+) {
+    while let Some(line) = read_line(&mut reader).await {
+        let response = match line {
+            Some(line) if line.trim().is_empty() => continue,
+            Some(line) => {
+                debug!("control command: {}", line.trim());
+                match line.trim() {
+                    "help" => format!("ok {HELP}"),
+                    _ => match parse_command(&line) {
+                        Ok(command) => dispatch(&tx, command).await,
+                        Err(err) => format!("error {err}"),
+                    },
+                }
+            }
+            None => "error line too long".to_string(),
+        };
+        if writer
+            .write_all(format!("{response}\n").as_bytes())
+            .await
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+/// The next line, or `Some(None)` for one longer than `MAX_LINE_BYTES`,
+/// which is read to its end and dropped. `None` at end of input.
+async fn read_line(reader: &mut (impl AsyncBufRead + Unpin)) -> Option<Option<String>> {
+    let mut buf = Vec::new();
+    let limit = MAX_LINE_BYTES as u64 + 1;
+    match (&mut *reader).take(limit).read_until(b'\n', &mut buf).await {
+        Ok(0) | Err(_) => return None,
+        Ok(_) => {}
+    }
+    if buf.last() == Some(&b'\n') || buf.len() <= MAX_LINE_BYTES {
+        return Some(Some(String::from_utf8_lossy(&buf).into_owned()));
+    }
+    loop {
+        buf.clear();
+        match (&mut *reader).take(limit).read_until(b'\n', &mut buf).await {
+            Ok(0) | Err(_) => return Some(None),
+            Ok(_) if buf.last() == Some(&b'\n') => return Some(None),
+            Ok(_) => {}
+        }
+    }
+}
+
+async fn dispatch(tx: &Sender<ControlRequest>, command: ControlCommand) -> String {
+    let (reply_tx, reply_rx) = oneshot::channel();
+    let request = ControlRequest {
+        command,
+        reply: reply_tx,
+    };
+    if tx.send(request).is_err() {
+        warn!("control command dropped: app is shutting down");
+        return "error app is shutting down".to_string();
+    }
+    match tokio::time::timeout(REPLY_TIMEOUT, reply_rx).await {
+        Ok(Ok(reply)) => reply,
+        _ => "error no reply from app".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_command, serve, ControlCommand, ControlRequest};
+    use crate::app::{LayoutMode, ThemeMode};
+    use crate::tasks::Tasks;
+    use std::sync::mpsc;
+    use std::thread;
+
+    #[test]
+    fn commands_parse() {
+        assert_eq!(
+            parse_command("filter UAL 12"),
+            Ok(ControlCommand::Filter("UAL 12".to_string()))
+        );
+        assert_eq!(
+            parse_command("clear-filter"),
+            Ok(ControlCommand::Filter(String::new()))
+        );
+        assert_eq!(
+            parse_command("layout radar"),
+            Ok(ControlCommand::Layout(LayoutMode::Radar))
+        );
+        assert_eq!(
+            parse_command("THEME amber"),
+            Ok(ControlCommand::Theme(ThemeMode::Amber))
+        );
+        assert_eq!(parse_command("export json"), Ok(ControlCommand::ExportJson));
+        assert_eq!(
+            parse_command("export GeoJSON"),
+            Ok(ControlCommand::ExportGeoJson)
+        );
+        assert_eq!(parse_command("zoom out"), Ok(ControlCommand::Zoom(1.25)));
+        assert_eq!(parse_command("site next"), Ok(ControlCommand::Site(None)));
+        assert!(parse_command("select").is_err());
+        assert!(parse_command("layout grid").is_err());
+        assert!(parse_command("reboot").is_err());
+    }
+
+    #[test]
+    fn each_line_gets_one_reply() {
+        let (tx, rx) = mpsc::channel();
+        let app = thread::spawn(move || {
+            let request: ControlRequest = rx.recv().unwrap();
+            assert_eq!(
+                request.command,
+                ControlCommand::Select("abc123".to_string())
+            );
+            request.reply.send("ok select abc123".to_string()).unwrap();
+        });
+        let mut out = Vec::new();
+        Tasks::new()
+            .unwrap()
+            .block_on(serve(&b"select abc123\n\nwhat\n"[..], &mut out, tx));
+        app.join().unwrap();
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "ok select abc123");
+        assert!(lines[1].starts_with("error unknown command `what`"));
+    }
+
+    #[test]
+    fn overlong_lines_are_rejected_without_buffering_them() {
+        let (tx, _rx) = mpsc::channel();
+        let mut input = "x".repeat(super::MAX_LINE_BYTES * 3);
+        input.push_str("\nhelp\n");
+        let mut out = Vec::new();
+        Tasks::new()
+            .unwrap()
+            .block_on(serve(input.as_bytes(), &mut out, tx));
+        let out = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], "error line too long");
+        assert!(lines[1].starts_with("ok commands:"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_socket_is_private_and_removed_on_shutdown() {
+        use super::spawn_control_listener;
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("adsb-tui-control-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("control.sock");
+        let tasks = Tasks::new().unwrap();
+
+        std::fs::write(&path, "not a socket").unwrap();
+        let (tx, _rx) = mpsc::channel();
+        assert!(spawn_control_listener(&tasks, path.to_str().unwrap(), tx.clone()).is_err());
+        std::fs::remove_file(&path).unwrap();
+
+        spawn_control_listener(&tasks, path.to_str().unwrap(), tx).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        tasks.shutdown();
+        assert!(!path.exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
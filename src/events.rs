@@ -0,0 +1,128 @@
+//! Session event log for the Events modal.
+
+use std::collections::{HashSet, VecDeque};
+
+use chrono::{DateTime, Local};
+
+pub const EVENT_LOG_CAP: usize = 1_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventKind {
+    Military,
+    Emergency,
+    Watch,
+    Range,
+    NewType,
+}
+
+impl EventKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            EventKind::Military => "MIL",
+            EventKind::Emergency => "EMERG",
+            EventKind::Watch => "WATCH",
+            EventKind::Range => "RANGE",
+            EventKind::NewType => "TYPE",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    pub kind: EventKind,
+    pub at: i64,
+    pub message: String,
+}
+
+#[derive(Debug, Default)]
+pub struct EventLog {
+    events: VecDeque<Event>,
+    military: HashSet<String>,
+    types: HashSet<String>,
+    types_seeded: bool,
+}
+
+impl EventLog {
+    pub fn push(&mut self, kind: EventKind, at: i64, message: String) {
+        self.events.push_back(Event { kind, at, message });
+        while self.events.len() > EVENT_LOG_CAP {
+            self.events.pop_front();
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn events(&self) -> impl DoubleEndedIterator<Item = &Event> + ExactSizeIterator {
+        self.events.iter()
+    }
+
+    pub fn first_military(&mut self, hex: &str) -> bool {
+        self.military.insert(hex.trim().to_ascii_lowercase())
+    }
+
+    /// The types in a snapshot not seen before this session. The first
+    /// snapshot only sets the baseline, so startup does not log every type
+    /// in the sky.
+    pub fn new_types<'a>(&mut self, types: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let seeded = std::mem::replace(&mut self.types_seeded, true);
+        let mut new = Vec::new();
+        for ac_type in types {
+            let ac_type = ac_type.trim().to_ascii_uppercase();
+            if !ac_type.is_empty() && self.types.insert(ac_type.clone()) && seeded {
+                new.push(ac_type);
+            }
+        }
+        new
+    }
+
+    pub fn to_text(&self) -> String {
+        self.events
+            .iter()
+            .map(|event| {
+                let at = DateTime::from_timestamp(event.at, 0)
+                    .map(|dt| {
+                        dt.with_timezone(&Local)
+                            .format("%Y-%m-%d %H:%M:%S")
+                            .to_string()
+                    })
+                    .unwrap_or_else(|| "--".to_string());
+                format!("{at}  {:<5}  {}\n", event.kind.label(), event.message)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventKind, EventLog, EVENT_LOG_CAP};
+
+    #[test]
+    fn keeps_the_newest_events_up_to_the_cap() {
+        let mut log = EventLog::default();
+        for i in 0..EVENT_LOG_CAP + 5 {
+            log.push(EventKind::Watch, i as i64, format!("WATCH {i}"));
+        }
+        assert_eq!(log.len(), EVENT_LOG_CAP);
+        assert_eq!(log.events().next().unwrap().message, "WATCH 5");
+        let text = log.to_text();
+        assert_eq!(text.lines().count(), EVENT_LOG_CAP);
+        assert!(text.lines().last().unwrap().ends_with("WATCH  WATCH 1004"));
+    }
+
+    #[test]
+    fn first_sightings_are_reported_once() {
+        let mut log = EventLog::default();
+        assert!(log.first_military("AE1234"));
+        assert!(!log.first_military("ae1234 "));
+
+        assert!(log.new_types(["B738", "A320"]).is_empty(), "baseline");
+        assert_eq!(log.new_types(["b738", "C17", " ", "C17"]), ["C17"]);
+        assert!(log.new_types(["C17"]).is_empty());
+    }
+}
@@ -1,57 +1,314 @@
 use anyhow::{Context, Result};
-use chrono::Local;
+use chrono::{DateTime, Local, SecondsFormat, Utc};
+use serde_json::json;
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use crate::app::App;
-use crate::model::seen_seconds;
+use crate::country;
+use crate::model::{seen_seconds, Altitude, ApiResponse};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExportKind {
+    TableCsv,
+    SnapshotJson,
+    GeoJson,
+    TrailsCsv,
+    TrailsJson,
+    SessionCsv,
+    SessionJson,
+    Events,
+}
+
+impl ExportKind {
+    pub const ALL: [ExportKind; 8] = [
+        ExportKind::TableCsv,
+        ExportKind::SnapshotJson,
+        ExportKind::GeoJson,
+        ExportKind::TrailsCsv,
+        ExportKind::TrailsJson,
+        ExportKind::SessionCsv,
+        ExportKind::SessionJson,
+        ExportKind::Events,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportKind::TableCsv => "Table (CSV)",
+            ExportKind::SnapshotJson => "Snapshot (JSON)",
+            ExportKind::GeoJson => "Map (GeoJSON)",
+            ExportKind::TrailsCsv => "Trails (CSV)",
+            ExportKind::TrailsJson => "Trails (JSON)",
+            ExportKind::SessionCsv => "Session summary (CSV)",
+            ExportKind::SessionJson => "Session summary (JSON)",
+            ExportKind::Events => "Event log (text)",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportKind::TableCsv => "csv",
+            ExportKind::SnapshotJson => "json",
+            ExportKind::GeoJson => "geojson",
+            ExportKind::TrailsCsv => "trails csv",
+            ExportKind::TrailsJson => "trails json",
+            ExportKind::SessionCsv => "session csv",
+            ExportKind::SessionJson => "session json",
+            ExportKind::Events => "events",
+        }
+    }
+}
+
+pub fn export(app: &App, indices: &[usize], kind: ExportKind) -> Result<String> {
+    match kind {
+        ExportKind::TableCsv => export_csv(app, indices),
+        ExportKind::SnapshotJson => export_json(app),
+        ExportKind::GeoJson => export_geojson(app, indices),
+        ExportKind::TrailsCsv => write_export(app, "adsb-trails", "csv", trails_csv_text(app)),
+        ExportKind::TrailsJson => write_export(
+            app,
+            "adsb-trails",
+            "json",
+            serde_json::to_string_pretty(&trails_json(app))?,
+        ),
+        ExportKind::SessionCsv => write_export(app, "adsb-session", "csv", session_csv_text(app)),
+        ExportKind::SessionJson => write_export(
+            app,
+            "adsb-session",
+            "json",
+            serde_json::to_string_pretty(&session_json(app))?,
+        ),
+        ExportKind::Events => write_export(app, "adsb-events", "txt", app.events.to_text()),
+    }
+}
 
 pub fn export_csv(app: &App, indices: &[usize]) -> Result<String> {
-    let filename = format!("adsb-snapshot-{}.csv", Local::now().format("%Y%m%d-%H%M%S"));
-    let mut path = export_path(&filename)?;
+    write_export(app, "adsb-snapshot", "csv", csv_text(app, indices))
+}
+
+fn write_export(app: &App, prefix: &str, ext: &str, contents: String) -> Result<String> {
+    let filename = format!("{prefix}-{}.{ext}", Local::now().format("%Y%m%d-%H%M%S"));
+    let mut path = export_path(&app.export_dir, &filename)?;
     if path.exists() {
         path = unique_path(&path);
     }
 
+    fs::write(&path, contents).with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+pub fn csv_text(app: &App, indices: &[usize]) -> String {
     let mut lines = Vec::new();
-    lines.push("hex,flight,reg,type,alt_baro,alt_geom,gs,track,lat,lon,seen,messages".to_string());
+    lines.push(
+        "hex,flight,reg,type,alt_baro,alt_geom,gs,track,lat,lon,seen,messages,vs,category,country"
+            .to_string(),
+    );
     for idx in indices {
         let ac = &app.data.aircraft[*idx];
         lines.push(format!(
-            "{},{},{},{},{},{},{},{},{},{},{},{}",
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
             csv_field(ac.hex.as_deref()),
             csv_field(ac.flight.as_deref()),
             csv_field(ac.r.as_deref()),
             csv_field(ac.t.as_deref()),
-            opt_i64(ac.alt_baro),
+            opt_alt(ac.alt_baro),
             opt_i64(ac.alt_geom),
             opt_f64(ac.gs, 1),
             opt_f64(ac.track, 1),
             opt_f64(ac.lat, 4),
             opt_f64(ac.lon, 4),
             opt_f64(seen_seconds(ac), 1),
-            opt_u64(ac.messages)
+            opt_u64(ac.messages),
+            opt_i64(app.trend_for(ac).vs),
+            csv_field(ac.category.as_deref()),
+            csv_field(country::of(ac).map(|country| country.code))
         ));
     }
-
-    fs::write(&path, lines.join("\n"))
-        .with_context(|| format!("Failed to write {}", path.display()))?;
-    Ok(path.to_string_lossy().to_string())
+    lines.join("\n")
 }
 
 pub fn export_json(app: &App) -> Result<String> {
-    let filename = format!(
-        "adsb-snapshot-{}.json",
-        Local::now().format("%Y%m%d-%H%M%S")
-    );
-    let mut path = export_path(&filename)?;
-    if path.exists() {
-        path = unique_path(&path);
+    let payload = serde_json::to_string_pretty(&app.data)?;
+    write_export(app, "adsb-snapshot", "json", payload)
+}
+
+pub fn export_geojson(app: &App, indices: &[usize]) -> Result<String> {
+    let payload = serde_json::to_string_pretty(&geojson(app, indices))?;
+    write_export(app, "adsb-snapshot", "geojson", payload)
+}
+
+/// The table rows as a GeoJSON FeatureCollection: a point for each aircraft
+/// with a position and a line string for each trail of two or more points.
+pub fn geojson(app: &App, indices: &[usize]) -> serde_json::Value {
+    let mut points = Vec::new();
+    let mut trails = Vec::new();
+    for ac in indices.iter().filter_map(|idx| app.data.aircraft.get(*idx)) {
+        let callsign = ac
+            .flight
+            .as_deref()
+            .map(str::trim)
+            .filter(|flight| !flight.is_empty());
+        if let (Some(lat), Some(lon)) = (ac.lat, ac.lon) {
+            points.push(json!({
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [lon, lat] },
+                "properties": {
+                    "kind": "aircraft",
+                    "callsign": callsign,
+                    "hex": ac.hex,
+                    "alt": ac.alt_baro.map(|alt| alt.feet()),
+                    "ground": matches!(ac.alt_baro, Some(Altitude::Ground)),
+                    "gs": ac.gs,
+                    "vs": app.trend_for(ac).vs,
+                    "category": ac.category,
+                    "country": country::of(ac).map(|country| country.code),
+                    "squawk": ac.squawk,
+                },
+            }));
+        }
+        let Some(trail) = app.trail_for(ac).filter(|trail| trail.len() >= 2) else {
+            continue;
+        };
+        let coordinates: Vec<[f64; 2]> = trail.iter().map(|point| [point.lon, point.lat]).collect();
+        trails.push(json!({
+            "type": "Feature",
+            "geometry": { "type": "LineString", "coordinates": coordinates },
+            "properties": {
+                "kind": "trail",
+                "callsign": callsign,
+                "hex": ac.hex,
+                "start": trail.front().map(|point| rfc3339(point.at)),
+                "end": trail.back().map(|point| rfc3339(point.at)),
+            },
+        }));
     }
+    points.extend(trails);
+    json!({ "type": "FeatureCollection", "features": points })
+}
 
-    let payload = serde_json::to_string_pretty(&app.data)?;
-    fs::write(&path, payload).with_context(|| format!("Failed to write {}", path.display()))?;
-    Ok(path.to_string_lossy().to_string())
+pub fn trails_csv_text(app: &App) -> String {
+    let mut lines = vec!["hex,flight,time,lat,lon".to_string()];
+    for (hex, points) in sorted_trails(app) {
+        let flight = trail_callsign(app, hex);
+        for point in points {
+            lines.push(format!(
+                "{},{},{},{:.5},{:.5}",
+                csv_field(Some(hex)),
+                csv_field(flight),
+                rfc3339(point.at),
+                point.lat,
+                point.lon
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+pub fn trails_json(app: &App) -> serde_json::Value {
+    let aircraft: Vec<serde_json::Value> = sorted_trails(app)
+        .into_iter()
+        .map(|(hex, points)| {
+            let points: Vec<serde_json::Value> = points
+                .iter()
+                .map(|point| json!({ "time": rfc3339(point.at), "lat": point.lat, "lon": point.lon }))
+                .collect();
+            json!({ "hex": hex, "flight": trail_callsign(app, hex), "points": points })
+        })
+        .collect();
+    json!({ "exported": rfc3339(SystemTime::now()), "aircraft": aircraft })
+}
+
+pub fn session_csv_text(app: &App) -> String {
+    let mut lines =
+        vec!["hex,flight,reg,type,first_seen,last_seen,max_alt,max_gs,max_distance_nm".to_string()];
+    for entry in app.session.entries() {
+        lines.push(format!(
+            "{},{},{},{},{},{},{},{},{}",
+            csv_field(Some(&entry.hex)),
+            csv_field(entry.callsign.as_deref()),
+            csv_field(entry.registration.as_deref()),
+            csv_field(entry.aircraft_type.as_deref()),
+            epoch_rfc3339(entry.first_seen),
+            epoch_rfc3339(entry.last_seen),
+            opt_i64(entry.max_alt),
+            opt_f64(entry.max_gs, 1),
+            opt_f64(entry.max_distance_nm, 1)
+        ));
+    }
+    lines.join("\n")
+}
+
+pub fn session_json(app: &App) -> serde_json::Value {
+    let aircraft: Vec<serde_json::Value> = app
+        .session
+        .entries()
+        .into_iter()
+        .map(|entry| {
+            json!({
+                "hex": entry.hex,
+                "flight": entry.callsign,
+                "reg": entry.registration,
+                "type": entry.aircraft_type,
+                "first_seen": epoch_rfc3339(entry.first_seen),
+                "last_seen": epoch_rfc3339(entry.last_seen),
+                "max_alt": entry.max_alt,
+                "max_gs": entry.max_gs,
+                "max_distance_nm": entry.max_distance_nm,
+            })
+        })
+        .collect();
+    json!({
+        "started": rfc3339(app.start_time),
+        "exported": rfc3339(SystemTime::now()),
+        "aircraft": aircraft,
+    })
+}
+
+fn sorted_trails(app: &App) -> Vec<(&str, &std::collections::VecDeque<crate::app::TrailPoint>)> {
+    let mut trails: Vec<_> = app
+        .trail_points
+        .iter()
+        .filter(|(_, points)| !points.is_empty())
+        .map(|(hex, points)| (hex.as_ref(), points))
+        .collect();
+    trails.sort_by(|a, b| a.0.cmp(b.0));
+    trails
+}
+
+/// Aircraft may have left the feed, so the callsign comes from the session.
+fn trail_callsign<'a>(app: &'a App, hex: &str) -> Option<&'a str> {
+    app.session
+        .get(hex)
+        .and_then(|entry| entry.callsign.as_deref())
+}
+
+fn rfc3339(at: SystemTime) -> String {
+    DateTime::<Utc>::from(at).to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn epoch_rfc3339(secs: i64) -> String {
+    DateTime::<Utc>::from_timestamp(secs, 0)
+        .map(|at| at.to_rfc3339_opts(SecondsFormat::Secs, true))
+        .unwrap_or_default()
+}
+
+pub fn filtered_response(app: &App, indices: &[usize]) -> ApiResponse {
+    ApiResponse {
+        aircraft: indices
+            .iter()
+            .map(|idx| app.data.aircraft[*idx].clone())
+            .collect(),
+        ..app.data.clone()
+    }
+}
+
+fn opt_alt(value: Option<Altitude>) -> String {
+    match value {
+        Some(Altitude::Ground) => "ground".to_string(),
+        Some(Altitude::Feet(feet)) => feet.to_string(),
+        None => String::new(),
+    }
 }
 
 fn opt_i64(value: Option<i64>) -> String {
@@ -92,9 +349,8 @@ fn guard_csv_formula(text: &str) -> String {
     }
 }
 
-fn export_path(filename: &str) -> Result<PathBuf> {
-    let dir = PathBuf::from("exports");
-    fs::create_dir_all(&dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+fn export_path(dir: &Path, filename: &str) -> Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
     Ok(dir.join(filename))
 }
 
@@ -122,7 +378,8 @@ fn unique_path(path: &Path) -> PathBuf {
 
 #[cfg(test)]
 mod tests {
-    use super::csv_field;
+    use super::{csv_field, epoch_rfc3339, rfc3339};
+    use std::time::{Duration, SystemTime};
 
     #[test]
     fn csv_field_guards_formulas() {
@@ -137,4 +394,11 @@ mod tests {
         assert_eq!(csv_field(Some("line\nbreak")), "\"line\nbreak\"");
         assert_eq!(csv_field(Some("quote\"here")), "\"quote\"\"here\"");
     }
+
+    #[test]
+    fn timestamps_are_utc_rfc3339() {
+        assert_eq!(epoch_rfc3339(1_700_000_000), "2023-11-14T22:13:20Z");
+        let at = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        assert_eq!(rfc3339(at), "2023-11-14T22:13:20Z");
+    }
 }
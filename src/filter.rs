@@ -0,0 +1,593 @@
+//! Filter expressions, e.g. `alt<10000 && type=B73* && dist<25`. Text without
+//! any of `= < > ! & | ( )` is a plain substring search.
+
+use crate::country;
+use crate::model::Aircraft;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Filter {
+    All,
+    Text(String),
+    Expr(Expr),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    Or(Vec<Expr>),
+    And(Vec<Expr>),
+    Not(Box<Expr>),
+    Text(String),
+    Compare(Field, CmpOp, Value),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CmpOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            CmpOp::Eq => "=",
+            CmpOp::Ne => "!=",
+            CmpOp::Lt => "<",
+            CmpOp::Le => "<=",
+            CmpOp::Gt => ">",
+            CmpOp::Ge => ">=",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Number(f64),
+    /// Lowercase text; `*` and `?` are wildcards.
+    Pattern(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Field {
+    Callsign,
+    Hex,
+    Reg,
+    Type,
+    Desc,
+    Operator,
+    Squawk,
+    Category,
+    Emergency,
+    Country,
+    Alt,
+    GeomAlt,
+    Speed,
+    Track,
+    VertRate,
+    Dist,
+    Rssi,
+    Seen,
+    Messages,
+    Lat,
+    Lon,
+    Mach,
+    Ias,
+    Tas,
+}
+
+const FIELDS: &[(&str, Field)] = &[
+    ("callsign", Field::Callsign),
+    ("call", Field::Callsign),
+    ("flight", Field::Callsign),
+    ("hex", Field::Hex),
+    ("icao", Field::Hex),
+    ("reg", Field::Reg),
+    ("r", Field::Reg),
+    ("type", Field::Type),
+    ("t", Field::Type),
+    ("desc", Field::Desc),
+    ("op", Field::Operator),
+    ("operator", Field::Operator),
+    ("squawk", Field::Squawk),
+    ("sqk", Field::Squawk),
+    ("cat", Field::Category),
+    ("category", Field::Category),
+    ("emergency", Field::Emergency),
+    ("country", Field::Country),
+    ("ctry", Field::Country),
+    ("alt", Field::Alt),
+    ("alt_baro", Field::Alt),
+    ("galt", Field::GeomAlt),
+    ("alt_geom", Field::GeomAlt),
+    ("gs", Field::Speed),
+    ("speed", Field::Speed),
+    ("spd", Field::Speed),
+    ("track", Field::Track),
+    ("trk", Field::Track),
+    ("vs", Field::VertRate),
+    ("rate", Field::VertRate),
+    ("dist", Field::Dist),
+    ("distance", Field::Dist),
+    ("rssi", Field::Rssi),
+    ("seen", Field::Seen),
+    ("msgs", Field::Messages),
+    ("messages", Field::Messages),
+    ("lat", Field::Lat),
+    ("lon", Field::Lon),
+    ("mach", Field::Mach),
+    ("ias", Field::Ias),
+    ("tas", Field::Tas),
+];
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        let name = name.to_ascii_lowercase();
+        FIELDS
+            .iter()
+            .find(|(alias, _)| *alias == name)
+            .map(|(_, field)| *field)
+    }
+
+    fn is_numeric(self) -> bool {
+        !matches!(
+            self,
+            Field::Callsign
+                | Field::Hex
+                | Field::Reg
+                | Field::Type
+                | Field::Desc
+                | Field::Operator
+                | Field::Squawk
+                | Field::Category
+                | Field::Emergency
+                | Field::Country
+        )
+    }
+
+    fn text(self, ac: &Aircraft) -> Option<&str> {
+        let value = match self {
+            Field::Callsign => ac.flight.as_deref(),
+            Field::Hex => ac.hex.as_deref(),
+            Field::Reg => ac.r.as_deref(),
+            Field::Type => ac.t.as_deref(),
+            Field::Desc => ac.desc.as_deref(),
+            Field::Operator => ac.own_op.as_deref(),
+            Field::Squawk => ac.squawk.as_deref(),
+            Field::Category => ac.category.as_deref(),
+            Field::Emergency => ac.emergency.as_deref(),
+            _ => None,
+        };
+        value.map(str::trim).filter(|value| !value.is_empty())
+    }
+
+    fn number(self, ac: &Aircraft, distance_nm: &dyn Fn() -> Option<f64>) -> Option<f64> {
+        match self {
+            Field::Alt => ac.alt_baro.map(|alt| alt.feet() as f64),
+            Field::GeomAlt => ac.alt_geom.map(|alt| alt as f64),
+            Field::Speed => ac.gs,
+            Field::Track => ac.track,
+            Field::VertRate => ac.baro_rate.or(ac.geom_rate).map(|rate| rate as f64),
+            Field::Dist => distance_nm(),
+            Field::Rssi => ac.rssi,
+            Field::Seen => ac.seen,
+            Field::Messages => ac.messages.map(|count| count as f64),
+            Field::Lat => ac.lat,
+            Field::Lon => ac.lon,
+            Field::Mach => ac.mach,
+            Field::Ias => ac.ias.map(|ias| ias as f64),
+            Field::Tas => ac.tas.map(|tas| tas as f64),
+            _ => None,
+        }
+        .filter(|value| value.is_finite())
+    }
+}
+
+impl Filter {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(Filter::All);
+        }
+        if !input.contains(['=', '<', '>', '!', '&', '|', '(', ')']) {
+            return Ok(Filter::Text(input.to_lowercase()));
+        }
+        let tokens = tokenize(input)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected {}", token.describe()));
+        }
+        Ok(Filter::Expr(expr))
+    }
+
+    /// Whether `ac` passes; `distance_nm` is only called for `dist`.
+    pub fn matches(&self, ac: &Aircraft, distance_nm: &dyn Fn() -> Option<f64>) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Text(needle) => text_matches(ac, needle),
+            Filter::Expr(expr) => expr.matches(ac, distance_nm),
+        }
+    }
+}
+
+impl Expr {
+    fn matches(&self, ac: &Aircraft, distance_nm: &dyn Fn() -> Option<f64>) -> bool {
+        match self {
+            Expr::Or(terms) => terms.iter().any(|term| term.matches(ac, distance_nm)),
+            Expr::And(terms) => terms.iter().all(|term| term.matches(ac, distance_nm)),
+            Expr::Not(term) => !term.matches(ac, distance_nm),
+            Expr::Text(needle) => text_matches(ac, needle),
+            Expr::Compare(field, op, Value::Number(wanted)) => {
+                let Some(value) = field.number(ac, distance_nm) else {
+                    return false;
+                };
+                match op {
+                    CmpOp::Eq => value == *wanted,
+                    CmpOp::Ne => value != *wanted,
+                    CmpOp::Lt => value < *wanted,
+                    CmpOp::Le => value <= *wanted,
+                    CmpOp::Gt => value > *wanted,
+                    CmpOp::Ge => value >= *wanted,
+                }
+            }
+            Expr::Compare(Field::Country, op, Value::Pattern(pattern)) => {
+                let Some(country) = country::of(ac) else {
+                    return false;
+                };
+                let matched = [country.code, country.name]
+                    .iter()
+                    .any(|value| wildcard_match(pattern, &value.to_lowercase()));
+                if *op == CmpOp::Ne {
+                    !matched
+                } else {
+                    matched
+                }
+            }
+            Expr::Compare(field, op, Value::Pattern(pattern)) => {
+                let Some(value) = field.text(ac) else {
+                    return false;
+                };
+                let matched = wildcard_match(pattern, &value.to_lowercase());
+                if *op == CmpOp::Ne {
+                    !matched
+                } else {
+                    matched
+                }
+            }
+        }
+    }
+}
+
+fn text_matches(ac: &Aircraft, needle: &str) -> bool {
+    let haystacks = [
+        ac.flight.as_deref(),
+        ac.r.as_deref(),
+        ac.t.as_deref(),
+        ac.desc.as_deref(),
+        ac.own_op.as_deref(),
+        ac.hex.as_deref(),
+        ac.squawk.as_deref(),
+        ac.emergency.as_deref().filter(|value| *value != "none"),
+        ac.category_class().map(|class| class.name()),
+    ];
+
+    haystacks.iter().any(|value| {
+        value
+            .map(|v| v.to_lowercase().contains(needle))
+            .unwrap_or(false)
+    }) || ac
+        .nav_modes
+        .iter()
+        .flatten()
+        .any(|mode| mode.to_lowercase().contains(needle))
+}
+
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|ch| *ch == '*')
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Cmp(CmpOp),
+    And,
+    Or,
+    Not,
+    Open,
+    Close,
+}
+
+impl Token {
+    fn describe(&self) -> String {
+        match self {
+            Token::Word(word) => format!("\"{word}\""),
+            Token::Cmp(op) => format!("\"{}\"", op.symbol()),
+            Token::And => "\"&&\"".to_string(),
+            Token::Or => "\"||\"".to_string(),
+            Token::Not => "\"!\"".to_string(),
+            Token::Open => "\"(\"".to_string(),
+            Token::Close => "\")\"".to_string(),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        chars.next();
+        let token = match ch {
+            '(' => Token::Open,
+            ')' => Token::Close,
+            '&' | '|' => {
+                if chars.peek() == Some(&ch) {
+                    chars.next();
+                }
+                if ch == '&' {
+                    Token::And
+                } else {
+                    Token::Or
+                }
+            }
+            '=' => {
+                if chars.peek() == Some(&'=') {
+                    chars.next();
+                }
+                Token::Cmp(CmpOp::Eq)
+            }
+            '!' | '<' | '>' => {
+                let equals = chars.peek() == Some(&'=');
+                if equals {
+                    chars.next();
+                }
+                match (ch, equals) {
+                    ('!', true) => Token::Cmp(CmpOp::Ne),
+                    ('!', false) => Token::Not,
+                    ('<', true) => Token::Cmp(CmpOp::Le),
+                    ('<', false) => Token::Cmp(CmpOp::Lt),
+                    ('>', true) => Token::Cmp(CmpOp::Ge),
+                    _ => Token::Cmp(CmpOp::Gt),
+                }
+            }
+            '"' | '\'' => {
+                let mut word = String::new();
+                loop {
+                    match chars.next() {
+                        Some(c) if c == ch => break,
+                        Some(c) => word.push(c),
+                        None => return Err("unterminated quote".to_string()),
+                    }
+                }
+                tokens.push(Token::Word(word));
+                continue;
+            }
+            _ => {
+                let mut word = ch.to_string();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || "()&|=!<>\"'".contains(c) {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                match word.to_ascii_lowercase().as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    _ => Token::Word(word),
+                }
+            }
+        };
+        tokens.push(token);
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut terms = vec![self.and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            terms.push(self.and()?);
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            Expr::Or(terms)
+        })
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut terms = vec![self.unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    terms.push(self.unary()?);
+                }
+                Some(Token::Word(_) | Token::Not | Token::Open) => terms.push(self.unary()?),
+                _ => break,
+            }
+        }
+        Ok(if terms.len() == 1 {
+            terms.remove(0)
+        } else {
+            Expr::And(terms)
+        })
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.next() {
+            Some(Token::Not) => Ok(Expr::Not(Box::new(self.unary()?))),
+            Some(Token::Open) => {
+                let expr = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("missing \")\"".to_string()),
+                }
+            }
+            Some(Token::Word(word)) => match self.peek() {
+                Some(Token::Cmp(op)) => {
+                    let op = *op;
+                    self.pos += 1;
+                    self.comparison(&word, op)
+                }
+                _ => Ok(Expr::Text(word.to_lowercase())),
+            },
+            Some(token) => Err(format!("unexpected {}", token.describe())),
+            None => Err("expression ends early".to_string()),
+        }
+    }
+
+    fn comparison(&mut self, name: &str, op: CmpOp) -> Result<Expr, String> {
+        let field = Field::parse(name).ok_or_else(|| format!("unknown field \"{name}\""))?;
+        let Some(Token::Word(value)) = self.next() else {
+            return Err(format!("missing value after \"{name}{}\"", op.symbol()));
+        };
+        let value = if field.is_numeric() {
+            Value::Number(
+                value
+                    .parse::<f64>()
+                    .map_err(|_| format!("\"{name}\" needs a number, not \"{value}\""))?,
+            )
+        } else if matches!(op, CmpOp::Eq | CmpOp::Ne) {
+            Value::Pattern(value.to_lowercase())
+        } else {
+            return Err(format!(
+                "\"{name}\" is text; use = or != (with * wildcards)"
+            ));
+        };
+        Ok(Expr::Compare(field, op, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{wildcard_match, Filter};
+    use crate::model::{Aircraft, Altitude};
+
+    fn aircraft(flight: &str, ac_type: &str, alt: i64) -> Aircraft {
+        Aircraft {
+            hex: Some("a1b2c3".to_string()),
+            flight: Some(format!("{flight} ")),
+            t: Some(ac_type.to_string()),
+            alt_baro: Some(Altitude::Feet(alt)),
+            gs: Some(250.0),
+            ..Aircraft::default()
+        }
+    }
+
+    fn matches(filter: &str, ac: &Aircraft, distance_nm: Option<f64>) -> bool {
+        Filter::parse(filter).unwrap().matches(ac, &|| distance_nm)
+    }
+
+    #[test]
+    fn comparisons_wildcards_and_boolean_operators() {
+        let low = aircraft("SWA123", "B738", 8_000);
+        let high = aircraft("UAL9", "A320", 35_000);
+        let filter = "alt<10000 && type=B73* && dist<25";
+        assert!(matches(filter, &low, Some(12.0)));
+        assert!(!matches(filter, &low, Some(40.0)));
+        assert!(!matches(filter, &low, None));
+        assert!(!matches(filter, &high, Some(12.0)));
+
+        assert!(matches("alt_baro > 10000 AND speed > 200", &high, None));
+        assert!(matches("type=a32? || alt<=8000", &low, None));
+        assert!(matches("!(call=UAL*) type!=A3*", &low, None));
+        assert!(!matches("not call=swa* or alt>40000", &low, None));
+        assert!(matches("swa & alt>=8000", &low, None));
+        assert!(matches("call=\"SWA123\"", &low, None));
+    }
+
+    #[test]
+    fn country_matches_code_or_name() {
+        let mut ac = aircraft("EIN123", "A320", 30_000);
+        ac.hex = Some("4ca1d2".to_string());
+        assert!(matches("country=IE", &ac, None));
+        assert!(matches("ctry=irel*", &ac, None));
+        assert!(!matches("country!=ie", &ac, None));
+        ac.hex = Some("~4ca1d2".to_string());
+        assert!(!matches("country=ie", &ac, None));
+    }
+
+    #[test]
+    fn plain_text_keeps_substring_search() {
+        let ac = aircraft("SWA123", "B738", 8_000);
+        assert_eq!(Filter::parse("  "), Ok(Filter::All));
+        assert_eq!(
+            Filter::parse("Swa 1"),
+            Ok(Filter::Text("swa 1".to_string()))
+        );
+        assert!(matches("a123", &ac, None));
+        assert!(matches("B73", &ac, None));
+        assert!(!matches("UAL", &ac, None));
+    }
+
+    #[test]
+    fn reports_bad_expressions() {
+        for (filter, error) in [
+            ("alt<high", "\"alt\" needs a number, not \"high\""),
+            ("wingspan>30", "unknown field \"wingspan\""),
+            (
+                "type>B7",
+                "\"type\" is text; use = or != (with * wildcards)",
+            ),
+            ("(alt<1000", "missing \")\""),
+            ("alt<1000 &&", "expression ends early"),
+            ("alt<", "missing value after \"alt<\""),
+            ("alt<1000)", "unexpected \")\""),
+        ] {
+            assert_eq!(Filter::parse(filter), Err(error.to_string()), "{filter}");
+        }
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match("b73*", "b738"));
+        assert!(wildcard_match("*12?", "swa123"));
+        assert!(wildcard_match("a*b*c", "axxbyyc"));
+        assert!(!wildcard_match("a*b*c", "axxbyy"));
+        assert!(!wildcard_match("b73", "b738"));
+    }
+}
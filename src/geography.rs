@@ -0,0 +1,309 @@
+//! Coastlines and borders from GeoJSON for the canvas radar.
+
+use std::cell::RefCell;
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::units::{bearing_deg, distance_nm};
+
+const NM_PER_DEG_LAT: f64 = 60.0;
+
+/// Outline segments in radar coordinates (nm east, nm north of the center).
+pub type Segments = Vec<[(f64, f64); 2]>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Bounds {
+    min_lat: f64,
+    max_lat: f64,
+    min_lon: f64,
+    max_lon: f64,
+}
+
+impl Bounds {
+    fn of(points: &[[f64; 2]]) -> Self {
+        let mut bounds = Bounds {
+            min_lat: f64::INFINITY,
+            max_lat: f64::NEG_INFINITY,
+            min_lon: f64::INFINITY,
+            max_lon: f64::NEG_INFINITY,
+        };
+        for [lat, lon] in points {
+            bounds.min_lat = bounds.min_lat.min(*lat);
+            bounds.max_lat = bounds.max_lat.max(*lat);
+            bounds.min_lon = bounds.min_lon.min(*lon);
+            bounds.max_lon = bounds.max_lon.max(*lon);
+        }
+        bounds
+    }
+
+    fn around(lat: f64, lon: f64, range_nm: f64) -> Self {
+        let dlat = range_nm / NM_PER_DEG_LAT;
+        let cos_lat = lat.to_radians().cos();
+        let dlon = if cos_lat > 0.01 {
+            (dlat / cos_lat).min(180.0)
+        } else {
+            180.0
+        };
+        Bounds {
+            min_lat: lat - dlat,
+            max_lat: lat + dlat,
+            min_lon: lon - dlon,
+            max_lon: lon + dlon,
+        }
+    }
+
+    fn overlaps(&self, other: &Bounds) -> bool {
+        self.min_lat <= other.max_lat
+            && other.min_lat <= self.max_lat
+            && self.min_lon <= other.max_lon
+            && other.min_lon <= self.max_lon
+    }
+}
+
+#[derive(Debug)]
+struct Polyline {
+    points: Vec<[f64; 2]>,
+    bounds: Bounds,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ViewKey {
+    lat: f64,
+    lon: f64,
+    range_nm: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct Geography {
+    lines: Vec<Polyline>,
+    cache: RefCell<Option<(ViewKey, Arc<Segments>)>>,
+}
+
+impl Geography {
+    pub fn parse_geojson(text: &str) -> Result<Self, String> {
+        let root: Value = serde_json::from_str(text).map_err(|err| err.to_string())?;
+        let mut geography = Geography::default();
+        geography.collect(&root)?;
+        Ok(geography)
+    }
+
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    fn collect(&mut self, value: &Value) -> Result<(), String> {
+        let kind = value
+            .get("type")
+            .and_then(Value::as_str)
+            .ok_or("GeoJSON object without a type")?;
+        let coords = || value.get("coordinates").unwrap_or(&Value::Null);
+        match kind {
+            "FeatureCollection" => {
+                for feature in value
+                    .get("features")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                {
+                    self.collect(feature)?;
+                }
+            }
+            "Feature" => match value.get("geometry") {
+                Some(geometry) if !geometry.is_null() => self.collect(geometry)?,
+                _ => {}
+            },
+            "GeometryCollection" => {
+                for geometry in value
+                    .get("geometries")
+                    .and_then(Value::as_array)
+                    .into_iter()
+                    .flatten()
+                {
+                    self.collect(geometry)?;
+                }
+            }
+            "LineString" => self.push_line(coords()),
+            "MultiLineString" | "Polygon" => {
+                for line in coords().as_array().into_iter().flatten() {
+                    self.push_line(line);
+                }
+            }
+            "MultiPolygon" => {
+                for polygon in coords().as_array().into_iter().flatten() {
+                    for ring in polygon.as_array().into_iter().flatten() {
+                        self.push_line(ring);
+                    }
+                }
+            }
+            "Point" | "MultiPoint" => {}
+            other => return Err(format!("unsupported GeoJSON type {other}")),
+        }
+        Ok(())
+    }
+
+    /// GeoJSON positions are `[lon, lat]`; malformed ones are dropped.
+    fn push_line(&mut self, coords: &Value) {
+        let points: Vec<[f64; 2]> = coords
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|position| {
+                let lon = position.get(0)?.as_f64()?;
+                let lat = position.get(1)?.as_f64()?;
+                ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon))
+                    .then_some([lat, lon])
+            })
+            .collect();
+        if points.len() < 2 {
+            return;
+        }
+        let bounds = Bounds::of(&points);
+        self.lines.push(Polyline { points, bounds });
+    }
+
+    /// Outline segments within `range_nm` of the center, projected like the
+    /// radar blips and clipped to the radar square. The result is cached
+    /// until the center or range changes.
+    pub fn segments(&self, center_lat: f64, center_lon: f64, range_nm: f64) -> Arc<Segments> {
+        let key = ViewKey {
+            lat: center_lat,
+            lon: center_lon,
+            range_nm,
+        };
+        if let Some((cached, segments)) = self.cache.borrow().as_ref() {
+            if *cached == key {
+                return Arc::clone(segments);
+            }
+        }
+        let segments = Arc::new(self.project(key));
+        *self.cache.borrow_mut() = Some((key, Arc::clone(&segments)));
+        segments
+    }
+
+    fn project(&self, view: ViewKey) -> Segments {
+        let window = Bounds::around(view.lat, view.lon, view.range_nm);
+        let project = |[lat, lon]: [f64; 2]| {
+            let dist = distance_nm(view.lat, view.lon, lat, lon);
+            let bearing = bearing_deg(view.lat, view.lon, lat, lon).to_radians();
+            (dist * bearing.sin(), dist * bearing.cos())
+        };
+        let mut segments = Vec::new();
+        for line in self
+            .lines
+            .iter()
+            .filter(|line| line.bounds.overlaps(&window))
+        {
+            for edge in line.points.windows(2) {
+                // Far-off edges would be badly distorted by the projection.
+                if !Bounds::of(edge).overlaps(&window) {
+                    continue;
+                }
+                if let Some(segment) = clip(project(edge[0]), project(edge[1]), view.range_nm) {
+                    segments.push(segment);
+                }
+            }
+        }
+        segments
+    }
+}
+
+/// Liang-Barsky clip of a segment to the square `[-limit, limit]`.
+fn clip(start: (f64, f64), end: (f64, f64), limit: f64) -> Option<[(f64, f64); 2]> {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let mut t0: f64 = 0.0;
+    let mut t1: f64 = 1.0;
+    for (p, q) in [
+        (-dx, start.0 + limit),
+        (dx, limit - start.0),
+        (-dy, start.1 + limit),
+        (dy, limit - start.1),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+            continue;
+        }
+        let t = q / p;
+        if p < 0.0 {
+            t0 = t0.max(t);
+        } else {
+            t1 = t1.min(t);
+        }
+        if t0 > t1 {
+            return None;
+        }
+    }
+    Some([
+        (start.0 + t0 * dx, start.1 + t0 * dy),
+        (start.0 + t1 * dx, start.1 + t1 * dy),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clip, Geography};
+    use std::sync::Arc;
+
+    const SAMPLE: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {"type": "Feature", "properties": {}, "geometry": {
+                "type": "LineString",
+                "coordinates": [[-1.0, 0.0], [1.0, 0.0]]
+            }},
+            {"type": "Feature", "properties": {}, "geometry": {
+                "type": "MultiPolygon",
+                "coordinates": [[[[10.0, 10.0], [11.0, 10.0], [11.0, 11.0], [10.0, 10.0]]]]
+            }},
+            {"type": "Feature", "properties": {}, "geometry": {
+                "type": "Point", "coordinates": [0.0, 0.0]
+            }},
+            {"type": "Feature", "properties": {}, "geometry": null}
+        ]
+    }"#;
+
+    #[test]
+    fn parses_lines_and_rings() {
+        let geography = Geography::parse_geojson(SAMPLE).unwrap();
+        assert_eq!(geography.len(), 2);
+        assert!(Geography::parse_geojson(r#"{"type": "Blob"}"#).is_err());
+        assert!(Geography::parse_geojson("not json").is_err());
+    }
+
+    #[test]
+    fn segments_are_clipped_to_range() {
+        let geography = Geography::parse_geojson(SAMPLE).unwrap();
+        let segments = geography.segments(0.0, 0.0, 30.0);
+        assert_eq!(segments.len(), 1, "the far polygon is skipped");
+        let [(x1, y1), (x2, y2)] = segments[0];
+        assert!((x1 + 30.0).abs() < 1e-6 && (x2 - 30.0).abs() < 1e-6);
+        assert!(y1.abs() < 1e-6 && y2.abs() < 1e-6);
+    }
+
+    #[test]
+    fn segments_are_cached_per_view() {
+        let geography = Geography::parse_geojson(SAMPLE).unwrap();
+        let first = geography.segments(0.0, 0.0, 30.0);
+        assert!(Arc::ptr_eq(&first, &geography.segments(0.0, 0.0, 30.0)));
+        assert!(!Arc::ptr_eq(&first, &geography.segments(0.0, 0.0, 60.0)));
+    }
+
+    #[test]
+    fn clip_rejects_outside_segments() {
+        assert_eq!(clip((20.0, 5.0), (30.0, 5.0), 10.0), None);
+        assert_eq!(
+            clip((0.0, 0.0), (5.0, 5.0), 10.0),
+            Some([(0.0, 0.0), (5.0, 5.0)])
+        );
+        assert_eq!(
+            clip((0.0, -20.0), (0.0, 20.0), 10.0),
+            Some([(0.0, -10.0), (0.0, 10.0)])
+        );
+    }
+}
@@ -1,9 +1,23 @@
+use std::time::SystemTime;
+
+use chrono::{DateTime, Local};
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Style};
-use ratatui::widgets::{Block, BorderType, Borders, Sparkline};
+use ratatui::symbols::Marker;
+use ratatui::text::Line;
+use ratatui::widgets::canvas::{Canvas, Line as CanvasLine, Points};
+use ratatui::widgets::{
+    Axis, Bar, BarChart, Block, BorderType, Borders, Chart, Dataset, GraphType, Paragraph,
+    Sparkline,
+};
 use ratatui::Frame;
 
-use crate::app::App;
+use crate::app::{
+    App, PerfChart, PerfWindow, PerformanceSnapshot, TrafficHistograms, ALTITUDE_BANDS,
+    DISTANCE_RINGS, RSSI_BANDS,
+};
+use crate::model::{ReceiverStats, SourceStatus};
+use crate::traffic::TrafficMetric;
 
 pub struct GraphTheme {
     pub accent: Color,
@@ -11,40 +25,423 @@ pub struct GraphTheme {
     pub panel_bg: Color,
 }
 
-pub fn render_performance_body(f: &mut Frame, area: Rect, app: &App, theme: &GraphTheme) {
+pub fn render_performance_body(
+    f: &mut Frame,
+    area: Rect,
+    app: &App,
+    indices: &[usize],
+    theme: &GraphTheme,
+) {
     let snapshot = app.performance_snapshot();
+    // Rows follow the `perf_panel_N` slots; "none" and unknown names are
+    // skipped.
+    let panels: Vec<(String, Constraint)> = app
+        .perf_panels
+        .iter()
+        .map(|panel| panel.trim().to_ascii_lowercase())
+        .filter_map(|panel| {
+            let height = match panel.as_str() {
+                "messages" | "flights" | "app" => Constraint::Length(7),
+                "signal" => Constraint::Min(7),
+                "histograms" | "traffic" => Constraint::Length(9),
+                "receivers" => Constraint::Length(app.data.sources.len().max(1) as u16 + 3),
+                name if PerfChart::from_panel(name).is_some() => Constraint::Length(9),
+                _ => return None,
+            };
+            Some((panel, height))
+        })
+        .collect();
     let sections = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Length(7),
-            Constraint::Length(7),
-            Constraint::Min(7),
-        ])
+        .constraints(panels.iter().map(|(_, height)| *height))
         .split(area);
 
-    let msg_title = match snapshot.latest_msg_rate {
-        Some(rate) => format!("MESSAGES {rate:.1}/s"),
-        None => "MESSAGES --".to_string(),
+    for ((panel, _), area) in panels.iter().zip(sections.iter().copied()) {
+        match panel.as_str() {
+            "messages" => {
+                let title = match snapshot.latest_msg_rate {
+                    Some(rate) => format!("MESSAGES {rate:.1}/s"),
+                    None => "MESSAGES --".to_string(),
+                };
+                render_sparkline(
+                    f,
+                    area,
+                    &title,
+                    &snapshot.msg_rate,
+                    theme.accent,
+                    theme.panel_bg,
+                );
+            }
+            "flights" => {
+                let [v0, v1, v2] = snapshot.adsb_versions;
+                let title = format!(
+                    "FLIGHTS {} | ADS-B v0 {v0} v1 {v1} v2 {v2}",
+                    snapshot.latest_flights
+                );
+                render_sparkline(
+                    f,
+                    area,
+                    &title,
+                    &snapshot.flights,
+                    Color::Cyan,
+                    theme.panel_bg,
+                );
+            }
+            "signal" => match snapshot.receiver {
+                Some(receiver) => render_receiver(f, area, &snapshot, &receiver, theme),
+                None => render_rssi_signal(f, area, &snapshot, theme),
+            },
+            "app" => render_app_usage(f, area, &snapshot, theme),
+            "histograms" => render_histograms(f, area, app, indices, theme),
+            "traffic" => {
+                let row = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(24), Constraint::Length(RANGE_CHART_WIDTH)])
+                    .split(area);
+                render_hourly_chart(f, row[0], app, TrafficMetric::Aircraft, theme);
+                render_range_outline(f, row[1], app, theme);
+            }
+            "receivers" => render_receivers(f, area, &app.data.sources, theme),
+            name => {
+                if let Some(chart) = PerfChart::from_panel(name) {
+                    render_series_chart(f, area, app, chart, theme);
+                }
+            }
+        }
+    }
+}
+
+fn render_series_chart(f: &mut Frame, area: Rect, app: &App, chart: PerfChart, theme: &GraphTheme) {
+    let window = app.perf_window;
+    let now = app.last_update.unwrap_or_else(SystemTime::now);
+    let points = app.perf_series(chart, now);
+    let latest = match points.last() {
+        Some((_, value)) if chart == PerfChart::Aircraft => format!("{value:.0}"),
+        Some((_, value)) => format!("{value:.1}{}", chart.unit()),
+        None => "--".to_string(),
+    };
+    let title = format!("{} {latest} | {}", chart.title(), window.name());
+    let color = match chart {
+        PerfChart::MsgRate => theme.accent,
+        PerfChart::Aircraft => Color::Cyan,
+        PerfChart::Rssi => Color::Green,
+        PerfChart::MaxRange => theme.warn,
+        PerfChart::PositionRate => Color::Magenta,
+        PerfChart::RouteHits => Color::Yellow,
     };
+    let [low, high] = series_bounds(&points);
+    let axis_style = Style::default().fg(Color::Gray);
+    let dataset = Dataset::default()
+        .marker(Marker::Braille)
+        .graph_type(GraphType::Line)
+        .style(Style::default().fg(color))
+        .data(&points);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(title);
+    let widget = Chart::new(vec![dataset])
+        .block(block)
+        .style(Style::default().bg(theme.panel_bg))
+        .x_axis(
+            Axis::default()
+                .bounds([-window.duration().as_secs_f64(), 0.0])
+                .labels(window_labels(window))
+                .style(axis_style),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([low, high])
+                .labels([
+                    format!("{low:.0}"),
+                    format!("{:.0}", (low + high) / 2.0),
+                    format!("{high:.0}"),
+                ])
+                .style(axis_style),
+        );
+    f.render_widget(widget, area);
+}
+
+fn window_labels(window: PerfWindow) -> [&'static str; 3] {
+    match window {
+        PerfWindow::FiveMinutes => ["-5m", "-2m30s", "now"],
+        PerfWindow::ThirtyMinutes => ["-30m", "-15m", "now"],
+        PerfWindow::TwoHours => ["-2h", "-1h", "now"],
+    }
+}
+
+/// Value axis bounds: from zero for non-negative series, otherwise from the
+/// lowest value, with a little headroom above the highest.
+fn series_bounds(points: &[(f64, f64)]) -> [f64; 2] {
+    let (min, max) = points
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), (_, v)| {
+            (min.min(*v), max.max(*v))
+        });
+    if !min.is_finite() || !max.is_finite() {
+        return [0.0, 1.0];
+    }
+    let low = if min >= 0.0 { 0.0 } else { min.floor() };
+    let high = if max > 0.0 {
+        (max + max / 10.0).ceil()
+    } else {
+        max.ceil()
+    };
+    [low, high.max(low + 1.0)]
+}
+
+const RANGE_CHART_WIDTH: u16 = 26;
+
+const PROFILE_STEP_FT: f64 = 10_000.0;
+
+pub fn render_vertical_profile(
+    f: &mut Frame,
+    area: Rect,
+    app: &App,
+    indices: &[usize],
+    theme: &GraphTheme,
+) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded);
+    if app.site().is_none() {
+        f.render_widget(block.title("PROFILE (no site)"), area);
+        return;
+    }
+    let range = app.radar_range_nm.max(1.0);
+    let selected = app
+        .table_state
+        .selected()
+        .and_then(|row| indices.get(row))
+        .copied();
+    let mut points = Vec::new();
+    let mut highlight = Vec::new();
+    for &idx in indices {
+        let ac = &app.data.aircraft[idx];
+        if app.position_stale(ac) {
+            continue;
+        }
+        let (Some(geometry), Some(feet)) = (app.site_geometry(idx), app.altitude_ft(ac)) else {
+            continue;
+        };
+        if geometry.distance_nm > range {
+            continue;
+        }
+        let point = (geometry.distance_nm, feet.max(0) as f64);
+        if selected == Some(idx) {
+            highlight.push(point);
+        } else {
+            points.push(point);
+        }
+    }
+    let ceiling = profile_ceiling_ft(points.iter().chain(&highlight));
+    let title = format!("PROFILE {} | {range:.0}nm", points.len() + highlight.len());
+    let axis_style = Style::default().fg(Color::Gray);
+    let datasets = vec![
+        Dataset::default()
+            .marker(Marker::Braille)
+            .graph_type(GraphType::Scatter)
+            .style(Style::default().fg(theme.accent))
+            .data(&points),
+        Dataset::default()
+            .marker(Marker::Block)
+            .graph_type(GraphType::Scatter)
+            .style(Style::default().fg(theme.warn))
+            .data(&highlight),
+    ];
+    let flight_level = |feet: f64| format!("FL{:03.0}", feet / 100.0);
+    let widget = Chart::new(datasets)
+        .block(block.title(title))
+        .style(Style::default().bg(theme.panel_bg))
+        .x_axis(
+            Axis::default()
+                .bounds([0.0, range])
+                .labels([
+                    "0".to_string(),
+                    format!("{:.0}", range / 2.0),
+                    format!("{range:.0}nm"),
+                ])
+                .style(axis_style),
+        )
+        .y_axis(
+            Axis::default()
+                .bounds([0.0, ceiling])
+                .labels([
+                    "GND".to_string(),
+                    flight_level(ceiling / 2.0),
+                    flight_level(ceiling),
+                ])
+                .style(axis_style),
+        );
+    f.render_widget(widget, area);
+}
+
+/// Top of the profile's altitude axis: the highest aircraft rounded up to
+/// the next 10,000 ft, and at least 10,000 ft.
+fn profile_ceiling_ft<'a>(points: impl IntoIterator<Item = &'a (f64, f64)>) -> f64 {
+    let highest = points
+        .into_iter()
+        .map(|(_, feet)| *feet)
+        .fold(0.0, f64::max);
+    ((highest / PROFILE_STEP_FT).floor() + 1.0).max(1.0) * PROFILE_STEP_FT
+}
+
+fn render_range_outline(f: &mut Frame, area: Rect, app: &App, theme: &GraphTheme) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded);
+    let Some(range) = app.range_sectors() else {
+        f.render_widget(block.title("RANGE (no site)"), area);
+        return;
+    };
+    let peak = |sectors: &[f64]| sectors.iter().copied().fold(0.0, f64::max);
+    let (max_nm, now_nm) = (peak(&range.max), peak(&range.current));
+    let title = format!("RANGE {max_nm:.0} | now {now_nm:.0}nm");
+    // Cells are about twice as tall as wide; widen x so the plot stays round.
+    let radius = max_nm.max(1.0);
+    let rows = f64::from(area.height.saturating_sub(2).max(1));
+    let cols = f64::from(area.width.saturating_sub(2).max(1));
+    let half_width = radius * cols / (2.0 * rows);
+    let outline = |sectors: &[f64]| -> Vec<(f64, f64)> {
+        let width = 360.0 / sectors.len() as f64;
+        sectors
+            .iter()
+            .enumerate()
+            .map(|(sector, nm)| {
+                let bearing = ((sector as f64 + 0.5) * width).to_radians();
+                (nm * bearing.sin(), nm * bearing.cos())
+            })
+            .collect()
+    };
+    let max_points = outline(&range.max);
+    let now_points = outline(&range.current);
+    let canvas = Canvas::default()
+        .block(block.title(title))
+        .x_bounds([-half_width, half_width])
+        .y_bounds([-radius, radius])
+        .background_color(theme.panel_bg)
+        .marker(Marker::Braille)
+        .paint(|ctx| {
+            for (points, color) in [(&max_points, theme.accent), (&now_points, theme.warn)] {
+                for (i, &(x1, y1)) in points.iter().enumerate() {
+                    let (x2, y2) = points[(i + 1) % points.len()];
+                    ctx.draw(&CanvasLine {
+                        x1,
+                        y1,
+                        x2,
+                        y2,
+                        color,
+                    });
+                }
+            }
+            ctx.draw(&Points {
+                coords: &[(0.0, 0.0)],
+                color: Color::White,
+            });
+        });
+    f.render_widget(canvas, area);
+}
+
+fn render_app_usage(f: &mut Frame, area: Rect, snapshot: &PerformanceSnapshot, theme: &GraphTheme) {
+    let halves = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+        .split(area);
+    let usage = snapshot.latest_usage;
+    let cpu = usage
+        .cpu_percent
+        .map_or_else(|| "--".to_string(), |v| format!("{v:.1}%"));
+    let rss = usage.rss_bytes.map_or_else(
+        || "--".to_string(),
+        |bytes| format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0)),
+    );
+    let frame = snapshot
+        .frame_ms
+        .map_or_else(|| "--".to_string(), |v| format!("{v:.1} ms"));
     render_sparkline(
         f,
-        sections[0],
-        &msg_title,
-        &snapshot.msg_rate,
-        theme.accent,
+        halves[0],
+        &format!("APP CPU {cpu} | RSS {rss} | FRAME {frame}"),
+        &snapshot.app_cpu,
+        Color::Magenta,
         theme.panel_bg,
     );
-
-    let flights_title = format!("FLIGHTS {}", snapshot.latest_flights);
+    let fetch = snapshot
+        .latest_fetch_ms
+        .map_or_else(|| "--".to_string(), |v| format!("{v:.0} ms"));
+    let cache = snapshot
+        .feed_cache
+        .hit_percent()
+        .map_or_else(String::new, |pct| format!(" | 304 {pct:.0}%"));
     render_sparkline(
         f,
-        sections[1],
-        &flights_title,
-        &snapshot.flights,
-        Color::Cyan,
+        halves[1],
+        &format!("FETCH {fetch}{cache}"),
+        &snapshot.fetch_ms,
+        theme.accent,
         theme.panel_bg,
     );
+}
+
+fn render_receivers(f: &mut Frame, area: Rect, sources: &[SourceStatus], theme: &GraphTheme) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .style(Style::default().bg(theme.panel_bg));
+    if sources.is_empty() {
+        let text = Paragraph::new("single feed (set merge_receivers to merge urls)")
+            .style(Style::default().fg(theme.accent));
+        f.render_widget(text.block(block.title("RECEIVERS")), area);
+        return;
+    }
+    let up = sources
+        .iter()
+        .filter(|status| status.error.is_none())
+        .count();
+    let title = format!("RECEIVERS {up}/{} up", sources.len());
+    let host_width = sources
+        .iter()
+        .map(|status| status.host().chars().count())
+        .max()
+        .unwrap_or(0)
+        .clamp(4, 28);
+    let mut lines = vec![Line::styled(
+        format!(
+            "{:<4}{:<host_width$} {:>5} {:>5} {:>5} {:>5} {:>7}  STATUS",
+            "RX", "HOST", "AC", "POS", "ONLY", "BEST", "FETCH"
+        ),
+        Style::default().fg(theme.accent),
+    )];
+    for (index, status) in sources.iter().enumerate() {
+        let host: String = status.host().chars().take(host_width).collect();
+        let fetch = status
+            .fetch_time
+            .map_or_else(|| "--".to_string(), |t| format!("{} ms", t.as_millis()));
+        let row = format!(
+            "{:<4}{host:<host_width$} {:>5} {:>5} {:>5} {:>5} {fetch:>7}  {}",
+            SourceStatus::label(index),
+            status.aircraft,
+            status.positions,
+            status.exclusive,
+            status.primary,
+            status.error.as_deref().unwrap_or("OK"),
+        );
+        let color = if status.error.is_some() {
+            theme.warn
+        } else {
+            Color::Reset
+        };
+        lines.push(Line::styled(row, Style::default().fg(color)));
+    }
+    f.render_widget(Paragraph::new(lines).block(block.title(title)), area);
+}
 
+fn render_rssi_signal(
+    f: &mut Frame,
+    area: Rect,
+    snapshot: &PerformanceSnapshot,
+    theme: &GraphTheme,
+) {
     let signal_title = match (snapshot.latest_signal, snapshot.latest_signal_rsi) {
         (Some(rssi), Some(rsi)) => {
             format!("SIGNAL avg {rssi:.1} dB | RSI {rsi:.0} (scale -50..0)")
@@ -55,7 +452,7 @@ pub fn render_performance_body(f: &mut Frame, area: Rect, app: &App, theme: &Gra
     };
     render_sparkline(
         f,
-        sections[2],
+        area,
         &signal_title,
         &snapshot.signal,
         theme.warn,
@@ -63,7 +460,274 @@ pub fn render_performance_body(f: &mut Frame, area: Rect, app: &App, theme: &Gra
     );
 }
 
-fn render_sparkline(f: &mut Frame, area: Rect, title: &str, data: &[u64], fg: Color, bg: Color) {
+fn render_histograms(f: &mut Frame, area: Rect, app: &App, indices: &[usize], theme: &GraphTheme) {
+    let histograms = app.traffic_histograms(indices);
+    let halves = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+            Constraint::Ratio(1, 3),
+        ])
+        .split(area);
+    let altitude: Vec<(&str, u64)> = ALTITUDE_BANDS
+        .iter()
+        .zip(histograms.altitude)
+        .map(|((label, _), count)| (*label, count))
+        .collect();
+    render_histogram(f, halves[0], "ALTITUDE (ft)", &altitude, Color::Cyan, theme);
+    match histograms.distance {
+        Some(rings) => {
+            let distance: Vec<(&str, u64)> = DISTANCE_RINGS
+                .iter()
+                .zip(rings)
+                .map(|((label, _), count)| (*label, count))
+                .collect();
+            render_histogram(
+                f,
+                halves[1],
+                "DISTANCE (nm)",
+                &distance,
+                theme.accent,
+                theme,
+            );
+        }
+        None => render_histogram(
+            f,
+            halves[1],
+            "DISTANCE (set site_lat/site_lon)",
+            &[],
+            theme.accent,
+            theme,
+        ),
+    }
+    render_rssi_distribution(f, halves[2], &histograms, theme);
+}
+
+/// Per-aircraft RSSI histogram; bands holding p10, p50 and p90 are drawn in
+/// the warning colour so the spread reads at a glance.
+fn render_rssi_distribution(
+    f: &mut Frame,
+    area: Rect,
+    histograms: &TrafficHistograms,
+    theme: &GraphTheme,
+) {
+    let Some(percentiles) = histograms.rssi_percentiles else {
+        render_histogram(f, area, "RSSI (dBFS) --", &[], Color::Green, theme);
+        return;
+    };
+    let [p10, p50, p90] = percentiles;
+    let marked: Vec<usize> = percentiles
+        .iter()
+        .filter_map(|level| RSSI_BANDS.iter().position(|(_, upper)| *level < *upper))
+        .collect();
+    let bars: Vec<Bar> = RSSI_BANDS
+        .iter()
+        .zip(histograms.rssi)
+        .enumerate()
+        .map(|(band, ((label, _), count))| {
+            let fg = if marked.contains(&band) {
+                theme.warn
+            } else {
+                Color::Green
+            };
+            Bar::default()
+                .label(*label)
+                .value(count)
+                .style(Style::default().fg(fg))
+                .value_style(Style::default().fg(theme.panel_bg).bg(fg))
+        })
+        .collect();
+    let title = format!("RSSI (dBFS) | p10 {p10:.1} p50 {p50:.1} p90 {p90:.1}");
+    render_bars(f, area, &title, bars, Color::Green, theme);
+}
+
+pub fn render_hourly_chart(
+    f: &mut Frame,
+    area: Rect,
+    app: &App,
+    metric: TrafficMetric,
+    theme: &GraphTheme,
+) {
+    let now_secs = Local::now().timestamp();
+    let day = app.traffic.last_day(now_secs);
+    let hour_label = |hour: i64| {
+        DateTime::from_timestamp(hour * 3600, 0)
+            .map(|t| t.with_timezone(&Local).format("%H").to_string())
+            .unwrap_or_default()
+    };
+    let messages: u64 = day.iter().map(|totals| totals.messages).sum();
+    let title = match day.iter().max_by_key(|totals| metric.value(totals)) {
+        Some(peak) if metric.value(peak) > 0 => format!(
+            "TRAFFIC 24H | {} | peak {}:00 {} | msgs {messages}",
+            metric.label(),
+            hour_label(peak.hour),
+            metric.value(peak)
+        ),
+        _ => format!("TRAFFIC 24H | {} | no data yet", metric.label()),
+    };
+    let bars: Vec<Bar> = day
+        .iter()
+        .map(|totals| {
+            Bar::default()
+                .label(hour_label(totals.hour))
+                .value(metric.value(totals))
+        })
+        .collect();
+    let slot = (area.width.saturating_sub(2) / day.len() as u16).max(2);
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(title);
+    let chart = BarChart::new(bars)
+        .block(block)
+        .bar_width(slot - 1)
+        .bar_gap(1)
+        .bar_style(Style::default().fg(theme.warn))
+        .value_style(Style::default().fg(theme.panel_bg).bg(theme.warn))
+        .style(Style::default().bg(theme.panel_bg));
+    f.render_widget(chart, area);
+}
+
+fn render_histogram(
+    f: &mut Frame,
+    area: Rect,
+    title: &str,
+    buckets: &[(&str, u64)],
+    fg: Color,
+    theme: &GraphTheme,
+) {
+    let bars: Vec<Bar> = buckets
+        .iter()
+        .map(|(label, count)| Bar::default().label(*label).value(*count))
+        .collect();
+    render_bars(f, area, title, bars, fg, theme);
+}
+
+fn render_bars(
+    f: &mut Frame,
+    area: Rect,
+    title: &str,
+    bars: Vec<Bar>,
+    fg: Color,
+    theme: &GraphTheme,
+) {
+    // Narrow the bars rather than dropping bands when the column is tight.
+    let slot = area.width.saturating_sub(2) / bars.len().max(1) as u16;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title(title);
+    let chart = BarChart::new(bars)
+        .block(block)
+        .bar_width(slot.saturating_sub(1).clamp(1, 7))
+        .bar_gap(1)
+        .bar_style(Style::default().fg(fg))
+        .value_style(Style::default().fg(theme.panel_bg).bg(fg))
+        .style(Style::default().bg(theme.panel_bg));
+    f.render_widget(chart, area);
+}
+
+fn render_receiver(
+    f: &mut Frame,
+    area: Rect,
+    snapshot: &PerformanceSnapshot,
+    receiver: &ReceiverStats,
+    theme: &GraphTheme,
+) {
+    let breakdown = receiver.df_breakdown();
+    let widths: &[Constraint] = if breakdown.is_some() {
+        &[
+            Constraint::Percentage(40),
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+        ]
+    } else {
+        &[Constraint::Percentage(55), Constraint::Percentage(45)]
+    };
+    let halves = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints(widths)
+        .split(area);
+    let db = |value: Option<f64>| value.map_or_else(|| "--".to_string(), |v| format!("{v:.1}"));
+    let strong = receiver
+        .strong_signals
+        .map_or_else(|| "--".to_string(), |n| n.to_string());
+    let signal_title = format!(
+        "SIGNAL {} dBFS | NOISE {} | PEAK {} | STRONG {strong}",
+        db(receiver.signal),
+        db(receiver.noise),
+        db(receiver.peak_signal),
+    );
+    render_sparkline(
+        f,
+        halves[0],
+        &signal_title,
+        &snapshot.receiver_signal,
+        theme.warn,
+        theme.panel_bg,
+    );
+
+    let msps = receiver
+        .msps()
+        .map_or_else(|| "--".to_string(), |v| format!("{v:.2}"));
+    let cpu = receiver
+        .cpu_percent()
+        .map_or_else(|| "--".to_string(), |v| format!("{v:.0}%"));
+    render_sparkline(
+        f,
+        halves[1],
+        &format!("RECEIVER {msps} Msps | CPU {cpu}"),
+        &snapshot.receiver_cpu,
+        theme.accent,
+        theme.panel_bg,
+    );
+
+    if let Some(breakdown) = breakdown {
+        render_df_breakdown(f, halves[2], &breakdown, theme);
+    }
+}
+
+fn render_df_breakdown(
+    f: &mut Frame,
+    area: Rect,
+    breakdown: &[(&'static str, u64)],
+    theme: &GraphTheme,
+) {
+    let total = breakdown.iter().map(|(_, count)| count).sum::<u64>().max(1);
+    let bars: Vec<Bar> = breakdown
+        .iter()
+        .map(|(label, count)| {
+            let share = count * 100 / total;
+            Bar::default()
+                .label(*label)
+                .value(share)
+                .text_value(format!("{share}%"))
+        })
+        .collect();
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .title("MESSAGES BY DF");
+    let chart = BarChart::horizontal(bars)
+        .block(block)
+        .bar_width(1)
+        .bar_gap(0)
+        .max(100)
+        .bar_style(Style::default().fg(Color::Cyan))
+        .value_style(Style::default().fg(theme.accent))
+        .style(Style::default().bg(theme.panel_bg));
+    f.render_widget(chart, area);
+}
+
+pub fn render_sparkline(
+    f: &mut Frame,
+    area: Rect,
+    title: &str,
+    data: &[u64],
+    fg: Color,
+    bg: Color,
+) {
     let (spark_data, spark_max) = sparkline_tail(data, area.width);
     let block = Block::default()
         .borders(Borders::ALL)
@@ -95,7 +759,23 @@ fn sparkline_tail(data: &[u64], width: u16) -> (Vec<u64>, u64) {
 
 #[cfg(test)]
 mod tests {
-    use super::sparkline_tail;
+    use super::{profile_ceiling_ft, series_bounds, sparkline_tail};
+
+    #[test]
+    fn profile_ceiling_clears_the_highest_aircraft() {
+        assert_eq!(profile_ceiling_ft(&[]), 10_000.0);
+        assert_eq!(profile_ceiling_ft(&[(5.0, 0.0), (8.0, 3_500.0)]), 10_000.0);
+        assert_eq!(profile_ceiling_ft(&[(5.0, 37_000.0)]), 40_000.0);
+        assert_eq!(profile_ceiling_ft(&[(5.0, 40_000.0)]), 50_000.0);
+    }
+
+    #[test]
+    fn series_bounds_start_at_zero_unless_negative() {
+        assert_eq!(series_bounds(&[]), [0.0, 1.0]);
+        assert_eq!(series_bounds(&[(-10.0, 40.0), (0.0, 90.0)]), [0.0, 99.0]);
+        assert_eq!(series_bounds(&[(-10.0, -22.4), (0.0, -8.6)]), [-23.0, -8.0]);
+        assert_eq!(series_bounds(&[(0.0, 0.0)]), [0.0, 1.0]);
+    }
 
     #[test]
     fn sparkline_tail_pads_and_limits_width() {
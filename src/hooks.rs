@@ -0,0 +1,330 @@
+//! Alert hooks: webhook, MQTT and command.
+
+use std::collections::HashMap;
+use std::io::Write;
+use std::process::{Child, Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::DateTime;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tracing::{debug, warn};
+
+use crate::mqtt::{check_publish_topic, MqttPublisher, MqttTarget};
+use crate::net;
+use crate::notifier::{AlertEvent, AlertKinds};
+use crate::tasks::{self, Tasks};
+
+pub const DEFAULT_PAYLOAD: &str = r#"{"event":"{event}","message":"{message}","hex":"{hex}","callsign":"{callsign}","reg":"{reg}","squawk":"{squawk}","distance_mi":{distance_mi},"alt_ft":{alt_ft},"time":"{time}"}"#;
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug)]
+pub struct HookSettings {
+    pub url: String,
+    pub command: String,
+    pub mqtt: String,
+    pub payload: String,
+    pub events: AlertKinds,
+    pub cooldown: Duration,
+    pub insecure: bool,
+}
+
+impl HookSettings {
+    pub fn is_enabled(&self) -> bool {
+        !self.url.trim().is_empty()
+            || !self.command.trim().is_empty()
+            || !self.mqtt.trim().is_empty()
+    }
+}
+
+pub fn spawn_alert_hooks(tasks: &Tasks, settings: HookSettings) -> UnboundedSender<AlertEvent> {
+    let (tx, rx) = unbounded_channel();
+    let publisher = if settings.mqtt.trim().is_empty() {
+        None
+    } else {
+        match MqttTarget::parse(&settings.mqtt).and_then(|target| {
+            check_publish_topic(&target)?;
+            Ok(target)
+        }) {
+            Ok(target) => Some(MqttPublisher::spawn(tasks, target)),
+            Err(err) => {
+                warn!("alert mqtt disabled: {err}");
+                None
+            }
+        }
+    };
+    tasks.spawn("alert-hooks", run_hooks(settings, publisher, rx));
+    tx
+}
+
+async fn run_hooks(
+    settings: HookSettings,
+    publisher: Option<MqttPublisher>,
+    mut rx: UnboundedReceiver<AlertEvent>,
+) {
+    let client = if settings.url.trim().is_empty() {
+        None
+    } else {
+        match net::build_async_client(settings.insecure, WEBHOOK_TIMEOUT) {
+            Ok(client) => Some(client),
+            Err(err) => {
+                warn!("alert webhook disabled: {err}");
+                None
+            }
+        }
+    };
+    let mut limiter = RateLimiter::new(settings.cooldown);
+
+    while let Some(event) = rx.recv().await {
+        if !settings.events.enabled(event.kind) || !limiter.allow(&event, Instant::now()) {
+            continue;
+        }
+        let payload = render_payload(&settings.payload, &event);
+        if let Some(client) = &client {
+            let sent = client
+                .post(settings.url.trim())
+                .header("Content-Type", "application/json")
+                .body(payload.clone())
+                .send()
+                .await
+                .and_then(|resp| resp.error_for_status());
+            match sent {
+                Ok(_) => debug!("alert webhook sent: {}", event.message),
+                // The URL may carry the webhook's token.
+                Err(err) => warn!("alert webhook failed: {}", err.without_url()),
+            }
+        }
+        if let Some(publisher) = &publisher {
+            match publisher.publish(payload.clone()) {
+                Ok(()) => debug!("alert mqtt queued: {}", event.message),
+                Err(err) => warn!("alert mqtt failed: {err}"),
+            }
+        }
+        if !settings.command.trim().is_empty() {
+            let command = settings.command.clone();
+            let ran = tasks::blocking(move || run_command(&command, &event, &payload)).await;
+            if let Some(Err(err)) = ran {
+                warn!("alert command failed: {err}");
+            }
+        }
+    }
+}
+
+struct RateLimiter {
+    cooldown: Duration,
+    sent: HashMap<String, Instant>,
+}
+
+impl RateLimiter {
+    fn new(cooldown: Duration) -> Self {
+        Self {
+            cooldown,
+            sent: HashMap::new(),
+        }
+    }
+
+    fn allow(&mut self, event: &AlertEvent, now: Instant) -> bool {
+        let cooldown = self.cooldown;
+        self.sent
+            .retain(|_, last| now.duration_since(*last) < cooldown);
+        let aircraft = if event.hex.is_empty() {
+            &event.callsign
+        } else {
+            &event.hex
+        };
+        let key = format!("{}:{}", event.kind.name(), aircraft.to_ascii_lowercase());
+        if self.sent.contains_key(&key) {
+            return false;
+        }
+        self.sent.insert(key, now);
+        true
+    }
+}
+
+/// Fill the `{placeholder}`s of `template` (or `DEFAULT_PAYLOAD` when it is
+/// empty). Text is JSON-escaped; missing numbers become `null`.
+pub fn render_payload(template: &str, event: &AlertEvent) -> String {
+    let template = if template.trim().is_empty() {
+        DEFAULT_PAYLOAD
+    } else {
+        template
+    };
+    let number = |value: Option<String>| value.unwrap_or_else(|| "null".to_string());
+    let fields = [
+        ("event", json_escape(event.kind.name())),
+        ("message", json_escape(&event.message)),
+        ("hex", json_escape(&event.hex)),
+        ("callsign", json_escape(&event.callsign)),
+        ("reg", json_escape(&event.reg)),
+        ("squawk", json_escape(&event.squawk)),
+        (
+            "distance_mi",
+            number(event.distance_mi.map(|mi| format!("{mi:.2}"))),
+        ),
+        ("alt_ft", number(event.alt_ft.map(|ft| ft.to_string()))),
+        ("at", event.at.to_string()),
+        ("time", json_escape(&event_time(event))),
+    ];
+    // One pass over the template: a value containing `{hex}` or the like is
+    // copied as it is, never expanded again.
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let value = rest.find('}').and_then(|close| {
+            let (_, value) = fields.iter().find(|(name, _)| *name == &rest[1..close])?;
+            Some((value, close))
+        });
+        match value {
+            Some((value, close)) => {
+                out.push_str(value);
+                rest = &rest[close + 1..];
+            }
+            None => {
+                out.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn event_time(event: &AlertEvent) -> String {
+    DateTime::from_timestamp(event.at, 0)
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_default()
+}
+
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Run `command` through the shell with the alert in `ADSB_*` variables and
+/// the payload on stdin. Aircraft data never ends up in the command line.
+fn run_command(command: &str, event: &AlertEvent, payload: &str) -> std::io::Result<()> {
+    let mut child = shell(command)
+        .env("ADSB_EVENT", event.kind.name())
+        .env("ADSB_MESSAGE", &event.message)
+        .env("ADSB_HEX", &event.hex)
+        .env("ADSB_CALLSIGN", &event.callsign)
+        .env("ADSB_REG", &event.reg)
+        .env("ADSB_SQUAWK", &event.squawk)
+        .env(
+            "ADSB_DISTANCE_MI",
+            event
+                .distance_mi
+                .map(|mi| format!("{mi:.2}"))
+                .unwrap_or_default(),
+        )
+        .env(
+            "ADSB_ALT_FT",
+            event.alt_ft.map(|ft| ft.to_string()).unwrap_or_default(),
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(payload.as_bytes());
+    }
+    wait_with_timeout(&mut child, COMMAND_TIMEOUT)
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
+
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> std::io::Result<()> {
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return if status.success() {
+                Ok(())
+            } else {
+                Err(std::io::Error::other(format!("exited with {status}")))
+            };
+        }
+        if started.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(std::io::Error::other(format!(
+                "killed after {}s",
+                timeout.as_secs()
+            )));
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{render_payload, RateLimiter};
+    use crate::notifier::{AlertEvent, AlertKind};
+    use std::time::{Duration, Instant};
+
+    fn event(kind: AlertKind, hex: &str) -> AlertEvent {
+        AlertEvent {
+            kind,
+            message: format!("WATCH \"Lifeguard\" {hex}"),
+            hex: hex.to_string(),
+            callsign: "EXS12".to_string(),
+            reg: String::new(),
+            squawk: "7700".to_string(),
+            distance_mi: Some(4.256),
+            alt_ft: None,
+            at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn payload_escapes_text_and_nulls_missing_numbers() {
+        let payload = render_payload("", &event(AlertKind::Watch, "40621d"));
+        let json: serde_json::Value = serde_json::from_str(&payload).unwrap();
+        assert_eq!(json["event"], "watch");
+        assert_eq!(json["message"], "WATCH \"Lifeguard\" 40621d");
+        assert_eq!(json["distance_mi"], 4.26);
+        assert!(json["alt_ft"].is_null());
+        assert_eq!(json["time"], "2023-11-14T22:13:20+00:00");
+
+        let custom = render_payload(
+            r#"{"text":"{callsign} squawking {squawk}"}"#,
+            &event(AlertKind::Emergency, "40621d"),
+        );
+        assert_eq!(custom, r#"{"text":"EXS12 squawking 7700"}"#);
+
+        let mut spoofed = event(AlertKind::Watch, "40621d");
+        spoofed.callsign = "{squawk}{".to_string();
+        let payload = render_payload("{callsign} {reg}{unknown} {squawk}", &spoofed);
+        assert_eq!(payload, "{squawk}{ {unknown} 7700");
+    }
+
+    #[test]
+    fn limiter_allows_one_alert_per_aircraft_and_type_per_cooldown() {
+        let mut limiter = RateLimiter::new(Duration::from_secs(600));
+        let start = Instant::now();
+        assert!(limiter.allow(&event(AlertKind::Watch, "40621d"), start));
+        assert!(!limiter.allow(&event(AlertKind::Watch, "40621D"), start));
+        assert!(limiter.allow(&event(AlertKind::Emergency, "40621d"), start));
+        assert!(limiter.allow(&event(AlertKind::Watch, "a1b2c3"), start));
+        let later = start + Duration::from_secs(601);
+        assert!(limiter.allow(&event(AlertKind::Watch, "40621d"), later));
+    }
+}
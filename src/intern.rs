@@ -0,0 +1,132 @@
+//! Normalized aircraft keys, shared as `Arc<str>`.
+
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use crate::model::Aircraft;
+
+/// Raw strings are only pruned once the cache holds this many times the
+/// current snapshot (and at least `PRUNE_MIN` entries).
+const PRUNE_FACTOR: usize = 4;
+const PRUNE_MIN: usize = 1024;
+
+/// Identity of an aircraft across snapshots: ICAO hex when present,
+/// otherwise the callsign.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AircraftKey {
+    Hex(Arc<str>),
+    Flight(Arc<str>),
+}
+
+#[derive(Debug, Default)]
+pub struct KeyInterner {
+    hex: HashMap<Box<str>, Arc<str>>,
+    callsign: HashMap<Box<str>, Arc<str>>,
+}
+
+impl KeyInterner {
+    pub fn update(&mut self, aircraft: &[Aircraft]) {
+        for ac in aircraft {
+            if let Some(hex) = ac.hex.as_deref() {
+                intern(&mut self.hex, hex, normalize_hex);
+            }
+            if let Some(callsign) = ac.flight.as_deref() {
+                intern(&mut self.callsign, callsign, normalize_callsign);
+            }
+        }
+        let limit = (aircraft.len() * PRUNE_FACTOR).max(PRUNE_MIN);
+        if self.hex.len() + self.callsign.len() > limit {
+            let hexes: HashSet<&str> = aircraft.iter().filter_map(|ac| ac.hex.as_deref()).collect();
+            let callsigns: HashSet<&str> = aircraft
+                .iter()
+                .filter_map(|ac| ac.flight.as_deref())
+                .collect();
+            self.hex.retain(|raw, _| hexes.contains(&**raw));
+            self.callsign.retain(|raw, _| callsigns.contains(&**raw));
+        }
+    }
+
+    pub fn hex<'a>(&'a self, raw: &str) -> Cow<'a, str> {
+        match self.hex.get(raw) {
+            Some(key) => Cow::Borrowed(key),
+            None => Cow::Owned(normalize_hex(raw)),
+        }
+    }
+
+    pub fn callsign<'a>(&'a self, raw: &str) -> Cow<'a, str> {
+        match self.callsign.get(raw) {
+            Some(key) => Cow::Borrowed(key),
+            None => Cow::Owned(normalize_callsign(raw)),
+        }
+    }
+
+    pub fn hex_key(&self, raw: &str) -> Arc<str> {
+        shared(&self.hex, raw, normalize_hex)
+    }
+
+    pub fn key(&self, ac: &Aircraft) -> Option<AircraftKey> {
+        if let Some(hex) = ac.hex.as_deref() {
+            return Some(AircraftKey::Hex(self.hex_key(hex)));
+        }
+        ac.flight.as_deref().map(|callsign| {
+            AircraftKey::Flight(shared(&self.callsign, callsign, normalize_callsign))
+        })
+    }
+}
+
+fn intern(map: &mut HashMap<Box<str>, Arc<str>>, raw: &str, normalize: fn(&str) -> String) {
+    if !map.contains_key(raw) {
+        map.insert(raw.into(), normalize(raw).into());
+    }
+}
+
+fn shared(map: &HashMap<Box<str>, Arc<str>>, raw: &str, normalize: fn(&str) -> String) -> Arc<str> {
+    map.get(raw)
+        .cloned()
+        .unwrap_or_else(|| normalize(raw).into())
+}
+
+pub fn normalize_hex(value: &str) -> String {
+    value.trim().to_ascii_lowercase()
+}
+
+pub fn normalize_callsign(value: &str) -> String {
+    value.trim().to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AircraftKey, KeyInterner};
+    use crate::model::Aircraft;
+    use std::borrow::Cow;
+    use std::sync::Arc;
+
+    fn aircraft(hex: Option<&str>, flight: Option<&str>) -> Aircraft {
+        Aircraft {
+            hex: hex.map(str::to_string),
+            flight: flight.map(str::to_string),
+            ..Aircraft::default()
+        }
+    }
+
+    #[test]
+    fn interned_keys_are_borrowed_and_shared() {
+        let mut keys = KeyInterner::default();
+        keys.update(&[aircraft(Some("ABC123 "), Some("UAL1  "))]);
+
+        assert!(matches!(keys.hex("ABC123 "), Cow::Borrowed("abc123")));
+        assert!(matches!(keys.callsign("UAL1  "), Cow::Borrowed("ual1")));
+        assert!(matches!(keys.hex("DEF456"), Cow::Owned(ref key) if key == "def456"));
+        assert!(Arc::ptr_eq(
+            &keys.hex_key("ABC123 "),
+            &keys.hex_key("ABC123 ")
+        ));
+
+        assert_eq!(
+            keys.key(&aircraft(None, Some("UAL1  "))),
+            Some(AircraftKey::Flight("ual1".into()))
+        );
+        assert_eq!(keys.key(&aircraft(None, None)), None);
+    }
+}
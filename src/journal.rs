@@ -0,0 +1,531 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Lines, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+use crate::model::ApiResponse;
+use crate::tasks::{self, Tasks};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub at_ms: u64,
+    pub data: ApiResponse,
+}
+
+/// Snapshots written to one zstd frame before it is closed. A journal cut
+/// short loses at most the unfinished frame's tail.
+const ZSTD_FRAME_ENTRIES: usize = 60;
+const ZSTD_LEVEL: i32 = 3;
+
+fn is_zstd(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zst"))
+}
+
+/// Appends snapshots to a journal: JSON lines, zstd-compressed when the path
+/// ends in `.zst`. Each snapshot is flushed as it is written.
+pub enum JournalWriter {
+    Plain(File),
+    Zstd {
+        encoder: Option<zstd::Encoder<'static, File>>,
+        in_frame: usize,
+    },
+}
+
+impl JournalWriter {
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create journal dir: {}", parent.display()))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open journal: {}", path.display()))?;
+        if !is_zstd(path) {
+            return Ok(Self::Plain(file));
+        }
+        // zstd frames can be concatenated, so appending starts a new one.
+        Ok(Self::Zstd {
+            encoder: Some(zstd::Encoder::new(file, ZSTD_LEVEL)?),
+            in_frame: 0,
+        })
+    }
+
+    pub fn append(&mut self, entry: &JournalEntry) -> Result<()> {
+        match self {
+            Self::Plain(file) => append_entry(file, entry),
+            Self::Zstd { encoder, in_frame } => {
+                let Some(writer) = encoder.as_mut() else {
+                    return Ok(());
+                };
+                append_entry(writer, entry)?;
+                *in_frame += 1;
+                if *in_frame >= ZSTD_FRAME_ENTRIES {
+                    if let Some(done) = encoder.take() {
+                        *encoder = Some(zstd::Encoder::new(done.finish()?, ZSTD_LEVEL)?);
+                    }
+                    *in_frame = 0;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    pub fn finish(mut self) -> Result<()> {
+        if let Self::Zstd { encoder, .. } = &mut self {
+            if let Some(encoder) = encoder.take() {
+                encoder.finish()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn append_entry<W: Write>(writer: &mut W, entry: &JournalEntry) -> Result<()> {
+    let line = serde_json::to_string(entry)?;
+    writeln!(writer, "{line}")?;
+    writer.flush()?;
+    Ok(())
+}
+
+type JournalLines = Lines<Box<dyn BufRead + Send>>;
+
+fn open_lines(path: &Path) -> Result<JournalLines> {
+    let file =
+        File::open(path).with_context(|| format!("Failed to open journal: {}", path.display()))?;
+    let reader: Box<dyn BufRead + Send> = if is_zstd(path) {
+        Box::new(BufReader::new(zstd::Decoder::new(file)?))
+    } else {
+        Box::new(BufReader::new(file))
+    };
+    Ok(reader.lines())
+}
+
+/// Reads the next entry, skipping lines that do not parse. A read error
+/// (such as the unfinished last frame of an interrupted recording) ends the
+/// journal.
+fn next_entry(lines: &mut JournalLines, line_no: &mut usize) -> Option<JournalEntry> {
+    for line in lines.by_ref() {
+        *line_no += 1;
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                warn!("journal read stopped at line {line_no}: {err}");
+                return None;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<JournalEntry>(&line) {
+            Ok(entry) => return Some(entry),
+            Err(err) => warn!("journal line {line_no} skipped: {err}"),
+        }
+    }
+    None
+}
+
+/// A recorded journal, decoded as it is played. Only the snapshot times are
+/// kept in memory; seeking back reopens the file and reads forward.
+pub struct Journal {
+    path: PathBuf,
+    stamps: Vec<u64>,
+    lines: JournalLines,
+    line_no: usize,
+    next: usize,
+}
+
+impl Journal {
+    pub fn open(path: &Path) -> Result<Self> {
+        let mut lines = open_lines(path)?;
+        let mut line_no = 0;
+        let mut stamps = Vec::new();
+        while let Some(entry) = next_entry(&mut lines, &mut line_no) {
+            stamps.push(entry.at_ms);
+        }
+        Ok(Self {
+            path: path.to_path_buf(),
+            stamps,
+            lines: open_lines(path)?,
+            line_no: 0,
+            next: 0,
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.stamps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stamps.is_empty()
+    }
+
+    fn read(&mut self, index: usize) -> Result<Option<JournalEntry>> {
+        if index < self.next {
+            self.lines = open_lines(&self.path)?;
+            self.line_no = 0;
+            self.next = 0;
+        }
+        while self.next < index {
+            if next_entry(&mut self.lines, &mut self.line_no).is_none() {
+                return Ok(None);
+            }
+            self.next += 1;
+        }
+        let entry = next_entry(&mut self.lines, &mut self.line_no);
+        if entry.is_some() {
+            self.next += 1;
+        }
+        Ok(entry)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ReplayControl {
+    TogglePause,
+    Faster,
+    Slower,
+    Seek(i64),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReplayStatus {
+    pub position: usize,
+    pub total: usize,
+    pub at_ms: u64,
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub speed: f64,
+    pub paused: bool,
+}
+
+impl ReplayStatus {
+    pub fn finished(&self) -> bool {
+        self.position >= self.total
+    }
+
+    pub fn progress(&self) -> f64 {
+        let span = self.end_ms.saturating_sub(self.start_ms);
+        if span == 0 {
+            return if self.finished() { 1.0 } else { 0.0 };
+        }
+        (self.at_ms.saturating_sub(self.start_ms) as f64 / span as f64).clamp(0.0, 1.0)
+    }
+}
+
+const MIN_SPEED: f64 = 0.25;
+const MAX_SPEED: f64 = 64.0;
+
+struct Player {
+    journal: Journal,
+    next: usize,
+    speed: f64,
+    paused: bool,
+}
+
+impl Player {
+    fn new(journal: Journal, speed: f64) -> Self {
+        let speed = if speed.is_finite() && speed > 0.0 {
+            speed
+        } else {
+            1.0
+        };
+        Self {
+            journal,
+            next: 0,
+            speed,
+            paused: false,
+        }
+    }
+
+    fn apply(&mut self, control: ReplayControl) {
+        match control {
+            ReplayControl::TogglePause => self.paused = !self.paused,
+            ReplayControl::Faster => self.speed = (self.speed * 2.0).min(MAX_SPEED),
+            ReplayControl::Slower => self.speed = (self.speed / 2.0).max(MIN_SPEED),
+            ReplayControl::Seek(delta_ms) => {
+                let current = self.status().at_ms;
+                let target = current.saturating_add_signed(delta_ms);
+                // Re-send the snapshot at or before the target so the view
+                // jumps there at once.
+                let after = self.journal.stamps.partition_point(|at| *at <= target);
+                self.next = after.saturating_sub(1);
+            }
+        }
+    }
+
+    fn status(&self) -> ReplayStatus {
+        let stamps = &self.journal.stamps;
+        let at = |idx: usize| stamps.get(idx).copied().unwrap_or(0);
+        ReplayStatus {
+            position: self.next,
+            total: stamps.len(),
+            at_ms: at(self.next.saturating_sub(1)),
+            start_ms: at(0),
+            end_ms: at(stamps.len().saturating_sub(1)),
+            speed: self.speed,
+            paused: self.paused,
+        }
+    }
+
+    fn advance(&mut self) -> Option<(ApiResponse, Duration)> {
+        let at = *self.journal.stamps.get(self.next)?;
+        let entry = match self.journal.read(self.next) {
+            Ok(Some(entry)) => entry,
+            Ok(None) => {
+                // The file changed under us; treat it as the end.
+                self.next = self.journal.len();
+                return None;
+            }
+            Err(err) => {
+                warn!("journal read failed: {err:#}");
+                self.next = self.journal.len();
+                return None;
+            }
+        };
+        let wait = self
+            .journal
+            .stamps
+            .get(self.next + 1)
+            .map_or(Duration::ZERO, |next| replay_delay(at, *next, self.speed));
+        self.next += 1;
+        Some((entry.data, wait))
+    }
+}
+
+/// Play `entries` into `tx`, following `control` and publishing progress on
+/// `status`. At the end of the journal playback holds on the last snapshot,
+/// so seeking back still works.
+pub fn spawn_replay(
+    tasks: &Tasks,
+    journal: Journal,
+    speed: f64,
+    mut control: UnboundedReceiver<ReplayControl>,
+    status: watch::Sender<ReplayStatus>,
+    tx: UnboundedSender<Result<ApiResponse, String>>,
+) {
+    tasks.spawn("replay", async move {
+        info!("replay started: {} snapshots", journal.len());
+        let mut player = Player::new(journal, speed);
+        let mut due = Instant::now();
+        loop {
+            let _ = status.send(player.status());
+            let idle = player.paused || player.status().finished();
+            let wait = due.saturating_duration_since(Instant::now());
+            if idle || !wait.is_zero() {
+                let received = if idle {
+                    control.recv().await
+                } else {
+                    match tokio::time::timeout(wait, control.recv()).await {
+                        Ok(received) => received,
+                        Err(_) => continue,
+                    }
+                };
+                let Some(command) = received else {
+                    debug!("replay controls dropped, exiting replay");
+                    return;
+                };
+                player.apply(command);
+                if matches!(command, ReplayControl::TogglePause | ReplayControl::Seek(_)) {
+                    due = Instant::now();
+                }
+                continue;
+            }
+            let Some((returned, advanced)) = tasks::blocking(move || {
+                let advanced = player.advance();
+                (player, advanced)
+            })
+            .await
+            else {
+                return;
+            };
+            player = returned;
+            let Some((data, wait)) = advanced else {
+                continue;
+            };
+            if tx.send(Ok(data)).is_err() {
+                debug!("receiver dropped, exiting replay");
+                return;
+            }
+            due = Instant::now() + wait;
+        }
+    });
+}
+
+fn replay_delay(prev_ms: u64, next_ms: u64, speed: f64) -> Duration {
+    let speed = if speed.is_finite() && speed > 0.0 {
+        speed
+    } else {
+        1.0
+    };
+    let gap = next_ms.saturating_sub(prev_ms) as f64 / speed;
+    Duration::from_millis(gap.min(60_000.0) as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        append_entry, replay_delay, Journal, JournalEntry, JournalWriter, Player, ReplayControl,
+        ZSTD_FRAME_ENTRIES,
+    };
+    use crate::model::{Aircraft, Altitude, ApiResponse};
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    fn temp_journal(name: &str) -> PathBuf {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        std::env::temp_dir().join(format!("adsb-tui-journal-{suffix}-{name}"))
+    }
+
+    fn snapshot(at_ms: u64) -> JournalEntry {
+        JournalEntry {
+            at_ms,
+            data: ApiResponse {
+                now: Some(at_ms as i64 / 10_000),
+                ..ApiResponse::default()
+            },
+        }
+    }
+
+    fn entries(journal: &mut Journal) -> Vec<JournalEntry> {
+        (0..journal.len())
+            .map_while(|i| journal.read(i).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn journal_roundtrip_skips_bad_lines() {
+        let path = temp_journal("plain.jsonl");
+        let mut buf = Vec::new();
+        let entry = JournalEntry {
+            at_ms: 1_000,
+            data: ApiResponse {
+                now: Some(1),
+                messages: Some(42),
+                aircraft: vec![Aircraft {
+                    hex: Some("abc123".to_string()),
+                    alt_baro: Some(Altitude::Feet(12000)),
+                    ..Aircraft::default()
+                }],
+                ..ApiResponse::default()
+            },
+        };
+        append_entry(&mut buf, &entry).unwrap();
+        buf.extend_from_slice(b"not json\n");
+        append_entry(
+            &mut buf,
+            &JournalEntry {
+                at_ms: 3_000,
+                ..entry
+            },
+        )
+        .unwrap();
+        fs::write(&path, buf).unwrap();
+
+        let mut journal = Journal::open(&path).unwrap();
+        assert_eq!(journal.len(), 2);
+        let entries = entries(&mut journal);
+        assert_eq!(entries[0].data.messages, Some(42));
+        assert_eq!(
+            entries[0].data.aircraft[0].alt_baro,
+            Some(Altitude::Feet(12000))
+        );
+        assert_eq!(entries[1].at_ms, 3_000);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn zstd_journals_span_frames_and_survive_a_cut_off_tail() {
+        let path = temp_journal("frames.jsonl.zst");
+        let count = ZSTD_FRAME_ENTRIES as u64 + 5;
+        let mut writer = JournalWriter::open(&path).unwrap();
+        for i in 0..count {
+            writer.append(&snapshot(i * 1_000)).unwrap();
+        }
+        writer.finish().unwrap();
+        // A second recording session appends frames of its own.
+        let mut writer = JournalWriter::open(&path).unwrap();
+        writer.append(&snapshot(count * 1_000)).unwrap();
+        writer.finish().unwrap();
+        assert_ne!(&fs::read(&path).unwrap()[..1], b"{");
+
+        let mut journal = Journal::open(&path).unwrap();
+        assert_eq!(journal.len() as u64, count + 1);
+        assert_eq!(
+            journal.read(count as usize).unwrap().unwrap().at_ms,
+            count * 1_000
+        );
+        // Going back reopens the file and reads forward again.
+        assert_eq!(journal.read(3).unwrap().unwrap().at_ms, 3_000);
+
+        // An interrupted recording leaves an unfinished frame; the flushed
+        // snapshots before the cut still play.
+        let mut writer = JournalWriter::open(&path).unwrap();
+        writer.append(&snapshot(999_000)).unwrap();
+        drop(writer);
+        let journal = Journal::open(&path).unwrap();
+        assert_eq!(journal.len() as u64, count + 2);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn replay_delay_scales_and_clamps() {
+        assert_eq!(
+            replay_delay(1_000, 3_000, 1.0),
+            Duration::from_millis(2_000)
+        );
+        assert_eq!(replay_delay(1_000, 3_000, 4.0), Duration::from_millis(500));
+        assert_eq!(replay_delay(3_000, 1_000, 1.0), Duration::ZERO);
+        assert_eq!(replay_delay(0, 10_000, 0.0), Duration::from_millis(10_000));
+        assert_eq!(replay_delay(0, 600_000, 1.0), Duration::from_millis(60_000));
+    }
+
+    #[test]
+    fn player_pauses_changes_speed_and_seeks() {
+        let path = temp_journal("player.jsonl");
+        let mut writer = JournalWriter::open(&path).unwrap();
+        for i in 0..5 {
+            writer.append(&snapshot(i * 10_000)).unwrap();
+        }
+        writer.finish().unwrap();
+        let mut player = Player::new(Journal::open(&path).unwrap(), 1.0);
+        let (data, wait) = player.advance().unwrap();
+        assert_eq!(data.now, Some(0));
+        assert_eq!(wait, Duration::from_secs(10));
+
+        player.apply(ReplayControl::Faster);
+        player.apply(ReplayControl::Faster);
+        let (_, wait) = player.advance().unwrap();
+        assert_eq!(wait, Duration::from_millis(2_500));
+        assert_eq!(player.status().at_ms, 10_000);
+
+        player.apply(ReplayControl::Seek(25_000));
+        let (data, _) = player.advance().unwrap();
+        assert_eq!(data.now, Some(3));
+        player.apply(ReplayControl::Seek(-60_000));
+        assert_eq!(player.status().position, 0);
+
+        player.apply(ReplayControl::TogglePause);
+        assert!(player.status().paused);
+        for _ in 0..8 {
+            player.apply(ReplayControl::Slower);
+        }
+        assert_eq!(player.status().speed, 0.25);
+        while player.advance().is_some() {}
+        let status = player.status();
+        assert!(status.finished());
+        assert_eq!(status.progress(), 1.0);
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,389 @@
+use std::collections::HashMap;
+
+use crate::config;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    Sort,
+    Filter,
+    ClearFilter,
+    Favorite,
+    Theme,
+    Layout,
+    Radar,
+    Performance,
+    Watch,
+    Profile,
+    Labels,
+    Vectors,
+    Follow,
+    Site,
+    ZoomIn,
+    ZoomOut,
+    Columns,
+    Config,
+    WatchlistAdd,
+    Lookup,
+    Watchlist,
+    History,
+    Stats,
+    Zones,
+    Help,
+    Export,
+    ExportCsv,
+    ExportJson,
+    ReloadRoles,
+    Events,
+    Notifications,
+    Dnd,
+    PinTrail,
+    ClearTrails,
+    ReplayPause,
+    ReplaySlower,
+    ReplayFaster,
+    Detail,
+    Profiler,
+}
+
+impl Action {
+    pub const ALL: [Action; 40] = [
+        Action::Quit,
+        Action::Sort,
+        Action::Filter,
+        Action::ClearFilter,
+        Action::Favorite,
+        Action::Theme,
+        Action::Layout,
+        Action::Radar,
+        Action::Performance,
+        Action::Watch,
+        Action::Profile,
+        Action::Labels,
+        Action::Vectors,
+        Action::Follow,
+        Action::Site,
+        Action::ZoomIn,
+        Action::ZoomOut,
+        Action::Columns,
+        Action::Config,
+        Action::WatchlistAdd,
+        Action::Lookup,
+        Action::Watchlist,
+        Action::History,
+        Action::Stats,
+        Action::Zones,
+        Action::Help,
+        Action::Export,
+        Action::ExportCsv,
+        Action::ExportJson,
+        Action::ReloadRoles,
+        Action::Events,
+        Action::Notifications,
+        Action::Dnd,
+        Action::PinTrail,
+        Action::ClearTrails,
+        Action::ReplayPause,
+        Action::ReplaySlower,
+        Action::ReplayFaster,
+        Action::Detail,
+        Action::Profiler,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Sort => "sort",
+            Action::Filter => "filter",
+            Action::ClearFilter => "clear_filter",
+            Action::Favorite => "favorite",
+            Action::Theme => "theme",
+            Action::Layout => "layout",
+            Action::Radar => "radar",
+            Action::Performance => "performance",
+            Action::Watch => "watch",
+            Action::Profile => "profile",
+            Action::Labels => "labels",
+            Action::Vectors => "vectors",
+            Action::Follow => "follow",
+            Action::Site => "site",
+            Action::ZoomIn => "zoom_in",
+            Action::ZoomOut => "zoom_out",
+            Action::Columns => "columns",
+            Action::Config => "config",
+            Action::WatchlistAdd => "watchlist_add",
+            Action::Lookup => "lookup",
+            Action::Watchlist => "watchlist",
+            Action::History => "history",
+            Action::Stats => "stats",
+            Action::Zones => "zones",
+            Action::Help => "help",
+            Action::Export => "export",
+            Action::ExportCsv => "export_csv",
+            Action::ExportJson => "export_json",
+            Action::ReloadRoles => "reload_roles",
+            Action::Events => "events",
+            Action::Notifications => "notifications",
+            Action::Dnd => "dnd",
+            Action::PinTrail => "pin_trail",
+            Action::ClearTrails => "clear_trails",
+            Action::ReplayPause => "replay_pause",
+            Action::ReplaySlower => "replay_slower",
+            Action::ReplayFaster => "replay_faster",
+            Action::Detail => "detail",
+            Action::Profiler => "profiler",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|action| action.name() == name)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    Char(char),
+    F(u8),
+    Enter,
+}
+
+impl std::fmt::Display for Key {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Key::Char(' ') => f.write_str("Space"),
+            Key::Char(ch) => write!(f, "{ch}"),
+            Key::F(n) => write!(f, "F{n}"),
+            Key::Enter => f.write_str("Enter"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyMap {
+    bindings: Vec<(Action, Vec<Key>)>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        Self::from_overrides(&HashMap::new()).expect("default key bindings are valid")
+    }
+}
+
+impl KeyMap {
+    /// Builds the map from the `keys.*` defaults in `config_specs()` plus the
+    /// `[keys]` entries of the config file. Fails with every binding that does
+    /// not parse and every key bound to two actions.
+    pub fn from_overrides(overrides: &HashMap<String, String>) -> Result<Self, Vec<String>> {
+        let mut map = Self {
+            bindings: Action::ALL.iter().map(|a| (*a, Vec::new())).collect(),
+        };
+        for spec in config::config_specs() {
+            if let Some(action) = spec.key.strip_prefix("keys.").and_then(Action::from_name) {
+                map.set(action, &spec.default_string())
+                    .expect("default key bindings parse");
+            }
+        }
+        let mut names: Vec<_> = overrides.keys().collect();
+        names.sort();
+        let mut errors: Vec<String> = names
+            .into_iter()
+            .filter_map(|name| {
+                let action = Action::from_name(name)?;
+                map.set(action, &overrides[name]).err()
+            })
+            .collect();
+        errors.extend(map.conflicts().into_iter().map(|(key, first, second)| {
+            format!(
+                "key `{key}` is bound to both keys.{} and keys.{}",
+                first.name(),
+                second.name()
+            )
+        }));
+        if errors.is_empty() {
+            Ok(map)
+        } else {
+            Err(errors)
+        }
+    }
+
+    pub fn set(&mut self, action: Action, binding: &str) -> Result<(), String> {
+        let parsed = parse_binding(binding)
+            .map_err(|err| format!("keys.{} = \"{binding}\": {err}", action.name()))?;
+        if let Some((_, keys)) = self.bindings.iter_mut().find(|(a, _)| *a == action) {
+            *keys = parsed;
+        }
+        Ok(())
+    }
+
+    pub fn action(&self, key: Key) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(_, keys)| keys.contains(&key))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[Key] {
+        self.bindings
+            .iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, keys)| keys.as_slice())
+            .unwrap_or_default()
+    }
+
+    pub fn primary(&self, action: Action) -> String {
+        self.keys(action)
+            .first()
+            .map(Key::to_string)
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    pub fn label(&self, action: Action) -> String {
+        let keys = self.keys(action);
+        if keys.is_empty() {
+            return "-".to_string();
+        }
+        keys.iter()
+            .map(Key::to_string)
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+
+    fn conflicts(&self) -> Vec<(Key, Action, Action)> {
+        let mut seen: HashMap<Key, Action> = HashMap::new();
+        let mut conflicts = Vec::new();
+        for (action, keys) in &self.bindings {
+            for key in keys {
+                match seen.get(key) {
+                    Some(first) => conflicts.push((*key, *first, *action)),
+                    None => {
+                        seen.insert(*key, *action);
+                    }
+                }
+            }
+        }
+        conflicts
+    }
+}
+
+/// Parses a space separated list of single characters and function keys
+/// (`F1` to `F12`); `space` and `enter` bind those keys.
+pub fn parse_binding(text: &str) -> Result<Vec<Key>, String> {
+    text.split_whitespace()
+        .map(|token| {
+            if token.eq_ignore_ascii_case("space") {
+                return Ok(Key::Char(' '));
+            }
+            if token.eq_ignore_ascii_case("enter") {
+                return Ok(Key::Enter);
+            }
+            let mut chars = token.chars();
+            match (chars.next(), chars.next()) {
+                (Some(ch), None) => return Ok(Key::Char(ch)),
+                (Some('F' | 'f'), Some(_)) => {}
+                _ => return Err(format!("`{token}` is not a single character")),
+            }
+            match token[1..].parse::<u8>() {
+                Ok(n @ 1..=12) => Ok(Key::F(n)),
+                _ => Err(format!(
+                    "`{token}` is not a single character or function key"
+                )),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_binding, Action, Key, KeyMap};
+    use std::collections::HashMap;
+
+    #[test]
+    fn defaults_match_builtin_keys() {
+        let map = KeyMap::default();
+        assert_eq!(map.action(Key::Char('q')), Some(Action::Quit));
+        assert_eq!(map.action(Key::Char('R')), Some(Action::Radar));
+        assert_eq!(map.action(Key::Char('r')), Some(Action::Radar));
+        assert_eq!(map.action(Key::Char('E')), Some(Action::ExportJson));
+        assert_eq!(map.label(Action::ZoomIn), "+ / =");
+        assert_eq!(map.action(Key::Char(' ')), Some(Action::ReplayPause));
+        assert_eq!(map.action(Key::Enter), Some(Action::Detail));
+        assert_eq!(map.action(Key::F(12)), Some(Action::Profiler));
+        assert!(map.conflicts().is_empty());
+        assert!(Action::ALL
+            .iter()
+            .all(|action| !map.keys(*action).is_empty()));
+    }
+
+    #[test]
+    fn overrides_replace_bindings() {
+        let overrides = HashMap::from([
+            ("quit".to_string(), "x".to_string()),
+            ("sort".to_string(), "o space".to_string()),
+            ("replay_pause".to_string(), "p".to_string()),
+            ("performance".to_string(), "P".to_string()),
+        ]);
+        let map = KeyMap::from_overrides(&overrides).unwrap();
+        assert_eq!(map.action(Key::Char('x')), Some(Action::Quit));
+        assert_eq!(map.action(Key::Char('q')), None);
+        assert_eq!(map.action(Key::Char(' ')), Some(Action::Sort));
+        assert_eq!(map.action(Key::Char('p')), Some(Action::ReplayPause));
+        assert_eq!(map.primary(Action::Sort), "o");
+    }
+
+    #[test]
+    fn bad_bindings_and_conflicts_are_errors() {
+        let overrides = HashMap::from([
+            ("quit".to_string(), "ctrl-q".to_string()),
+            ("theme".to_string(), "f".to_string()),
+        ]);
+        assert_eq!(
+            KeyMap::from_overrides(&overrides),
+            Err(vec![
+                "keys.quit = \"ctrl-q\": `ctrl-q` is not a single character".to_string(),
+                "key `f` is bound to both keys.favorite and keys.theme".to_string(),
+            ])
+        );
+    }
+
+    #[test]
+    fn binding_parse_rejects_words() {
+        assert_eq!(
+            parse_binding("a  B"),
+            Ok(vec![Key::Char('a'), Key::Char('B')])
+        );
+        assert!(parse_binding("ctrl-q").is_err());
+    }
+
+    #[test]
+    fn function_keys_bind_and_label() {
+        assert_eq!(
+            parse_binding("F2 f11 F"),
+            Ok(vec![Key::F(2), Key::F(11), Key::Char('F')])
+        );
+        assert_eq!(parse_binding("F12 enter"), Ok(vec![Key::F(12), Key::Enter]));
+        assert!(parse_binding("F13").is_err());
+        assert!(parse_binding("Fx").is_err());
+
+        let overrides = HashMap::from([("radar".to_string(), "F2 R".to_string())]);
+        let map = KeyMap::from_overrides(&overrides).unwrap();
+        assert_eq!(map.action(Key::F(2)), Some(Action::Radar));
+        assert_eq!(map.label(Action::Radar), "F2 / R");
+
+        let overrides = HashMap::from([("help".to_string(), "F12".to_string())]);
+        assert_eq!(
+            KeyMap::from_overrides(&overrides),
+            Err(vec![
+                "key `F12` is bound to both keys.help and keys.profiler".to_string()
+            ])
+        );
+        let overrides = HashMap::from([
+            ("detail".to_string(), "o".to_string()),
+            ("profiler".to_string(), "enter".to_string()),
+            ("sort".to_string(), "F12".to_string()),
+        ]);
+        let map = KeyMap::from_overrides(&overrides).unwrap();
+        assert_eq!(map.action(Key::Enter), Some(Action::Profiler));
+        assert_eq!(map.action(Key::Char('o')), Some(Action::Detail));
+        assert_eq!(map.action(Key::F(12)), Some(Action::Sort));
+    }
+}
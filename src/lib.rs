@@ -0,0 +1,49 @@
+mod aircraft_db;
+mod airports;
+mod app;
+mod beast;
+pub mod cli;
+pub mod commands;
+pub mod config;
+mod control;
+mod country;
+mod events;
+mod export;
+mod filter;
+mod geography;
+mod graph;
+mod hooks;
+mod intern;
+mod journal;
+mod keys;
+pub mod logging;
+mod lookup;
+mod merge;
+mod military;
+pub mod model;
+mod mqtt;
+mod net;
+mod notifier;
+mod photos;
+mod pool;
+mod profile;
+mod quiet;
+mod radar;
+mod reload;
+mod roles;
+mod routes;
+mod runtime;
+mod secrets;
+mod session;
+mod sightings;
+mod sort;
+mod storage;
+pub mod synthetic;
+mod tasks;
+mod traffic;
+pub mod ui;
+mod units;
+mod validate;
+mod watchlist;
+mod weather;
+mod zones;
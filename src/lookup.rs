@@ -1,11 +1,14 @@
-use std::sync::mpsc::{Receiver, Sender};
-use std::thread;
 use std::time::Duration;
 
 use crate::model::ApiResponse;
-use reqwest::blocking::Client;
+use crate::net::build_async_client;
+use crate::tasks::Tasks;
+use reqwest::Client;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tracing::{debug, error};
 
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(6);
+
 #[derive(Clone, Debug)]
 pub enum LookupKind {
     Hex(Vec<String>),
@@ -31,64 +34,107 @@ pub enum LookupMessage {
 }
 
 pub fn spawn_lookup_fetcher(
+    tasks: &Tasks,
     base_url: String,
     insecure: bool,
     api_key: Option<String>,
     api_key_header: Option<String>,
-    rx: Receiver<LookupRequest>,
-    tx: Sender<LookupMessage>,
+    mut rx: UnboundedReceiver<LookupRequest>,
+    tx: UnboundedSender<LookupMessage>,
 ) {
-    thread::spawn(move || {
-        let client = match Client::builder()
-            .danger_accept_invalid_certs(insecure)
-            .timeout(Duration::from_secs(6))
-            .build()
-        {
-            Ok(c) => c,
-            Err(err) => {
-                error!("lookup client error: {err}");
-                let _ = tx.send(LookupMessage::Error(format!("Client error: {err}")));
-                return;
-            }
-        };
-
+    let pool = tasks.pool().clone();
+    tasks.spawn("lookup", async move {
         let base = base_url.trim_end_matches('/');
         let base_v2 = format!("{base}/v2");
+        // Built on the first lookup; most sessions never run one.
+        let mut client: Option<Client> = None;
 
-        while let Ok(req) = rx.recv() {
-            let url = build_url(&base_v2, &req.kind);
-            let mut call = client.get(&url);
-            if let (Some(key), Some(header)) = (api_key.as_deref(), api_key_header.as_deref()) {
-                if !key.trim().is_empty() && !header.trim().is_empty() {
-                    call = call.header(header, key);
-                }
-            }
-
-            match call.send() {
-                Ok(resp) => {
-                    let status = resp.status();
-                    if !status.is_success() {
-                        let _ = tx.send(LookupMessage::Error(format!("HTTP {}", status)));
-                        continue;
-                    }
-                    match resp.json::<ApiResponse>() {
-                        Ok(data) => {
-                            let _ = tx.send(LookupMessage::Result(data));
-                        }
-                        Err(err) => {
-                            let _ = tx.send(LookupMessage::Error(format!("Parse error: {err}")));
-                        }
+        while let Some(req) = rx.recv().await {
+            let client = match client.as_ref() {
+                Some(client) => client.clone(),
+                None => match build_async_client(insecure, LOOKUP_TIMEOUT) {
+                    Ok(built) => client.insert(built).clone(),
+                    Err(err) => {
+                        error!("lookup client error: {err}");
+                        let _ = tx.send(LookupMessage::Error(format!("Client error: {err}")));
+                        return;
                     }
-                }
-                Err(err) => {
-                    debug!("lookup request error: {err}");
-                    let _ = tx.send(LookupMessage::Error(err.to_string()));
-                }
-            }
+                },
+            };
+            let result = pool
+                .run(
+                    &base_v2,
+                    fetch_lookup(
+                        &client,
+                        &base_v2,
+                        &req.kind,
+                        api_key.as_deref(),
+                        api_key_header.as_deref(),
+                    ),
+                )
+                .await
+                .unwrap_or_else(|| Err("Lookup aborted".to_string()));
+            let message = match result {
+                Ok(data) => LookupMessage::Result(data),
+                Err(err) => LookupMessage::Error(err),
+            };
+            let _ = tx.send(message);
         }
     });
 }
 
+pub fn lookup_once(
+    base_url: &str,
+    insecure: bool,
+    api_key: Option<&str>,
+    api_key_header: Option<&str>,
+    kind: &LookupKind,
+) -> Result<ApiResponse, String> {
+    // The one-shot command runs outside the task runtime; a throwaway
+    // current-thread runtime drives the same request code.
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|err| format!("Runtime error: {err}"))?;
+    let client = build_async_client(insecure, LOOKUP_TIMEOUT)
+        .map_err(|err| format!("Client error: {err}"))?;
+    let base_v2 = format!("{}/v2", base_url.trim_end_matches('/'));
+    runtime.block_on(fetch_lookup(
+        &client,
+        &base_v2,
+        kind,
+        api_key,
+        api_key_header,
+    ))
+}
+
+async fn fetch_lookup(
+    client: &Client,
+    base_v2: &str,
+    kind: &LookupKind,
+    api_key: Option<&str>,
+    api_key_header: Option<&str>,
+) -> Result<ApiResponse, String> {
+    let url = build_url(base_v2, kind);
+    let mut call = client.get(&url);
+    if let (Some(key), Some(header)) = (api_key, api_key_header) {
+        if !key.trim().is_empty() && !header.trim().is_empty() {
+            call = call.header(header, key);
+        }
+    }
+
+    let resp = call.send().await.map_err(|err| {
+        debug!("lookup request error: {err}");
+        err.to_string()
+    })?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {}", status));
+    }
+    let body = resp.bytes().await.map_err(|err| err.to_string())?;
+    ApiResponse::from_json(body.into()).map_err(|err| format!("Parse error: {err}"))
+}
+
 fn build_url(base_v2: &str, kind: &LookupKind) -> String {
     match kind {
         LookupKind::Hex(values) => format!("{}/hex/{}", base_v2, join(values)),
@@ -1,222 +1,60 @@
-mod app;
-mod config;
-mod export;
-mod graph;
-mod logging;
-mod lookup;
-mod model;
-mod net;
-mod radar;
-mod routes;
-mod runtime;
-mod storage;
-mod ui;
-mod watchlist;
-
 use anyhow::Result;
-use std::collections::HashSet;
-use std::sync::mpsc;
-use std::time::Duration;
+use clap::Parser;
 
-use app::{App, FlagStyle, LayoutMode, RadarBlip, RadarRenderer, SiteLocation, ThemeMode};
-use config::parse_args;
-use logging::init as init_logging;
-use lookup::spawn_lookup_fetcher;
-use net::spawn_fetcher;
-use routes::spawn_route_fetcher;
-use runtime::{init_terminal, restore_terminal, run_app, LookupChannels, RouteChannels};
-use std::path::PathBuf;
-use storage::{ensure_watchlist_file, load_favorites, load_watchlist};
-use tracing::{debug, info, warn};
+use adsb_tui::cli::{Cli, Command, ValidateArgs};
+use adsb_tui::commands;
+use adsb_tui::config::{self, load_config};
+use adsb_tui::logging::init as init_logging;
+use tracing::{debug, info};
 
 fn main() -> Result<()> {
-    let config = parse_args()?;
-    let _log_guard = init_logging(&config);
-    info!("adsb-tui starting");
-    debug!("config path: {}", config.config_path.display());
-    let (tx, rx) = mpsc::channel();
-
-    let api_key = if config.api_key.trim().is_empty() {
-        None
-    } else {
-        Some(config.api_key.clone())
-    };
-    let api_key_header = if config.api_key_header.trim().is_empty() {
-        None
-    } else {
-        Some(config.api_key_header.clone())
-    };
-    let feed_templates = config::active_url_templates(&config);
-    let feed_urls = config::initial_fetch_urls(&config)?;
-    let display_url = feed_urls
-        .first()
-        .cloned()
-        .unwrap_or_else(|| config.url.clone());
-
-    let mut favorites: HashSet<String> = config
-        .favorites
-        .into_iter()
-        .map(|v| v.trim().to_ascii_lowercase())
-        .filter(|v| !v.is_empty())
-        .collect();
-
-    let favorites_path = if config.favorites_file.trim().is_empty() {
-        None
-    } else {
-        Some(PathBuf::from(config.favorites_file))
-    };
-
-    if let Some(path) = favorites_path.as_ref() {
-        if let Ok(file_favs) = load_favorites(path) {
-            favorites.extend(file_favs);
-        }
+    let cli = Cli::parse();
+    let config_args = cli.config;
+    let headless = cli.headless;
+    if cli.print_config {
+        return commands::print_config(&config_args);
     }
-
-    let watchlist_path = if config.watchlist_file.trim().is_empty() {
-        None
-    } else {
-        Some(PathBuf::from(config.watchlist_file))
+    let command = match cli.command {
+        _ if cli.check => Command::ValidateConfig(ValidateArgs::default()),
+        Some(command) => command,
+        None => Command::Run,
     };
-    let mut watchlist = Vec::new();
-    if let Some(path) = watchlist_path.as_ref() {
-        if let Ok(created) = ensure_watchlist_file(path) {
-            if created {
-                info!("watchlist template created at {}", path.display());
-            }
-        }
-        if let Ok(entries) = load_watchlist(path) {
-            watchlist = entries;
-        } else if config.watchlist_enabled {
-            warn!("failed to load watchlist from {}", path.display());
+    match &command {
+        Command::ValidateConfig(args) => return commands::validate_config(&config_args, args),
+        Command::Completions(args) => {
+            commands::completions(args);
+            return Ok(());
         }
+        Command::Secret(command) => return commands::secret(command),
+        _ => {}
     }
-    let watchlist_enabled = config.watchlist_enabled || !watchlist.is_empty();
-
-    let layout_mode = LayoutMode::from_str(&config.layout);
-    let theme_mode = ThemeMode::from_str(&config.theme);
-    let radar_renderer = RadarRenderer::from_str(&config.radar_renderer);
-    let radar_blip = RadarBlip::from_str(&config.radar_blip);
-    let flag_style = FlagStyle::from_str(&config.flag_style);
-    let site = match (config.site_lat, config.site_lon) {
-        (Some(lat), Some(lon)) => Some(SiteLocation {
-            lat,
-            lon,
-            alt_m: config.site_alt_m.unwrap_or(0.0),
-        }),
-        _ => None,
-    };
-    let (feed_update_tx, feed_update_rx) = mpsc::channel();
 
-    spawn_fetcher(
-        feed_urls,
-        config.refresh,
-        config.insecure,
-        api_key.clone(),
-        api_key_header.clone(),
-        feed_update_rx,
-        tx,
-    );
+    if headless && !matches!(command, Command::Run) {
+        return Err(anyhow::anyhow!(
+            "--headless only applies to the run command"
+        ));
+    }
 
-    let mut terminal = init_terminal()?;
-    let route_channels = if config.route_enabled {
-        let (route_req_tx, route_req_rx) = mpsc::channel();
-        let (route_res_tx, route_res_rx) = mpsc::channel();
-        spawn_route_fetcher(
-            config.route_base.clone(),
-            config.route_mode.clone(),
-            config.route_path.clone(),
-            config.insecure,
-            Duration::from_secs(config.route_timeout_secs.max(2)),
-            route_res_tx,
-            route_req_rx,
-        );
-        Some(RouteChannels {
-            req_tx: route_req_tx,
-            res_rx: route_res_rx,
-        })
-    } else {
-        None
-    };
+    let config = load_config(&config_args)?;
+    let _log_guard = init_logging(&config);
+    info!("adsb-tui starting");
+    debug!("config path: {}", config.config_path.display());
+    for message in config::migrate_legacy_data_files(&config) {
+        info!("{message}");
+        eprintln!("{message}");
+    }
 
-    let lookup_channels = {
-        let (lookup_req_tx, lookup_req_rx) = mpsc::channel();
-        let (lookup_res_tx, lookup_res_rx) = mpsc::channel();
-        spawn_lookup_fetcher(
-            config.route_base.clone(),
-            config.insecure,
-            api_key.clone(),
-            api_key_header.clone(),
-            lookup_req_rx,
-            lookup_res_tx,
-        );
-        LookupChannels {
-            req_tx: lookup_req_tx,
-            res_rx: lookup_res_rx,
+    match command {
+        Command::Run => commands::run(config, config_args, headless)?,
+        Command::Export(args) => commands::export(config, args)?,
+        Command::Snapshot(args) => commands::snapshot(config, args)?,
+        Command::Lookup(args) => commands::lookup(config, args)?,
+        Command::ValidateConfig(_) | Command::Completions(_) | Command::Secret(_) => {
+            unreachable!("handled before config load")
         }
-    };
-
-    let res = run_app(
-        &mut terminal,
-        App::new(
-            display_url,
-            feed_templates,
-            config.refresh,
-            config.stale_secs as f64,
-            config.hide_stale,
-            config.low_nic,
-            config.low_nac,
-            favorites,
-            config.filter,
-            layout_mode,
-            theme_mode,
-            config.role_enabled,
-            config.role_highlight,
-            config.column_cache,
-            Duration::from_millis(400),
-            config.config_path.clone(),
-            config.trail_len as usize,
-            favorites_path.clone(),
-            site,
-            config.demo_mode,
-            config.radar_range_nm,
-            config.radar_aspect,
-            radar_renderer,
-            config.radar_labels,
-            radar_blip,
-            config.route_enabled,
-            config.route_mode.eq_ignore_ascii_case("tar1090"),
-            Duration::from_secs(config.route_ttl_secs),
-            Duration::from_secs(config.route_refresh_secs),
-            config.route_batch as usize,
-            config.ui_fps,
-            config.smooth_mode,
-            config.smooth_merge,
-            Duration::from_millis(config.rate_window_ms),
-            config.rate_min_secs,
-            config.notify_radius_mi,
-            config.overpass_mi,
-            Duration::from_secs(config.notify_cooldown_secs),
-            config.altitude_trend_arrows,
-            config.track_arrows,
-            config.flags_enabled,
-            flag_style,
-            config.stats_metric_1.clone(),
-            config.stats_metric_2.clone(),
-            config.stats_metric_3.clone(),
-            watchlist_enabled,
-            watchlist_path.clone(),
-            watchlist,
-        ),
-        rx,
-        route_channels,
-        Some(lookup_channels),
-        Some(feed_update_tx),
-    );
-    restore_terminal(&mut terminal)?;
-
-    if let Err(err) = res {
-        warn!("runtime error: {err}");
-        eprintln!("{err}");
+        Command::Record(args) => commands::record(config, args)?,
+        Command::Replay(args) => commands::replay(config, config_args, args)?,
+        Command::Stress(args) => commands::stress(config, config_args, args)?,
     }
 
     info!("adsb-tui exited");
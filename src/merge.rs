@@ -0,0 +1,309 @@
+//! Merges snapshots from several receivers by ICAO hex.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use crate::model::{Aircraft, ApiResponse, SourceStatus};
+
+/// Combine `(source index, snapshot)` pairs into one snapshot and update the
+/// per-receiver counts in `sources`. Receivers missing from `parts` keep
+/// their `error` but report no aircraft.
+pub fn merge_snapshots(
+    parts: Vec<(usize, ApiResponse)>,
+    sources: &mut [SourceStatus],
+) -> ApiResponse {
+    for status in sources.iter_mut() {
+        status.aircraft = 0;
+        status.positions = 0;
+        status.exclusive = 0;
+        status.primary = 0;
+    }
+
+    let mut merged = ApiResponse::default();
+    let mut groups: Vec<Vec<(usize, Aircraft)>> = Vec::new();
+    let mut by_hex: HashMap<String, usize> = HashMap::new();
+    for (source, data) in parts {
+        if let Some(status) = sources.get_mut(source) {
+            status.aircraft = data.aircraft.len();
+            status.positions = data
+                .aircraft
+                .iter()
+                .filter(|ac| ac.lat.is_some() && ac.lon.is_some())
+                .count();
+            status.messages = data.messages;
+            status.fetch_time = data.fetch_time;
+            status.error = None;
+        }
+        merged.now = merged.now.max(data.now);
+        merged.messages = match (merged.messages, data.messages) {
+            (Some(total), Some(count)) => Some(total + count),
+            (total, count) => total.or(count),
+        };
+        merged.parse_warnings += data.parse_warnings;
+        merged.fetch_time = merged.fetch_time.max(data.fetch_time);
+        for ac in data.aircraft {
+            let key = ac
+                .hex
+                .as_deref()
+                .map(|hex| hex.trim().to_ascii_lowercase())
+                .filter(|hex| !hex.is_empty());
+            match key {
+                Some(key) => {
+                    let slot = *by_hex.entry(key).or_insert_with(|| {
+                        groups.push(Vec::new());
+                        groups.len() - 1
+                    });
+                    groups[slot].push((source, ac));
+                }
+                None => groups.push(vec![(source, ac)]),
+            }
+        }
+    }
+
+    merged.aircraft = groups
+        .into_iter()
+        .map(|group| merge_aircraft(group, sources))
+        .collect();
+    merged
+}
+
+fn merge_aircraft(mut group: Vec<(usize, Aircraft)>, sources: &mut [SourceStatus]) -> Aircraft {
+    let mut heard: Vec<usize> = group.iter().map(|(source, _)| *source).collect();
+    heard.sort_unstable();
+    heard.dedup();
+
+    let best = (0..group.len())
+        .max_by(|&a, &b| signal_order(&group[a].1, &group[b].1))
+        .unwrap_or(0);
+    let (source, mut ac) = group.swap_remove(best);
+
+    // The freshest position may come from a receiver with a weaker signal.
+    let position_age = |ac: &Aircraft| {
+        (ac.lat.is_some() && ac.lon.is_some()).then(|| ac.seen_pos.or(ac.seen).unwrap_or(f64::MAX))
+    };
+    let fresher = group
+        .iter()
+        .filter_map(|(_, other)| Some((position_age(other)?, other)))
+        .min_by(|a, b| a.0.total_cmp(&b.0))
+        .filter(|(age, _)| position_age(&ac).is_none_or(|own| *age < own));
+    if let Some((_, other)) = fresher {
+        ac.lat = other.lat;
+        ac.lon = other.lon;
+        ac.seen_pos = other.seen_pos;
+        ac.nic = other.nic;
+        ac.rc = other.rc;
+        ac.nac_p = other.nac_p;
+    }
+
+    for (_, other) in group {
+        ac.seen = min_f64(ac.seen, other.seen);
+        ac.messages = ac.messages.max(other.messages);
+        fill_missing(&mut ac, other);
+    }
+
+    if let Some(status) = sources.get_mut(source) {
+        status.primary += 1;
+        if heard.len() == 1 {
+            status.exclusive += 1;
+        }
+    }
+    ac.source = u8::try_from(source).ok();
+    ac.heard_by = u8::try_from(heard.len()).unwrap_or(u8::MAX);
+    ac
+}
+
+/// Stronger RSSI wins; without a difference, the more recently heard record.
+fn signal_order(a: &Aircraft, b: &Aircraft) -> Ordering {
+    let rssi = |ac: &Aircraft| ac.rssi.unwrap_or(f64::NEG_INFINITY);
+    let seen = |ac: &Aircraft| ac.seen.unwrap_or(f64::MAX);
+    rssi(a)
+        .total_cmp(&rssi(b))
+        .then_with(|| seen(b).total_cmp(&seen(a)))
+}
+
+fn min_f64(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn fill_missing(ac: &mut Aircraft, other: Aircraft) {
+    macro_rules! fill {
+        ($($field:ident),* $(,)?) => {
+            $(
+                if ac.$field.is_none() {
+                    ac.$field = other.$field;
+                }
+            )*
+        };
+    }
+    fill!(
+        hex,
+        kind,
+        flight,
+        r,
+        t,
+        desc,
+        own_op,
+        year,
+        alt_baro,
+        alt_geom,
+        gs,
+        track,
+        baro_rate,
+        geom_rate,
+        track_rate,
+        roll,
+        true_heading,
+        mag_heading,
+        ias,
+        tas,
+        mach,
+        squawk,
+        emergency,
+        category,
+        nav_qnh,
+        nav_altitude_mcp,
+        nav_altitude_fms,
+        nav_heading,
+        nav_modes,
+        lat,
+        lon,
+        nic,
+        rc,
+        seen_pos,
+        last_position,
+        version,
+        nic_baro,
+        nac_p,
+        nac_v,
+        sil,
+        sil_type,
+        gva,
+        sda,
+        alert,
+        spi,
+        rssi,
+        wd,
+        ws,
+        oat,
+        tat,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge_snapshots;
+    use crate::model::{Aircraft, ApiResponse, SourceStatus};
+
+    fn aircraft(hex: &str, rssi: f64, seen: f64) -> Aircraft {
+        Aircraft {
+            hex: Some(hex.to_string()),
+            rssi: Some(rssi),
+            seen: Some(seen),
+            ..Aircraft::default()
+        }
+    }
+
+    fn snapshot(messages: u64, aircraft: Vec<Aircraft>) -> ApiResponse {
+        ApiResponse {
+            now: Some(1_700_000_000),
+            messages: Some(messages),
+            aircraft,
+            ..ApiResponse::default()
+        }
+    }
+
+    fn sources(count: usize) -> Vec<SourceStatus> {
+        (0..count)
+            .map(|i| SourceStatus::new(&format!("http://rx{i}.test/data/aircraft.json")))
+            .collect()
+    }
+
+    #[test]
+    fn merge_dedupes_by_hex_and_keeps_strongest_signal() {
+        let mut status = sources(2);
+        let near = Aircraft {
+            flight: Some("DLH4AB".to_string()),
+            ..aircraft("3C6444", -8.0, 0.4)
+        };
+        let far = Aircraft {
+            squawk: Some("1000".to_string()),
+            messages: Some(900),
+            ..aircraft("3c6444", -24.0, 0.1)
+        };
+        let merged = merge_snapshots(
+            vec![
+                (0, snapshot(100, vec![far, aircraft("a1b2c3", -20.0, 1.0)])),
+                (1, snapshot(50, vec![near])),
+            ],
+            &mut status,
+        );
+
+        assert_eq!(merged.messages, Some(150));
+        assert_eq!(merged.aircraft.len(), 2);
+        let ac = &merged.aircraft[0];
+        assert_eq!(ac.source, Some(1));
+        assert_eq!(ac.heard_by, 2);
+        assert_eq!(ac.rssi, Some(-8.0));
+        assert_eq!(ac.flight.as_deref(), Some("DLH4AB"));
+        assert_eq!(ac.squawk.as_deref(), Some("1000"));
+        assert_eq!(ac.seen, Some(0.1));
+        assert_eq!(ac.messages, Some(900));
+
+        assert_eq!(
+            (status[0].aircraft, status[0].primary, status[0].exclusive),
+            (2, 1, 1)
+        );
+        assert_eq!(
+            (status[1].aircraft, status[1].primary, status[1].exclusive),
+            (1, 1, 0)
+        );
+    }
+
+    #[test]
+    fn merge_takes_freshest_position_from_any_receiver() {
+        let mut status = sources(2);
+        let strong = Aircraft {
+            lat: Some(52.0),
+            lon: Some(4.0),
+            seen_pos: Some(9.0),
+            ..aircraft("484506", -5.0, 0.2)
+        };
+        let weak = Aircraft {
+            lat: Some(52.1),
+            lon: Some(4.1),
+            seen_pos: Some(0.5),
+            nic: Some(8),
+            ..aircraft("484506", -30.0, 0.5)
+        };
+        let merged = merge_snapshots(
+            vec![(0, snapshot(1, vec![strong])), (1, snapshot(1, vec![weak]))],
+            &mut status,
+        );
+
+        let ac = &merged.aircraft[0];
+        assert_eq!(ac.source, Some(0));
+        assert_eq!((ac.lat, ac.lon), (Some(52.1), Some(4.1)));
+        assert_eq!((ac.seen_pos, ac.nic), (Some(0.5), Some(8)));
+        assert_eq!(status[0].positions, 1);
+    }
+
+    #[test]
+    fn failed_receivers_report_no_aircraft() {
+        let mut status = sources(2);
+        status[1].aircraft = 12;
+        status[1].error = Some("HTTP 502".to_string());
+        let merged = merge_snapshots(
+            vec![(0, snapshot(7, vec![aircraft("abc123", -10.0, 1.0)]))],
+            &mut status,
+        );
+
+        assert_eq!(merged.aircraft.len(), 1);
+        assert_eq!(merged.aircraft[0].heard_by, 1);
+        assert_eq!(status[1].aircraft, 0);
+        assert_eq!(status[1].error.as_deref(), Some("HTTP 502"));
+        assert_eq!(status[0].host(), "rx0.test");
+    }
+}
@@ -0,0 +1,163 @@
+//! Military ICAO address ranges. One range per line, `first-last` or an
+//! `x`-padded prefix, optionally followed by a label.
+
+const BUILTIN: &[(u32, u32, &str)] = &[
+    (0x010070, 0x01008F, "Egypt"),
+    (0x0A4000, 0x0A4FFF, "Algeria"),
+    (0x33FF00, 0x33FFFF, "Italy"),
+    (0x350000, 0x37FFFF, "Spain"),
+    (0x3AA000, 0x3AFFFF, "France"),
+    (0x3B7000, 0x3BFFFF, "France"),
+    (0x3EA000, 0x3EBFFF, "Germany"),
+    (0x3F4000, 0x3FBFFF, "Germany"),
+    (0x400000, 0x40003F, "United Kingdom"),
+    (0x43C000, 0x43CFFF, "United Kingdom"),
+    (0x444000, 0x446FFF, "Austria"),
+    (0x44F000, 0x44FFFF, "Belgium"),
+    (0x457000, 0x457FFF, "Bulgaria"),
+    (0x45F400, 0x45F4FF, "Denmark"),
+    (0x468000, 0x4683FF, "Greece"),
+    (0x473C00, 0x473C0F, "Hungary"),
+    (0x478100, 0x4781FF, "Norway"),
+    (0x480000, 0x480FFF, "Netherlands"),
+    (0x48D800, 0x48D87F, "Poland"),
+    (0x497C00, 0x497CFF, "Portugal"),
+    (0x498420, 0x49842F, "Czechia"),
+    (0x4B7000, 0x4B7FFF, "Switzerland"),
+    (0x4B8200, 0x4B82FF, "Turkey"),
+    (0x506F00, 0x506FFF, "Slovenia"),
+    (0x70C070, 0x70C07F, "Oman"),
+    (0x710258, 0x71028F, "Saudi Arabia"),
+    (0x710380, 0x71039F, "Saudi Arabia"),
+    (0x738A00, 0x738AFF, "Israel"),
+    (0x7CF800, 0x7CFAFF, "Australia"),
+    (0x800200, 0x8002FF, "India"),
+    (0xADF7C8, 0xAFFFFF, "United States"),
+    (0xC20000, 0xC3FFFF, "Canada"),
+    (0xE40000, 0xE41FFF, "Brazil"),
+    (0xE80600, 0xE806FF, "Chile"),
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HexRange {
+    pub first: u32,
+    pub last: u32,
+    pub label: String,
+}
+
+/// `first-last` or an `x`-padded prefix, as first and last address.
+pub fn parse_range(spec: &str) -> Option<(u32, u32)> {
+    if let Some((first, last)) = spec.split_once('-') {
+        let (first, last) = (parse_hex(first)?, parse_hex(last)?);
+        return (first <= last).then_some((first, last));
+    }
+    let prefix = spec.trim_end_matches(['x', 'X']);
+    if prefix.is_empty() || spec.len() > 6 {
+        return None;
+    }
+    let shift = 4 * (6 - prefix.len() as u32);
+    let first = u32::from_str_radix(prefix, 16).ok()? << shift;
+    Some((first, first | ((1 << shift) - 1)))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MilitaryRanges {
+    ranges: Vec<HexRange>,
+}
+
+impl Default for MilitaryRanges {
+    fn default() -> Self {
+        let ranges = BUILTIN
+            .iter()
+            .map(|&(first, last, label)| HexRange {
+                first,
+                last,
+                label: label.to_string(),
+            })
+            .collect();
+        Self { ranges }
+    }
+}
+
+impl MilitaryRanges {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut ranges = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((spec, label)) = line
+                .split_once(char::is_whitespace)
+                .or((!line.is_empty()).then_some((line, "")))
+            else {
+                continue;
+            };
+            let (first, last) = parse_range(spec)
+                .ok_or_else(|| format!("line {}: `{spec}` is not a hex range", number + 1))?;
+            ranges.push(HexRange {
+                first,
+                last,
+                label: label.trim().to_string(),
+            });
+        }
+        Ok(Self { ranges })
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn ranges(&self) -> impl Iterator<Item = &HexRange> {
+        self.ranges.iter()
+    }
+}
+
+pub fn parse_hex(hex: &str) -> Option<u32> {
+    let hex = hex.trim();
+    if hex.len() != 6 {
+        return None;
+    }
+    u32::from_str_radix(hex, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_hex, MilitaryRanges};
+
+    fn label<'a>(ranges: &'a MilitaryRanges, hex: &str) -> Option<&'a str> {
+        let address = parse_hex(hex)?;
+        ranges
+            .ranges()
+            .find(|range| (range.first..=range.last).contains(&address))
+            .map(|range| range.label.as_str())
+    }
+
+    #[test]
+    fn matches_ranges_and_prefixes() {
+        let ranges = MilitaryRanges::parse(
+            "# air arms\nADF7C8-AFFFFF  United States\n\n43cxxx United Kingdom # RAF\nADFE\n",
+        )
+        .unwrap();
+        assert_eq!(ranges.len(), 3);
+        assert_eq!(label(&ranges, "AE1234"), Some("United States"));
+        assert_eq!(label(&ranges, "43c0a1"), Some("United Kingdom"));
+        assert_eq!(label(&ranges, "adfe00"), Some("United States"));
+        assert_eq!(label(&ranges, "43d000"), None);
+        assert_eq!(label(&ranges, "a12345"), None);
+        assert_eq!(label(&ranges, "~ae1234"), None);
+
+        assert!(MilitaryRanges::parse("AFFFFF-ADF7C8").is_err());
+        assert!(MilitaryRanges::parse("43cxxxx").is_err());
+        assert!(MilitaryRanges::parse("ADFEZZ").is_err());
+    }
+
+    #[test]
+    fn builtin_list_covers_known_blocks() {
+        let ranges = MilitaryRanges::default();
+        assert_eq!(label(&ranges, "7cf9a0"), Some("Australia"));
+        assert_eq!(label(&ranges, "ae01ce"), Some("United States"));
+        assert_eq!(label(&ranges, "a0b1c2"), None, "civil US register");
+    }
+}
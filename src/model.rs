@@ -1,14 +1,144 @@
-use serde::{Deserialize, Deserializer, Serialize};
+use serde::de::{SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
+use std::cell::Cell;
+use std::fmt;
+use std::time::Duration;
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
+#[serde(from = "ApiResponseWire")]
 pub struct ApiResponse {
-    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
     pub now: Option<i64>,
-    #[serde(default, deserialize_with = "de_opt_u64_from_any")]
     pub messages: Option<u64>,
-    #[serde(default, alias = "ac")]
     pub aircraft: Vec<Aircraft>,
+    /// Values that could not be parsed in this snapshot, including aircraft
+    /// records that were skipped entirely.
+    #[serde(skip)]
+    pub parse_warnings: u64,
+    #[serde(skip)]
+    pub fetch_time: Option<Duration>,
+    #[serde(skip)]
+    pub sources: Vec<SourceStatus>,
+    /// Set by the live fetcher when every feed answered `304 Not Modified`;
+    /// the snapshot is then empty and the previous one still stands.
+    #[serde(skip)]
+    pub not_modified: bool,
+    #[serde(skip)]
+    pub cache: FeedCache,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeedCache {
+    pub requests: u64,
+    pub not_modified: u64,
+}
+
+impl FeedCache {
+    pub fn add(&mut self, other: FeedCache) {
+        self.requests += other.requests;
+        self.not_modified += other.not_modified;
+    }
+
+    pub fn hit_percent(&self) -> Option<f64> {
+        (self.requests > 0).then(|| self.not_modified as f64 * 100.0 / self.requests as f64)
+    }
+}
+
+#[derive(Deserialize)]
+struct ApiResponseWire {
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    now: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_u64_from_any")]
+    messages: Option<u64>,
+    #[serde(default, alias = "ac")]
+    aircraft: AircraftList,
+}
+
+impl ApiResponse {
+    /// Parse a feed snapshot. Built with the `simd-json` feature this uses
+    /// simd-json, which parses in place and is noticeably faster on large
+    /// feeds; otherwise serde_json.
+    #[cfg(feature = "simd-json")]
+    pub fn from_json(mut body: Vec<u8>) -> Result<Self, String> {
+        simd_json::serde::from_slice(&mut body).map_err(|err| err.to_string())
+    }
+
+    #[cfg(not(feature = "simd-json"))]
+    pub fn from_json(body: Vec<u8>) -> Result<Self, String> {
+        serde_json::from_slice(&body).map_err(|err| err.to_string())
+    }
+}
+
+impl From<ApiResponseWire> for ApiResponse {
+    fn from(wire: ApiResponseWire) -> Self {
+        Self {
+            now: wire.now,
+            messages: wire.messages,
+            aircraft: wire.aircraft.aircraft,
+            parse_warnings: wire.aircraft.warnings,
+            fetch_time: None,
+            sources: Vec::new(),
+            not_modified: false,
+            cache: FeedCache::default(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct AircraftList {
+    aircraft: Vec<Aircraft>,
+    warnings: u64,
+}
+
+impl<'de> Deserialize<'de> for AircraftList {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(AircraftListVisitor)
+    }
+}
+
+/// Converts one record at a time so a large snapshot is never held as a
+/// full `Vec<Value>` alongside the parsed aircraft.
+struct AircraftListVisitor;
+
+impl<'de> Visitor<'de> for AircraftListVisitor {
+    type Value = AircraftList;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of aircraft")
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut list = AircraftList {
+            aircraft: Vec::with_capacity(seq.size_hint().unwrap_or(0)),
+            warnings: 0,
+        };
+        while let Some(value) = seq.next_element::<Value>()? {
+            FIELD_WARNINGS.with(|count| count.set(0));
+            match Aircraft::deserialize(value) {
+                Ok(ac) => {
+                    list.warnings += FIELD_WARNINGS.with(Cell::get);
+                    list.aircraft.push(ac);
+                }
+                Err(_) => list.warnings += 1,
+            }
+        }
+        Ok(list)
+    }
+}
+
+thread_local! {
+    static FIELD_WARNINGS: Cell<u64> = const { Cell::new(0) };
+}
+
+fn field_warning<T>() -> Option<T> {
+    FIELD_WARNINGS.with(|count| count.set(count.get() + 1));
+    None
 }
 
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
@@ -31,8 +161,8 @@ pub struct Aircraft {
     pub own_op: Option<String>,
     #[serde(default)]
     pub year: Option<String>,
-    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
-    pub alt_baro: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_altitude_from_any")]
+    pub alt_baro: Option<Altitude>,
     #[serde(default, deserialize_with = "de_opt_i64_from_any")]
     pub alt_geom: Option<i64>,
     #[serde(default, deserialize_with = "de_opt_f64_from_any")]
@@ -41,12 +171,38 @@ pub struct Aircraft {
     pub track: Option<f64>,
     #[serde(default, deserialize_with = "de_opt_i64_from_any")]
     pub baro_rate: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub geom_rate: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_f64_from_any")]
+    pub track_rate: Option<f64>,
+    #[serde(default, deserialize_with = "de_opt_f64_from_any")]
+    pub roll: Option<f64>,
+    #[serde(default, deserialize_with = "de_opt_f64_from_any")]
+    pub true_heading: Option<f64>,
+    #[serde(default, deserialize_with = "de_opt_f64_from_any")]
+    pub mag_heading: Option<f64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub ias: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub tas: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_f64_from_any")]
+    pub mach: Option<f64>,
+    #[serde(default, deserialize_with = "de_opt_squawk_from_any")]
+    pub squawk: Option<String>,
+    #[serde(default)]
+    pub emergency: Option<String>,
     #[serde(default)]
     pub category: Option<String>,
     #[serde(default, deserialize_with = "de_opt_f64_from_any")]
     pub nav_qnh: Option<f64>,
     #[serde(default, deserialize_with = "de_opt_i64_from_any")]
     pub nav_altitude_mcp: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub nav_altitude_fms: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_f64_from_any")]
+    pub nav_heading: Option<f64>,
+    #[serde(default)]
+    pub nav_modes: Option<Vec<String>>,
     #[serde(default, deserialize_with = "de_opt_f64_from_any")]
     pub lat: Option<f64>,
     #[serde(default, deserialize_with = "de_opt_f64_from_any")]
@@ -57,6 +213,8 @@ pub struct Aircraft {
     pub rc: Option<i64>,
     #[serde(default, deserialize_with = "de_opt_f64_from_any")]
     pub seen_pos: Option<f64>,
+    #[serde(default, rename = "lastPosition")]
+    pub last_position: Option<LastPosition>,
     #[serde(default, deserialize_with = "de_opt_i64_from_any")]
     pub version: Option<i64>,
     #[serde(default, deserialize_with = "de_opt_i64_from_any")]
@@ -70,6 +228,10 @@ pub struct Aircraft {
     #[serde(default)]
     pub sil_type: Option<String>,
     #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub gva: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub sda: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
     pub alert: Option<i64>,
     #[serde(default, deserialize_with = "de_opt_i64_from_any")]
     pub spi: Option<i64>,
@@ -79,6 +241,379 @@ pub struct Aircraft {
     pub seen: Option<f64>,
     #[serde(default, deserialize_with = "de_opt_f64_from_any")]
     pub rssi: Option<f64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub wd: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub ws: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub oat: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub tat: Option<i64>,
+    #[serde(skip)]
+    pub source: Option<u8>,
+    #[serde(skip)]
+    pub heard_by: u8,
+}
+
+/// Last known position that readsb keeps once the live position has timed
+/// out; `lat`/`lon` are then absent from the aircraft itself.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct LastPosition {
+    #[serde(default, deserialize_with = "de_opt_f64_from_any")]
+    pub lat: Option<f64>,
+    #[serde(default, deserialize_with = "de_opt_f64_from_any")]
+    pub lon: Option<f64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub nic: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_i64_from_any")]
+    pub rc: Option<i64>,
+    #[serde(default, deserialize_with = "de_opt_f64_from_any")]
+    pub seen_pos: Option<f64>,
+}
+
+/// Barometric altitude as reported by readsb, which sends the string
+/// `"ground"` instead of a number for surface traffic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Altitude {
+    Ground,
+    Feet(i64),
+}
+
+impl Altitude {
+    pub fn feet(self) -> i64 {
+        match self {
+            Altitude::Ground => 0,
+            Altitude::Feet(value) => value,
+        }
+    }
+}
+
+impl fmt::Display for Altitude {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Altitude::Ground => f.pad("GND"),
+            Altitude::Feet(value) => f.pad(&value.to_string()),
+        }
+    }
+}
+
+impl Serialize for Altitude {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Altitude::Ground => serializer.serialize_str("ground"),
+            Altitude::Feet(value) => serializer.serialize_i64(*value),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CategoryClass {
+    Light,
+    Medium,
+    Heavy,
+    Super,
+    HighPerformance,
+    Rotorcraft,
+    Glider,
+    LighterThanAir,
+    Parachutist,
+    Ultralight,
+    Uav,
+    Space,
+    Surface,
+    Obstacle,
+}
+
+impl CategoryClass {
+    pub fn from_code(code: &str) -> Option<Self> {
+        let class = match code.trim().to_ascii_uppercase().as_str() {
+            "A1" => CategoryClass::Light,
+            "A2" | "A3" | "A4" => CategoryClass::Medium,
+            "A5" => CategoryClass::Heavy,
+            "A6" => CategoryClass::HighPerformance,
+            "A7" => CategoryClass::Rotorcraft,
+            "B1" => CategoryClass::Glider,
+            "B2" => CategoryClass::LighterThanAir,
+            "B3" => CategoryClass::Parachutist,
+            "B4" => CategoryClass::Ultralight,
+            "B6" => CategoryClass::Uav,
+            "B7" => CategoryClass::Space,
+            "C1" | "C2" => CategoryClass::Surface,
+            "C3" | "C4" | "C5" | "C6" | "C7" => CategoryClass::Obstacle,
+            _ => return None,
+        };
+        Some(class)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            CategoryClass::Light => "light",
+            CategoryClass::Medium => "medium",
+            CategoryClass::Heavy => "heavy",
+            CategoryClass::Super => "super",
+            CategoryClass::HighPerformance => "highperf",
+            CategoryClass::Rotorcraft => "rotorcraft",
+            CategoryClass::Glider => "glider",
+            CategoryClass::LighterThanAir => "balloon",
+            CategoryClass::Parachutist => "parachutist",
+            CategoryClass::Ultralight => "ultralight",
+            CategoryClass::Uav => "uav",
+            CategoryClass::Space => "space",
+            CategoryClass::Surface => "surface",
+            CategoryClass::Obstacle => "obstacle",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CategoryClass::Light => "LIGHT",
+            CategoryClass::Medium => "MEDIUM",
+            CategoryClass::Heavy => "HEAVY",
+            CategoryClass::Super => "SUPER",
+            CategoryClass::HighPerformance => "HI-PERF",
+            CategoryClass::Rotorcraft => "ROTOR",
+            CategoryClass::Glider => "GLIDER",
+            CategoryClass::LighterThanAir => "BALLOON",
+            CategoryClass::Parachutist => "PARA",
+            CategoryClass::Ultralight => "ULTRALT",
+            CategoryClass::Uav => "UAV",
+            CategoryClass::Space => "SPACE",
+            CategoryClass::Surface => "SURFACE",
+            CategoryClass::Obstacle => "OBSTACLE",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DataSource {
+    Adsb,
+    Adsr,
+    Tisb,
+    Adsc,
+    Mlat,
+    ModeS,
+    Other,
+}
+
+impl DataSource {
+    pub fn from_kind(kind: &str) -> Option<Self> {
+        let source = match kind.trim().to_ascii_lowercase().as_str() {
+            "adsb_icao" | "adsb_icao_nt" | "adsb_other" => DataSource::Adsb,
+            "adsr_icao" | "adsr_other" => DataSource::Adsr,
+            "tisb_icao" | "tisb_other" | "tisb_trackfile" => DataSource::Tisb,
+            "adsc" => DataSource::Adsc,
+            "mlat" => DataSource::Mlat,
+            "mode_s" => DataSource::ModeS,
+            "" => return None,
+            _ => DataSource::Other,
+        };
+        Some(source)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DataSource::Adsb => "ADS-B",
+            DataSource::Adsr => "ADS-R",
+            DataSource::Tisb => "TIS-B",
+            DataSource::Adsc => "ADS-C",
+            DataSource::Mlat => "MLAT",
+            DataSource::ModeS => "MODE-S",
+            DataSource::Other => "OTHER",
+        }
+    }
+}
+
+/// ICAO type designators in the Super wake turbulence category. ADS-B has no
+/// emitter code for them, so they report `A5` like any other heavy.
+const SUPER_TYPES: [&str; 2] = ["A388", "A225"];
+
+impl Aircraft {
+    pub fn on_ground(&self) -> bool {
+        self.alt_baro == Some(Altitude::Ground)
+    }
+
+    /// Emergency the aircraft reports: its `emergency` status, or the meaning
+    /// of a 7500/7600/7700 squawk.
+    pub fn emergency_code(&self) -> Option<&str> {
+        let status = self
+            .emergency
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty() && !value.eq_ignore_ascii_case("none"));
+        status.or(match self.squawk.as_deref().map(str::trim) {
+            Some("7500") => Some("unlawful"),
+            Some("7600") => Some("nordo"),
+            Some("7700") => Some("general"),
+            _ => None,
+        })
+    }
+
+    /// Decoded emitter category, upgrading heavies to Super by type.
+    pub fn category_class(&self) -> Option<CategoryClass> {
+        let class = CategoryClass::from_code(self.category.as_deref()?)?;
+        let is_super = self
+            .t
+            .as_deref()
+            .map(|t| SUPER_TYPES.contains(&t.trim().to_ascii_uppercase().as_str()))
+            .unwrap_or(false);
+        if class == CategoryClass::Heavy && is_super {
+            return Some(CategoryClass::Super);
+        }
+        Some(class)
+    }
+
+    pub fn data_source(&self) -> Option<DataSource> {
+        DataSource::from_kind(self.kind.as_deref()?)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SourceStatus {
+    pub url: String,
+    pub aircraft: usize,
+    pub positions: usize,
+    pub exclusive: usize,
+    pub primary: usize,
+    pub messages: Option<u64>,
+    pub fetch_time: Option<Duration>,
+    pub error: Option<String>,
+}
+
+impl SourceStatus {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            ..Self::default()
+        }
+    }
+
+    pub fn label(index: usize) -> String {
+        format!("R{}", index + 1)
+    }
+
+    pub fn host(&self) -> &str {
+        let rest = self
+            .url
+            .split_once("://")
+            .map_or(self.url.as_str(), |(_, rest)| rest);
+        rest.split(['/', '?']).next().unwrap_or(rest)
+    }
+}
+
+/// Receiver health from a dump1090/readsb `stats.json`, taken from its
+/// `last1min` period. Signal levels are in dBFS; the CPU time sums the
+/// demodulator, reader and background threads. `messages_by_df` counts
+/// accepted messages per downlink format where the decoder reports it.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReceiverStats {
+    pub period_secs: f64,
+    pub samples_processed: Option<u64>,
+    pub signal: Option<f64>,
+    pub noise: Option<f64>,
+    pub peak_signal: Option<f64>,
+    pub strong_signals: Option<u64>,
+    pub cpu_ms: Option<u64>,
+    pub messages_by_df: Option<[u64; 32]>,
+}
+
+#[derive(Deserialize)]
+struct StatsFileWire {
+    last1min: Option<StatsPeriodWire>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct StatsPeriodWire {
+    start: Option<f64>,
+    end: Option<f64>,
+    local: Option<StatsLocalWire>,
+    cpu: Option<StatsCpuWire>,
+    messages_by_df: Option<Vec<u64>>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct StatsLocalWire {
+    samples_processed: Option<u64>,
+    signal: Option<f64>,
+    noise: Option<f64>,
+    peak_signal: Option<f64>,
+    strong_signals: Option<u64>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default)]
+struct StatsCpuWire {
+    demod: Option<u64>,
+    reader: Option<u64>,
+    background: Option<u64>,
+}
+
+impl ReceiverStats {
+    pub fn from_json(body: &[u8]) -> Result<Self, String> {
+        let wire: StatsFileWire = serde_json::from_slice(body).map_err(|err| err.to_string())?;
+        let period = wire
+            .last1min
+            .ok_or_else(|| "no last1min period".to_string())?;
+        let local = period.local.unwrap_or_default();
+        let cpu_ms = period.cpu.and_then(|cpu| {
+            let parts = [cpu.demod, cpu.reader, cpu.background];
+            parts
+                .iter()
+                .any(Option::is_some)
+                .then(|| parts.iter().flatten().sum())
+        });
+        Ok(Self {
+            period_secs: match (period.start, period.end) {
+                (Some(start), Some(end)) if end > start => end - start,
+                _ => 60.0,
+            },
+            samples_processed: local.samples_processed,
+            signal: local.signal,
+            noise: local.noise,
+            peak_signal: local.peak_signal,
+            strong_signals: local.strong_signals,
+            cpu_ms,
+            messages_by_df: period.messages_by_df.map(|counts| {
+                let mut by_df = [0; 32];
+                for (slot, count) in by_df.iter_mut().zip(counts) {
+                    *slot = count;
+                }
+                by_df
+            }),
+        })
+    }
+
+    pub fn df_breakdown(&self) -> Option<[(&'static str, u64); 6]> {
+        let df = self.messages_by_df?;
+        let total: u64 = df.iter().sum();
+        if total == 0 {
+            return None;
+        }
+        let groups = [
+            ("DF17", df[17]),
+            ("DF18", df[18]),
+            ("DF4/5", df[4] + df[5]),
+            ("COMM-B", df[20] + df[21]),
+            ("DF11", df[11]),
+        ];
+        let grouped: u64 = groups.iter().map(|(_, count)| count).sum();
+        let [a, b, c, d, e] = groups;
+        Some([a, b, c, d, e, ("OTHER", total - grouped)])
+    }
+
+    pub fn msps(&self) -> Option<f64> {
+        self.samples_processed
+            .map(|samples| samples as f64 / self.period_secs / 1_000_000.0)
+    }
+
+    pub fn cpu_percent(&self) -> Option<f64> {
+        self.cpu_ms
+            .map(|ms| ms as f64 / (self.period_secs * 1000.0) * 100.0)
+    }
 }
 
 pub fn seen_seconds(ac: &Aircraft) -> Option<f64> {
@@ -89,6 +624,14 @@ pub fn seen_seconds(ac: &Aircraft) -> Option<f64> {
     }
 }
 
+pub fn message_seconds(ac: &Aircraft) -> Option<f64> {
+    ac.seen.or(ac.seen_pos)
+}
+
+pub fn position_seconds(ac: &Aircraft) -> Option<f64> {
+    ac.seen_pos.or(ac.seen)
+}
+
 fn de_opt_i64_from_any<'de, D>(deserializer: D) -> Result<Option<i64>, D::Error>
 where
     D: Deserializer<'de>,
@@ -112,13 +655,25 @@ where
             } else if let Ok(value) = trimmed.parse::<f64>() {
                 Ok(Some(value as i64))
             } else {
-                Ok(None)
+                Ok(field_warning())
             }
         }
         Value::Null => Ok(None),
-        other => Err(serde::de::Error::custom(format!(
-            "expected number or null, got {other}"
-        ))),
+        _ => Ok(field_warning()),
+    }
+}
+
+fn de_opt_altitude_from_any<'de, D>(deserializer: D) -> Result<Option<Altitude>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(text) if text.trim().eq_ignore_ascii_case("ground") => {
+            Ok(Some(Altitude::Ground))
+        }
+        other => Ok(de_opt_i64_from_any(other)
+            .map_err(serde::de::Error::custom)?
+            .map(Altitude::Feet)),
     }
 }
 
@@ -127,10 +682,7 @@ where
     D: Deserializer<'de>,
 {
     match Value::deserialize(deserializer)? {
-        Value::Number(number) => number
-            .as_f64()
-            .map(Some)
-            .ok_or_else(|| serde::de::Error::custom("expected float-compatible number")),
+        Value::Number(number) => Ok(number.as_f64().or_else(field_warning)),
         Value::String(text) => {
             let trimmed = text.trim();
             if trimmed.is_empty() {
@@ -140,13 +692,11 @@ where
             } else if let Ok(value) = trimmed.parse::<i64>() {
                 Ok(Some(value as f64))
             } else {
-                Ok(None)
+                Ok(field_warning())
             }
         }
         Value::Null => Ok(None),
-        other => Err(serde::de::Error::custom(format!(
-            "expected number or null, got {other}"
-        ))),
+        _ => Ok(field_warning()),
     }
 }
 
@@ -173,19 +723,37 @@ where
             } else if let Ok(value) = trimmed.parse::<f64>() {
                 Ok(Some(value.max(0.0) as u64))
             } else {
-                Ok(None)
+                Ok(field_warning())
             }
         }
         Value::Null => Ok(None),
-        other => Err(serde::de::Error::custom(format!(
-            "expected number or null, got {other}"
-        ))),
+        _ => Ok(field_warning()),
+    }
+}
+
+/// Squawk codes are four octal digits; some feeds send them as numbers,
+/// which loses leading zeros.
+fn de_opt_squawk_from_any<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Value::deserialize(deserializer)? {
+        Value::String(text) if text.trim().is_empty() => Ok(None),
+        Value::String(text) => Ok(Some(text.trim().to_string())),
+        Value::Number(number) => match number.as_u64() {
+            Some(code) => Ok(Some(format!("{code:04}"))),
+            None => Ok(field_warning()),
+        },
+        Value::Null => Ok(None),
+        _ => Ok(field_warning()),
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{seen_seconds, ApiResponse};
+    use super::{
+        seen_seconds, Aircraft, Altitude, ApiResponse, CategoryClass, DataSource, ReceiverStats,
+    };
 
     const MOCK: &str = r#"{
         "now": 1769903354,
@@ -204,6 +772,23 @@ mod tests {
                 "gs": 347.7,
                 "track": 339.64,
                 "baro_rate": -1024,
+                "geom_rate": -992,
+                "squawk": "7700",
+                "emergency": "general",
+                "ias": 260,
+                "tas": 370,
+                "mach": 0.604,
+                "roll": -1.2,
+                "track_rate": 0.03,
+                "true_heading": 335.1,
+                "nav_heading": 337.5,
+                "nav_modes": ["autopilot", "vnav", "tcas"],
+                "wd": 270,
+                "ws": 42,
+                "oat": -21,
+                "tat": -4,
+                "gva": 2,
+                "sda": 2,
                 "category": "A3",
                 "nav_qnh": 1013.6,
                 "nav_altitude_mcp": 19008,
@@ -226,7 +811,7 @@ mod tests {
                 "seen": 4.4,
                 "rssi": -3.7
             },
-            { "hex": "a716f6" },
+            { "hex": "a716f6", "alt_baro": "ground", "gs": 12.1 },
             { "hex": "a54118" },
             { "hex": "e80444" },
             { "hex": "ac0048" },
@@ -246,6 +831,32 @@ mod tests {
         assert_eq!(first.own_op.as_deref(), Some("SOUTHWEST AIRLINES CO"));
         assert_eq!(first.baro_rate, Some(-1024));
         assert_eq!(seen_seconds(first), Some(4.355));
+        assert_eq!(first.squawk.as_deref(), Some("7700"));
+        assert_eq!(first.emergency.as_deref(), Some("general"));
+        assert_eq!(first.geom_rate, Some(-992));
+        assert_eq!(first.ias, Some(260));
+        assert_eq!(first.mach, Some(0.604));
+        assert_eq!(first.true_heading, Some(335.1));
+        assert_eq!(
+            first.nav_modes.as_deref(),
+            Some(
+                &[
+                    "autopilot".to_string(),
+                    "vnav".to_string(),
+                    "tcas".to_string()
+                ][..]
+            )
+        );
+        assert_eq!((first.wd, first.ws), (Some(270), Some(42)));
+        assert_eq!((first.gva, first.sda), (Some(2), Some(2)));
+        assert_eq!(first.alt_baro, Some(Altitude::Feet(22925)));
+        assert!(!first.on_ground());
+
+        let surface = &data.aircraft[1];
+        assert_eq!(surface.alt_baro, Some(Altitude::Ground));
+        assert!(surface.on_ground());
+        assert_eq!(surface.alt_baro.map(Altitude::feet), Some(0));
+        assert_eq!(surface.alt_baro.unwrap().to_string(), "GND");
     }
 
     #[test]
@@ -255,4 +866,142 @@ mod tests {
         assert_eq!(data.now, Some(123));
         assert_eq!(data.messages, Some(42));
     }
+
+    #[test]
+    fn ground_altitude_round_trips() {
+        let data: ApiResponse =
+            serde_json::from_str(r#"{"aircraft": [{"alt_baro": "ground"}, {"alt_baro": 350}]}"#)
+                .unwrap();
+        let text = serde_json::to_string(&data).unwrap();
+        let back: ApiResponse = serde_json::from_str(&text).unwrap();
+        assert_eq!(back.aircraft[0].alt_baro, Some(Altitude::Ground));
+        assert_eq!(back.aircraft[1].alt_baro, Some(Altitude::Feet(350)));
+    }
+
+    #[test]
+    fn category_codes_decode_to_classes() {
+        assert_eq!(CategoryClass::from_code("A1"), Some(CategoryClass::Light));
+        assert_eq!(CategoryClass::from_code("a3"), Some(CategoryClass::Medium));
+        assert_eq!(
+            CategoryClass::from_code("A7"),
+            Some(CategoryClass::Rotorcraft)
+        );
+        assert_eq!(CategoryClass::from_code("B6"), Some(CategoryClass::Uav));
+        assert_eq!(CategoryClass::from_code("A0"), None);
+        assert_eq!(CategoryClass::from_code("B5"), None);
+
+        let mut ac = Aircraft {
+            category: Some("A5".to_string()),
+            t: Some("B744".to_string()),
+            ..Aircraft::default()
+        };
+        assert_eq!(ac.category_class(), Some(CategoryClass::Heavy));
+        ac.t = Some("A388".to_string());
+        assert_eq!(ac.category_class(), Some(CategoryClass::Super));
+        ac.category = None;
+        assert_eq!(ac.category_class(), None);
+    }
+
+    #[test]
+    fn malformed_values_are_counted_not_fatal() {
+        let data: ApiResponse = serde_json::from_str(
+            r#"{"aircraft": [
+                {"hex": "a1", "messages": "12.0", "nic": 8.0, "squawk": 1200},
+                {"hex": "a2", "nic": "eight", "gs": true, "squawk": 17},
+                {"hex": ["not", "a", "string"]},
+                {"hex": "a3", "seen": {"bad": 1}}
+            ]}"#,
+        )
+        .unwrap();
+
+        assert_eq!(data.aircraft.len(), 3);
+        assert_eq!(data.parse_warnings, 4);
+        let first = &data.aircraft[0];
+        assert_eq!(first.messages, Some(12));
+        assert_eq!(first.nic, Some(8));
+        assert_eq!(first.squawk.as_deref(), Some("1200"));
+        let second = &data.aircraft[1];
+        assert_eq!(second.nic, None);
+        assert_eq!(second.gs, None);
+        assert_eq!(second.squawk.as_deref(), Some("0017"));
+        assert_eq!(data.aircraft[2].seen, None);
+    }
+    #[test]
+    fn from_json_matches_serde_json() {
+        let expected: ApiResponse = serde_json::from_str(MOCK).unwrap();
+        let data = ApiResponse::from_json(MOCK.as_bytes().to_vec()).unwrap();
+        assert_eq!(
+            serde_json::to_value(&data).unwrap(),
+            serde_json::to_value(&expected).unwrap()
+        );
+
+        let data =
+            ApiResponse::from_json(br#"{"ac": [{"hex": "a1"}, {"hex": 7}], "now": "5"}"#.to_vec())
+                .unwrap();
+        assert_eq!(data.now, Some(5));
+        assert_eq!(data.aircraft.len(), 1);
+        assert_eq!(data.parse_warnings, 1);
+        assert!(ApiResponse::from_json(b"not json".to_vec()).is_err());
+    }
+
+    #[test]
+    fn receiver_stats_read_last_minute() {
+        let body = br#"{
+            "latest": {"start": 1700000050.0, "end": 1700000060.0},
+            "last1min": {
+                "start": 1700000000.0,
+                "end": 1700000060.0,
+                "local": {
+                    "samples_processed": 144000000,
+                    "signal": -14.2,
+                    "noise": -32.5,
+                    "peak_signal": -1.8,
+                    "strong_signals": 12
+                },
+                "cpu": {"demod": 9000, "reader": 2400, "background": 600},
+                "messages_by_df": [100, 0, 0, 0, 900, 600, 0, 0, 0, 0, 0, 2000, 0, 0, 0, 0,
+                    50, 40000, 1500, 0, 3000, 3850],
+                "messages": 52000
+            }
+        }"#;
+        let stats = ReceiverStats::from_json(body).unwrap();
+        assert_eq!(stats.signal, Some(-14.2));
+        assert_eq!(stats.noise, Some(-32.5));
+        assert_eq!(stats.peak_signal, Some(-1.8));
+        assert_eq!(stats.strong_signals, Some(12));
+        assert_eq!(stats.msps(), Some(2.4));
+        assert_eq!(stats.cpu_percent(), Some(20.0));
+        assert_eq!(
+            stats.df_breakdown(),
+            Some([
+                ("DF17", 40000),
+                ("DF18", 1500),
+                ("DF4/5", 1500),
+                ("COMM-B", 6850),
+                ("DF11", 2000),
+                ("OTHER", 150),
+            ])
+        );
+
+        let remote_only = ReceiverStats::from_json(br#"{"last1min": {"messages": 5}}"#).unwrap();
+        assert_eq!(remote_only.signal, None);
+        assert_eq!(remote_only.cpu_percent(), None);
+        assert_eq!(remote_only.df_breakdown(), None);
+        assert!(ReceiverStats::from_json(br#"{"total": {}}"#).is_err());
+    }
+
+    #[test]
+    fn data_source_groups_readsb_types() {
+        let data: ApiResponse = serde_json::from_str(MOCK).unwrap();
+        assert_eq!(data.aircraft[0].data_source(), Some(DataSource::Adsb));
+        assert_eq!(data.aircraft[1].data_source(), None);
+        assert_eq!(DataSource::from_kind("MLAT"), Some(DataSource::Mlat));
+        assert_eq!(
+            DataSource::from_kind("tisb_trackfile"),
+            Some(DataSource::Tisb)
+        );
+        assert_eq!(DataSource::from_kind("adsr_icao"), Some(DataSource::Adsr));
+        assert_eq!(DataSource::from_kind("unknown"), Some(DataSource::Other));
+        assert_eq!(DataSource::Adsr.label(), "ADS-R");
+    }
 }
@@ -1,38 +1,91 @@
-use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
-use std::thread;
 use std::time::{Duration, Instant};
 
-use crate::model::ApiResponse;
-use tracing::{debug, error, info};
+use reqwest::header::{
+    HeaderMap, HeaderValue, ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED,
+};
+use reqwest::StatusCode;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+use tokio::task::JoinSet;
+
+use crate::merge::merge_snapshots;
+use crate::model::{ApiResponse, FeedCache, ReceiverStats, SourceStatus};
+use crate::tasks::Tasks;
+use tracing::{debug, error, info, warn};
+
+const FEED_TIMEOUT: Duration = Duration::from_secs(5);
+/// Limit for DNS and the TCP/TLS handshake, so an unreachable host fails
+/// fast instead of using up the whole request timeout.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
 
 #[derive(Clone, Debug)]
 struct SourceState {
     url: String,
     attempts: u32,
     backoff_until: Option<Instant>,
+    validators: Validators,
+    /// Last full snapshot, kept by the merged fetcher so a receiver that
+    /// answers 304 still contributes its aircraft.
+    last: Option<ApiResponse>,
+}
+
+/// Cache validators from a feed's last full response, sent back as
+/// `If-None-Match` / `If-Modified-Since` so an unchanged file costs a 304
+/// with no body.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct Validators {
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_headers(headers: &HeaderMap) -> Self {
+        let text = |name| {
+            headers
+                .get(name)
+                .and_then(|value: &HeaderValue| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            etag: text(ETAG),
+            last_modified: text(LAST_MODIFIED),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    fn apply(&self, mut req: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        if let Some(etag) = &self.etag {
+            req = req.header(IF_NONE_MATCH, etag);
+        }
+        if let Some(last_modified) = &self.last_modified {
+            req = req.header(IF_MODIFIED_SINCE, last_modified);
+        }
+        req
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_fetcher(
+    tasks: &Tasks,
     urls: Vec<String>,
     refresh: Duration,
     insecure: bool,
     api_key: Option<String>,
     api_key_header: Option<String>,
-    update_rx: Receiver<Vec<String>>,
-    tx: Sender<Result<ApiResponse, String>>,
+    mut update_rx: UnboundedReceiver<Vec<String>>,
+    tx: UnboundedSender<Result<ApiResponse, String>>,
 ) {
-    thread::spawn(move || {
+    tasks.spawn("fetcher", async move {
         info!("fetcher started");
         let mut sources = build_sources(urls);
         if sources.is_empty() {
             let _ = tx.send(Err("No URLs configured".to_string()));
             return;
         }
-        let client = match reqwest::blocking::Client::builder()
-            .danger_accept_invalid_certs(insecure)
-            .timeout(Duration::from_secs(5))
-            .build()
-        {
+        let client = match build_async_client(insecure, FEED_TIMEOUT) {
             Ok(client) => client,
             Err(err) => {
                 error!("client error: {err}");
@@ -49,7 +102,7 @@ pub fn spawn_fetcher(
 
         let mut current = 0usize;
         loop {
-            drain_source_updates(&update_rx, &mut sources, &mut current);
+            drain_source_updates(&mut update_rx, &mut sources, &mut current);
             let now = Instant::now();
 
             // Find next source that is not in backoff.
@@ -72,16 +125,28 @@ pub fn spawn_fetcher(
             }
             if checked == sources.len() {
                 let wait = min_wait.unwrap_or(sleep);
-                wait_for_source_update(&update_rx, &mut sources, &mut current, wait);
+                wait_for_source_update(&mut update_rx, &mut sources, &mut current, wait).await;
                 continue;
             }
 
             let src = &mut sources[current];
-            let url = src.url.clone();
-            let outcome = fetch_once(&client, &url, api_key.as_deref(), api_key_header.as_deref());
+            let started = Instant::now();
+            let outcome = fetch_feed(
+                &client,
+                &src.url,
+                api_key.as_deref(),
+                api_key_header.as_deref(),
+                &mut src.validators,
+            )
+            .await;
 
             match outcome {
-                FetchResult::Ok(data) => {
+                FetchResult::Ok(mut data) => {
+                    data.fetch_time = Some(started.elapsed());
+                    data.cache = FeedCache {
+                        requests: 1,
+                        not_modified: 0,
+                    };
                     src.attempts = 0;
                     src.backoff_until = None;
                     if tx.send(Ok(data)).is_err() {
@@ -89,6 +154,23 @@ pub fn spawn_fetcher(
                         break;
                     }
                 }
+                FetchResult::NotModified => {
+                    src.attempts = 0;
+                    src.backoff_until = None;
+                    let data = ApiResponse {
+                        fetch_time: Some(started.elapsed()),
+                        not_modified: true,
+                        cache: FeedCache {
+                            requests: 1,
+                            not_modified: 1,
+                        },
+                        ..ApiResponse::default()
+                    };
+                    if tx.send(Ok(data)).is_err() {
+                        debug!("receiver dropped, exiting fetcher");
+                        break;
+                    }
+                }
                 FetchResult::Err {
                     message,
                     retry_after,
@@ -110,11 +192,351 @@ pub fn spawn_fetcher(
                 }
             }
 
-            wait_for_source_update(&update_rx, &mut sources, &mut current, sleep);
+            wait_for_source_update(&mut update_rx, &mut sources, &mut current, sleep).await;
+        }
+    });
+}
+
+/// Poll every source each interval and send one merged snapshot, for
+/// `merge_receivers`. A failing source backs off on its own while the others
+/// keep feeding the view; only when none answer is an error sent.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_merged_fetcher(
+    tasks: &Tasks,
+    urls: Vec<String>,
+    refresh: Duration,
+    insecure: bool,
+    api_key: Option<String>,
+    api_key_header: Option<String>,
+    mut update_rx: UnboundedReceiver<Vec<String>>,
+    tx: UnboundedSender<Result<ApiResponse, String>>,
+) {
+    tasks.spawn("merged-fetcher", async move {
+        let mut sources = build_sources(urls);
+        info!("merged fetcher started: {} receivers", sources.len());
+        if sources.is_empty() {
+            let _ = tx.send(Err("No URLs configured".to_string()));
+            return;
+        }
+        let client = match build_async_client(insecure, FEED_TIMEOUT) {
+            Ok(client) => client,
+            Err(err) => {
+                error!("client error: {err}");
+                let _ = tx.send(Err(format!("Client error: {err}")));
+                return;
+            }
+        };
+
+        let sleep = if refresh.is_zero() {
+            Duration::from_millis(200)
+        } else {
+            refresh
+        };
+
+        let mut current = 0usize;
+        let mut status: Vec<SourceStatus> = sources
+            .iter()
+            .map(|src| SourceStatus::new(&src.url))
+            .collect();
+        loop {
+            drain_source_updates(&mut update_rx, &mut sources, &mut current);
+            if !status
+                .iter()
+                .map(|s| &s.url)
+                .eq(sources.iter().map(|s| &s.url))
+            {
+                status = sources
+                    .iter()
+                    .map(|src| SourceStatus::new(&src.url))
+                    .collect();
+            }
+            let now = Instant::now();
+
+            // Start every due request before awaiting any of them. Dropping
+            // the set on shutdown aborts the ones still in flight.
+            let mut pending = JoinSet::new();
+            for (index, src) in sources.iter().enumerate() {
+                if src.backoff_until.is_some_and(|until| until > now) {
+                    continue;
+                }
+                let client = client.clone();
+                let url = src.url.clone();
+                let api_key = api_key.clone();
+                let api_key_header = api_key_header.clone();
+                let mut validators = src.validators.clone();
+                pending.spawn(async move {
+                    let started = Instant::now();
+                    let outcome = fetch_feed(
+                        &client,
+                        &url,
+                        api_key.as_deref(),
+                        api_key_header.as_deref(),
+                        &mut validators,
+                    )
+                    .await;
+                    (index, outcome, validators, started.elapsed())
+                });
+            }
+
+            let mut parts = Vec::new();
+            let mut last_err = None;
+            let mut cache = FeedCache::default();
+            let mut fresh = false;
+            while let Some(joined) = pending.join_next().await {
+                let Ok((index, outcome, validators, elapsed)) = joined else {
+                    continue;
+                };
+                let src = &mut sources[index];
+                src.validators = validators;
+                cache.requests += 1;
+                match outcome {
+                    FetchResult::Ok(mut data) => {
+                        data.fetch_time = Some(elapsed);
+                        src.attempts = 0;
+                        src.backoff_until = None;
+                        // Only worth a copy when the server can answer 304.
+                        src.last = (!src.validators.is_empty()).then(|| data.clone());
+                        fresh = true;
+                        parts.push((index, data));
+                    }
+                    FetchResult::NotModified => {
+                        src.attempts = 0;
+                        src.backoff_until = None;
+                        cache.not_modified += 1;
+                        if let Some(mut data) = src.last.clone() {
+                            data.fetch_time = Some(elapsed);
+                            parts.push((index, data));
+                        }
+                    }
+                    FetchResult::Err {
+                        message,
+                        retry_after,
+                    } => {
+                        src.attempts = src.attempts.saturating_add(1);
+                        let backoff = retry_after.unwrap_or_else(|| backoff_duration(src.attempts));
+                        src.backoff_until = Some(now + backoff);
+                        debug!(
+                            "receiver {} failed: {message} (backoff {backoff:?})",
+                            src.url
+                        );
+                        status[index].error = Some(message.clone());
+                        last_err = Some(message);
+                    }
+                }
+            }
+
+            // Merge in configuration order, whichever answered first.
+            parts.sort_by_key(|(index, _)| *index);
+            let result = if parts.is_empty() {
+                last_err.map(|message| Err(format!("All receivers failed: {message}")))
+            } else if !fresh && last_err.is_none() {
+                // Every receiver answered 304: nothing to merge again.
+                Some(Ok(ApiResponse {
+                    not_modified: true,
+                    cache,
+                    ..ApiResponse::default()
+                }))
+            } else {
+                let mut data = merge_snapshots(parts, &mut status);
+                data.sources = status.clone();
+                data.cache = cache;
+                Some(Ok(data))
+            };
+            if let Some(result) = result {
+                if tx.send(result).is_err() {
+                    debug!("receiver dropped, exiting merged fetcher");
+                    break;
+                }
+            }
+
+            wait_for_source_update(&mut update_rx, &mut sources, &mut current, sleep).await;
+        }
+    });
+}
+
+const STATS_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Poll the receiver's `stats.json`. It is rewritten once a minute, so the
+/// interval is long; a missing file backs off further instead of stopping, as
+/// the decoder may simply not be up yet.
+pub fn spawn_stats_fetcher(
+    tasks: &Tasks,
+    url: String,
+    insecure: bool,
+    tx: UnboundedSender<Result<ReceiverStats, String>>,
+) {
+    tasks.spawn("stats", async move {
+        info!("stats fetcher started: {url}");
+        let client = match build_async_client(insecure, FEED_TIMEOUT) {
+            Ok(client) => client,
+            Err(err) => {
+                error!("stats client error: {err}");
+                return;
+            }
+        };
+        let mut failures = 0u32;
+        loop {
+            let result = fetch_stats(&client, &url).await;
+            if let Err(err) = &result {
+                failures = failures.saturating_add(1);
+                if failures == 1 {
+                    warn!("stats fetch from {url} failed: {err}");
+                }
+            } else {
+                failures = 0;
+            }
+            if tx.send(result).is_err() {
+                debug!("receiver dropped, exiting stats fetcher");
+                return;
+            }
+            let wait = STATS_INTERVAL * failures.clamp(1, 8);
+            tokio::time::sleep(wait).await;
+        }
+    });
+}
+
+async fn fetch_stats(client: &reqwest::Client, url: &str) -> Result<ReceiverStats, String> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {status}"));
+    }
+    let body = resp.bytes().await.map_err(|err| err.to_string())?;
+    ReceiverStats::from_json(&body)
+}
+
+/// Fetch tar1090's `history_N.json` snapshots under `base` (the feed URL
+/// without `aircraft.json`) and send those from the last `minutes`, oldest
+/// first, so trails are filled in at startup. Nothing is sent when the
+/// receiver keeps no history.
+pub fn spawn_history_backfill(
+    tasks: &Tasks,
+    base: String,
+    minutes: u64,
+    insecure: bool,
+    tx: oneshot::Sender<Vec<ApiResponse>>,
+) {
+    tasks.spawn("history-backfill", async move {
+        let client = match build_async_client(insecure, FEED_TIMEOUT) {
+            Ok(client) => client,
+            Err(err) => {
+                error!("history client error: {err}");
+                return;
+            }
+        };
+        match fetch_history(&client, &base).await {
+            Ok(snapshots) => {
+                let snapshots = recent_history(snapshots, minutes);
+                info!(
+                    "history backfill: {} snapshots from {base}",
+                    snapshots.len()
+                );
+                let _ = tx.send(snapshots);
+            }
+            Err(err) => warn!("history backfill from {base} failed: {err}"),
         }
     });
 }
 
+async fn fetch_history(client: &reqwest::Client, base: &str) -> Result<Vec<ApiResponse>, String> {
+    let receiver = fetch_bytes(client, &format!("{base}receiver.json")).await?;
+    let receiver: serde_json::Value =
+        serde_json::from_slice(&receiver).map_err(|err| err.to_string())?;
+    let count = receiver
+        .get("history")
+        .and_then(serde_json::Value::as_u64)
+        .ok_or("receiver.json lists no history files")?;
+    let mut pending = JoinSet::new();
+    for index in 0..count {
+        let client = client.clone();
+        let url = format!("{base}history_{index}.json");
+        pending.spawn(async move {
+            fetch_bytes(&client, &url)
+                .await
+                .and_then(ApiResponse::from_json)
+        });
+    }
+    let mut snapshots = Vec::new();
+    while let Some(joined) = pending.join_next().await {
+        match joined {
+            Ok(Ok(data)) => snapshots.push(data),
+            Ok(Err(err)) => debug!("history file skipped: {err}"),
+            Err(_) => {}
+        }
+    }
+    Ok(snapshots)
+}
+
+async fn fetch_bytes(client: &reqwest::Client, url: &str) -> Result<Vec<u8>, String> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("{url}: HTTP {status}"));
+    }
+    let body = resp.bytes().await.map_err(|err| err.to_string())?;
+    Ok(body.to_vec())
+}
+
+fn recent_history(mut snapshots: Vec<ApiResponse>, minutes: u64) -> Vec<ApiResponse> {
+    snapshots.retain(|data| data.now.is_some());
+    snapshots.sort_by_key(|data| data.now);
+    let newest = snapshots
+        .last()
+        .and_then(|data| data.now)
+        .unwrap_or_default();
+    let cutoff = newest.saturating_sub(minutes.saturating_mul(60) as i64);
+    snapshots.retain(|data| data.now.is_some_and(|now| now >= cutoff));
+    snapshots
+}
+
+pub fn build_client(insecure: bool) -> reqwest::Result<reqwest::blocking::Client> {
+    reqwest::blocking::Client::builder()
+        .danger_accept_invalid_certs(insecure)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(FEED_TIMEOUT)
+        .build()
+}
+
+/// Client for the background tasks. Requests are futures on the shared
+/// runtime: shutdown drops them mid-flight, and a slow DNS lookup or
+/// handshake holds up only its own request.
+pub fn build_async_client(insecure: bool, timeout: Duration) -> reqwest::Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .danger_accept_invalid_certs(insecure)
+        .connect_timeout(CONNECT_TIMEOUT.min(timeout))
+        .timeout(timeout)
+        .build()
+}
+
+pub fn fetch_first(
+    client: &reqwest::blocking::Client,
+    urls: &[String],
+    api_key: Option<&str>,
+    api_key_header: Option<&str>,
+) -> Result<ApiResponse, String> {
+    let mut last_err = "No URLs configured".to_string();
+    for url in urls.iter().map(|u| u.trim()).filter(|u| !u.is_empty()) {
+        match fetch_once(client, url, api_key, api_key_header) {
+            FetchResult::Ok(data) => return Ok(data),
+            // No validators are sent here, so a 304 is a misbehaving server.
+            FetchResult::NotModified => last_err = "HTTP 304 Not Modified".to_string(),
+            FetchResult::Err { message, .. } => {
+                debug!("fetch {url} failed: {message}");
+                last_err = message;
+            }
+        }
+    }
+    Err(last_err)
+}
+
 fn build_sources(urls: Vec<String>) -> Vec<SourceState> {
     urls.into_iter()
         .map(|u| u.trim().to_string())
@@ -123,6 +545,8 @@ fn build_sources(urls: Vec<String>) -> Vec<SourceState> {
             url,
             attempts: 0,
             backoff_until: None,
+            validators: Validators::default(),
+            last: None,
         })
         .collect()
 }
@@ -147,7 +571,7 @@ fn apply_source_update(
 }
 
 fn drain_source_updates(
-    update_rx: &Receiver<Vec<String>>,
+    update_rx: &mut UnboundedReceiver<Vec<String>>,
     sources: &mut Vec<SourceState>,
     current: &mut usize,
 ) {
@@ -156,15 +580,20 @@ fn drain_source_updates(
     }
 }
 
-fn wait_for_source_update(
-    update_rx: &Receiver<Vec<String>>,
+async fn wait_for_source_update(
+    update_rx: &mut UnboundedReceiver<Vec<String>>,
     sources: &mut Vec<SourceState>,
     current: &mut usize,
     timeout: Duration,
 ) -> bool {
-    match update_rx.recv_timeout(timeout) {
-        Ok(urls) => apply_source_update(sources, current, urls),
-        Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => false,
+    match tokio::time::timeout(timeout, update_rx.recv()).await {
+        Ok(Some(urls)) => apply_source_update(sources, current, urls),
+        // With the sender gone there is nothing to wait for but the timer.
+        Ok(None) => {
+            tokio::time::sleep(timeout).await;
+            false
+        }
+        Err(_) => false,
     }
 }
 
@@ -175,36 +604,101 @@ fn fetch_once(
     api_key_header: Option<&str>,
 ) -> FetchResult {
     let mut req = client.get(url);
-    if let (Some(key), Some(header)) = (api_key, api_key_header) {
-        if !key.trim().is_empty() && !header.trim().is_empty() {
-            req = req.header(header, key);
+    if let Some((header, key)) = api_key_pair(api_key, api_key_header) {
+        req = req.header(header, key);
+    }
+    match req.send() {
+        Ok(resp) => {
+            let (status, headers) = (resp.status(), resp.headers().clone());
+            let body = resp.bytes().map(Vec::from).map_err(|err| err.to_string());
+            feed_result(status, &headers, body, &mut Validators::default())
         }
+        Err(err) => FetchResult::Err {
+            message: err.to_string(),
+            retry_after: None,
+        },
     }
-    let resp = match req.send() {
-        Ok(resp) => resp,
-        Err(err) => {
-            return FetchResult::Err {
-                message: err.to_string(),
-                retry_after: None,
-            }
+}
+
+async fn fetch_feed(
+    client: &reqwest::Client,
+    url: &str,
+    api_key: Option<&str>,
+    api_key_header: Option<&str>,
+    validators: &mut Validators,
+) -> FetchResult {
+    let mut req = validators.apply(client.get(url));
+    if let Some((header, key)) = api_key_pair(api_key, api_key_header) {
+        req = req.header(header, key);
+    }
+    match req.send().await {
+        Ok(resp) => {
+            let (status, headers) = (resp.status(), resp.headers().clone());
+            let body = resp
+                .bytes()
+                .await
+                .map(Vec::from)
+                .map_err(|err| err.to_string());
+            feed_result(status, &headers, body, validators)
         }
-    };
+        Err(err) => FetchResult::Err {
+            message: err.to_string(),
+            retry_after: None,
+        },
+    }
+}
 
-    let status = resp.status();
+fn api_key_pair<'a>(
+    api_key: Option<&'a str>,
+    api_key_header: Option<&'a str>,
+) -> Option<(&'a str, &'a str)> {
+    match (api_key, api_key_header) {
+        (Some(key), Some(header)) if !key.trim().is_empty() && !header.trim().is_empty() => {
+            Some((header, key))
+        }
+        _ => None,
+    }
+}
+
+fn feed_result(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: Result<Vec<u8>, String>,
+    validators: &mut Validators,
+) -> FetchResult {
+    if status == StatusCode::NOT_MODIFIED {
+        return FetchResult::NotModified;
+    }
     if !status.is_success() {
-        let retry_after =
-            retry_after_header(resp.headers()).or_else(|| parse_retry_after_msg(resp));
-        let message = format!("HTTP {}", status);
+        let retry_after = retry_after_header(headers).or_else(|| {
+            body.ok()
+                .and_then(|body| parse_retry_after_text(&String::from_utf8_lossy(&body)))
+        });
         return FetchResult::Err {
-            message,
+            message: format!("HTTP {}", status),
             retry_after,
         };
     }
 
-    match resp.json::<ApiResponse>() {
-        Ok(data) => FetchResult::Ok(data),
-        Err(err) => FetchResult::Err {
-            message: err.to_string(),
+    let body = match body {
+        Ok(body) => body,
+        Err(message) => {
+            return FetchResult::Err {
+                message,
+                retry_after: None,
+            }
+        }
+    };
+    match ApiResponse::from_json(body) {
+        Ok(data) => {
+            *validators = Validators::from_headers(headers);
+            if data.parse_warnings > 0 {
+                debug!("{} unparseable value(s) in feed", data.parse_warnings);
+            }
+            FetchResult::Ok(data)
+        }
+        Err(message) => FetchResult::Err {
+            message,
             retry_after: None,
         },
     }
@@ -213,13 +707,14 @@ fn fetch_once(
 #[derive(Debug)]
 enum FetchResult {
     Ok(ApiResponse),
+    NotModified,
     Err {
         message: String,
         retry_after: Option<Duration>,
     },
 }
 
-fn retry_after_header(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+fn retry_after_header(headers: &HeaderMap) -> Option<Duration> {
     headers
         .get(reqwest::header::RETRY_AFTER)
         .and_then(|v| v.to_str().ok())
@@ -230,21 +725,19 @@ fn parse_retry_after_value(value: &str) -> Option<Duration> {
     value.trim().parse::<u64>().ok().map(Duration::from_secs)
 }
 
-fn parse_retry_after_msg(resp: reqwest::blocking::Response) -> Option<Duration> {
-    // If Retry-After header is absent, try to parse from an error string if available.
-    if let Ok(text) = resp.text() {
-        if let Some(idx) = text.to_ascii_lowercase().find("retry-after=") {
-            let tail = &text[idx + "retry-after=".len()..];
-            if let Some(end) = tail.find(|c: char| [' ', ';', '\n'].contains(&c)) {
-                if let Ok(secs) = tail[..end].trim_end_matches('s').parse::<u64>() {
-                    return Some(Duration::from_secs(secs));
-                }
-            } else if let Ok(secs) = tail.trim_end_matches('s').parse::<u64>() {
-                return Some(Duration::from_secs(secs));
-            }
-        }
-    }
-    None
+fn parse_retry_after_text(text: &str) -> Option<Duration> {
+    // If Retry-After header is absent, try to parse it from the error body.
+    let idx = text.to_ascii_lowercase().find("retry-after=")?;
+    let tail = &text[idx + "retry-after=".len()..];
+    let value = match tail.find(|c: char| [' ', ';', '\n'].contains(&c)) {
+        Some(end) => &tail[..end],
+        None => tail,
+    };
+    value
+        .trim_end_matches('s')
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
 }
 
 fn backoff_duration(attempts: u32) -> Duration {
@@ -260,7 +753,112 @@ fn backoff_duration(attempts: u32) -> Duration {
 
 #[cfg(test)]
 mod source_tests {
-    use super::{apply_source_update, build_sources, source_urls};
+    use super::{
+        apply_source_update, build_sources, feed_result, recent_history, source_urls, FetchResult,
+        Validators,
+    };
+    use crate::model::ApiResponse;
+    use reqwest::header::{HeaderMap, HeaderValue, ETAG, LAST_MODIFIED, RETRY_AFTER};
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    #[test]
+    fn history_keeps_the_last_minutes_oldest_first() {
+        let snapshot = |now: Option<i64>| ApiResponse {
+            now,
+            ..ApiResponse::default()
+        };
+        let snapshots = vec![
+            snapshot(Some(1_000)),
+            snapshot(Some(400)),
+            snapshot(None),
+            snapshot(Some(300)),
+            snapshot(Some(700)),
+        ];
+        let kept: Vec<_> = recent_history(snapshots, 10)
+            .iter()
+            .map(|data| data.now)
+            .collect();
+        assert_eq!(kept, [Some(400), Some(700), Some(1_000)]);
+    }
+
+    #[test]
+    fn feed_result_reads_back_off_hints() {
+        let ok = feed_result(
+            StatusCode::OK,
+            &HeaderMap::new(),
+            Ok(br#"{"now":1,"aircraft":[]}"#.to_vec()),
+            &mut Validators::default(),
+        );
+        assert!(matches!(ok, FetchResult::Ok(data) if data.now == Some(1)));
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("30"));
+        let limited = feed_result(
+            StatusCode::TOO_MANY_REQUESTS,
+            &headers,
+            Ok(Vec::new()),
+            &mut Validators::default(),
+        );
+        assert!(matches!(
+            limited,
+            FetchResult::Err { retry_after: Some(wait), .. } if wait == Duration::from_secs(30)
+        ));
+
+        let body = b"slow down; retry-after=12s please".to_vec();
+        let hinted = feed_result(
+            StatusCode::SERVICE_UNAVAILABLE,
+            &HeaderMap::new(),
+            Ok(body),
+            &mut Validators::default(),
+        );
+        assert!(matches!(
+            hinted,
+            FetchResult::Err { message, retry_after: Some(wait) }
+                if message.starts_with("HTTP 503") && wait == Duration::from_secs(12)
+        ));
+    }
+
+    #[test]
+    fn full_responses_keep_validators_for_the_next_poll() {
+        let mut validators = Validators::default();
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"65f1-1a2b\""));
+        headers.insert(
+            LAST_MODIFIED,
+            HeaderValue::from_static("Tue, 13 Oct 2026 10:00:00 GMT"),
+        );
+        let ok = feed_result(
+            StatusCode::OK,
+            &headers,
+            Ok(br#"{"aircraft":[]}"#.to_vec()),
+            &mut validators,
+        );
+        assert!(matches!(ok, FetchResult::Ok(_)));
+        assert_eq!(validators.etag.as_deref(), Some("\"65f1-1a2b\""));
+        assert!(validators.last_modified.is_some());
+
+        let unchanged = feed_result(
+            StatusCode::NOT_MODIFIED,
+            &HeaderMap::new(),
+            Ok(Vec::new()),
+            &mut validators,
+        );
+        assert!(matches!(unchanged, FetchResult::NotModified));
+        assert!(!validators.is_empty(), "a 304 keeps the old validators");
+
+        let failed = feed_result(
+            StatusCode::OK,
+            &HeaderMap::new(),
+            Ok(b"not json".to_vec()),
+            &mut validators,
+        );
+        assert!(matches!(failed, FetchResult::Err { .. }));
+        assert!(
+            !validators.is_empty(),
+            "a bad body keeps the old validators"
+        );
+    }
 
     #[test]
     fn source_update_rebuilds_and_trims_urls() {
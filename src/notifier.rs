@@ -0,0 +1,195 @@
+//! Desktop notifications: `notify-send`, `osascript`, or the terminal bell.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tracing::{debug, warn};
+
+use crate::tasks::{self, Tasks};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlertKind {
+    Near,
+    Over,
+    Cpa,
+    Watch,
+    Emergency,
+    Zone,
+}
+
+impl AlertKind {
+    pub const ALL: [AlertKind; 6] = [
+        AlertKind::Near,
+        AlertKind::Over,
+        AlertKind::Cpa,
+        AlertKind::Watch,
+        AlertKind::Emergency,
+        AlertKind::Zone,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            AlertKind::Near => "near",
+            AlertKind::Over => "over",
+            AlertKind::Cpa => "cpa",
+            AlertKind::Watch => "watch",
+            AlertKind::Emergency => "emergency",
+            AlertKind::Zone => "zone",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AlertKinds {
+    enabled: [bool; AlertKind::ALL.len()],
+}
+
+impl AlertKinds {
+    /// Parse a list such as "near watch" or "near,over"; "all" turns on every
+    /// type and "" or "off" none.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut alerts = Self::default();
+        for word in value
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|word| !word.is_empty())
+        {
+            match word.to_ascii_lowercase().as_str() {
+                "off" | "none" => {}
+                "all" => alerts.enabled = [true; AlertKind::ALL.len()],
+                name => {
+                    let kind = AlertKind::ALL
+                        .into_iter()
+                        .find(|kind| kind.name() == name)
+                        .ok_or_else(|| {
+                            format!(
+                                "unknown alert type \"{word}\" (near, over, cpa, watch, emergency, zone, all)"
+                            )
+                        })?;
+                    alerts.enabled[kind as usize] = true;
+                }
+            }
+        }
+        Ok(alerts)
+    }
+
+    pub fn from_str(value: &str) -> Self {
+        Self::parse(value).unwrap_or_else(|_| {
+            let known = value
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|word| Self::parse(word).is_ok())
+                .collect::<Vec<_>>()
+                .join(" ");
+            Self::parse(&known).unwrap_or_default()
+        })
+    }
+
+    pub fn enabled(&self, kind: AlertKind) -> bool {
+        self.enabled[kind as usize]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AlertEvent {
+    pub kind: AlertKind,
+    pub message: String,
+    pub hex: String,
+    pub callsign: String,
+    pub reg: String,
+    pub squawk: String,
+    pub distance_mi: Option<f64>,
+    pub alt_ft: Option<i64>,
+    pub at: i64,
+}
+
+pub fn spawn_notifier(tasks: &Tasks) -> UnboundedSender<AlertEvent> {
+    let (tx, rx) = unbounded_channel();
+    tasks.spawn("notifier", run_notifier(rx));
+    tx
+}
+
+async fn run_notifier(mut rx: UnboundedReceiver<AlertEvent>) {
+    let mut bell_only = cfg!(not(unix));
+    while let Some(alert) = rx.recv().await {
+        if !bell_only {
+            let shown = tasks::blocking(move || show(&alert)).await;
+            match shown {
+                Some(Ok(())) => continue,
+                Some(Err(err)) => {
+                    warn!("desktop notification failed, using the terminal bell: {err}");
+                    bell_only = true;
+                }
+                None => continue,
+            }
+        }
+        ring_bell();
+    }
+}
+
+fn show(alert: &AlertEvent) -> io::Result<()> {
+    debug!(
+        "desktop notification {}: {}",
+        alert.kind.name(),
+        alert.message
+    );
+    let mut command = notify_command("ADS-B TUI", &alert.message);
+    let status = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("notifier exited with {status}")))
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn notify_command(title: &str, body: &str) -> Command {
+    let quote = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+    let mut command = Command::new("osascript");
+    command.arg("-e").arg(format!(
+        "display notification \"{}\" with title \"{}\"",
+        quote(body),
+        quote(title)
+    ));
+    command
+}
+
+#[cfg(not(target_os = "macos"))]
+fn notify_command(title: &str, body: &str) -> Command {
+    let mut command = Command::new("notify-send");
+    command.arg("--app-name=adsb-tui").arg(title).arg(body);
+    command
+}
+
+fn ring_bell() {
+    let mut stdout = io::stdout();
+    let _ = stdout.write_all(b"\x07");
+    let _ = stdout.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AlertKind, AlertKinds};
+
+    #[test]
+    fn parses_alert_type_lists() {
+        let alerts = AlertKinds::parse("near, WATCH").unwrap();
+        assert!(alerts.enabled(AlertKind::Near));
+        assert!(alerts.enabled(AlertKind::Watch));
+        assert!(!alerts.enabled(AlertKind::Over));
+        assert!(!alerts.enabled(AlertKind::Cpa));
+
+        assert_eq!(AlertKinds::parse(""), Ok(AlertKinds::default()));
+        assert_eq!(AlertKinds::parse("off"), Ok(AlertKinds::default()));
+        let all = AlertKinds::parse("all").unwrap();
+        assert!(AlertKind::ALL.into_iter().all(|kind| all.enabled(kind)));
+
+        assert!(AlertKinds::parse("near overhead").is_err());
+        let lenient = AlertKinds::from_str("near overhead");
+        assert!(lenient.enabled(AlertKind::Near));
+        assert!(!lenient.enabled(AlertKind::Over));
+    }
+}
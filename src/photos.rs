@@ -0,0 +1,310 @@
+//! planespotters.net photo metadata, cached on disk by hex.
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use reqwest::header::USER_AGENT;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tracing::{debug, error, info};
+
+use crate::net::build_async_client;
+use crate::tasks::Tasks;
+
+const PHOTO_TIMEOUT: Duration = Duration::from_secs(8);
+const PHOTO_CACHE_CAP: usize = 1_000;
+/// planespotters.net rejects requests without a user agent.
+const CLIENT_NAME: &str = concat!("adsb-tui/", env!("CARGO_PKG_VERSION"));
+
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct PhotoInfo {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail_large: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub photographer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub airline: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub first_flight: Option<i32>,
+    pub fetched_at: i64,
+}
+
+impl PhotoInfo {
+    pub fn has_photo(&self) -> bool {
+        self.thumbnail.is_some() || self.link.is_some()
+    }
+
+    /// The first photo of a `/pub/photos/hex/{hex}` answer. Airframe fields
+    /// are read from the photo or a top-level `airframe` object when
+    /// present; the public API does not always include them.
+    pub fn from_json(body: &Value, now_secs: i64) -> Self {
+        let photo = body
+            .get("photos")
+            .and_then(Value::as_array)
+            .and_then(|photos| photos.first());
+        let text = |value: Option<&Value>| {
+            value
+                .and_then(Value::as_str)
+                .map(str::trim)
+                .filter(|text| !text.is_empty())
+                .map(str::to_string)
+        };
+        let src = |key: &str| text(photo.and_then(|p| p.get(key)).and_then(|t| t.get("src")));
+        let airframe = |keys: &[&str]| {
+            [photo, body.get("airframe")]
+                .into_iter()
+                .flatten()
+                .flat_map(|object| keys.iter().filter_map(|key| object.get(*key)))
+                .next()
+        };
+        let first_flight = airframe(&["first_flight", "first_flight_date"]).and_then(|value| {
+            value
+                .as_i64()
+                .or_else(|| value.as_str()?.get(..4)?.parse().ok())
+                .and_then(|year| i32::try_from(year).ok())
+        });
+        Self {
+            thumbnail: src("thumbnail"),
+            thumbnail_large: src("thumbnail_large"),
+            link: text(photo.and_then(|p| p.get("link"))),
+            photographer: text(photo.and_then(|p| p.get("photographer"))),
+            airline: text(airframe(&["airline", "operator"])),
+            first_flight,
+            fetched_at: now_secs,
+        }
+    }
+}
+
+/// Photo answers by lowercase hex; entries older than the TTL are ignored
+/// and dropped on load. Past `PHOTO_CACHE_CAP` the least recently used
+/// entry goes.
+#[derive(Debug, Default)]
+pub struct PhotoCache {
+    entries: HashMap<String, (PhotoInfo, u64)>,
+    ttl_secs: i64,
+    clock: u64,
+}
+
+impl PhotoCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            entries: HashMap::new(),
+            ttl_secs: i64::try_from(ttl.as_secs()).unwrap_or(i64::MAX),
+            clock: 0,
+        }
+    }
+
+    pub fn with_entries(
+        mut self,
+        entries: impl IntoIterator<Item = (String, PhotoInfo)>,
+        now_secs: i64,
+    ) -> Self {
+        let mut fresh: Vec<_> = entries
+            .into_iter()
+            .filter(|(_, info)| self.is_fresh(info, now_secs))
+            .collect();
+        fresh.sort_by_key(|(_, info)| info.fetched_at);
+        for (hex, info) in fresh {
+            self.insert(&hex, info);
+        }
+        self
+    }
+
+    fn is_fresh(&self, info: &PhotoInfo, now_secs: i64) -> bool {
+        now_secs.saturating_sub(info.fetched_at) < self.ttl_secs
+    }
+
+    pub fn get(&self, hex: &str, now_secs: i64) -> Option<&PhotoInfo> {
+        self.entries
+            .get(&hex.trim().to_ascii_lowercase())
+            .map(|(info, _)| info)
+            .filter(|info| self.is_fresh(info, now_secs))
+    }
+
+    pub fn touch(&mut self, hex: &str) {
+        if let Some((_, used)) = self.entries.get_mut(&hex.trim().to_ascii_lowercase()) {
+            self.clock += 1;
+            *used = self.clock;
+        }
+    }
+
+    pub fn insert(&mut self, hex: &str, info: PhotoInfo) {
+        self.clock += 1;
+        self.entries
+            .insert(hex.trim().to_ascii_lowercase(), (info, self.clock));
+        if self.entries.len() > PHOTO_CACHE_CAP {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(hex, _)| hex.clone());
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = (&String, &PhotoInfo)> {
+        self.entries.iter().map(|(hex, (info, _))| (hex, info))
+    }
+}
+
+#[derive(Debug)]
+pub enum PhotoMessage {
+    Result { hex: String, info: PhotoInfo },
+    Error { hex: String, message: String },
+}
+
+pub fn spawn_photo_fetcher(
+    tasks: &Tasks,
+    base_url: String,
+    insecure: bool,
+    mut rx: UnboundedReceiver<String>,
+    tx: UnboundedSender<PhotoMessage>,
+) {
+    let pool = tasks.pool().clone();
+    tasks.spawn("photos", async move {
+        info!("photo fetcher started");
+        let base = base_url.trim_end_matches('/').to_string();
+        // Built on the first request; most sessions never open the tab.
+        let mut client: Option<reqwest::Client> = None;
+
+        while let Some(hex) = rx.recv().await {
+            let client = match client.as_ref() {
+                Some(client) => client.clone(),
+                None => match build_async_client(insecure, PHOTO_TIMEOUT) {
+                    Ok(built) => client.insert(built).clone(),
+                    Err(err) => {
+                        error!("photo client error: {err}");
+                        let message = format!("Client error: {err}");
+                        let _ = tx.send(PhotoMessage::Error { hex, message });
+                        return;
+                    }
+                },
+            };
+            let result = pool
+                .run(&base, fetch_photo(&client, &base, &hex))
+                .await
+                .unwrap_or_else(|| Err("Photo lookup aborted".to_string()));
+            let message = match result {
+                Ok(info) => {
+                    debug!("photo {hex}: found={}", info.has_photo());
+                    PhotoMessage::Result { hex, info }
+                }
+                Err(message) => PhotoMessage::Error { hex, message },
+            };
+            if tx.send(message).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+async fn fetch_photo(client: &reqwest::Client, base: &str, hex: &str) -> Result<PhotoInfo, String> {
+    let url = format!("{base}/hex/{}", hex.trim().to_ascii_lowercase());
+    let resp = client
+        .get(&url)
+        .header(USER_AGENT, CLIENT_NAME)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {status}"));
+    }
+    let body: Value = resp.json().await.map_err(|err| err.to_string())?;
+    let now_secs = chrono::Utc::now().timestamp();
+    Ok(PhotoInfo::from_json(&body, now_secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PhotoCache, PhotoInfo, PHOTO_CACHE_CAP};
+    use std::time::Duration;
+
+    #[test]
+    fn reads_the_first_photo_and_airframe_fields() {
+        let body = serde_json::json!({
+            "photos": [{
+                "id": "123",
+                "thumbnail": {"src": "https://t.plnspttrs.net/1_t.jpg", "size": {"width": 200}},
+                "thumbnail_large": {"src": "https://t.plnspttrs.net/1_280.jpg"},
+                "link": "https://www.planespotters.net/photo/123",
+                "photographer": "A. Spotter",
+                "airline": "Aer Lingus"
+            }, {
+                "id": "456",
+                "photographer": "Someone Else"
+            }],
+            "airframe": {"first_flight_date": "2009-05-14"}
+        });
+        let info = PhotoInfo::from_json(&body, 1_000);
+        assert!(info.has_photo());
+        assert_eq!(
+            info.thumbnail.as_deref(),
+            Some("https://t.plnspttrs.net/1_t.jpg")
+        );
+        assert_eq!(info.photographer.as_deref(), Some("A. Spotter"));
+        assert_eq!(info.airline.as_deref(), Some("Aer Lingus"));
+        assert_eq!(info.first_flight, Some(2009));
+
+        let none = PhotoInfo::from_json(&serde_json::json!({"photos": []}), 1_000);
+        assert!(!none.has_photo());
+        assert_eq!(none.fetched_at, 1_000);
+    }
+
+    #[test]
+    fn cache_entries_expire_after_the_ttl() {
+        let stale = PhotoInfo {
+            fetched_at: 0,
+            ..PhotoInfo::default()
+        };
+        let fresh = PhotoInfo {
+            fetched_at: 5_000,
+            ..PhotoInfo::default()
+        };
+        let mut cache = PhotoCache::new(Duration::from_secs(3_600)).with_entries(
+            [("ABC123".to_string(), fresh), ("def456".to_string(), stale)],
+            6_000,
+        );
+        assert!(cache.get("abc123", 6_000).is_some());
+        assert!(cache.get("def456", 6_000).is_none());
+        assert!(cache.get("abc123", 9_000).is_none(), "expired in memory");
+        cache.insert(" DEF456 ", PhotoInfo::default());
+        assert_eq!(cache.entries().count(), 2);
+    }
+
+    #[test]
+    fn cache_drops_the_least_recently_used_past_the_cap() {
+        let saved = (0..PHOTO_CACHE_CAP as i64 + 5).map(|n| {
+            let info = PhotoInfo {
+                fetched_at: n,
+                ..PhotoInfo::default()
+            };
+            (format!("{n:06x}"), info)
+        });
+        let mut cache = PhotoCache::new(Duration::from_secs(3_600)).with_entries(saved, 100);
+        assert_eq!(cache.len(), PHOTO_CACHE_CAP);
+        assert!(cache.get("000004", 100).is_none(), "oldest fetches dropped");
+        assert!(cache.get("000005", 100).is_some());
+
+        cache.touch("000005");
+        cache.insert("abcdef", PhotoInfo::default());
+        assert_eq!(cache.len(), PHOTO_CACHE_CAP);
+        assert!(cache.get("000005", 100).is_some(), "recently used");
+        assert!(cache.get("000006", 100).is_none());
+    }
+}
@@ -0,0 +1,107 @@
+//! Worker pool for enrichment requests, spaced out per host.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tokio::sync::Semaphore;
+use tracing::debug;
+
+#[derive(Clone, Debug)]
+pub struct WorkerPool {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    workers: Semaphore,
+    next_slot: Mutex<HashMap<String, Instant>>,
+    host_interval: Duration,
+}
+
+impl WorkerPool {
+    pub fn new(workers: usize, host_interval: Duration) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                workers: Semaphore::new(workers.max(1)),
+                next_slot: Mutex::new(HashMap::new()),
+                host_interval,
+            }),
+        }
+    }
+
+    /// Run request work against `url`'s host once its rate-limit slot comes
+    /// up and a worker is free. Jobs wait in FIFO order. `None` if the pool
+    /// was closed.
+    pub async fn run<T, F>(&self, url: &str, work: F) -> Option<T>
+    where
+        F: Future<Output = T>,
+    {
+        let host = host_key(url);
+        let wait = self.reserve(&host, Instant::now());
+        if !wait.is_zero() {
+            debug!("pool: {host} rate limited, waiting {}ms", wait.as_millis());
+            tokio::time::sleep(wait).await;
+        }
+        let _permit = self.inner.workers.acquire().await.ok()?;
+        Some(work.await)
+    }
+
+    fn reserve(&self, host: &str, now: Instant) -> Duration {
+        let mut slots = self
+            .inner
+            .next_slot
+            .lock()
+            .unwrap_or_else(|err| err.into_inner());
+        let start = slots.get(host).map_or(now, |next| (*next).max(now));
+        slots.insert(host.to_string(), start + self.inner.host_interval);
+        start - now
+    }
+}
+
+fn host_key(url: &str) -> String {
+    reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| {
+            parsed.host_str().map(|host| match parsed.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host.to_string(),
+            })
+        })
+        .unwrap_or_else(|| url.trim().to_string())
+        .to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{host_key, WorkerPool};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn requests_to_one_host_are_spaced() {
+        let pool = WorkerPool::new(2, Duration::from_millis(500));
+        let now = Instant::now();
+        assert_eq!(pool.reserve("api.example", now), Duration::ZERO);
+        assert_eq!(pool.reserve("api.example", now), Duration::from_millis(500));
+        assert_eq!(
+            pool.reserve("api.example", now + Duration::from_millis(200)),
+            Duration::from_millis(800)
+        );
+        assert_eq!(pool.reserve("other.example", now), Duration::ZERO);
+        assert_eq!(
+            pool.reserve("api.example", now + Duration::from_secs(5)),
+            Duration::ZERO
+        );
+    }
+
+    #[test]
+    fn host_key_ignores_path_and_case() {
+        assert_eq!(
+            host_key("https://API.adsb.lol/api/0/routeset"),
+            "api.adsb.lol"
+        );
+        assert_eq!(host_key("http://127.0.0.1:8080/data"), "127.0.0.1:8080");
+        assert_eq!(host_key("not a url"), "not a url");
+    }
+}
@@ -0,0 +1,229 @@
+//! Frame profiler and process CPU/memory sampling.
+
+use std::cell::Cell;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use tracing::debug;
+
+const LOG_INTERVAL: Duration = Duration::from_secs(30);
+const AVG_WEIGHT: f64 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stage {
+    Update,
+    Visible,
+    Table,
+    Radar,
+    Frame,
+}
+
+impl Stage {
+    pub const ALL: [Stage; 5] = [
+        Stage::Update,
+        Stage::Visible,
+        Stage::Table,
+        Stage::Radar,
+        Stage::Frame,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::Update => "update",
+            Stage::Visible => "visible",
+            Stage::Table => "table",
+            Stage::Radar => "radar",
+            Stage::Frame => "frame",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StageStats {
+    pub last: Duration,
+    pub avg: Duration,
+    pub max: Duration,
+    pub count: u64,
+}
+
+/// Uses interior mutability so rendering code holding `&App` can record.
+#[derive(Debug, Default)]
+pub struct Profiler {
+    stats: [Cell<StageStats>; 5],
+    logged_at: Cell<Option<Instant>>,
+}
+
+impl Profiler {
+    pub fn record(&self, stage: Stage, elapsed: Duration) {
+        let cell = &self.stats[stage as usize];
+        let mut stats = cell.get();
+        stats.avg = if stats.count == 0 {
+            elapsed
+        } else {
+            stats.avg.mul_f64(1.0 - AVG_WEIGHT) + elapsed.mul_f64(AVG_WEIGHT)
+        };
+        stats.last = elapsed;
+        stats.max = stats.max.max(elapsed);
+        stats.count += 1;
+        cell.set(stats);
+    }
+
+    pub fn time<T>(&self, stage: Stage, work: impl FnOnce() -> T) -> T {
+        let started = Instant::now();
+        let value = work();
+        self.record(stage, started.elapsed());
+        value
+    }
+
+    pub fn stats(&self, stage: Stage) -> StageStats {
+        self.stats[stage as usize].get()
+    }
+
+    pub fn maybe_log(&self, now: Instant) {
+        let Some(logged_at) = self.logged_at.get() else {
+            self.logged_at.set(Some(now));
+            return;
+        };
+        if now.duration_since(logged_at) < LOG_INTERVAL {
+            return;
+        }
+        self.logged_at.set(Some(now));
+        let summary = Stage::ALL
+            .iter()
+            .filter(|stage| self.stats(**stage).count > 0)
+            .map(|stage| {
+                let stats = self.stats(*stage);
+                format!(
+                    "{}={:.2}/{:.2}ms",
+                    stage.label(),
+                    millis(stats.avg),
+                    millis(stats.max)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        debug!("profile avg/max {summary}");
+        for cell in &self.stats {
+            let mut stats = cell.get();
+            stats.max = Duration::ZERO;
+            cell.set(stats);
+        }
+    }
+}
+
+pub fn millis(value: Duration) -> f64 {
+    value.as_secs_f64() * 1000.0
+}
+
+/// CPU share and resident memory of this process. Both are `None` where
+/// `/proc/self` is unavailable (anything but Linux).
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ProcessUsage {
+    pub cpu_percent: Option<f64>,
+    pub rss_bytes: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+pub struct UsageSampler {
+    last: Option<(Instant, Duration)>,
+}
+
+impl UsageSampler {
+    pub fn sample(&mut self, now: Instant) -> ProcessUsage {
+        let cpu_time = fs::read_to_string("/proc/self/stat")
+            .ok()
+            .and_then(|stat| parse_stat_cpu_time(&stat));
+        let rss_bytes = fs::read_to_string("/proc/self/status")
+            .ok()
+            .and_then(|status| parse_status_rss(&status));
+        ProcessUsage {
+            cpu_percent: self.cpu_percent(now, cpu_time),
+            rss_bytes,
+        }
+    }
+
+    fn cpu_percent(&mut self, now: Instant, cpu_time: Option<Duration>) -> Option<f64> {
+        let previous = self.last;
+        self.last = cpu_time.map(|total| (now, total));
+        let (at, before) = previous?;
+        let wall = now.checked_duration_since(at)?.as_secs_f64();
+        if wall <= 0.0 {
+            return None;
+        }
+        let used = cpu_time?.saturating_sub(before).as_secs_f64();
+        Some(used / wall * 100.0)
+    }
+}
+
+/// User plus system time from `/proc/self/stat`. Fields are counted after
+/// the parenthesised command name, which may itself contain spaces; the
+/// kernel reports them in USER_HZ ticks, fixed at 100 per second.
+fn parse_stat_cpu_time(stat: &str) -> Option<Duration> {
+    let mut fields = stat.rsplit_once(')')?.1.split_whitespace().skip(11);
+    let utime: u64 = fields.next()?.parse().ok()?;
+    let stime: u64 = fields.next()?.parse().ok()?;
+    Some(Duration::from_millis((utime + stime) * 10))
+}
+
+fn parse_status_rss(status: &str) -> Option<u64> {
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_stat_cpu_time, parse_status_rss, Profiler, Stage, UsageSampler};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn parses_proc_cpu_time_and_rss() {
+        let stat = "4242 (adsb tui) S 1 4242 4242 0 -1 4194560 900 0 0 0 250 37 0 0 20 0 9 0";
+        assert_eq!(
+            parse_stat_cpu_time(stat),
+            Some(Duration::from_millis(2_870))
+        );
+        assert_eq!(parse_stat_cpu_time("4242 (adsb"), None);
+
+        let status = "Name:\tadsb-tui\nVmPeak:\t  90000 kB\nVmRSS:\t   24576 kB\n";
+        assert_eq!(parse_status_rss(status), Some(24 * 1024 * 1024));
+        assert_eq!(parse_status_rss("Name:\tadsb-tui\n"), None);
+    }
+
+    #[test]
+    fn cpu_percent_covers_interval_since_last_sample() {
+        let mut sampler = UsageSampler::default();
+        let start = Instant::now();
+        assert_eq!(
+            sampler.cpu_percent(start, Some(Duration::from_secs(3))),
+            None
+        );
+        let later = start + Duration::from_secs(2);
+        let percent = sampler
+            .cpu_percent(later, Some(Duration::from_millis(3_500)))
+            .unwrap();
+        assert!((percent - 25.0).abs() < 1e-9);
+        assert_eq!(sampler.cpu_percent(later, None), None);
+    }
+
+    #[test]
+    fn records_last_average_and_window_max() {
+        let profiler = Profiler::default();
+        profiler.record(Stage::Table, Duration::from_millis(10));
+        profiler.record(Stage::Table, Duration::from_millis(20));
+
+        let stats = profiler.stats(Stage::Table);
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.last, Duration::from_millis(20));
+        assert_eq!(stats.max, Duration::from_millis(20));
+        assert_eq!(stats.avg, Duration::from_millis(11));
+        assert_eq!(profiler.stats(Stage::Radar).count, 0);
+
+        let start = Instant::now();
+        profiler.maybe_log(start);
+        profiler.maybe_log(start + Duration::from_secs(31));
+        let stats = profiler.stats(Stage::Table);
+        assert_eq!(stats.max, Duration::ZERO);
+        assert_eq!(stats.avg, Duration::from_millis(11));
+    }
+}
@@ -0,0 +1,81 @@
+//! Quiet hours, e.g. `23:00-07:00`.
+
+use chrono::{NaiveTime, Timelike};
+
+/// Windows as `[start, end)` minutes after local midnight; a window whose
+/// end is before its start runs past midnight.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct QuietHours {
+    windows: Vec<(u16, u16)>,
+}
+
+impl QuietHours {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let mut windows = Vec::new();
+        for window in value
+            .split(',')
+            .map(str::trim)
+            .filter(|window| !window.is_empty() && !window.eq_ignore_ascii_case("off"))
+        {
+            let (start, end) = window
+                .split_once(['-', '–'])
+                .ok_or_else(|| format!("\"{window}\" is not a HH:MM-HH:MM window"))?;
+            let (start, end) = (parse_minute(start), parse_minute(end));
+            match (start, end) {
+                (Some(start), Some(end)) if start != end => windows.push((start, end)),
+                (Some(_), Some(_)) => return Err(format!("\"{window}\" is empty")),
+                _ => return Err(format!("\"{window}\" is not a HH:MM-HH:MM window")),
+            }
+        }
+        Ok(Self { windows })
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        let minute = (time.hour() * 60 + time.minute()) as u16;
+        self.windows.iter().any(|&(start, end)| {
+            if start < end {
+                (start..end).contains(&minute)
+            } else {
+                minute >= start || minute < end
+            }
+        })
+    }
+}
+
+fn parse_minute(value: &str) -> Option<u16> {
+    let (hour, minute) = value.trim().split_once(':')?;
+    let (hour, minute): (u16, u16) = (hour.parse().ok()?, minute.parse().ok()?);
+    (hour < 24 && minute < 60).then_some(hour * 60 + minute)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QuietHours;
+    use chrono::NaiveTime;
+
+    fn at(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn windows_may_wrap_midnight() {
+        let quiet = QuietHours::parse("23:00–07:00, 12:30-13:00").unwrap();
+        assert!(quiet.contains(at(23, 0)));
+        assert!(quiet.contains(at(2, 15)));
+        assert!(!quiet.contains(at(7, 0)));
+        assert!(quiet.contains(at(12, 45)));
+        assert!(!quiet.contains(at(13, 0)));
+        assert!(!quiet.contains(at(18, 0)));
+
+        assert_eq!(QuietHours::parse(""), Ok(QuietHours::default()));
+        assert_eq!(QuietHours::parse("off"), Ok(QuietHours::default()));
+        assert!(QuietHours::parse("23:00").is_err());
+        assert!(QuietHours::parse("24:00-07:00").is_err());
+        assert!(QuietHours::parse("07:00-07:00").is_err());
+        assert!(QuietHours::parse("bogus, 22:00-06:00").is_err());
+        assert_eq!(
+            QuietHours::parse(" 23:00 - 07:00 ,12:30 -13:00"),
+            QuietHours::parse("23:00-07:00,12:30-13:00")
+        );
+    }
+}
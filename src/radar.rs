@@ -1,4 +1,6 @@
 use std::cmp::Ordering;
+use std::f64::consts::TAU;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use ratatui::layout::Rect;
@@ -6,16 +8,27 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::symbols::Marker;
 use ratatui::text::{Line as TextLine, Span};
 use ratatui::widgets::canvas::{Canvas, Circle, Line as CanvasLine, Points};
-use ratatui::widgets::{Block, BorderType, Borders, Paragraph, Wrap};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap};
 use ratatui::Frame;
 
-use crate::app::{App, LayoutMode, RadarBlip, RadarRenderer};
-use crate::model::{seen_seconds, Aircraft};
+use crate::airports::RadarAirports;
+use crate::app::{App, LayoutMode, PositionQuality, RadarBlip, RadarRenderer, SiteGeometry};
+use crate::geography::Segments;
+use crate::model::{seen_seconds, Aircraft, Altitude};
+use crate::units::{bearing_deg, distance_nm};
+use crate::zones::ZoneShape;
 
 const SWEEP_PERIOD_MS: u64 = 4500;
 const MIN_RANGE_NM: f64 = 1.0;
 const MIN_ASPECT: f64 = 0.2;
 const LABEL_MAX_LEN: usize = 6;
+const ZONE_CIRCLE_STEPS: usize = 48;
+const AIRPORT_GLYPH: char = '△';
+const SITE_GLYPH: char = '⌂';
+const COMPASS_MIN_SIZE: (u16, u16) = (20, 8);
+const RING_LABELS_MIN_SIZE: (u16, u16) = (32, 16);
+const TRAIL_FADE: [f64; 3] = [0.0, 0.35, 0.6];
+const TRAIL_GLYPHS: [char; 3] = ['o', ';', ','];
 
 #[derive(Clone, Copy)]
 pub struct RadarTheme {
@@ -25,6 +38,41 @@ pub struct RadarTheme {
     pub warn: Color,
     pub highlight: Color,
     pub panel_bg: Color,
+    pub altitude: Option<AltitudePalette>,
+    pub trail_altitude: Option<AltitudePalette>,
+}
+
+const ALTITUDE_STOPS_FT: [f64; 6] = [0.0, 2_000.0, 6_000.0, 12_000.0, 25_000.0, 40_000.0];
+/// Gradient colors are computed per band so blips of similar height share a color.
+const ALTITUDE_BAND_FT: i64 = 1_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AltitudePalette {
+    pub ground: (u8, u8, u8),
+    pub stops: [(u8, u8, u8); 6],
+}
+
+impl AltitudePalette {
+    pub fn color(&self, altitude: Altitude) -> Color {
+        let feet = match altitude {
+            Altitude::Ground => {
+                let (r, g, b) = self.ground;
+                return Color::Rgb(r, g, b);
+            }
+            Altitude::Feet(feet) => (feet.max(0) / ALTITUDE_BAND_FT * ALTITUDE_BAND_FT) as f64,
+        };
+        let last = ALTITUDE_STOPS_FT.len() - 1;
+        let upper = ALTITUDE_STOPS_FT
+            .iter()
+            .position(|stop| feet < *stop)
+            .unwrap_or(last)
+            .max(1);
+        let (low_ft, high_ft) = (ALTITUDE_STOPS_FT[upper - 1], ALTITUDE_STOPS_FT[upper]);
+        let t = ((feet - low_ft) / (high_ft - low_ft)).clamp(0.0, 1.0);
+        let (low, high) = (self.stops[upper - 1], self.stops[upper]);
+        let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+        Color::Rgb(mix(low.0, high.0), mix(low.1, high.1), mix(low.2, high.2))
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -33,6 +81,19 @@ pub struct RadarSettings {
     pub aspect: f64,
     pub renderer: RadarRenderer,
     pub blip: RadarBlip,
+    pub sweep: bool,
+    pub vector_secs: Option<f64>,
+    pub compass: bool,
+    /// Age at which trail points disappear; `None` draws every point at
+    /// full strength.
+    pub trail_fade_secs: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RadarHit {
+    pub col: u16,
+    pub row: u16,
+    pub idx: usize,
 }
 
 pub fn render(
@@ -42,21 +103,22 @@ pub fn render(
     indices: &[usize],
     theme: RadarTheme,
     settings: RadarSettings,
-) {
+) -> Vec<RadarHit> {
     let show_labels = app.radar_labels && matches!(app.layout_mode, LayoutMode::Radar);
     let data = match collect_data(app, indices, settings.range_nm, show_labels) {
         Some(data) => data,
         None => {
             render_empty(f, area, theme);
-            return;
+            return Vec::new();
         }
     };
 
     let use_canvas = matches!(settings.renderer, RadarRenderer::Canvas);
-    if use_canvas && area.width >= 8 && area.height >= 6 {
+    let canvas = use_canvas && area.width >= 8 && area.height >= 6;
+    if canvas {
         render_canvas(f, area, &data, theme, settings);
     } else {
-        render_ascii(f, area, &data, theme);
+        render_ascii(f, area, &data, theme, settings);
     }
 
     if matches!(app.layout_mode, LayoutMode::Radar) {
@@ -64,17 +126,164 @@ pub fn render(
             render_selection_panel(f, area, theme, selection);
         }
     }
+
+    // The ASCII grid ignores the aspect setting.
+    let aspect = if canvas {
+        settings.aspect.max(MIN_ASPECT)
+    } else {
+        1.0
+    };
+    let hits: Vec<RadarHit> = data
+        .points
+        .iter()
+        .filter_map(|point| {
+            let idx = point.idx?;
+            let (col, row) = screen_cell(area, data.range_nm, aspect, point.x, point.y)?;
+            Some(RadarHit { col, row, idx })
+        })
+        .collect();
+
+    if let Some((col, row)) = app.radar_hover {
+        if let Some(idx) = hit_at(&hits, col, row) {
+            render_tooltip(f, area, theme, &app.data.aircraft[idx], app, (col, row));
+        }
+    }
+    hits
+}
+
+fn render_tooltip(
+    f: &mut Frame,
+    area: Rect,
+    theme: RadarTheme,
+    ac: &Aircraft,
+    app: &App,
+    (col, row): (u16, u16),
+) {
+    let callsign = ac
+        .flight
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .or(ac.hex.as_deref())
+        .unwrap_or("--");
+    let alt_text = match app.altitude_for(ac) {
+        Some(Altitude::Ground) => "GND".to_string(),
+        Some(alt) => format!("{alt} ft"),
+        None => "--".to_string(),
+    };
+    let gs_text = ac
+        .gs
+        .map(|v| format!("{v:.0} kt"))
+        .unwrap_or_else(|| "--".to_string());
+    let lines = vec![
+        TextLine::from(Span::styled(
+            callsign.to_string(),
+            Style::default()
+                .fg(theme.accent)
+                .add_modifier(Modifier::BOLD),
+        )),
+        TextLine::from(format!("ALT {alt_text}")),
+        TextLine::from(format!("GS  {gs_text}")),
+    ];
+    let width = lines.iter().map(|line| line.width()).max().unwrap_or(0) as u16 + 2;
+    let height = lines.len() as u16 + 2;
+    if area.width < width + 2 || area.height < height + 2 {
+        return;
+    }
+    // Prefer below-right of the cursor, flipping when it would leave the panel.
+    let right = area.x + area.width - 1;
+    let bottom = area.y + area.height - 1;
+    let x = if col + 1 + width <= right {
+        col + 1
+    } else {
+        col.saturating_sub(width).max(area.x + 1)
+    };
+    let y = if row + 1 + height <= bottom {
+        row + 1
+    } else {
+        row.saturating_sub(height).max(area.y + 1)
+    };
+    let panel = Rect {
+        x,
+        y,
+        width,
+        height,
+    };
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded);
+    f.render_widget(Clear, panel);
+    f.render_widget(
+        Paragraph::new(lines)
+            .block(block)
+            .style(Style::default().bg(theme.panel_bg)),
+        panel,
+    );
+}
+
+fn compass_marks(area: Rect, enabled: bool) -> (bool, bool) {
+    let fits = |(width, height): (u16, u16)| area.width >= width && area.height >= height;
+    (
+        enabled && fits(COMPASS_MIN_SIZE),
+        enabled && fits(RING_LABELS_MIN_SIZE),
+    )
+}
+
+fn ring_label(radius_nm: f64, outer: bool) -> String {
+    let text = if radius_nm >= 10.0 || radius_nm.fract() == 0.0 {
+        format!("{radius_nm:.0}")
+    } else {
+        format!("{radius_nm:.1}")
+    };
+    if outer {
+        format!("{text}nm")
+    } else {
+        text
+    }
+}
+
+fn screen_cell(area: Rect, range_nm: f64, aspect: f64, x: f64, y: f64) -> Option<(u16, u16)> {
+    let width = area.width.saturating_sub(2);
+    let height = area.height.saturating_sub(2);
+    if width == 0 || height == 0 {
+        return None;
+    }
+    let dx = x / range_nm;
+    let dy = y / (range_nm * aspect);
+    if dx.abs() > 1.0 || dy.abs() > 1.0 {
+        return None;
+    }
+    let col = ((dx + 1.0) * 0.5 * (width - 1) as f64) as u16;
+    let row = ((1.0 - (dy + 1.0) * 0.5) * (height - 1) as f64) as u16;
+    Some((area.x + 1 + col, area.y + 1 + row))
+}
+
+pub fn hit_at(hits: &[RadarHit], col: u16, row: u16) -> Option<usize> {
+    hits.iter()
+        .filter_map(|hit| {
+            let dc = (hit.col as i32 - col as i32).abs();
+            let dr = (hit.row as i32 - row as i32).abs();
+            // Cells are about twice as tall as they are wide.
+            (dc <= 2 && dr <= 1).then_some((dc * dc + 4 * dr * dr, hit.idx))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, idx)| idx)
 }
 
 #[derive(Clone, Copy)]
 struct RadarPoint {
     x: f64,
     y: f64,
+    idx: Option<usize>,
     track: Option<f64>,
+    gs: Option<f64>,
     fav: bool,
     current: bool,
     selected: bool,
+    degraded: bool,
+    ghost: bool,
     seen_secs: Option<f64>,
+    altitude: Option<Altitude>,
 }
 
 struct RadarLabel {
@@ -93,6 +302,30 @@ struct RadarData {
     range_nm: f64,
     selection: Option<RadarSelection>,
     labels: Vec<RadarLabel>,
+    zones: Vec<Vec<(f64, f64)>>,
+    airports: Vec<RadarAirport>,
+    geography: Arc<Segments>,
+    /// Closed ring through the farthest position seen in each bearing
+    /// sector, when `radar_range_rose` is on; canvas renderer only.
+    range_rose: Vec<(f64, f64)>,
+    site: Option<(f64, f64)>,
+    following: bool,
+}
+
+impl RadarData {
+    fn title(&self) -> &'static str {
+        if self.following {
+            "RADAR FOLLOW"
+        } else {
+            "RADAR"
+        }
+    }
+}
+
+struct RadarAirport {
+    x: f64,
+    y: f64,
+    text: String,
 }
 
 struct RadarSelection {
@@ -109,12 +342,18 @@ fn collect_data(
     struct RawPoint {
         lat: f64,
         lon: f64,
+        idx: Option<usize>,
         track: Option<f64>,
+        gs: Option<f64>,
         fav: bool,
         current: bool,
         selected: bool,
+        degraded: bool,
+        ghost: bool,
         seen_secs: Option<f64>,
+        altitude: Option<Altitude>,
         label: Option<LabelInfo>,
+        geometry: Option<SiteGeometry>,
     }
 
     let selected_idx = app
@@ -126,86 +365,156 @@ fn collect_data(
     let mut sum_lat = 0.0;
     let mut sum_lon = 0.0;
     let mut current_points = 0usize;
+    let mut ghost_points = 0usize;
     let mut raw_points: Vec<RawPoint> = Vec::new();
 
     for idx in indices {
         let ac = &app.data.aircraft[*idx];
         let selected = selected_idx == Some(*idx);
-        if let (Some(lat), Some(lon)) = (ac.lat, ac.lon) {
+        let low_quality = app.low_quality_position(ac);
+        let suppressed = (low_quality && app.low_quality_positions == PositionQuality::Hide)
+            || app.position_stale(ac);
+        if let (Some(lat), Some(lon), false) = (ac.lat, ac.lon, suppressed) {
             let label = if collect_labels { label_info(ac) } else { None };
+            let predicted = app.predicted_position(ac);
+            let (lat, lon) = predicted.unwrap_or((lat, lon));
             raw_points.push(RawPoint {
                 lat,
                 lon,
-                track: ac.track,
+                idx: Some(*idx),
+                track: app.course_for(ac).map(|course| course.deg),
+                gs: ac.gs,
                 fav: app.is_favorite(ac),
                 current: true,
                 selected,
+                degraded: low_quality && app.low_quality_positions == PositionQuality::Dim,
+                ghost: false,
                 seen_secs: seen_seconds(ac),
+                altitude: app.altitude_for(ac),
                 label,
+                geometry: app.site_geometry(*idx).filter(|_| predicted.is_none()),
             });
             sum_lat += lat;
             sum_lon += lon;
             current_points += 1;
         }
+        let ghost = if app.radar_ghosts {
+            app.ghost_position(ac)
+        } else {
+            None
+        };
+        if let Some((lat, lon, age)) = ghost {
+            raw_points.push(RawPoint {
+                lat,
+                lon,
+                idx: Some(*idx),
+                track: None,
+                gs: None,
+                fav: app.is_favorite(ac),
+                current: false,
+                selected,
+                degraded: false,
+                ghost: true,
+                seen_secs: age,
+                altitude: None,
+                label: None,
+                geometry: None,
+            });
+            ghost_points += 1;
+        }
         if let Some(trail) = app.trail_for(ac) {
+            let pinned = app.is_trail_pinned(ac);
             for point in trail {
+                let age = app
+                    .last_update
+                    .and_then(|now| now.duration_since(point.at).ok())
+                    .map(|age| age.as_secs_f64());
+                if app.trail_fade_secs > 0
+                    && !pinned
+                    && age.is_some_and(|age| age > app.trail_fade_secs as f64)
+                {
+                    continue;
+                }
                 raw_points.push(RawPoint {
                     lat: point.lat,
                     lon: point.lon,
+                    idx: None,
                     track: None,
+                    gs: None,
                     fav: app.is_favorite(ac),
                     current: false,
                     selected: false,
-                    seen_secs: None,
+                    degraded: false,
+                    ghost: false,
+                    seen_secs: age,
+                    altitude: point.alt,
                     label: None,
+                    geometry: None,
                 });
             }
         }
     }
 
-    if raw_points.is_empty() || current_points == 0 {
+    if raw_points.is_empty() || current_points + ghost_points == 0 {
         return None;
     }
 
-    let (center_lat, center_lon) = app
-        .radar_center()
+    let follow_center = app.radar_follow_center(indices);
+    let (center_lat, center_lon) = follow_center
+        .or(app.radar_center())
         .map(|center| (center.lat, center.lon))
         .or_else(|| app.site().map(|site| (site.lat, site.lon)))
-        .unwrap_or((
-            sum_lat / current_points as f64,
-            sum_lon / current_points as f64,
-        ));
+        .or_else(|| {
+            (current_points > 0).then(|| {
+                (
+                    sum_lat / current_points as f64,
+                    sum_lon / current_points as f64,
+                )
+            })
+        })?;
 
     let range_nm = range_nm.max(MIN_RANGE_NM);
     let mut points = Vec::with_capacity(raw_points.len());
     let mut labels = Vec::new();
+    let centered_on_site = centered_on_site(app, center_lat, center_lon);
     for raw in raw_points {
-        let dist = distance_nm(center_lat, center_lon, raw.lat, raw.lon);
+        let (dist, bearing) = match raw.geometry.filter(|_| centered_on_site) {
+            Some(geometry) => (geometry.distance_nm, geometry.bearing_deg),
+            None => (
+                distance_nm(center_lat, center_lon, raw.lat, raw.lon),
+                bearing_deg(center_lat, center_lon, raw.lat, raw.lon),
+            ),
+        };
         if dist > range_nm {
             continue;
         }
-        let bearing = bearing_deg(center_lat, center_lon, raw.lat, raw.lon).to_radians();
+        let bearing = bearing.to_radians();
         let x = dist * bearing.sin();
         let y = dist * bearing.cos();
         points.push(RadarPoint {
             x,
             y,
+            idx: raw.idx,
             track: raw.track,
+            gs: raw.gs,
             fav: raw.fav,
             current: raw.current,
             selected: raw.selected,
+            degraded: raw.degraded,
+            ghost: raw.ghost,
             seen_secs: raw.seen_secs,
+            altitude: raw.altitude,
         });
         if collect_labels {
             if let Some(info) = raw.label {
-                let fresh = raw.seen_secs.map(|s| s <= 1.0).unwrap_or(false);
+                let fresh = !raw.degraded && raw.seen_secs.map(|s| s <= 1.0).unwrap_or(false);
                 labels.push(RadarLabel {
                     x,
                     y,
                     text: info.text,
                     id: info.id,
                     dist,
-                    fav: raw.fav,
+                    fav: raw.fav && !raw.degraded,
                     fresh,
                     selected: raw.selected,
                 });
@@ -213,16 +522,134 @@ fn collect_data(
         }
     }
 
-    let selection = selected_aircraft(app, indices, center_lat, center_lon, range_nm);
+    let selection = selected_aircraft(
+        app,
+        indices,
+        center_lat,
+        center_lon,
+        range_nm,
+        follow_center.is_some(),
+    );
+    let zones = zone_outlines(app, center_lat, center_lon);
+    let airports = airport_markers(app, center_lat, center_lon, range_nm, collect_labels);
+    let geography = app.geography.segments(center_lat, center_lon, range_nm);
+    let range_rose = if app.radar_range_rose {
+        range_rose(app, center_lat, center_lon)
+    } else {
+        Vec::new()
+    };
+    let site = app.site().filter(|_| !centered_on_site).and_then(|site| {
+        let dist = distance_nm(center_lat, center_lon, site.lat, site.lon);
+        let bearing = bearing_deg(center_lat, center_lon, site.lat, site.lon).to_radians();
+        (dist <= range_nm).then(|| (dist * bearing.sin(), dist * bearing.cos()))
+    });
 
     Some(RadarData {
         points,
         range_nm,
         selection,
         labels,
+        zones,
+        airports,
+        geography,
+        range_rose,
+        site,
+        following: follow_center.is_some(),
     })
 }
 
+fn range_rose(app: &App, center_lat: f64, center_lon: f64) -> Vec<(f64, f64)> {
+    let Some(site) = app.site() else {
+        return Vec::new();
+    };
+    let Some(sectors) = app
+        .traffic
+        .max_range((site.lat, site.lon))
+        .filter(|sectors| sectors.iter().any(|nm| *nm > 0.0))
+    else {
+        return Vec::new();
+    };
+    let dist = distance_nm(center_lat, center_lon, site.lat, site.lon);
+    let bearing = bearing_deg(center_lat, center_lon, site.lat, site.lon).to_radians();
+    let (site_x, site_y) = (dist * bearing.sin(), dist * bearing.cos());
+    let width = TAU / sectors.len() as f64;
+    let mut ring: Vec<(f64, f64)> = sectors
+        .iter()
+        .enumerate()
+        .map(|(sector, nm)| {
+            let bearing = (sector as f64 + 0.5) * width;
+            (site_x + nm * bearing.sin(), site_y + nm * bearing.cos())
+        })
+        .collect();
+    ring.push(ring[0]);
+    ring
+}
+
+fn airport_markers(
+    app: &App,
+    center_lat: f64,
+    center_lon: f64,
+    range_nm: f64,
+    with_codes: bool,
+) -> Vec<RadarAirport> {
+    if app.radar_airports == RadarAirports::Off {
+        return Vec::new();
+    }
+    app.airports
+        .within(center_lat, center_lon, range_nm)
+        .into_iter()
+        .filter(|(airport, _)| app.radar_airports.shows(airport.size))
+        .map(|(airport, dist)| {
+            let bearing =
+                bearing_deg(center_lat, center_lon, airport.lat, airport.lon).to_radians();
+            RadarAirport {
+                x: dist * bearing.sin(),
+                y: dist * bearing.cos(),
+                text: if with_codes {
+                    format!("{AIRPORT_GLYPH}{}", airport.code)
+                } else {
+                    AIRPORT_GLYPH.to_string()
+                },
+            }
+        })
+        .collect()
+}
+
+fn zone_outlines(app: &App, center_lat: f64, center_lon: f64) -> Vec<Vec<(f64, f64)>> {
+    let project = |lat: f64, lon: f64| {
+        let dist = distance_nm(center_lat, center_lon, lat, lon);
+        let bearing = bearing_deg(center_lat, center_lon, lat, lon).to_radians();
+        (dist * bearing.sin(), dist * bearing.cos())
+    };
+    app.zones
+        .iter()
+        .filter(|zone| zone.is_enabled())
+        .filter_map(|zone| {
+            let mut ring: Vec<(f64, f64)> = match zone.shape()? {
+                ZoneShape::Circle {
+                    lat,
+                    lon,
+                    radius_nm,
+                } => {
+                    let (cx, cy) = project(lat, lon);
+                    (0..ZONE_CIRCLE_STEPS)
+                        .map(|step| {
+                            let angle = step as f64 / ZONE_CIRCLE_STEPS as f64 * TAU;
+                            (cx + radius_nm * angle.sin(), cy + radius_nm * angle.cos())
+                        })
+                        .collect()
+                }
+                ZoneShape::Polygon(points) => points
+                    .iter()
+                    .map(|[lat, lon]| project(*lat, *lon))
+                    .collect(),
+            };
+            ring.push(ring[0]);
+            Some(ring)
+        })
+        .collect()
+}
+
 fn render_empty(f: &mut Frame, area: Rect, theme: RadarTheme) {
     let block = Block::default()
         .borders(Borders::ALL)
@@ -253,37 +680,50 @@ fn render_canvas(
     let sweep_rad = sweep_angle(SWEEP_PERIOD_MS);
     let sweep_x = range * sweep_rad.sin();
     let sweep_y = range * sweep_rad.cos();
+    let (show_compass, show_ring_labels) = compass_marks(area, settings.compass);
 
-    let mut trail = Vec::new();
-    let mut trail_fav = Vec::new();
+    let mut trail: Vec<(Color, Vec<(f64, f64)>)> = Vec::new();
     let mut current = Vec::new();
     let mut current_fresh = Vec::new();
     let mut current_fav = Vec::new();
     let mut current_selected = Vec::new();
+    let mut current_by_altitude: Vec<(Color, Vec<(f64, f64)>)> = Vec::new();
 
     for point in &data.points {
         let coord = (point.x, point.y);
+        if point.ghost {
+            continue;
+        }
         if point.current {
             if point.selected {
                 current_selected.push(coord);
+            } else if point.degraded {
+                current.push(coord);
             } else if point.fav {
                 current_fav.push(coord);
+            } else if let Some(color) = altitude_color(&theme, point) {
+                match current_by_altitude.iter_mut().find(|(c, _)| *c == color) {
+                    Some((_, coords)) => coords.push(coord),
+                    None => current_by_altitude.push((color, vec![coord])),
+                }
             } else if point.seen_secs.map(|s| s <= 1.0).unwrap_or(false) {
                 current_fresh.push(coord);
             } else {
                 current.push(coord);
             }
-        } else if point.fav {
-            trail_fav.push(coord);
         } else {
-            trail.push(coord);
+            let color = trail_color(&theme, point, settings.trail_fade_secs);
+            match trail.iter_mut().find(|(c, _)| *c == color) {
+                Some((_, coords)) => coords.push(coord),
+                None => trail.push((color, vec![coord])),
+            }
         }
     }
 
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .title("RADAR");
+        .title(data.title());
     let canvas = Canvas::default()
         .block(block)
         .x_bounds(x_bounds)
@@ -291,6 +731,15 @@ fn render_canvas(
         .background_color(theme.panel_bg)
         .marker(Marker::Braille)
         .paint(|ctx| {
+            for [(x1, y1), (x2, y2)] in data.geography.iter().copied() {
+                ctx.draw(&CanvasLine {
+                    x1,
+                    y1,
+                    x2,
+                    y2,
+                    color: theme.dim,
+                });
+            }
             for i in 1..=4 {
                 let radius = range * (i as f64 / 4.0);
                 ctx.draw(&Circle {
@@ -314,25 +763,102 @@ fn render_canvas(
                 y2: range,
                 color: theme.dim,
             });
-            ctx.draw(&CanvasLine {
-                x1: 0.0,
-                y1: 0.0,
-                x2: sweep_x,
-                y2: sweep_y,
-                color: theme.warn,
-            });
-            if !trail.is_empty() {
-                ctx.draw(&Points {
-                    coords: &trail,
-                    color: theme.dim,
+            for edge in data.range_rose.windows(2) {
+                ctx.draw(&CanvasLine {
+                    x1: edge[0].0,
+                    y1: edge[0].1,
+                    x2: edge[1].0,
+                    y2: edge[1].1,
+                    color: theme.accent,
+                });
+            }
+            for ring in &data.zones {
+                for edge in ring.windows(2) {
+                    ctx.draw(&CanvasLine {
+                        x1: edge[0].0,
+                        y1: edge[0].1,
+                        x2: edge[1].0,
+                        y2: edge[1].1,
+                        color: theme.warn,
+                    });
+                }
+            }
+            if show_ring_labels {
+                // Down the south-east diagonal, clear of the compass marks.
+                let (dx, dy) = (TAU / 8.0).sin_cos();
+                for i in 1..=4 {
+                    let radius = range * (i as f64 / 4.0);
+                    ctx.print(
+                        radius * dx,
+                        -radius * dy,
+                        TextLine::from(Span::styled(
+                            ring_label(radius, i == 4),
+                            Style::default().fg(theme.dim),
+                        )),
+                    );
+                }
+            }
+            if show_compass {
+                let style = Style::default().fg(theme.dim).add_modifier(Modifier::BOLD);
+                for (x, y, mark) in [
+                    (0.0, y_bounds[1], "N"),
+                    (x_bounds[1], 0.0, "E"),
+                    (0.0, y_bounds[0], "S"),
+                    (x_bounds[0], 0.0, "W"),
+                ] {
+                    ctx.print(x, y, TextLine::from(Span::styled(mark, style)));
+                }
+            }
+            if settings.sweep {
+                ctx.draw(&CanvasLine {
+                    x1: 0.0,
+                    y1: 0.0,
+                    x2: sweep_x,
+                    y2: sweep_y,
+                    color: theme.warn,
                 });
             }
-            if !trail_fav.is_empty() {
+            for airport in &data.airports {
+                ctx.print(
+                    airport.x,
+                    airport.y,
+                    TextLine::from(Span::styled(
+                        airport.text.clone(),
+                        Style::default().fg(theme.dim),
+                    )),
+                );
+            }
+            if let Some((x, y)) = data.site {
+                ctx.print(
+                    x,
+                    y,
+                    TextLine::from(Span::styled(
+                        SITE_GLYPH.to_string(),
+                        Style::default()
+                            .fg(theme.accent)
+                            .add_modifier(Modifier::BOLD),
+                    )),
+                );
+            }
+            for (color, coords) in &trail {
                 ctx.draw(&Points {
-                    coords: &trail_fav,
-                    color: theme.fav,
+                    coords,
+                    color: *color,
                 });
             }
+            if let Some(secs) = settings.vector_secs {
+                for point in data.points.iter().filter(|point| point.current) {
+                    if let Some((x2, y2)) = vector_end(point, secs) {
+                        ctx.draw(&CanvasLine {
+                            x1: point.x,
+                            y1: point.y,
+                            x2,
+                            y2,
+                            color: point_color(&theme, point),
+                        });
+                    }
+                }
+            }
 
             match settings.blip {
                 RadarBlip::Dot => {
@@ -348,6 +874,12 @@ fn render_canvas(
                             color: theme.accent,
                         });
                     }
+                    for (color, coords) in &current_by_altitude {
+                        ctx.draw(&Points {
+                            coords,
+                            color: *color,
+                        });
+                    }
                     if !current_fav.is_empty() {
                         ctx.draw(&Points {
                             coords: &current_fav,
@@ -366,13 +898,7 @@ fn render_canvas(
                         if !point.current || point.selected {
                             continue;
                         }
-                        let color = if point.fav {
-                            theme.fav
-                        } else if point.seen_secs.map(|s| s <= 1.0).unwrap_or(false) {
-                            theme.accent
-                        } else {
-                            theme.dim
-                        };
+                        let color = point_color(&theme, point);
                         let glyph = blip_glyph(settings.blip, point.track);
                         ctx.print(
                             point.x,
@@ -385,6 +911,20 @@ fn render_canvas(
                     }
                 }
             }
+            for point in data.points.iter().filter(|point| point.ghost) {
+                ctx.print(
+                    point.x,
+                    point.y,
+                    TextLine::from(Span::styled(
+                        ghost_text(point.seen_secs),
+                        Style::default().fg(if point.selected {
+                            theme.highlight
+                        } else {
+                            theme.dim
+                        }),
+                    )),
+                );
+            }
             if !data.labels.is_empty() {
                 let label_offset = (range * 0.02).clamp(0.6, 5.0);
                 let mut selected_labels = Vec::new();
@@ -480,21 +1020,95 @@ fn render_canvas(
     f.render_widget(canvas, area);
 }
 
-fn render_ascii(f: &mut Frame, area: Rect, data: &RadarData, theme: RadarTheme) {
+fn point_color(theme: &RadarTheme, point: &RadarPoint) -> Color {
+    if point.selected {
+        theme.highlight
+    } else if point.degraded {
+        theme.dim
+    } else if point.fav {
+        theme.fav
+    } else if let Some(color) = altitude_color(theme, point) {
+        color
+    } else if point.seen_secs.map(|s| s <= 1.0).unwrap_or(false) {
+        theme.accent
+    } else {
+        theme.dim
+    }
+}
+
+/// End of the leader line: where the aircraft will be after `secs` on its
+/// current track and ground speed.
+fn vector_end(point: &RadarPoint, secs: f64) -> Option<(f64, f64)> {
+    let (track, gs) = (point.track?, point.gs?);
+    if gs <= 0.0 {
+        return None;
+    }
+    let length = gs * secs / 3600.0;
+    let track = track.to_radians();
+    Some((
+        point.x + length * track.sin(),
+        point.y + length * track.cos(),
+    ))
+}
+
+fn altitude_color(theme: &RadarTheme, point: &RadarPoint) -> Option<Color> {
+    Some(theme.altitude?.color(point.altitude?))
+}
+
+/// Age step of a trail point, 0 for the newest third of `fade_secs`.
+fn trail_age_step(point: &RadarPoint, fade_secs: Option<f64>) -> usize {
+    match (point.seen_secs, fade_secs) {
+        (Some(age), Some(fade)) if fade > 0.0 => {
+            ((age / fade * TRAIL_FADE.len() as f64) as usize).min(TRAIL_FADE.len() - 1)
+        }
+        _ => 0,
+    }
+}
+
+/// Trail point color: favorite, altitude or dim, blended toward the panel
+/// background as the point ages. Named colors cannot be blended, so they
+/// only change glyph in the ASCII renderer.
+fn trail_color(theme: &RadarTheme, point: &RadarPoint, fade_secs: Option<f64>) -> Color {
+    let color = if point.fav {
+        theme.fav
+    } else {
+        theme
+            .trail_altitude
+            .zip(point.altitude)
+            .map(|(palette, altitude)| palette.color(altitude))
+            .unwrap_or(theme.dim)
+    };
+    let t = TRAIL_FADE[trail_age_step(point, fade_secs)];
+    match (color, theme.panel_bg) {
+        (Color::Rgb(r, g, b), Color::Rgb(br, bg, bb)) if t > 0.0 => {
+            let mix = |a: u8, b: u8| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+            Color::Rgb(mix(r, br), mix(g, bg), mix(b, bb))
+        }
+        _ => color,
+    }
+}
+
+fn render_ascii(
+    f: &mut Frame,
+    area: Rect,
+    data: &RadarData,
+    theme: RadarTheme,
+    settings: RadarSettings,
+) {
     let width = area.width.saturating_sub(2) as usize;
     let height = area.height.saturating_sub(2) as usize;
     if width == 0 || height == 0 {
         return;
     }
 
-    let mut grid = vec![vec![('.', 0u8); width]; height];
+    let mut grid = vec![vec![('.', 0u8, None); width]; height];
     let cx = width / 2;
     let cy = height / 2;
     set_grid(&mut grid, cx, cy, '+', 1);
 
     let sweep_rad = sweep_angle(SWEEP_PERIOD_MS);
     let max_r = (width.min(height) as f64 / 2.0).max(1.0) as usize;
-    for r in 0..=max_r {
+    for r in (0..=max_r).filter(|_| settings.sweep) {
         let x = (cx as f64 + r as f64 * sweep_rad.sin()).round() as isize;
         let y = (cy as f64 - r as f64 * sweep_rad.cos()).round() as isize;
         let xi = x.clamp(0, width.saturating_sub(1) as isize) as usize;
@@ -502,6 +1116,46 @@ fn render_ascii(f: &mut Frame, area: Rect, data: &RadarData, theme: RadarTheme)
         set_grid(&mut grid, xi, yi, ':', 0);
     }
 
+    for ring in &data.zones {
+        for edge in ring.windows(2) {
+            let ((x1, y1), (x2, y2)) = (edge[0], edge[1]);
+            let cells = (x2 - x1).hypot(y2 - y1) / data.range_nm * width.max(height) as f64;
+            let steps = (cells.ceil() as usize).clamp(1, 400);
+            for step in 0..=steps {
+                let t = step as f64 / steps as f64;
+                let dx = (x1 + (x2 - x1) * t) / data.range_nm;
+                let dy = (y1 + (y2 - y1) * t) / data.range_nm;
+                if dx.abs() > 1.0 || dy.abs() > 1.0 {
+                    continue;
+                }
+                let x = ((dx + 1.0) * 0.5 * (width.saturating_sub(1)) as f64) as usize;
+                let y = ((1.0 - (dy + 1.0) * 0.5) * (height.saturating_sub(1)) as f64) as usize;
+                set_grid(&mut grid, x, y, '#', 0);
+            }
+        }
+    }
+
+    for airport in &data.airports {
+        let dx = airport.x / data.range_nm;
+        let dy = airport.y / data.range_nm;
+        if dx.abs() > 1.0 || dy.abs() > 1.0 {
+            continue;
+        }
+        let x = ((dx + 1.0) * 0.5 * (width.saturating_sub(1)) as f64) as usize;
+        let y = ((1.0 - (dy + 1.0) * 0.5) * (height.saturating_sub(1)) as f64) as usize;
+        set_grid(&mut grid, x, y, 'A', 1);
+    }
+
+    if let Some((x, y)) = data.site {
+        let dx = x / data.range_nm;
+        let dy = y / data.range_nm;
+        if dx.abs() <= 1.0 && dy.abs() <= 1.0 {
+            let x = ((dx + 1.0) * 0.5 * (width.saturating_sub(1)) as f64) as usize;
+            let y = ((1.0 - (dy + 1.0) * 0.5) * (height.saturating_sub(1)) as f64) as usize;
+            set_grid(&mut grid, x, y, 'S', 1);
+        }
+    }
+
     for point in &data.points {
         let dx = point.x / data.range_nm;
         let dy = point.y / data.range_nm;
@@ -509,8 +1163,10 @@ fn render_ascii(f: &mut Frame, area: Rect, data: &RadarData, theme: RadarTheme)
         let y = ((1.0 - (dy + 1.0) * 0.5) * (height.saturating_sub(1)) as f64) as isize;
         let xi = x.clamp(0, width.saturating_sub(1) as isize) as usize;
         let yi = y.clamp(0, height.saturating_sub(1) as isize) as usize;
-        let (ch, prio) = ascii_point_marker(point);
-        set_grid(&mut grid, xi, yi, ch, prio);
+        let (ch, prio) = ascii_point_marker(point, settings.trail_fade_secs);
+        let color = (!point.current && !point.ghost)
+            .then(|| trail_color(&theme, point, settings.trail_fade_secs));
+        set_grid_colored(&mut grid, xi, yi, ch, prio, color);
     }
 
     if let Some(selection) = &data.selection {
@@ -527,17 +1183,29 @@ fn render_ascii(f: &mut Frame, area: Rect, data: &RadarData, theme: RadarTheme)
 
     let mut lines = Vec::with_capacity(height);
     for row in grid {
-        let line: String = row.into_iter().map(|(ch, _)| ch).collect();
-        lines.push(TextLine::from(Span::styled(
-            line,
-            Style::default().fg(theme.dim),
-        )));
+        // One span per run of cells sharing a color.
+        let mut spans: Vec<Span> = Vec::new();
+        let mut run = String::new();
+        let mut run_color = theme.dim;
+        for (ch, _, color) in row {
+            let color = color.unwrap_or(theme.dim);
+            if color != run_color && !run.is_empty() {
+                spans.push(Span::styled(
+                    std::mem::take(&mut run),
+                    Style::default().fg(run_color),
+                ));
+            }
+            run_color = color;
+            run.push(ch);
+        }
+        spans.push(Span::styled(run, Style::default().fg(run_color)));
+        lines.push(TextLine::from(spans));
     }
 
     let block = Block::default()
         .borders(Borders::ALL)
         .border_type(BorderType::Rounded)
-        .title("RADAR");
+        .title(data.title());
     let paragraph = Paragraph::new(lines)
         .block(block)
         .style(Style::default().bg(theme.panel_bg));
@@ -625,15 +1293,29 @@ fn selected_blip_glyph(style: RadarBlip, track: Option<f64>) -> &'static str {
     }
 }
 
-fn ascii_point_marker(point: &RadarPoint) -> (char, u8) {
+fn ascii_point_marker(point: &RadarPoint, trail_fade_secs: Option<f64>) -> (char, u8) {
     if point.selected {
         return ('X', 5);
     }
+    if point.degraded {
+        return ('?', 2);
+    }
+    if point.ghost {
+        return ('O', 2);
+    }
     match (point.fav, point.current) {
         (true, true) => ('F', 4),
         (false, true) => ('*', 3),
         (true, false) => ('f', 2),
-        (false, false) => ('o', 1),
+        (false, false) => (TRAIL_GLYPHS[trail_age_step(point, trail_fade_secs)], 1),
+    }
+}
+
+fn ghost_text(age: Option<f64>) -> String {
+    match age {
+        Some(secs) if secs >= 60.0 => format!("○{:.0}m", secs / 60.0),
+        Some(secs) => format!("○{secs:.0}s"),
+        None => "○".to_string(),
     }
 }
 
@@ -655,11 +1337,24 @@ fn direction_glyph(track: f64) -> &'static str {
     }
 }
 
-fn set_grid(grid: &mut [Vec<(char, u8)>], x: usize, y: usize, ch: char, prio: u8) {
+type GridCell = (char, u8, Option<Color>);
+
+fn set_grid(grid: &mut [Vec<GridCell>], x: usize, y: usize, ch: char, prio: u8) {
+    set_grid_colored(grid, x, y, ch, prio, None);
+}
+
+fn set_grid_colored(
+    grid: &mut [Vec<GridCell>],
+    x: usize,
+    y: usize,
+    ch: char,
+    prio: u8,
+    color: Option<Color>,
+) {
     if let Some(row) = grid.get_mut(y) {
         if let Some(cell) = row.get_mut(x) {
             if prio >= cell.1 {
-                *cell = (ch, prio);
+                *cell = (ch, prio, color);
             }
         }
     }
@@ -680,28 +1375,6 @@ fn sweep_angle(period_ms: u64) -> f64 {
     sweep_pos * std::f64::consts::TAU
 }
 
-fn distance_nm(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
-    let dlat = (lat2 - lat1).to_radians();
-    let dlon = (lon2 - lon1).to_radians();
-    let lat1 = lat1.to_radians();
-    let lat2 = lat2.to_radians();
-    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
-    2.0 * a.sqrt().asin() * 3440.065
-}
-
-fn bearing_deg(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
-    let lat1 = lat1.to_radians();
-    let lat2 = lat2.to_radians();
-    let dlon = (lon2 - lon1).to_radians();
-    let y = dlon.sin() * lat2.cos();
-    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
-    let mut deg = y.atan2(x).to_degrees();
-    if deg < 0.0 {
-        deg += 360.0;
-    }
-    deg
-}
-
 struct LabelInfo {
     id: String,
     text: String,
@@ -726,6 +1399,14 @@ fn label_info(ac: &Aircraft) -> Option<LabelInfo> {
     Some(LabelInfo { id, text })
 }
 
+/// Whether the radar is centred on the site, so the app's cached site
+/// distance/bearing can stand in for a fresh computation.
+fn centered_on_site(app: &App, center_lat: f64, center_lon: f64) -> bool {
+    app.site()
+        .map(|site| site.lat == center_lat && site.lon == center_lon)
+        .unwrap_or(false)
+}
+
 fn truncate_text(value: &str, max_len: usize) -> String {
     if max_len == 0 {
         return value.to_string();
@@ -739,6 +1420,7 @@ fn selected_aircraft(
     center_lat: f64,
     center_lon: f64,
     range_nm: f64,
+    following: bool,
 ) -> Option<RadarSelection> {
     let idx = app
         .table_state
@@ -758,28 +1440,59 @@ fn selected_aircraft(
 
     let mut position = None;
     if let (Some(lat), Some(lon)) = (ac.lat, ac.lon) {
-        let dist = distance_nm(center_lat, center_lon, lat, lon);
-        let brg = bearing_deg(center_lat, center_lon, lat, lon);
-        let dist_text = format!("{dist:.1} nm");
-        let brg_text = format!("{brg:.0}°");
+        let predicted = app.predicted_position(ac);
+        let (lat, lon) = predicted.unwrap_or((lat, lon));
+        let (dist, brg) = match app
+            .site_geometry(idx)
+            .filter(|_| predicted.is_none() && centered_on_site(app, center_lat, center_lon))
+        {
+            Some(geometry) => (geometry.distance_nm, geometry.bearing_deg),
+            None => (
+                distance_nm(center_lat, center_lon, lat, lon),
+                bearing_deg(center_lat, center_lon, lat, lon),
+            ),
+        };
+        // Range from the followed aircraft itself is always zero; show the
+        // site's instead.
+        let (site_dist, site_brg) = app
+            .site_geometry(idx)
+            .filter(|_| following)
+            .map_or((dist, brg), |geometry| {
+                (geometry.distance_nm, geometry.bearing_deg)
+            });
+        let dist_text = format!("{site_dist:.1} nm");
+        let brg_text = format!("{site_brg:.0}°");
         lines.push(format!("RNG/BRG  {dist_text} / {brg_text}"));
         let bearing = brg.to_radians();
         let x = dist * bearing.sin();
         let y = dist * bearing.cos();
-        if dist <= range_nm {
+        let suppressed =
+            app.low_quality_positions == PositionQuality::Hide && app.low_quality_position(ac);
+        if app.position_stale(ac) {
+            lines.push("RADAR    POS STALE".to_string());
+        } else if suppressed {
+            lines.push("RADAR    LOW NIC/NAC".to_string());
+        } else if dist <= range_nm {
             position = Some((x, y));
         } else {
             lines.push("RADAR    OUT OF RANGE".to_string());
         }
+    } else if let Some((_, _, age)) = app.ghost_position(ac) {
+        lines.push("RNG/BRG  -- / --".to_string());
+        let age = age
+            .map(|v| format!("{v:.0}s"))
+            .unwrap_or_else(|| "--".to_string());
+        lines.push(format!("RADAR    LAST POS {age}"));
     } else {
         lines.push("RNG/BRG  -- / --".to_string());
         lines.push("RADAR    NO POSITION".to_string());
     }
 
-    let alt = ac.alt_baro.or(ac.alt_geom);
-    let alt_text = alt
-        .map(|v| format!("{v} ft"))
-        .unwrap_or_else(|| "--".to_string());
+    let alt_text = match app.altitude_for(ac) {
+        Some(Altitude::Ground) => "GND".to_string(),
+        Some(alt) => format!("{alt} ft"),
+        None => "--".to_string(),
+    };
     let gs_text = ac
         .gs
         .map(|v| format!("{v:.0} kt"))
@@ -801,61 +1514,32 @@ fn selected_aircraft(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::app::AppData;
     use crate::model::Aircraft;
-    use std::collections::HashSet;
-    use std::path::PathBuf;
     use std::time::Duration;
 
     fn make_app() -> App {
+        let mut config = crate::config::base_config();
+        config.layout = "radar".to_string();
+        config.radar_range_nm = 50.0;
+        config.radar_aspect = 1.0;
+        config.radar_renderer = "canvas".to_string();
+        config.radar_labels = false;
+        config.radar_blip = "dot".to_string();
+        config.radar_vectors = false;
+        config.predict_positions = false;
+        config.route_enabled = false;
+        config.watchlist_file = String::new();
+        config.favorites_file = String::new();
+        config.traffic_history_file = String::new();
+        config.sightings_file = String::new();
+        config.zones_file = String::new();
         App::new(
-            "http://example".to_string(),
-            Vec::new(),
-            Duration::from_secs(1),
-            60.0,
-            false,
-            5,
-            8,
-            HashSet::new(),
-            String::new(),
-            LayoutMode::Radar,
-            crate::app::ThemeMode::Default,
-            true,
-            true,
-            true,
-            Duration::from_millis(400),
-            PathBuf::from("adsb-tui.toml"),
-            3,
-            None,
-            None,
-            false,
-            50.0,
-            1.0,
-            RadarRenderer::Canvas,
-            false,
-            RadarBlip::Dot,
-            false,
-            false,
-            Duration::from_secs(1),
-            Duration::from_secs(1),
-            1,
-            10,
-            true,
-            true,
-            Duration::from_millis(300),
-            0.2,
-            10.0,
-            0.5,
-            Duration::from_secs(60),
-            true,
-            true,
-            true,
-            crate::app::FlagStyle::Emoji,
-            "msg_rate_total".to_string(),
-            "kbps_total".to_string(),
-            "msg_rate_avg".to_string(),
-            true,
-            Some(PathBuf::from("adsb-watchlist.toml")),
-            Vec::new(),
+            &config,
+            AppData {
+                url: "http://example".to_string(),
+                ..AppData::default()
+            },
         )
     }
 
@@ -967,13 +1651,327 @@ mod tests {
         let point = RadarPoint {
             x: 0.0,
             y: 0.0,
+            idx: None,
             track: None,
             fav: false,
             current: true,
             selected: true,
+            degraded: false,
+            ghost: false,
+            seen_secs: None,
+            altitude: None,
+            gs: None,
+        };
+
+        assert_eq!(ascii_point_marker(&point, None), ('X', 5));
+    }
+
+    #[test]
+    fn leader_line_covers_the_distance_flown_in_the_window() {
+        let point = RadarPoint {
+            x: 1.0,
+            y: 2.0,
+            idx: None,
+            track: Some(90.0),
+            gs: Some(240.0),
+            fav: false,
+            current: true,
+            selected: false,
+            degraded: false,
+            ghost: false,
             seen_secs: None,
+            altitude: None,
         };
+        // 240 kt for a minute is 4 nm due east.
+        let (x, y) = vector_end(&point, 60.0).unwrap();
+        assert!((x - 5.0).abs() < 1e-9);
+        assert!((y - 2.0).abs() < 1e-9);
+
+        assert!(vector_end(
+            &RadarPoint {
+                track: None,
+                ..point
+            },
+            60.0
+        )
+        .is_none());
+        assert!(vector_end(
+            &RadarPoint {
+                gs: Some(0.0),
+                ..point
+            },
+            60.0
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn altitude_palette_interpolates_between_stops() {
+        let palette = AltitudePalette {
+            ground: (1, 2, 3),
+            stops: [
+                (0, 0, 0),
+                (100, 0, 0),
+                (100, 100, 0),
+                (100, 100, 100),
+                (200, 100, 100),
+                (200, 200, 200),
+            ],
+        };
+        assert_eq!(palette.color(Altitude::Ground), Color::Rgb(1, 2, 3));
+        assert_eq!(palette.color(Altitude::Feet(-50)), Color::Rgb(0, 0, 0));
+        assert_eq!(palette.color(Altitude::Feet(1_000)), Color::Rgb(50, 0, 0));
+        // Banded to the 1,000 ft below.
+        assert_eq!(palette.color(Altitude::Feet(4_900)), Color::Rgb(100, 50, 0));
+        assert_eq!(
+            palette.color(Altitude::Feet(12_000)),
+            Color::Rgb(100, 100, 100)
+        );
+        assert_eq!(
+            palette.color(Altitude::Feet(51_000)),
+            Color::Rgb(200, 200, 200)
+        );
+    }
 
-        assert_eq!(ascii_point_marker(&point), ('X', 5));
+    #[test]
+    fn low_quality_positions_are_dimmed_or_hidden() {
+        let mut app = make_app();
+        app.radar_center = Some(crate::app::RadarCenter { lat: 0.0, lon: 0.0 });
+        let mut bad = positioned_aircraft("bad", 0.1, 0.0);
+        bad.nic = Some(2);
+        app.data.aircraft = vec![bad, positioned_aircraft("good", 0.0, 0.1)];
+        let indices = vec![0, 1];
+
+        let data = collect_data(&app, &indices, 50.0, false).expect("radar data");
+        assert_eq!(data.points.iter().filter(|p| p.degraded).count(), 0);
+
+        app.low_quality_positions = PositionQuality::Dim;
+        let data = collect_data(&app, &indices, 50.0, false).expect("radar data");
+        let degraded: Vec<_> = data.points.iter().filter(|p| p.degraded).collect();
+        assert_eq!(degraded.len(), 1);
+        assert!(degraded[0].y > 0.0);
+
+        app.low_quality_positions = PositionQuality::Hide;
+        let data = collect_data(&app, &indices, 50.0, false).expect("radar data");
+        assert_eq!(data.points.len(), 1);
+        assert!(data.points[0].x > 0.0);
+    }
+
+    #[test]
+    fn stale_positions_keep_only_their_trail() {
+        let mut app = make_app();
+        app.radar_center = Some(crate::app::RadarCenter { lat: 0.0, lon: 0.0 });
+        app.position_stale_secs = 20.0;
+        let mut old = positioned_aircraft("old", 0.1, 0.0);
+        old.seen = Some(1.0);
+        old.seen_pos = Some(30.0);
+        let mut fresh = positioned_aircraft("fresh", 0.0, 0.1);
+        fresh.seen_pos = Some(2.0);
+        app.data.aircraft = vec![old, fresh];
+
+        let data = collect_data(&app, &[0, 1], 50.0, false).expect("radar data");
+        assert_eq!(data.points.len(), 1);
+        assert!(data.points[0].x > 0.0);
+    }
+
+    #[test]
+    fn trail_points_fade_with_age_and_drop_past_fade_secs() {
+        let mut app = make_app();
+        app.radar_center = Some(crate::app::RadarCenter { lat: 0.0, lon: 0.0 });
+        app.data.aircraft = vec![positioned_aircraft("abc123", 0.0, 0.0)];
+        let now = UNIX_EPOCH + Duration::from_secs(1_000);
+        app.last_update = Some(now);
+        let point = |secs_ago: u64, feet: i64| crate::app::TrailPoint {
+            lat: 0.1,
+            lon: 0.0,
+            alt: Some(Altitude::Feet(feet)),
+            at: now - Duration::from_secs(secs_ago),
+        };
+        app.trail_points.insert(
+            Arc::from("abc123"),
+            [point(200, 1_000), point(50, 2_000), point(5, 3_000)].into(),
+        );
+
+        let data = collect_data(&app, &[0], 50.0, false).expect("radar data");
+        assert_eq!(data.points.len(), 4, "no fade keeps every point");
+
+        app.trail_fade_secs = 60;
+        let data = collect_data(&app, &[0], 50.0, false).expect("radar data");
+        let trail: Vec<_> = data.points.iter().filter(|p| !p.current).collect();
+        assert_eq!(trail.len(), 2);
+        assert_eq!(trail[0].altitude, Some(Altitude::Feet(2_000)));
+        let fade = Some(60.0);
+        assert_eq!(ascii_point_marker(trail[0], fade), (',', 1));
+        assert_eq!(ascii_point_marker(trail[1], fade), ('o', 1));
+
+        let theme = RadarTheme {
+            accent: Color::Rgb(0, 255, 0),
+            dim: Color::Rgb(100, 100, 100),
+            fav: Color::Rgb(255, 255, 0),
+            warn: Color::Rgb(255, 0, 0),
+            highlight: Color::Rgb(0, 0, 255),
+            panel_bg: Color::Rgb(0, 0, 0),
+            altitude: None,
+            trail_altitude: None,
+        };
+        assert_eq!(
+            trail_color(&theme, trail[1], fade),
+            Color::Rgb(100, 100, 100)
+        );
+        assert_eq!(trail_color(&theme, trail[0], fade), Color::Rgb(40, 40, 40));
+        assert_eq!(
+            trail_color(&theme, trail[0], None),
+            Color::Rgb(100, 100, 100)
+        );
+    }
+
+    #[test]
+    fn timed_out_positions_become_ghosts_when_enabled() {
+        let mut app = make_app();
+        app.radar_center = Some(crate::app::RadarCenter { lat: 0.0, lon: 0.0 });
+        let ac: Aircraft = serde_json::from_str(
+            r#"{"hex": "ghost", "lastPosition": {"lat": 0.1, "lon": 0.0, "seen_pos": 95.2}}"#,
+        )
+        .unwrap();
+        app.data.aircraft = vec![ac];
+        app.table_state.select(None);
+
+        assert!(collect_data(&app, &[0], 50.0, false).is_none());
+
+        app.radar_ghosts = true;
+        let data = collect_data(&app, &[0], 50.0, false).expect("radar data");
+        assert_eq!(data.points.len(), 1);
+        let point = data.points[0];
+        assert!(point.ghost && !point.current);
+        assert!(point.y > 0.0);
+        assert_eq!(ascii_point_marker(&point, None), ('O', 2));
+        assert_eq!(ghost_text(point.seen_secs), "○2m");
+    }
+
+    #[test]
+    fn airports_in_range_become_markers() {
+        let mut app = make_app();
+        app.radar_center = Some(crate::app::RadarCenter { lat: 0.0, lon: 0.0 });
+        app.airports = crate::airports::AirportDb::parse_csv(
+            "ident,type,name,latitude_deg,longitude_deg\n\
+             NEAR,large_airport,Near,0.0,0.1\n\
+             STRP,small_airport,Strip,0.1,0.0\n\
+             FAR,large_airport,Far,3.0,0.0\n",
+        )
+        .unwrap();
+        app.data.aircraft = vec![positioned_aircraft("a", 0.05, 0.0)];
+
+        let data = collect_data(&app, &[0], 50.0, true).expect("radar data");
+        assert_eq!(data.airports.len(), 1);
+        assert_eq!(data.airports[0].text, "△NEAR");
+        assert!(data.airports[0].x > 0.0);
+
+        app.radar_airports = RadarAirports::All;
+        let data = collect_data(&app, &[0], 50.0, false).expect("radar data");
+        assert_eq!(data.airports.len(), 2);
+        assert!(data.airports.iter().all(|airport| airport.text == "△"));
+
+        app.radar_airports = RadarAirports::Off;
+        let data = collect_data(&app, &[0], 50.0, false).expect("radar data");
+        assert!(data.airports.is_empty());
+    }
+
+    #[test]
+    fn screen_cell_maps_center_and_edges() {
+        let area = Rect::new(10, 5, 22, 12);
+        assert_eq!(screen_cell(area, 50.0, 1.0, 0.0, 0.0), Some((20, 10)));
+        assert_eq!(screen_cell(area, 50.0, 1.0, -50.0, 50.0), Some((11, 6)));
+        assert_eq!(screen_cell(area, 50.0, 1.0, 50.0, -50.0), Some((30, 15)));
+        assert_eq!(screen_cell(area, 50.0, 1.0, 60.0, 0.0), None);
+    }
+
+    #[test]
+    fn follow_centers_on_selected_aircraft_and_marks_site() {
+        let mut app = make_app();
+        app.site = Some(crate::app::SiteLocation {
+            lat: 0.0,
+            lon: 0.0,
+            alt_m: 0.0,
+        });
+        app.data.aircraft = vec![
+            positioned_aircraft("a", 0.0, 0.0),
+            positioned_aircraft("b", 0.0, 0.2),
+        ];
+        app.table_state.select(Some(1));
+        let data = collect_data(&app, &[0, 1], 50.0, false).expect("radar data");
+        assert!(!data.following && data.site.is_none());
+
+        app.toggle_radar_follow(&[0, 1]);
+        let data = collect_data(&app, &[0, 1], 50.0, false).expect("radar data");
+        assert!(data.following);
+        assert_eq!(data.title(), "RADAR FOLLOW");
+        let followed = data.points.iter().find(|p| p.idx == Some(1)).unwrap();
+        assert!(followed.x.abs() < 1e-6 && followed.y.abs() < 1e-6);
+        let (x, _) = data.site.expect("site marker");
+        assert!((x + 12.0).abs() < 0.1, "site is 12 nm west: {x}");
+        let selection = data.selection.expect("selection");
+        assert!(selection.lines[2].starts_with("RNG/BRG  12.0 nm / 90"));
+    }
+
+    #[test]
+    fn range_rose_outlines_the_saved_maximum() {
+        let mut app = make_app();
+        app.site = Some(crate::app::SiteLocation {
+            lat: 0.0,
+            lon: 0.0,
+            alt_m: 0.0,
+        });
+        app.data.aircraft = vec![positioned_aircraft("a", 0.0, 0.0)];
+        app.traffic.record_range((0.0, 0.0), 95.0, 30.0);
+        let data = collect_data(&app, &[0], 50.0, false).expect("radar data");
+        assert!(data.range_rose.is_empty(), "off by default");
+
+        app.radar_range_rose = true;
+        let data = collect_data(&app, &[0], 50.0, false).expect("radar data");
+        assert_eq!(data.range_rose.len(), 37, "36 sectors, closed");
+        assert_eq!(data.range_rose[0], data.range_rose[36]);
+        let (x, y) = data.range_rose[9];
+        assert!((x - 30.0 * 95f64.to_radians().sin()).abs() < 1e-9);
+        assert!((y - 30.0 * 95f64.to_radians().cos()).abs() < 1e-9);
+        assert!(data.range_rose[0].0.abs() < 1e-9 && data.range_rose[0].1.abs() < 1e-9);
+    }
+
+    #[test]
+    fn ring_labels_round_and_mark_the_outer_ring() {
+        assert_eq!(ring_label(50.0, false), "50");
+        assert_eq!(ring_label(200.0, true), "200nm");
+        assert_eq!(ring_label(2.5, false), "2.5");
+        assert_eq!(ring_label(12.6, false), "13");
+    }
+
+    #[test]
+    fn compass_marks_hide_on_small_panels() {
+        assert_eq!(compass_marks(Rect::new(0, 0, 60, 30), true), (true, true));
+        assert_eq!(compass_marks(Rect::new(0, 0, 40, 9), true), (true, false));
+        assert_eq!(compass_marks(Rect::new(0, 0, 16, 9), true), (false, false));
+        assert_eq!(
+            compass_marks(Rect::new(0, 0, 60, 30), false),
+            (false, false)
+        );
+    }
+
+    #[test]
+    fn hit_at_picks_nearest_blip() {
+        let hits = [
+            RadarHit {
+                col: 10,
+                row: 5,
+                idx: 0,
+            },
+            RadarHit {
+                col: 13,
+                row: 5,
+                idx: 1,
+            },
+        ];
+        assert_eq!(hit_at(&hits, 10, 5), Some(0));
+        assert_eq!(hit_at(&hits, 12, 5), Some(1));
+        assert_eq!(hit_at(&hits, 10, 6), Some(0));
+        assert_eq!(hit_at(&hits, 10, 8), None);
     }
 }
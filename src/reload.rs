@@ -0,0 +1,117 @@
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{info, warn};
+
+use crate::cli::ConfigArgs;
+use crate::config::{self, Config};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct ConfigReload {
+    pub config: Config,
+    pub restart_keys: Vec<&'static str>,
+}
+
+pub struct ConfigWatcher {
+    args: ConfigArgs,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Option<Instant>,
+    current: Config,
+}
+
+impl ConfigWatcher {
+    pub fn new(args: ConfigArgs, config: Config) -> Self {
+        let path = config.config_path.clone();
+        Self {
+            args,
+            modified: modified_time(&path),
+            path,
+            last_check: None,
+            current: config,
+        }
+    }
+
+    pub fn poll(&mut self, now: Instant) -> Option<Result<ConfigReload, String>> {
+        if self
+            .last_check
+            .is_some_and(|last| now.duration_since(last) < CHECK_INTERVAL)
+        {
+            return None;
+        }
+        self.last_check = Some(now);
+
+        let modified = modified_time(&self.path);
+        if modified.is_none() || modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(self.reload())
+    }
+
+    fn reload(&mut self) -> Result<ConfigReload, String> {
+        match config::load_config(&self.args) {
+            Ok(next) => {
+                let restart_keys = config::restart_required_keys(&self.current, &next);
+                info!("config reloaded from {}", self.path.display());
+                self.current = next.clone();
+                Ok(ConfigReload {
+                    config: next,
+                    restart_keys,
+                })
+            }
+            Err(err) => {
+                warn!("config reload failed: {err}");
+                Err(err.to_string())
+            }
+        }
+    }
+}
+
+fn modified_time(path: &PathBuf) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigWatcher;
+    use crate::cli::ConfigArgs;
+    use crate::config::load_config;
+    use std::fs;
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    #[test]
+    fn watcher_reloads_changed_file_and_reports_restart_keys() {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!("adsb-tui-reload-{suffix}.toml"));
+        fs::write(&path, "stale_secs = 30\nrefresh_secs = 2\n").unwrap();
+
+        let args = ConfigArgs {
+            config: Some(path.clone()),
+            ..ConfigArgs::default()
+        };
+        let config = load_config(&args).unwrap();
+        let mut watcher = ConfigWatcher::new(args, config);
+        let start = Instant::now();
+        assert!(watcher.poll(start).is_none());
+
+        fs::write(&path, "stale_secs = 90\nrefresh_secs = 5\n").unwrap();
+        let file = fs::File::options().write(true).open(&path).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(5))
+            .unwrap();
+
+        assert!(watcher.poll(start + Duration::from_millis(10)).is_none());
+        let reload = watcher
+            .poll(start + Duration::from_secs(2))
+            .expect("reload")
+            .expect("valid config");
+        assert_eq!(reload.config.stale_secs, 90);
+        assert_eq!(reload.restart_keys, vec!["refresh"]);
+        assert!(watcher.poll(start + Duration::from_secs(4)).is_none());
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,378 @@
+//! Role classification rules; the first matching rule wins.
+
+use ratatui::style::Color;
+use serde::Deserialize;
+
+use crate::app::AircraftRole;
+use crate::military::{self, MilitaryRanges};
+use crate::model::Aircraft;
+
+const MILITARY_CALLSIGNS: &[&str] = &[
+    "RCH", "MC", "MAF", "NAF", "BAF", "GAF", "LAGR", "TEXAN", "PAT", "SAM", "SPAR", "ASF", "CFC",
+    "CAF", "HK", "VV", "VM", "AF", "SEN", "CNV", "JENA", "KING",
+];
+
+const GOVERNMENT_CALLSIGNS: &[&str] = &[
+    "NATION", "GOV", "GVT", "POL", "RIDER", "EAG", "EAGLE", "COAST", "CST",
+];
+
+const MILITARY_OWNERS: &[&str] = &[
+    "air force",
+    "navy",
+    "marine",
+    "marines",
+    "army",
+    "usaf",
+    "usn",
+    "usmc",
+    "uscg",
+    "raf",
+    "rcaf",
+    "luftwaffe",
+    "bundeswehr",
+    "aeronautica",
+    "military",
+    "defence",
+    "defense",
+    "armée",
+    "ejército",
+];
+
+const MILITARY_TYPES: &[&str] = &[
+    "C17", "C-17", "C130", "C-130", "K35", "KC10", "KC-10", "KC46", "E3", "E-3", "E6", "E-6", "P8",
+    "P-8", "P3", "P-3", "B52", "B-52", "F1", "F2", "F3", "F4", "F5", "F6", "F14", "F15", "F16",
+    "F18", "F22", "F35",
+];
+
+const GOVERNMENT_OWNERS: &[&str] = &[
+    "government",
+    "gov",
+    "border",
+    "customs",
+    "police",
+    "gendarmerie",
+    "coast guard",
+    "homeland",
+    "cbp",
+    "state",
+    "federal",
+    "department",
+    "ministry",
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RolesFile {
+    #[serde(default = "default_builtin")]
+    builtin: bool,
+    #[serde(default, rename = "rule")]
+    rules: Vec<RuleSpec>,
+}
+
+fn default_builtin() -> bool {
+    true
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RuleSpec {
+    role: String,
+    color: Option<String>,
+    callsigns: Vec<String>,
+    hex: Vec<String>,
+    owners: Vec<String>,
+    types: Vec<String>,
+    categories: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoleRule {
+    pub role: AircraftRole,
+    pub color: Option<Color>,
+    callsigns: Vec<String>,
+    hex_ranges: Vec<(u32, u32)>,
+    owners: Vec<String>,
+    types: Vec<String>,
+    categories: Vec<String>,
+}
+
+impl RoleRule {
+    fn new(role: AircraftRole) -> Self {
+        Self {
+            role,
+            color: None,
+            callsigns: Vec::new(),
+            hex_ranges: Vec::new(),
+            owners: Vec::new(),
+            types: Vec::new(),
+            categories: Vec::new(),
+        }
+    }
+
+    fn from_spec(spec: RuleSpec) -> Result<Self, String> {
+        let role = match spec.role.trim().to_ascii_lowercase().as_str() {
+            "military" => AircraftRole::Military,
+            "government" => AircraftRole::Government,
+            "commercial" => AircraftRole::Commercial,
+            "" => return Err("missing role".to_string()),
+            other => {
+                return Err(format!(
+                    "unknown role `{other}` (military, government or commercial)"
+                ))
+            }
+        };
+        let color = spec
+            .color
+            .map(|color| {
+                color
+                    .trim()
+                    .parse::<Color>()
+                    .map_err(|_| format!("`{color}` is not a color"))
+            })
+            .transpose()?;
+        let hex_ranges = spec
+            .hex
+            .iter()
+            .map(|range| {
+                military::parse_range(range.trim())
+                    .ok_or_else(|| format!("`{range}` is not a hex range"))
+            })
+            .collect::<Result<_, _>>()?;
+        let upper = |values: Vec<String>| values.iter().map(|v| v.trim().to_uppercase()).collect();
+        let lower = |values: Vec<String>| values.iter().map(|v| v.trim().to_lowercase()).collect();
+        let rule = Self {
+            role,
+            color,
+            callsigns: upper(spec.callsigns),
+            hex_ranges,
+            owners: lower(spec.owners),
+            types: upper(spec.types),
+            categories: lower(spec.categories),
+        };
+        if rule.callsigns.is_empty()
+            && rule.hex_ranges.is_empty()
+            && rule.owners.is_empty()
+            && rule.types.is_empty()
+            && rule.categories.is_empty()
+        {
+            return Err("matches nothing: add callsigns, hex, owners, types or categories".into());
+        }
+        Ok(rule)
+    }
+
+    /// Any one of the rule's lists matching is enough.
+    fn matches(&self, ac: &Fields) -> bool {
+        (!ac.callsign.is_empty()
+            && self
+                .callsigns
+                .iter()
+                .any(|p| ac.callsign.starts_with(p.as_str())))
+            || ac.address.is_some_and(|address| {
+                self.hex_ranges
+                    .iter()
+                    .any(|&(first, last)| (first..=last).contains(&address))
+            })
+            || self
+                .owners
+                .iter()
+                .any(|k| ac.owner.contains(k.as_str()) || ac.desc.contains(k.as_str()))
+            || (!ac.ac_type.is_empty()
+                && self
+                    .types
+                    .iter()
+                    .any(|p| ac.ac_type.starts_with(p.as_str())))
+            || self
+                .categories
+                .iter()
+                .any(|c| ac.category.contains(c.as_str()))
+    }
+}
+
+struct Fields {
+    callsign: String,
+    ac_type: String,
+    owner: String,
+    desc: String,
+    category: String,
+    address: Option<u32>,
+}
+
+impl Fields {
+    fn new(ac: &Aircraft) -> Self {
+        fn field(value: &Option<String>) -> &str {
+            value.as_deref().unwrap_or("").trim()
+        }
+        Self {
+            callsign: field(&ac.flight).to_uppercase(),
+            ac_type: field(&ac.t).to_uppercase(),
+            owner: field(&ac.own_op).to_lowercase(),
+            desc: field(&ac.desc).to_lowercase(),
+            category: field(&ac.category).to_lowercase(),
+            address: ac.hex.as_deref().and_then(military::parse_hex),
+        }
+    }
+}
+
+fn texts(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RoleRules {
+    rules: Vec<RoleRule>,
+}
+
+impl Default for RoleRules {
+    fn default() -> Self {
+        Self::builtin(&MilitaryRanges::default())
+    }
+}
+
+impl RoleRules {
+    pub fn builtin(military: &MilitaryRanges) -> Self {
+        let mut by_callsign = RoleRule::new(AircraftRole::Military);
+        by_callsign.callsigns = texts(MILITARY_CALLSIGNS);
+        by_callsign.hex_ranges = military.ranges().map(|r| (r.first, r.last)).collect();
+        let mut government_callsign = RoleRule::new(AircraftRole::Government);
+        government_callsign.callsigns = texts(GOVERNMENT_CALLSIGNS);
+        let mut by_owner = RoleRule::new(AircraftRole::Military);
+        by_owner.owners = texts(MILITARY_OWNERS);
+        by_owner.types = texts(MILITARY_TYPES);
+        by_owner.categories = texts(&["mil"]);
+        let mut government_owner = RoleRule::new(AircraftRole::Government);
+        government_owner.owners = texts(GOVERNMENT_OWNERS);
+        Self {
+            rules: vec![by_callsign, government_callsign, by_owner, government_owner],
+        }
+    }
+
+    /// Parse a roles file, adding the built-in rules after its own unless
+    /// it sets `builtin = false`.
+    pub fn parse(text: &str, military: &MilitaryRanges) -> Result<Self, String> {
+        let file: RolesFile = toml::from_str(text).map_err(|err| err.to_string())?;
+        let mut rules = file
+            .rules
+            .into_iter()
+            .enumerate()
+            .map(|(i, spec)| {
+                RoleRule::from_spec(spec).map_err(|err| format!("rule {}: {err}", i + 1))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if file.builtin {
+            rules.extend(Self::builtin(military).rules);
+        }
+        Ok(Self { rules })
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn classify(&self, ac: &Aircraft) -> Option<&RoleRule> {
+        let fields = Fields::new(ac);
+        self.rules.iter().find(|rule| rule.matches(&fields))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RoleRules;
+    use crate::app::AircraftRole;
+    use crate::military::MilitaryRanges;
+    use crate::model::Aircraft;
+    use ratatui::style::Color;
+
+    fn aircraft(hex: &str, flight: &str, owner: &str) -> Aircraft {
+        Aircraft {
+            hex: Some(hex.to_string()),
+            flight: Some(flight.to_string()),
+            own_op: Some(owner.to_string()),
+            ..Aircraft::default()
+        }
+    }
+
+    fn role(rules: &RoleRules, ac: &Aircraft) -> Option<AircraftRole> {
+        rules.classify(ac).map(|rule| rule.role)
+    }
+
+    #[test]
+    fn builtin_rules_keep_callsign_then_owner_order() {
+        let rules = RoleRules::default();
+        let reach = aircraft("a00001", "RCH123 ", "");
+        assert_eq!(role(&rules, &reach), Some(AircraftRole::Military));
+        let police = aircraft("a00002", "POL1", "US Air Force");
+        assert_eq!(role(&rules, &police), Some(AircraftRole::Government));
+        let customs = aircraft("a00003", "", "US Customs and Border Protection");
+        assert_eq!(role(&rules, &customs), Some(AircraftRole::Government));
+        let raf = aircraft("43c123", "", "");
+        assert_eq!(role(&rules, &raf), Some(AircraftRole::Military));
+        let airline = aircraft("a00004", "SWA1", "Southwest Airlines");
+        assert_eq!(role(&rules, &airline), None);
+    }
+
+    #[test]
+    fn file_rules_run_first_with_colors() {
+        let text = r#"
+            [[rule]]
+            role = "government"
+            color = "green"
+            callsigns = ["life"]
+            owners = ["air ambulance"]
+
+            [[rule]]
+            role = "commercial"
+            hex = ["43C0xx"]
+        "#;
+        let rules = RoleRules::parse(text, &MilitaryRanges::default()).unwrap();
+        assert_eq!(rules.len(), 6);
+        let medevac = aircraft("a00001", "LIFE12", "");
+        let rule = rules.classify(&medevac).unwrap();
+        assert_eq!(
+            (rule.role, rule.color),
+            (AircraftRole::Government, Some(Color::Green))
+        );
+        assert_eq!(
+            role(&rules, &aircraft("43c0ff", "", "")),
+            Some(AircraftRole::Commercial)
+        );
+        assert_eq!(
+            role(&rules, &aircraft("43c1ff", "", "")),
+            Some(AircraftRole::Military)
+        );
+
+        let only = RoleRules::parse(
+            "builtin = false\n[[rule]]\nrole = \"military\"\ntypes = [\"EUFI\"]\n",
+            &MilitaryRanges::default(),
+        )
+        .unwrap();
+        assert_eq!(only.len(), 1);
+        assert_eq!(role(&only, &aircraft("43c123", "RCH1", "")), None);
+    }
+
+    #[test]
+    fn reports_bad_rules() {
+        let military = MilitaryRanges::default();
+        for (text, error) in [
+            (
+                "[[rule]]\nrole = \"pirate\"\ncallsigns = [\"X\"]",
+                "rule 1: unknown role",
+            ),
+            ("[[rule]]\nrole = \"military\"", "rule 1: matches nothing"),
+            (
+                "[[rule]]\nrole = \"military\"\ncolor = \"plaid\"\ntypes = [\"X\"]",
+                "rule 1: `plaid`",
+            ),
+            (
+                "[[rule]]\nrole = \"military\"\nhex = [\"XYZ\"]",
+                "rule 1: `XYZ`",
+            ),
+            (
+                "[[rule]]\nrole = \"military\"\ncallsign = [\"X\"]",
+                "unknown field",
+            ),
+        ] {
+            let err = RoleRules::parse(text, &military).unwrap_err();
+            assert!(err.contains(error), "{text}: {err}");
+        }
+    }
+}
@@ -1,9 +1,14 @@
 use serde_json::{Map, Value};
-use std::sync::mpsc::{Receiver, Sender};
-use std::thread;
 use std::time::Duration;
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 use tracing::{debug, error, info};
 
+use crate::net::build_async_client;
+use crate::tasks::Tasks;
+
+/// A route batch gets this many request timeouts in total.
+const BATCH_DEADLINE_REQUESTS: u32 = 4;
+
 #[derive(Clone, Debug)]
 pub struct RouteRequest {
     pub callsign: String,
@@ -25,41 +30,58 @@ pub enum RouteMessage {
     Error(String),
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn spawn_route_fetcher(
+    tasks: &Tasks,
     base_url: String,
     route_mode: String,
     route_path: String,
     insecure: bool,
     timeout: Duration,
-    tx: Sender<RouteMessage>,
-    rx: Receiver<Vec<RouteRequest>>,
+    tx: UnboundedSender<RouteMessage>,
+    mut rx: UnboundedReceiver<Vec<RouteRequest>>,
 ) {
-    thread::spawn(move || {
+    let pool = tasks.pool().clone();
+    tasks.spawn("routes", async move {
         info!("route fetcher started");
-        let client = match reqwest::blocking::Client::builder()
-            .danger_accept_invalid_certs(insecure)
-            .timeout(timeout)
-            .build()
-        {
-            Ok(client) => client,
-            Err(err) => {
-                error!("route client error: {err}");
-                let _ = tx.send(RouteMessage::Error(format!("Route client error: {err}")));
-                return;
-            }
-        };
+        // Built on the first batch so a slow TLS setup never delays startup.
+        let mut client: Option<reqwest::Client> = None;
 
-        while let Ok(batch) = rx.recv() {
-            let mode = route_mode.to_ascii_lowercase();
-            let result = if mode == "tar1090" {
-                fetch_tar1090(&client, &base_url, &route_path)
-            } else {
-                if batch.is_empty() {
-                    debug!("route fetch skipped (empty batch)");
-                    continue;
-                }
-                fetch_routeset(&client, &base_url, &batch)
+        while let Some(batch) = rx.recv().await {
+            let tar1090 = route_mode.eq_ignore_ascii_case("tar1090");
+            if !tar1090 && batch.is_empty() {
+                debug!("route fetch skipped (empty batch)");
+                continue;
+            }
+            let client = match client.as_ref() {
+                Some(client) => client.clone(),
+                None => match build_async_client(insecure, timeout) {
+                    Ok(built) => client.insert(built).clone(),
+                    Err(err) => {
+                        error!("route client error: {err}");
+                        let _ = tx.send(RouteMessage::Error(format!("Route client error: {err}")));
+                        return;
+                    }
+                },
             };
+            // A batch walks several endpoint and payload variants; cap the
+            // whole walk so one slow host cannot hold up the next batch.
+            let deadline = timeout * BATCH_DEADLINE_REQUESTS;
+            let result = pool
+                .run(&base_url, async {
+                    let fetch = async {
+                        if tar1090 {
+                            fetch_tar1090(&client, &base_url, &route_path).await
+                        } else {
+                            fetch_routeset(&client, &base_url, &batch).await
+                        }
+                    };
+                    tokio::time::timeout(deadline, fetch)
+                        .await
+                        .unwrap_or_else(|_| Err("Route batch timed out".to_string()))
+                })
+                .await
+                .unwrap_or_else(|| Err("Route fetch aborted".to_string()));
 
             match result {
                 Ok(results) => {
@@ -75,18 +97,18 @@ pub fn spawn_route_fetcher(
     });
 }
 
-fn fetch_routeset(
-    client: &reqwest::blocking::Client,
+async fn fetch_routeset(
+    client: &reqwest::Client,
     base_url: &str,
     batch: &[RouteRequest],
 ) -> Result<Vec<RouteResult>, String> {
-    match fetch_routeset_with_base(client, base_url, batch) {
+    match fetch_routeset_with_base(client, base_url, batch).await {
         Ok(results) => Ok(results),
         Err(err) => {
             // If airplanes.live is down or missing routeset, fall back to adsb.lol (shared backend).
             if base_url.contains("airplanes.live") {
                 let alt = "https://api.adsb.lol";
-                if let Ok(results) = fetch_routeset_with_base(client, alt, batch) {
+                if let Ok(results) = fetch_routeset_with_base(client, alt, batch).await {
                     return Ok(results);
                 }
             }
@@ -95,8 +117,8 @@ fn fetch_routeset(
     }
 }
 
-fn fetch_routeset_with_base(
-    client: &reqwest::blocking::Client,
+async fn fetch_routeset_with_base(
+    client: &reqwest::Client,
     base_url: &str,
     batch: &[RouteRequest],
 ) -> Result<Vec<RouteResult>, String> {
@@ -133,7 +155,7 @@ fn fetch_routeset_with_base(
 
     for url in urls.iter() {
         for payload in &payloads {
-            match post_payload(client, url, payload) {
+            match post_payload(client, url, payload).await {
                 Ok(body) => {
                     let parsed = parse_routes(body);
                     if !parsed.is_empty() {
@@ -152,14 +174,14 @@ fn fetch_routeset_with_base(
         }
     }
 
-    if let Ok(body) = get_with_callsigns(client, &urls, &callsigns) {
+    if let Ok(body) = get_with_callsigns(client, &urls, &callsigns).await {
         let parsed = parse_routes(body);
         if !parsed.is_empty() {
             return Ok(parsed);
         }
     }
 
-    if let Ok(results) = fetch_route_get_callsigns(client, base_url, &callsigns) {
+    if let Ok(results) = fetch_route_get_callsigns(client, base_url, &callsigns).await {
         if !results.is_empty() {
             return Ok(results);
         }
@@ -168,8 +190,8 @@ fn fetch_routeset_with_base(
     Err(last_err.unwrap_or_else(|| "Route request failed".to_string()))
 }
 
-fn get_with_callsigns(
-    client: &reqwest::blocking::Client,
+async fn get_with_callsigns(
+    client: &reqwest::Client,
     urls: &[String],
     callsigns: &[String],
 ) -> Result<Value, String> {
@@ -201,7 +223,7 @@ fn get_with_callsigns(
             };
             parsed.query_pairs_mut().append_pair(key, val);
 
-            match client.get(parsed).send() {
+            match client.get(parsed).send().await {
                 Ok(resp) => {
                     let status = resp.status();
                     if !status.is_success() {
@@ -216,7 +238,7 @@ fn get_with_callsigns(
                         last_err = Some(format!("Route HTTP {}", status));
                         continue;
                     }
-                    match resp.json::<Value>() {
+                    match resp.json::<Value>().await {
                         Ok(body) => return Ok(body),
                         Err(err) => {
                             last_err = Some(err.to_string());
@@ -235,8 +257,8 @@ fn get_with_callsigns(
     Err(last_err.unwrap_or_else(|| "Route request failed".to_string()))
 }
 
-fn fetch_route_get_callsigns(
-    client: &reqwest::blocking::Client,
+async fn fetch_route_get_callsigns(
+    client: &reqwest::Client,
     base_url: &str,
     callsigns: &[String],
 ) -> Result<Vec<RouteResult>, String> {
@@ -252,7 +274,7 @@ fn fetch_route_get_callsigns(
         }
         for path in &paths {
             let url = format!("{base}/{path}/{}", clean);
-            match client.get(&url).send() {
+            match client.get(&url).send().await {
                 Ok(resp) => {
                     let status = resp.status();
                     if !status.is_success() {
@@ -268,7 +290,7 @@ fn fetch_route_get_callsigns(
                         last_err = Some(format!("Route HTTP {}", status));
                         continue;
                     }
-                    match resp.json::<Value>() {
+                    match resp.json::<Value>().await {
                         Ok(body) => {
                             let mut parsed = parse_routes(body);
                             if parsed.is_empty() {
@@ -294,8 +316,8 @@ fn fetch_route_get_callsigns(
     Err(last_err.unwrap_or_else(|| "Route request failed".to_string()))
 }
 
-fn fetch_tar1090(
-    client: &reqwest::blocking::Client,
+async fn fetch_tar1090(
+    client: &reqwest::Client,
     base_url: &str,
     route_path: &str,
 ) -> Result<Vec<RouteResult>, String> {
@@ -304,17 +326,21 @@ fn fetch_tar1090(
     url.push('/');
     url.push_str(path);
 
-    let resp = client.get(url).send().map_err(|err| err.to_string())?;
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
     let status = resp.status();
     if !status.is_success() {
         return Err(format!("Route HTTP {}", status));
     }
-    let body: Value = resp.json().map_err(|err| err.to_string())?;
+    let body: Value = resp.json().await.map_err(|err| err.to_string())?;
     Ok(parse_routes(body))
 }
 
-fn post_payload(
-    client: &reqwest::blocking::Client,
+async fn post_payload(
+    client: &reqwest::Client,
     url: &str,
     payload: &Value,
 ) -> Result<Value, String> {
@@ -322,6 +348,7 @@ fn post_payload(
         .post(url)
         .json(payload)
         .send()
+        .await
         .map_err(|err| err.to_string())?;
     let status = resp.status();
     if !status.is_success() {
@@ -334,7 +361,7 @@ fn post_payload(
         }
         return Err(format!("Route HTTP {}", status));
     }
-    let body: Value = resp.json::<Value>().map_err(|err| err.to_string())?;
+    let body: Value = resp.json::<Value>().await.map_err(|err| err.to_string())?;
     Ok(body)
 }
 
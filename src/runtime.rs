@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::{DateTime, Local};
 use crossterm::event::{
     self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers, MouseButton,
     MouseEvent, MouseEventKind,
@@ -10,18 +11,30 @@ use crossterm::terminal::{
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 use std::io::{self, Stdout};
-use std::sync::mpsc::{Receiver, Sender};
-use std::time::{Duration, SystemTime};
+use std::sync::mpsc::Receiver;
+use std::time::{Duration, Instant, SystemTime};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::watch;
 
 use crate::app::{App, InputMode, LayoutMode, RadarDirection};
-use crate::export;
+use crate::control::{ControlCommand, ControlRequest};
+use crate::export::{self, ExportKind};
+use crate::journal::{ReplayControl, ReplayStatus};
+use crate::keys::{Action, Key};
 use crate::lookup::{LookupMessage, LookupRequest};
-use crate::model::ApiResponse;
+use crate::model::{ApiResponse, ReceiverStats};
+use crate::photos::PhotoMessage;
+use crate::profile::Stage;
+use crate::reload::ConfigWatcher;
 use crate::routes::{RouteMessage, RouteRequest};
 use crate::storage;
+use crate::tasks::Tasks;
 use crate::ui;
+use crate::weather::WeatherReport;
 use tracing::{debug, error, info};
 
+const REPLAY_SEEK_MS: i64 = 60_000;
+
 pub fn init_terminal() -> Result<Terminal<CrosstermBackend<Stdout>>> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -43,24 +56,35 @@ pub fn restore_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Re
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn run_app(
     terminal: &mut Terminal<CrosstermBackend<Stdout>>,
     mut app: App,
-    rx: Receiver<Result<ApiResponse, String>>,
-    routes: Option<RouteChannels>,
-    lookup: Option<LookupChannels>,
-    feed_updates: Option<Sender<Vec<String>>>,
+    mut rx: UnboundedReceiver<Result<ApiResponse, String>>,
+    mut routes: Option<RouteChannels>,
+    mut lookup: Option<LookupChannels>,
+    mut stats: Option<UnboundedReceiver<Result<ReceiverStats, String>>>,
+    mut weather: Option<UnboundedReceiver<Result<WeatherReport, String>>>,
+    mut photos: Option<PhotoChannels>,
+    mut replay: Option<ReplayChannels>,
+    feed_updates: Option<UnboundedSender<Vec<String>>>,
+    mut config_watcher: Option<ConfigWatcher>,
+    control: Option<Receiver<ControlRequest>>,
 ) -> Result<()> {
     let tick_rate = Duration::from_millis(50);
     info!("runtime loop started");
     let mut last_draw: Option<SystemTime> = None;
+    let mut last_phase: Option<u64> = None;
+    let mut indices = app.visible_indices();
     loop {
         let mut dirty = false;
         while let Ok(message) = rx.try_recv() {
             match message {
                 Ok(data) => {
                     debug!("data update received");
+                    let started = Instant::now();
                     app.apply_update(data);
+                    app.profiler.record(Stage::Update, started.elapsed());
                 }
                 Err(err) => {
                     error!("data error: {err}");
@@ -70,48 +94,90 @@ pub fn run_app(
             dirty = true;
         }
 
-        if let Some(routes) = &routes {
-            while let Ok(message) = routes.res_rx.try_recv() {
+        save_traffic_history(&mut app, false);
+
+        if let Some(routes) = routes.as_mut() {
+            dirty |= drain_route_messages(&mut app, routes);
+        }
+
+        if let Some(lookup) = lookup.as_mut() {
+            while let Ok(message) = lookup.res_rx.try_recv() {
                 match message {
-                    RouteMessage::Results(results) => {
-                        debug!("route results received: {}", results.len());
-                        app.apply_routes(results);
-                    }
-                    RouteMessage::Error(err) => {
-                        error!("route error: {err}");
-                        app.set_route_error(err);
-                    }
+                    LookupMessage::Result(data) => app.apply_lookup_result(data),
+                    LookupMessage::Error(err) => app.apply_lookup_error(err),
                 }
                 dirty = true;
             }
         }
 
-        if let Some(lookup) = &lookup {
-            while let Ok(message) = lookup.res_rx.try_recv() {
+        if let Some(stats) = stats.as_mut() {
+            while let Ok(message) = stats.try_recv() {
                 match message {
-                    LookupMessage::Result(data) => app.apply_lookup_result(data),
-                    LookupMessage::Error(err) => app.apply_lookup_error(err),
+                    Ok(reading) => {
+                        app.apply_receiver_stats(reading);
+                        dirty = true;
+                    }
+                    Err(err) => debug!("stats error: {err}"),
                 }
+            }
+        }
+
+        if let Some(weather) = weather.as_mut() {
+            while let Ok(message) = weather.try_recv() {
+                app.apply_weather(message);
                 dirty = true;
             }
         }
 
+        if let Some(photos) = photos.as_mut() {
+            while let Ok(message) = photos.res_rx.try_recv() {
+                app.apply_photo(message);
+                dirty = true;
+            }
+        }
+
+        if let Some(replay) = replay.as_mut() {
+            if replay.status_rx.has_changed().unwrap_or(false) {
+                app.replay = Some(*replay.status_rx.borrow_and_update());
+                dirty = true;
+            }
+        }
+
+        if let Some(watcher) = config_watcher.as_mut() {
+            dirty |= poll_config_watcher(&mut app, watcher, &feed_updates);
+        }
+
+        if let Some(control) = &control {
+            dirty |= drain_control_requests(&mut app, control, &feed_updates);
+        }
+
         let now = SystemTime::now();
-        app.maybe_swap_snapshot(now);
+        dirty |= app.update_dnd(now);
+        dirty |= app.maybe_swap_snapshot(now);
 
-        let draw_due = is_draw_due(now, last_draw, app.ui_interval);
+        let draw_due = redraw_due(
+            now,
+            last_draw,
+            app.ui_interval,
+            ui::frame_phase(&app, now),
+            last_phase,
+        );
         let poll_timeout = if dirty || draw_due {
             Duration::from_millis(0)
         } else {
             tick_rate
         };
 
-        let mut indices = app.visible_indices();
+        // Filtering and sorting only happen when something changed or a
+        // frame is about to be drawn, not on every idle pass.
+        let mut indices_stale = dirty || draw_due;
         if event::poll(poll_timeout)? {
+            if indices_stale {
+                indices = app.visible_indices();
+            }
             match event::read()? {
                 Event::Key(key) => match app.input_mode {
                     InputMode::Normal => match key.code {
-                        KeyCode::Char('q') => app.open_quit_confirm(),
                         KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => {
                             send_feed_update(&feed_updates, app.pan_radar(RadarDirection::North));
                         }
@@ -124,18 +190,30 @@ pub fn run_app(
                         KeyCode::Right if key.modifiers.contains(KeyModifiers::SHIFT) => {
                             send_feed_update(&feed_updates, app.pan_radar(RadarDirection::East));
                         }
-                        KeyCode::Up if app.layout_mode == LayoutMode::Radar => {
+                        KeyCode::Up if app.current_layout() == LayoutMode::Radar => {
                             app.select_radar_direction(&indices, RadarDirection::North);
                         }
-                        KeyCode::Down if app.layout_mode == LayoutMode::Radar => {
+                        KeyCode::Down if app.current_layout() == LayoutMode::Radar => {
                             app.select_radar_direction(&indices, RadarDirection::South);
                         }
-                        KeyCode::Left if app.layout_mode == LayoutMode::Radar => {
+                        KeyCode::Left if app.current_layout() == LayoutMode::Radar => {
                             app.select_radar_direction(&indices, RadarDirection::West);
                         }
-                        KeyCode::Right if app.layout_mode == LayoutMode::Radar => {
+                        KeyCode::Right if app.current_layout() == LayoutMode::Radar => {
                             app.select_radar_direction(&indices, RadarDirection::East);
                         }
+                        KeyCode::Left if app.current_layout() == LayoutMode::Performance => {
+                            app.cycle_perf_window(false);
+                        }
+                        KeyCode::Right if app.current_layout() == LayoutMode::Performance => {
+                            app.cycle_perf_window(true);
+                        }
+                        KeyCode::Left if app.current_layout() == LayoutMode::Replay => {
+                            send_replay_control(&replay, ReplayControl::Seek(-REPLAY_SEEK_MS));
+                        }
+                        KeyCode::Right if app.current_layout() == LayoutMode::Replay => {
+                            send_replay_control(&replay, ReplayControl::Seek(REPLAY_SEEK_MS));
+                        }
                         KeyCode::Down => {
                             app.next_row(indices.len());
                             app.update_selection_key(&indices);
@@ -144,53 +222,17 @@ pub fn run_app(
                             app.previous_row(indices.len());
                             app.update_selection_key(&indices);
                         }
-                        KeyCode::Char('s') => app.toggle_sort(),
-                        KeyCode::Char('/') => app.start_filter(),
-                        KeyCode::Char('c') => app.clear_filter(),
-                        KeyCode::Char('f') => toggle_favorite(&mut app, &indices),
-                        KeyCode::Char('t') => app.toggle_theme(),
-                        KeyCode::Char('l') => app.toggle_layout(),
-                        KeyCode::Char('R') | KeyCode::Char('r') => {
-                            app.set_layout(LayoutMode::Radar);
-                        }
-                        KeyCode::Char('P') | KeyCode::Char('p') => {
-                            app.set_layout(LayoutMode::Performance);
-                        }
-                        KeyCode::Char('b') | KeyCode::Char('B') => {
-                            app.toggle_radar_labels();
-                        }
-                        KeyCode::Char('+') | KeyCode::Char('=') => {
-                            send_feed_update(&feed_updates, app.zoom_radar(0.8));
-                        }
-                        KeyCode::Char('-') => {
-                            send_feed_update(&feed_updates, app.zoom_radar(1.25));
-                        }
-                        KeyCode::Char('m') => app.open_columns(),
-                        KeyCode::Char('C') => app.open_config(),
-                        KeyCode::Char('a') => {
-                            app.add_watchlist_from_selected(&indices);
-                        }
-                        KeyCode::Char('g') | KeyCode::Char('G') => app.open_lookup(),
-                        KeyCode::Char('W') | KeyCode::Char('w') => app.open_watchlist(),
-                        KeyCode::Char('?') | KeyCode::Char('h') => app.open_help(),
-                        KeyCode::Char('e') => match export::export_csv(&app, &indices) {
-                            Ok(path) => {
-                                info!("export csv {}", path);
-                                app.set_last_export(path);
-                            }
-                            Err(err) => error!("export csv failed: {err}"),
-                        },
-                        KeyCode::Char('E') => match export::export_json(&app) {
-                            Ok(path) => {
-                                info!("export json {}", path);
-                                app.set_last_export(path);
+                        code => {
+                            if let Some(action) = bound_action(&app, code) {
+                                handle_action(&mut app, action, &indices, &feed_updates, &replay);
                             }
-                            Err(err) => error!("export json failed: {err}"),
-                        },
-                        _ => {}
+                        }
                     },
                     InputMode::QuitConfirm => match key.code {
-                        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => return Ok(()),
+                        KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                            save_traffic_history(&mut app, true);
+                            return Ok(());
+                        }
                         KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
                             app.close_quit_confirm();
                         }
@@ -208,36 +250,43 @@ pub fn run_app(
                     },
                     InputMode::Columns => match key.code {
                         KeyCode::Esc => app.close_columns(),
-                        KeyCode::Char('q') => app.open_quit_confirm(),
-                        KeyCode::Char('m') => app.close_columns(),
+                        KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                            app.move_column(-1)
+                        }
+                        KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) => {
+                            app.move_column(1)
+                        }
                         KeyCode::Up => app.previous_column(),
                         KeyCode::Down => app.next_column(),
+                        KeyCode::Char('+') | KeyCode::Char('=') => app.adjust_column_width(1),
+                        KeyCode::Char('-') => app.adjust_column_width(-1),
+                        KeyCode::Char('0') => app.clear_column_width(),
                         KeyCode::Enter | KeyCode::Char(' ') => app.toggle_column(),
+                        _ if is_bound(&app, key.code, Action::Columns) => app.close_columns(),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
                         _ => {}
                     },
                     InputMode::Help => match key.code {
                         KeyCode::Esc => app.close_help(),
-                        KeyCode::Char('q') => app.open_quit_confirm(),
-                        KeyCode::Char('?') | KeyCode::Char('h') => app.close_help(),
                         KeyCode::Char('L') | KeyCode::Char('l') => app.open_legend(),
                         KeyCode::Up => app.help_scroll_up(1),
                         KeyCode::Down => app.help_scroll_down(1),
                         KeyCode::PageUp => app.help_scroll_up(5),
                         KeyCode::PageDown => app.help_scroll_down(5),
+                        _ if is_bound(&app, key.code, Action::Help) => app.close_help(),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
                         _ => {}
                     },
                     InputMode::Legend => match key.code {
                         KeyCode::Esc => app.close_legend(),
-                        KeyCode::Char('q') => app.open_quit_confirm(),
                         KeyCode::Char('L') | KeyCode::Char('l') => app.close_legend(),
                         KeyCode::Up => app.previous_cursor(ui::legend_len()),
                         KeyCode::Down => app.next_cursor(ui::legend_len()),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
                         _ => {}
                     },
                     InputMode::Watchlist => match key.code {
                         KeyCode::Esc => app.close_watchlist(),
-                        KeyCode::Char('q') => app.open_quit_confirm(),
-                        KeyCode::Char('W') | KeyCode::Char('w') => app.close_watchlist(),
                         KeyCode::Up => app.previous_watchlist_item(),
                         KeyCode::Down => app.next_watchlist_item(),
                         KeyCode::PageUp => app.watchlist_page_up(10),
@@ -257,16 +306,132 @@ pub fn run_app(
                         KeyCode::Char('s') => {
                             app.save_watchlist();
                         }
+                        _ if is_bound(&app, key.code, Action::Watchlist) => app.close_watchlist(),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
+                        _ => {}
+                    },
+                    InputMode::History => match key.code {
+                        KeyCode::Esc => app.close_history(),
+                        KeyCode::Up => app.previous_history_item(),
+                        KeyCode::Down => app.next_history_item(),
+                        KeyCode::PageUp => app.history_page_up(10),
+                        KeyCode::PageDown => app.history_page_down(10),
+                        _ if is_bound(&app, key.code, Action::History) => app.close_history(),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
+                        _ => {}
+                    },
+                    InputMode::Events => match key.code {
+                        KeyCode::Esc => app.close_events(),
+                        KeyCode::Char('e') => run_export(&mut app, &indices, ExportKind::Events),
+                        KeyCode::Up => app.previous_event(),
+                        KeyCode::Down => app.next_event(),
+                        KeyCode::PageUp => app.events_page_up(10),
+                        KeyCode::PageDown => app.events_page_down(10),
+                        _ if is_bound(&app, key.code, Action::Events) => app.close_events(),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
+                        _ => {}
+                    },
+                    InputMode::Notifications => match key.code {
+                        KeyCode::Esc => app.close_notifications(),
+                        KeyCode::Enter | KeyCode::Char(' ') => app.acknowledge_notification(),
+                        KeyCode::Char('a') => app.acknowledge_all_notifications(),
+                        KeyCode::Char('c') => app.clear_notifications(),
+                        KeyCode::Up => app.previous_notification(),
+                        KeyCode::Down => app.next_notification(),
+                        KeyCode::PageUp => app.notifications_page_up(10),
+                        KeyCode::PageDown => app.notifications_page_down(10),
+                        _ if is_bound(&app, key.code, Action::Notifications) => {
+                            app.close_notifications()
+                        }
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
+                        _ => {}
+                    },
+                    InputMode::Stats => match key.code {
+                        KeyCode::Esc => app.close_stats(),
+                        KeyCode::Right | KeyCode::Tab => app.next_stats_metric(),
+                        KeyCode::Left | KeyCode::BackTab => app.previous_stats_metric(),
+                        _ if is_bound(&app, key.code, Action::Stats) => app.close_stats(),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
+                        _ => {}
+                    },
+                    InputMode::Sort => match key.code {
+                        KeyCode::Esc => app.close_sort(),
+                        KeyCode::Up => app.previous_sort_key(),
+                        KeyCode::Down => app.next_sort_key(),
+                        KeyCode::Enter => app.set_primary_sort(),
+                        KeyCode::Char(' ') | KeyCode::Char('a') => app.toggle_secondary_sort(),
+                        KeyCode::Char('r') => app.reverse_sort_key(),
+                        _ if is_bound(&app, key.code, Action::Sort) => app.close_sort(),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
+                        _ => {}
+                    },
+                    InputMode::Export => match key.code {
+                        KeyCode::Esc => app.close_export(),
+                        KeyCode::Up => app.previous_export_kind(),
+                        KeyCode::Down => app.next_export_kind(),
+                        KeyCode::Enter => {
+                            let kind = app.selected_export_kind();
+                            app.close_export();
+                            run_export(&mut app, &indices, kind);
+                        }
+                        _ if is_bound(&app, key.code, Action::Export) => app.close_export(),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
+                        _ => {}
+                    },
+                    InputMode::Detail => match key.code {
+                        KeyCode::Esc | KeyCode::Enter => app.close_detail(),
+                        KeyCode::Right | KeyCode::Tab => app.next_detail_tab(),
+                        KeyCode::Left | KeyCode::BackTab => app.previous_detail_tab(),
+                        KeyCode::Down => {
+                            app.next_row(indices.len());
+                            app.update_selection_key(&indices);
+                        }
+                        KeyCode::Up => {
+                            app.previous_row(indices.len());
+                            app.update_selection_key(&indices);
+                        }
+                        _ if is_bound(&app, key.code, Action::Detail) => app.close_detail(),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
+                        _ => {}
+                    },
+                    InputMode::Zones => match key.code {
+                        KeyCode::Esc => app.close_zones(),
+                        KeyCode::Up => app.previous_zone_item(),
+                        KeyCode::Down => app.next_zone_item(),
+                        KeyCode::PageUp => app.zones_page_up(10),
+                        KeyCode::PageDown => app.zones_page_down(10),
+                        KeyCode::Char('a') => {
+                            app.add_zone_from_selected(&indices);
+                        }
+                        KeyCode::Char('e') => {
+                            app.toggle_zone_enabled_selected();
+                        }
+                        KeyCode::Char('n') => {
+                            app.cycle_zone_alert_selected();
+                        }
+                        KeyCode::Char('d') => {
+                            app.delete_zone_selected();
+                        }
+                        KeyCode::Char('r') => app.reload_zones(),
+                        KeyCode::Char('s') => app.save_zones(),
+                        _ if is_bound(&app, key.code, Action::Zones) => app.close_zones(),
+                        _ if is_bound(&app, key.code, Action::Quit) => app.open_quit_confirm(),
                         _ => {}
                     },
                     InputMode::Config => match key.code {
                         KeyCode::Esc if app.config_editing => {
                             app.cancel_config_edit();
                         }
-                        KeyCode::Esc => app.close_config(),
-                        KeyCode::Char('q') if !app.config_editing => app.open_quit_confirm(),
+                        KeyCode::Esc => {
+                            app.close_config();
+                            send_feed_update(&feed_updates, app.pending_feed_urls.take());
+                        }
+                        _ if !app.config_editing && is_bound(&app, key.code, Action::Quit) => {
+                            app.open_quit_confirm()
+                        }
                         KeyCode::Char('w') | KeyCode::Char('S') => {
                             app.save_config();
+                            send_feed_update(&feed_updates, app.pending_feed_urls.take());
                         }
                         KeyCode::Up if !app.config_editing => {
                             app.previous_config_item();
@@ -274,6 +439,12 @@ pub fn run_app(
                         KeyCode::Down if !app.config_editing => {
                             app.next_config_item();
                         }
+                        KeyCode::Left if !app.config_editing => {
+                            app.cycle_config_value(false);
+                        }
+                        KeyCode::Right if !app.config_editing => {
+                            app.cycle_config_value(true);
+                        }
                         KeyCode::Enter if app.config_editing => {
                             app.apply_config_edit();
                         }
@@ -289,6 +460,7 @@ pub fn run_app(
                         }
                         KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                             app.save_config();
+                            send_feed_update(&feed_updates, app.pending_feed_urls.take());
                         }
                         KeyCode::Char(ch) if app.config_editing => {
                             app.push_config_char(ch);
@@ -315,46 +487,349 @@ pub fn run_app(
                     },
                 },
                 Event::Mouse(mouse) => {
-                    handle_mouse(&mut app, &indices, mouse);
+                    handle_mouse(&mut app, &indices, mouse, &feed_updates);
                 }
                 _ => {}
             }
             dirty = true;
+            indices_stale = true;
         }
 
-        if dirty {
+        if indices_stale {
             indices = app.visible_indices();
-        }
-        app.restore_selection_by_key(&indices);
-        app.clamp_selection_to(indices.len());
-        app.update_selection_key(&indices);
+            app.restore_selection_by_key(&indices);
+            app.clamp_selection_to(indices.len());
+            app.update_selection_key(&indices);
 
-        if let Some(routes) = &routes {
-            let now = SystemTime::now();
-            if app.route_enabled() && app.route_refresh_due(now) {
-                if app.route_tar1090() {
-                    let _ = routes.req_tx.send(Vec::new());
-                    app.mark_route_poll(now);
-                } else {
-                    let requests = app.collect_route_requests(&indices, now);
-                    if !requests.is_empty() {
-                        let _ = routes.req_tx.send(requests);
-                        app.mark_route_poll(now);
-                    }
+            if let Some(routes) = &routes {
+                request_routes(&mut app, routes, &indices);
+            }
+
+            if let Some(photos) = &photos {
+                if let Some(hex) = app.photo_request(&indices) {
+                    let _ = photos.req_tx.send(hex);
                 }
             }
+            dirty |= app.refresh_selected_sighting(&indices);
         }
 
-        let now = SystemTime::now();
-        let draw_due = is_draw_due(now, last_draw, app.ui_interval);
         if dirty || draw_due {
+            let now = SystemTime::now();
+            let phase = ui::frame_phase(&app, now);
+            let started = Instant::now();
             terminal.draw(|f| ui::ui(f, &mut app, &indices))?;
+            app.profiler.record(Stage::Frame, started.elapsed());
+            app.profiler.maybe_log(started);
             app.advance_tick();
             last_draw = Some(now);
+            last_phase = Some(phase);
         }
     }
 }
 
+pub fn run_headless(
+    tasks: &Tasks,
+    mut app: App,
+    mut rx: UnboundedReceiver<Result<ApiResponse, String>>,
+    mut routes: Option<RouteChannels>,
+    feed_updates: Option<UnboundedSender<Vec<String>>>,
+    mut config_watcher: Option<ConfigWatcher>,
+    control: Option<Receiver<ControlRequest>>,
+) -> Result<()> {
+    let tick_rate = Duration::from_millis(250);
+    info!("headless loop started");
+    let mut printed_until = SystemTime::now();
+    let mut feed_error: Option<String> = None;
+    let mut stop = tasks.block_on(async { StopSignals::new() })?;
+    loop {
+        let next = tasks.block_on(async {
+            tokio::select! {
+                next = tokio::time::timeout(tick_rate, rx.recv()) => Some(next),
+                signal = stop.recv() => {
+                    info!("{signal} received, headless loop stopped");
+                    None
+                }
+            }
+        });
+        let Some(next) = next else {
+            save_traffic_history(&mut app, true);
+            return Ok(());
+        };
+        match next {
+            Ok(Some(message)) => {
+                let pending = std::iter::from_fn(|| rx.try_recv().ok());
+                for message in std::iter::once(message).chain(pending) {
+                    match message {
+                        Ok(data) => {
+                            debug!("data update received");
+                            app.apply_update(data);
+                        }
+                        Err(err) => {
+                            error!("data error: {err}");
+                            app.apply_error(err);
+                        }
+                    }
+                }
+            }
+            Err(_) => {}
+            Ok(None) => {
+                info!("feed closed, headless loop stopped");
+                save_traffic_history(&mut app, true);
+                return Ok(());
+            }
+        }
+        save_traffic_history(&mut app, false);
+
+        if let Some(routes) = routes.as_mut() {
+            drain_route_messages(&mut app, routes);
+        }
+        if let Some(watcher) = config_watcher.as_mut() {
+            poll_config_watcher(&mut app, watcher, &feed_updates);
+        }
+        if let Some(control) = &control {
+            drain_control_requests(&mut app, control, &feed_updates);
+        }
+        let now = SystemTime::now();
+        app.update_dnd(now);
+        app.maybe_swap_snapshot(now);
+        if let Some(routes) = routes.as_ref().filter(|_| app.route_refresh_due(now)) {
+            let indices = app.visible_indices();
+            request_routes(&mut app, routes, &indices);
+        }
+
+        if app.last_error != feed_error {
+            match &app.last_error {
+                Some(err) => println!("{} FEED ERR {err}", log_time(now)),
+                None => println!(
+                    "{} FEED OK {} aircraft",
+                    log_time(now),
+                    app.data.aircraft.len()
+                ),
+            }
+            feed_error = app.last_error.clone();
+        }
+        let mut latest = printed_until;
+        for note in app.notifications.iter().filter(|n| n.at > printed_until) {
+            info!("alert {}", note.message);
+            println!("{} {}", log_time(note.at), note.message);
+            latest = latest.max(note.at);
+        }
+        printed_until = latest;
+    }
+}
+
+/// Ctrl+C, or SIGTERM from a service manager.
+struct StopSignals {
+    #[cfg(unix)]
+    interrupt: tokio::signal::unix::Signal,
+    #[cfg(unix)]
+    terminate: tokio::signal::unix::Signal,
+}
+
+impl StopSignals {
+    #[cfg(unix)]
+    fn new() -> io::Result<Self> {
+        use tokio::signal::unix::{signal, SignalKind};
+        Ok(Self {
+            interrupt: signal(SignalKind::interrupt())?,
+            terminate: signal(SignalKind::terminate())?,
+        })
+    }
+
+    #[cfg(not(unix))]
+    fn new() -> io::Result<Self> {
+        Ok(Self {})
+    }
+
+    #[cfg(unix)]
+    async fn recv(&mut self) -> &'static str {
+        tokio::select! {
+            _ = self.interrupt.recv() => "SIGINT",
+            _ = self.terminate.recv() => "SIGTERM",
+        }
+    }
+
+    #[cfg(not(unix))]
+    async fn recv(&mut self) -> &'static str {
+        let _ = tokio::signal::ctrl_c().await;
+        "Ctrl+C"
+    }
+}
+
+fn log_time(time: SystemTime) -> String {
+    let time: DateTime<Local> = time.into();
+    time.format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+fn drain_route_messages(app: &mut App, routes: &mut RouteChannels) -> bool {
+    let mut received = false;
+    while let Ok(message) = routes.res_rx.try_recv() {
+        match message {
+            RouteMessage::Results(results) => {
+                debug!("route results received: {}", results.len());
+                app.apply_routes(results);
+            }
+            RouteMessage::Error(err) => {
+                error!("route error: {err}");
+                app.set_route_error(err);
+            }
+        }
+        received = true;
+    }
+    received
+}
+
+fn request_routes(app: &mut App, routes: &RouteChannels, indices: &[usize]) {
+    let now = SystemTime::now();
+    if !app.route_enabled() || !app.route_refresh_due(now) {
+        return;
+    }
+    if app.route_tar1090() {
+        let _ = routes.req_tx.send(Vec::new());
+        app.mark_route_poll(now);
+    } else {
+        let requests = app.collect_route_requests(indices, now);
+        if !requests.is_empty() {
+            let _ = routes.req_tx.send(requests);
+            app.mark_route_poll(now);
+        }
+    }
+}
+
+fn poll_config_watcher(
+    app: &mut App,
+    watcher: &mut ConfigWatcher,
+    feed_updates: &Option<UnboundedSender<Vec<String>>>,
+) -> bool {
+    match watcher.poll(Instant::now()) {
+        Some(Ok(reload)) => {
+            let urls = app.apply_config_reload(reload.config, &reload.restart_keys);
+            send_feed_update(feed_updates, urls);
+            true
+        }
+        Some(Err(err)) => {
+            app.apply_config_reload_error(err);
+            true
+        }
+        None => false,
+    }
+}
+
+fn drain_control_requests(
+    app: &mut App,
+    control: &Receiver<ControlRequest>,
+    feed_updates: &Option<UnboundedSender<Vec<String>>>,
+) -> bool {
+    let mut received = false;
+    while let Ok(request) = control.try_recv() {
+        let reply = handle_control(app, request.command, feed_updates);
+        let _ = request.reply.send(reply);
+        received = true;
+    }
+    received
+}
+
+fn handle_control(
+    app: &mut App,
+    command: ControlCommand,
+    feed_updates: &Option<UnboundedSender<Vec<String>>>,
+) -> String {
+    let indices = app.visible_indices();
+    match command {
+        ControlCommand::Filter(filter) => {
+            app.set_filter(&filter);
+            let visible = app.visible_indices().len();
+            app.clamp_selection_to(visible);
+            format!("ok filter {visible} visible")
+        }
+        ControlCommand::Select(hex) => {
+            if app.select_hex(&hex, &indices) {
+                format!("ok select {}", hex.trim().to_ascii_lowercase())
+            } else {
+                format!("error {} not visible", hex.trim())
+            }
+        }
+        ControlCommand::ExportCsv => match export::export_csv(app, &indices) {
+            Ok(path) => {
+                info!("export csv {}", path);
+                app.set_last_export(path.clone());
+                format!("ok export {path}")
+            }
+            Err(err) => {
+                error!("export csv failed: {err}");
+                format!("error export csv failed: {err}")
+            }
+        },
+        ControlCommand::ExportJson => match export::export_json(app) {
+            Ok(path) => {
+                info!("export json {}", path);
+                app.set_last_export(path.clone());
+                format!("ok export {path}")
+            }
+            Err(err) => {
+                error!("export json failed: {err}");
+                format!("error export json failed: {err}")
+            }
+        },
+        ControlCommand::ExportGeoJson => match export::export_geojson(app, &indices) {
+            Ok(path) => {
+                info!("export geojson {}", path);
+                app.set_last_export(path.clone());
+                format!("ok export {path}")
+            }
+            Err(err) => {
+                error!("export geojson failed: {err}");
+                format!("error export geojson failed: {err}")
+            }
+        },
+        ControlCommand::Layout(layout_mode) => {
+            app.set_layout(layout_mode);
+            format!("ok layout {}", layout_mode.label())
+        }
+        ControlCommand::Theme(theme_mode) => {
+            app.set_theme(theme_mode);
+            format!("ok theme {}", theme_mode.label())
+        }
+        ControlCommand::Zoom(factor) => {
+            send_feed_update(feed_updates, app.zoom_radar(factor));
+            format!("ok zoom {:.0}nm", app.radar_range_nm)
+        }
+        ControlCommand::Site(None) => {
+            send_feed_update(feed_updates, app.next_site());
+            format!("ok site {}", app.site_name().unwrap_or("-"))
+        }
+        ControlCommand::Site(Some(name)) => match app.select_site_named(&name) {
+            Ok(urls) => {
+                send_feed_update(feed_updates, urls);
+                format!("ok site {}", app.site_name().unwrap_or("-"))
+            }
+            Err(err) => format!("error {err}"),
+        },
+        ControlCommand::Status => format!(
+            "ok aircraft={} visible={} layout={} theme={} site={} filter={:?}",
+            app.data.aircraft.len(),
+            indices.len(),
+            app.layout_mode.label(),
+            app.theme_mode.label(),
+            app.site_name().unwrap_or("-"),
+            app.filter
+        ),
+    }
+}
+
+/// Periodic redraw without new data or input: only once the UI interval has
+/// elapsed and the animation phase has moved on, so static frames (reduced
+/// motion, nothing changing) are not redrawn.
+fn redraw_due(
+    now: SystemTime,
+    last_draw: Option<SystemTime>,
+    interval: Duration,
+    phase: u64,
+    last_phase: Option<u64>,
+) -> bool {
+    last_phase != Some(phase) && is_draw_due(now, last_draw, interval)
+}
+
 fn is_draw_due(now: SystemTime, last_draw: Option<SystemTime>, interval: Duration) -> bool {
     if interval.is_zero() {
         return true;
@@ -368,7 +843,94 @@ fn is_draw_due(now: SystemTime, last_draw: Option<SystemTime>, interval: Duratio
     }
 }
 
-fn send_feed_update(feed_updates: &Option<Sender<Vec<String>>>, urls: Option<Vec<String>>) {
+fn handle_action(
+    app: &mut App,
+    action: Action,
+    indices: &[usize],
+    feed_updates: &Option<UnboundedSender<Vec<String>>>,
+    replay: &Option<ReplayChannels>,
+) {
+    match action {
+        Action::Quit => app.open_quit_confirm(),
+        Action::Sort => app.open_sort(),
+        Action::Filter => app.start_filter(),
+        Action::ClearFilter => app.clear_filter(),
+        Action::Favorite => toggle_favorite(app, indices),
+        Action::Theme => app.toggle_theme(),
+        Action::Layout => app.toggle_layout(),
+        Action::Radar => app.set_layout(LayoutMode::Radar),
+        Action::Performance => app.set_layout(LayoutMode::Performance),
+        Action::Watch => app.set_layout(LayoutMode::Watch),
+        Action::Profile => app.set_layout(LayoutMode::Profile),
+        Action::Labels => app.toggle_radar_labels(),
+        Action::Vectors => app.toggle_radar_vectors(),
+        Action::Follow => app.toggle_radar_follow(indices),
+        Action::Site => send_feed_update(feed_updates, app.next_site()),
+        Action::ZoomIn => send_feed_update(feed_updates, app.zoom_radar(0.8)),
+        Action::ZoomOut => send_feed_update(feed_updates, app.zoom_radar(1.25)),
+        Action::Columns => app.open_columns(),
+        Action::Config => app.open_config(),
+        Action::WatchlistAdd => {
+            app.add_watchlist_from_selected(indices);
+        }
+        Action::Lookup => app.open_lookup(),
+        Action::Watchlist => app.open_watchlist(),
+        Action::History => app.open_history(),
+        Action::Stats => app.open_stats(),
+        Action::Zones => app.open_zones(),
+        Action::Help => app.open_help(),
+        Action::Detail => app.open_detail(indices),
+        Action::Profiler => app.toggle_profiler(),
+        Action::Export => app.open_export(),
+        Action::ExportCsv => run_export(app, indices, ExportKind::TableCsv),
+        Action::ExportJson => run_export(app, indices, ExportKind::SnapshotJson),
+        Action::ReloadRoles => app.reload_roles(),
+        Action::Events => app.open_events(),
+        Action::Notifications => app.open_notifications(),
+        Action::Dnd => app.toggle_dnd(),
+        Action::PinTrail => {
+            app.toggle_trail_pin_selected(indices);
+        }
+        Action::ClearTrails => app.clear_trails(),
+        Action::ReplayPause => send_replay_control(replay, ReplayControl::TogglePause),
+        Action::ReplaySlower => send_replay_control(replay, ReplayControl::Slower),
+        Action::ReplayFaster => send_replay_control(replay, ReplayControl::Faster),
+    }
+}
+
+fn bound_action(app: &App, code: KeyCode) -> Option<Action> {
+    match code {
+        KeyCode::Char(ch) => app.keymap.action(Key::Char(ch)),
+        KeyCode::F(n) => app.keymap.action(Key::F(n)),
+        KeyCode::Enter => app.keymap.action(Key::Enter),
+        _ => None,
+    }
+}
+
+fn is_bound(app: &App, code: KeyCode, action: Action) -> bool {
+    bound_action(app, code) == Some(action)
+}
+
+fn run_export(app: &mut App, indices: &[usize], kind: ExportKind) {
+    match export::export(app, indices, kind) {
+        Ok(path) => {
+            info!("export {} {}", kind.name(), path);
+            app.set_last_export(path);
+        }
+        Err(err) => error!("export {} failed: {err}", kind.name()),
+    }
+}
+
+fn send_replay_control(replay: &Option<ReplayChannels>, control: ReplayControl) {
+    if let Some(replay) = replay {
+        let _ = replay.control_tx.send(control);
+    }
+}
+
+fn send_feed_update(
+    feed_updates: &Option<UnboundedSender<Vec<String>>>,
+    urls: Option<Vec<String>>,
+) {
     let (Some(tx), Some(urls)) = (feed_updates.as_ref(), urls) else {
         return;
     };
@@ -392,21 +954,65 @@ fn toggle_favorite(app: &mut App, indices: &[usize]) {
     }
 }
 
+fn save_traffic_history(app: &mut App, flush: bool) {
+    let Some(path) = app.traffic_path.clone() else {
+        return;
+    };
+    let now = Instant::now();
+    if !app.traffic.save_due(now, flush) {
+        return;
+    }
+    app.traffic.mark_saved(now);
+    match storage::save_traffic_history(&path, &app.traffic) {
+        Ok(_) => debug!("traffic history saved {}", path.display()),
+        Err(err) => error!("traffic history save failed: {err}"),
+    }
+}
+
 pub struct RouteChannels {
-    pub req_tx: Sender<Vec<RouteRequest>>,
-    pub res_rx: Receiver<RouteMessage>,
+    pub req_tx: UnboundedSender<Vec<RouteRequest>>,
+    pub res_rx: UnboundedReceiver<RouteMessage>,
+}
+
+pub struct ReplayChannels {
+    pub control_tx: UnboundedSender<ReplayControl>,
+    pub status_rx: watch::Receiver<ReplayStatus>,
 }
 
 pub struct LookupChannels {
-    pub req_tx: Sender<LookupRequest>,
-    pub res_rx: Receiver<LookupMessage>,
+    pub req_tx: UnboundedSender<LookupRequest>,
+    pub res_rx: UnboundedReceiver<LookupMessage>,
+}
+
+pub struct PhotoChannels {
+    pub req_tx: UnboundedSender<String>,
+    pub res_rx: UnboundedReceiver<PhotoMessage>,
 }
 
-fn handle_mouse(app: &mut App, indices: &[usize], mouse: MouseEvent) {
+fn handle_mouse(
+    app: &mut App,
+    indices: &[usize],
+    mouse: MouseEvent,
+    feed_updates: &Option<UnboundedSender<Vec<String>>>,
+) {
     if app.input_mode != InputMode::Normal {
         return;
     }
+    let on_radar = app.radar_contains(mouse.column, mouse.row);
     match mouse.kind {
+        MouseEventKind::ScrollUp if on_radar => {
+            send_feed_update(feed_updates, app.zoom_radar(0.8));
+        }
+        MouseEventKind::ScrollDown if on_radar => {
+            send_feed_update(feed_updates, app.zoom_radar(1.25));
+        }
+        MouseEventKind::Moved => {
+            app.radar_hover = on_radar.then_some((mouse.column, mouse.row));
+        }
+        MouseEventKind::Down(MouseButton::Left) if on_radar => {
+            app.select_radar_aircraft_at(mouse.column, mouse.row, indices);
+            app.radar_hover = Some((mouse.column, mouse.row));
+        }
         MouseEventKind::ScrollUp => {
             app.previous_row(indices.len());
             app.update_selection_key(indices);
@@ -427,7 +1033,7 @@ fn handle_mouse(app: &mut App, indices: &[usize], mouse: MouseEvent) {
 
 #[cfg(test)]
 mod tests {
-    use super::is_draw_due;
+    use super::{is_draw_due, redraw_due};
     use std::time::{Duration, SystemTime};
 
     #[test]
@@ -442,4 +1048,14 @@ mod tests {
         assert!(is_draw_due(now, Some(last), Duration::from_secs(1)));
         assert!(!is_draw_due(now, Some(last), Duration::from_secs(5)));
     }
+
+    #[test]
+    fn redraw_skipped_while_phase_unchanged() {
+        let now = SystemTime::now();
+        let last = now - Duration::from_secs(2);
+        let interval = Duration::from_millis(100);
+        assert!(redraw_due(now, Some(last), interval, 8, Some(7)));
+        assert!(!redraw_due(now, Some(last), interval, 7, Some(7)));
+        assert!(!redraw_due(now, Some(now), interval, 8, Some(7)));
+    }
 }
@@ -0,0 +1,140 @@
+//! Aircraft seen since startup, for the session summary export.
+
+use std::collections::HashMap;
+
+use crate::model::Aircraft;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SessionEntry {
+    pub hex: String,
+    pub callsign: Option<String>,
+    pub registration: Option<String>,
+    pub aircraft_type: Option<String>,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub max_alt: Option<i64>,
+    pub max_gs: Option<f64>,
+    pub max_distance_nm: Option<f64>,
+}
+
+impl SessionEntry {
+    fn update(&mut self, now_secs: i64, ac: &Aircraft, distance_nm: Option<f64>) {
+        self.last_seen = self.last_seen.max(now_secs);
+        let text = |value: Option<&str>| {
+            value
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        // Keep the last non-empty value; callsigns change mid-flight.
+        if let Some(callsign) = text(ac.flight.as_deref()) {
+            self.callsign = Some(callsign);
+        }
+        if let Some(registration) = text(ac.r.as_deref()) {
+            self.registration = Some(registration);
+        }
+        if let Some(aircraft_type) = text(ac.t.as_deref()) {
+            self.aircraft_type = Some(aircraft_type);
+        }
+        if let Some(alt) = ac.alt_baro.map(|alt| alt.feet()) {
+            self.max_alt = Some(self.max_alt.map_or(alt, |max| max.max(alt)));
+        }
+        if let Some(gs) = ac.gs.filter(|gs| gs.is_finite()) {
+            if self.max_gs.is_none_or(|max| gs > max) {
+                self.max_gs = Some(gs);
+            }
+        }
+        if let Some(distance) = distance_nm.filter(|nm| nm.is_finite()) {
+            if self.max_distance_nm.is_none_or(|max| distance > max) {
+                self.max_distance_nm = Some(distance);
+            }
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct SessionLog {
+    entries: HashMap<String, SessionEntry>,
+}
+
+impl SessionLog {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn get(&self, hex: &str) -> Option<&SessionEntry> {
+        self.entries.get(&hex.trim().to_ascii_lowercase())
+    }
+
+    pub fn entries(&self) -> Vec<&SessionEntry> {
+        let mut list: Vec<&SessionEntry> = self.entries.values().collect();
+        list.sort_by(|a, b| {
+            a.first_seen
+                .cmp(&b.first_seen)
+                .then_with(|| a.hex.cmp(&b.hex))
+        });
+        list
+    }
+
+    pub fn record<'a>(
+        &mut self,
+        now_secs: i64,
+        aircraft: impl IntoIterator<Item = (&'a Aircraft, Option<f64>)>,
+    ) {
+        for (ac, distance_nm) in aircraft {
+            let Some(hex) = ac.hex.as_deref().map(str::trim).filter(|h| !h.is_empty()) else {
+                continue;
+            };
+            self.entries
+                .entry(hex.to_ascii_lowercase())
+                .or_insert_with_key(|key| SessionEntry {
+                    hex: key.clone(),
+                    first_seen: now_secs,
+                    last_seen: now_secs,
+                    ..SessionEntry::default()
+                })
+                .update(now_secs, ac, distance_nm);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SessionLog;
+    use crate::model::{Aircraft, Altitude};
+
+    #[test]
+    fn keeps_first_last_and_maxima() {
+        let mut log = SessionLog::default();
+        let early = Aircraft {
+            hex: Some("4CA7B5".to_string()),
+            flight: Some("RYR12AB ".to_string()),
+            alt_baro: Some(Altitude::Feet(3_000)),
+            gs: Some(180.0),
+            ..Aircraft::default()
+        };
+        let later = Aircraft {
+            hex: Some("4ca7b5".to_string()),
+            r: Some("EI-DCL".to_string()),
+            alt_baro: Some(Altitude::Feet(37_000)),
+            gs: Some(450.5),
+            ..Aircraft::default()
+        };
+        let other = Aircraft {
+            hex: Some("a00001".to_string()),
+            ..Aircraft::default()
+        };
+        log.record(1_000, [(&early, Some(12.0))]);
+        log.record(1_060, [(&later, Some(8.0)), (&other, None)]);
+
+        let entry = log.get("4CA7B5").unwrap();
+        assert_eq!((entry.first_seen, entry.last_seen), (1_000, 1_060));
+        assert_eq!(entry.callsign.as_deref(), Some("RYR12AB"));
+        assert_eq!(entry.registration.as_deref(), Some("EI-DCL"));
+        assert_eq!(entry.max_alt, Some(37_000));
+        assert_eq!(entry.max_gs, Some(450.5));
+        assert_eq!(entry.max_distance_nm, Some(12.0));
+        let hexes: Vec<&str> = log.entries().iter().map(|e| e.hex.as_str()).collect();
+        assert_eq!(hexes, ["4ca7b5", "a00001"]);
+    }
+}
@@ -0,0 +1,364 @@
+//! Sighting history in SQLite.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+use crate::model::Aircraft;
+use crate::tasks::{self, Tasks};
+
+/// An aircraft gone from the feed this long starts a new visit when it
+/// comes back.
+const VISIT_GAP_SECS: i64 = 30 * 60;
+const KEEP_SECS: i64 = 90 * 24 * 3600;
+const BUSY_TIMEOUT: Duration = Duration::from_secs(2);
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS sightings (
+        hex TEXT PRIMARY KEY NOT NULL,
+        callsign TEXT,
+        first_seen INTEGER NOT NULL,
+        last_seen INTEGER NOT NULL,
+        times_seen INTEGER NOT NULL,
+        min_alt INTEGER,
+        max_alt INTEGER,
+        max_distance_nm REAL
+    );
+    CREATE INDEX IF NOT EXISTS sightings_last_seen ON sightings (last_seen DESC, hex);
+";
+
+// Column values in the UPDATE refer to the stored row, so the visit gap is
+// measured against the previous `last_seen`. SQLite's two-argument min/max
+// return NULL if either side is NULL, hence the coalesce.
+const UPSERT: &str = "
+    INSERT INTO sightings
+        (hex, callsign, first_seen, last_seen, times_seen, min_alt, max_alt, max_distance_nm)
+    VALUES (?1, ?2, ?3, ?3, 1, ?4, ?4, ?5)
+    ON CONFLICT (hex) DO UPDATE SET
+        callsign = coalesce(excluded.callsign, callsign),
+        times_seen = times_seen + (excluded.last_seen - last_seen > ?6),
+        last_seen = max(last_seen, excluded.last_seen),
+        min_alt = coalesce(min(min_alt, excluded.min_alt), min_alt, excluded.min_alt),
+        max_alt = coalesce(max(max_alt, excluded.max_alt), max_alt, excluded.max_alt),
+        max_distance_nm = coalesce(
+            max(max_distance_nm, excluded.max_distance_nm),
+            max_distance_nm,
+            excluded.max_distance_nm
+        )
+";
+
+const COLUMNS: &str =
+    "hex, callsign, first_seen, last_seen, times_seen, min_alt, max_alt, max_distance_nm";
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Sighting {
+    pub hex: String,
+    pub callsign: Option<String>,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub times_seen: u32,
+    pub min_alt: Option<i64>,
+    pub max_alt: Option<i64>,
+    pub max_distance_nm: Option<f64>,
+}
+
+impl Sighting {
+    fn from_row(row: &Row) -> rusqlite::Result<Self> {
+        Ok(Self {
+            hex: row.get(0)?,
+            callsign: row.get(1)?,
+            first_seen: row.get(2)?,
+            last_seen: row.get(3)?,
+            times_seen: row.get(4)?,
+            min_alt: row.get(5)?,
+            max_alt: row.get(6)?,
+            max_distance_nm: row.get(7)?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SightingUpdate {
+    hex: String,
+    callsign: Option<String>,
+    alt: Option<i64>,
+    distance_nm: Option<f64>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SightingBatch {
+    now_secs: i64,
+    updates: Vec<SightingUpdate>,
+}
+
+impl SightingBatch {
+    pub fn new<'a>(
+        now_secs: i64,
+        aircraft: impl IntoIterator<Item = (&'a Aircraft, Option<f64>)>,
+    ) -> Self {
+        let updates = aircraft
+            .into_iter()
+            .filter_map(|(ac, distance_nm)| {
+                let hex = ac.hex.as_deref().map(str::trim).filter(|h| !h.is_empty())?;
+                Some(SightingUpdate {
+                    hex: hex.to_ascii_lowercase(),
+                    callsign: ac
+                        .flight
+                        .as_deref()
+                        .map(str::trim)
+                        .filter(|flight| !flight.is_empty())
+                        .map(str::to_string),
+                    alt: ac.alt_baro.map(|alt| alt.feet()),
+                    distance_nm: distance_nm.filter(|nm| nm.is_finite()),
+                })
+            })
+            .collect();
+        Self { now_secs, updates }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty()
+    }
+}
+
+fn open_connection(path: &Path) -> Result<Connection> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create sightings dir: {}", parent.display()))?;
+    }
+    let conn = Connection::open(path)
+        .with_context(|| format!("Failed to open sightings: {}", path.display()))?;
+    conn.busy_timeout(BUSY_TIMEOUT)?;
+    // WAL lets the UI read while the writer holds a transaction open.
+    conn.pragma_update(None, "journal_mode", "WAL")?;
+    conn.execute_batch(SCHEMA)
+        .with_context(|| format!("Failed to create sightings table: {}", path.display()))?;
+    Ok(conn)
+}
+
+fn write_batches(conn: &mut Connection, batches: &[SightingBatch]) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    {
+        let mut upsert = tx.prepare_cached(UPSERT)?;
+        for batch in batches {
+            for update in &batch.updates {
+                upsert.execute(params![
+                    update.hex,
+                    update.callsign,
+                    batch.now_secs,
+                    update.alt,
+                    update.distance_nm,
+                    VISIT_GAP_SECS,
+                ])?;
+            }
+        }
+    }
+    tx.commit()
+}
+
+/// Read side of the history. Without a file nothing is recorded and every
+/// query comes back empty.
+#[derive(Default)]
+pub struct SightingStore {
+    conn: Option<Connection>,
+}
+
+impl SightingStore {
+    pub fn open(path: &Path, now_secs: i64) -> Result<Self> {
+        let conn = open_connection(path)?;
+        let dropped = conn
+            .execute(
+                "DELETE FROM sightings WHERE last_seen <= ?1",
+                [now_secs - KEEP_SECS],
+            )
+            .with_context(|| format!("Failed to prune sightings: {}", path.display()))?;
+        if dropped > 0 {
+            debug!("dropped {dropped} old sightings");
+        }
+        Ok(Self { conn: Some(conn) })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.conn.is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.query(|conn| {
+            conn.query_row("SELECT count(*) FROM sightings", [], |row| {
+                row.get::<_, i64>(0)
+            })
+        })
+        .map_or(0, |count| count as usize)
+    }
+
+    pub fn get(&self, hex: &str) -> Option<Sighting> {
+        let hex = hex.trim().to_ascii_lowercase();
+        self.query(|conn| {
+            conn.prepare_cached(&format!("SELECT {COLUMNS} FROM sightings WHERE hex = ?1"))?
+                .query_row([hex], Sighting::from_row)
+                .optional()
+        })
+        .flatten()
+    }
+
+    pub fn page(&self, offset: usize, limit: usize) -> Vec<Sighting> {
+        self.query(|conn| {
+            conn.prepare_cached(&format!(
+                "SELECT {COLUMNS} FROM sightings ORDER BY last_seen DESC, hex LIMIT ?1 OFFSET ?2"
+            ))?
+            .query_map([limit as i64, offset as i64], Sighting::from_row)?
+            .collect()
+        })
+        .unwrap_or_default()
+    }
+
+    fn query<T>(&self, read: impl FnOnce(&Connection) -> rusqlite::Result<T>) -> Option<T> {
+        let conn = self.conn.as_ref()?;
+        read(conn)
+            .map_err(|err| warn!("sightings query failed: {err}"))
+            .ok()
+    }
+}
+
+/// Upsert every snapshot sent on the returned channel into the database at
+/// `path`. Snapshots that queue up while a write is running go in together,
+/// in one transaction, and whatever is queued at shutdown is still written.
+/// The returned counter goes up after every write.
+pub fn spawn_sightings_writer(
+    tasks: &Tasks,
+    path: PathBuf,
+) -> (UnboundedSender<SightingBatch>, watch::Receiver<u64>) {
+    let (tx, mut rx) = unbounded_channel::<SightingBatch>();
+    let (flushed_tx, flushed_rx) = watch::channel(0u64);
+    tasks.spawn_flushing("sightings", async move {
+        let opened = tasks::blocking({
+            let path = path.clone();
+            move || open_connection(&path)
+        })
+        .await;
+        let mut conn = match opened {
+            Some(Ok(conn)) => conn,
+            Some(Err(err)) => {
+                warn!("{err:#}");
+                return;
+            }
+            None => return,
+        };
+        while let Some(first) = rx.recv().await {
+            let mut batches = vec![first];
+            while let Ok(next) = rx.try_recv() {
+                batches.push(next);
+            }
+            let Some((returned, result)) = tasks::blocking(move || {
+                let result = write_batches(&mut conn, &batches);
+                (conn, result)
+            })
+            .await
+            else {
+                return;
+            };
+            conn = returned;
+            match result {
+                Ok(()) => flushed_tx.send_modify(|count| *count += 1),
+                Err(err) => warn!("sightings write failed {}: {err}", path.display()),
+            }
+        }
+    });
+    (tx, flushed_rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{open_connection, write_batches, SightingBatch, SightingStore};
+    use super::{KEEP_SECS, VISIT_GAP_SECS};
+    use crate::model::{Aircraft, Altitude};
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_db(name: &str) -> PathBuf {
+        let mut dir = std::env::temp_dir();
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        dir.push(format!("adsb-tui-test-{name}-{suffix}"));
+        let _ = fs::create_dir_all(&dir);
+        dir.push(name);
+        dir
+    }
+
+    fn aircraft(hex: &str, flight: Option<&str>, alt: Option<Altitude>) -> Aircraft {
+        Aircraft {
+            hex: Some(hex.to_string()),
+            flight: flight.map(str::to_string),
+            alt_baro: alt,
+            ..Aircraft::default()
+        }
+    }
+
+    fn record(path: &Path, batches: &[SightingBatch]) {
+        let mut conn = open_connection(path).unwrap();
+        write_batches(&mut conn, batches).unwrap();
+    }
+
+    #[test]
+    fn tracks_visits_altitude_band_and_range() {
+        let path = temp_db("visits.db");
+        let store = SightingStore::open(&path, 1_000).unwrap();
+        let climbing = aircraft("4CA7B5", Some("RYR12AB "), Some(Altitude::Feet(3_000)));
+        let cruising = aircraft("4ca7b5", None, Some(Altitude::Feet(37_000)));
+        let on_ground = aircraft("4ca7b5", Some(""), Some(Altitude::Ground));
+        record(
+            &path,
+            &[
+                SightingBatch::new(1_000, [(&climbing, Some(12.0))]),
+                SightingBatch::new(1_060, [(&cruising, Some(80.5)), (&on_ground, None)]),
+                SightingBatch::new(1_060 + VISIT_GAP_SECS + 1, [(&cruising, Some(40.0))]),
+            ],
+        );
+
+        let sighting = store.get("4CA7B5").unwrap();
+        assert_eq!(sighting.times_seen, 2);
+        assert_eq!(sighting.first_seen, 1_000);
+        assert_eq!(sighting.last_seen, 1_060 + VISIT_GAP_SECS + 1);
+        assert_eq!(sighting.callsign.as_deref(), Some("RYR12AB"));
+        assert_eq!(
+            (sighting.min_alt, sighting.max_alt),
+            (Some(0), Some(37_000))
+        );
+        assert_eq!(sighting.max_distance_nm, Some(80.5));
+        assert_eq!(store.len(), 1);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn pages_order_by_last_seen_and_open_drops_old_entries() {
+        let path = temp_db("pages.db");
+        let now = 2_000_000_000;
+        let a1 = aircraft("a1", None, None);
+        let b2 = aircraft("b2", None, None);
+        let c3 = aircraft("C3", None, None);
+        record(
+            &path,
+            &[
+                SightingBatch::new(now - KEEP_SECS, [(&b2, None)]),
+                SightingBatch::new(now - 10, [(&a1, None)]),
+                SightingBatch::new(now, [(&c3, None)]),
+            ],
+        );
+
+        let store = SightingStore::open(&path, now).unwrap();
+        let hexes: Vec<String> = store.page(0, 10).into_iter().map(|s| s.hex).collect();
+        assert_eq!(hexes, ["c3", "a1"]);
+        assert_eq!(store.page(1, 1)[0].hex, "a1");
+        assert!(store.get("C3").is_some());
+        assert!(SightingStore::default().page(0, 10).is_empty());
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
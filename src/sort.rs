@@ -0,0 +1,259 @@
+//! Table sort order, e.g. `alt desc, callsign`.
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortKey {
+    Seen,
+    Altitude,
+    Speed,
+    Distance,
+    Callsign,
+    Registration,
+    Type,
+    Messages,
+    Rssi,
+    Hex,
+}
+
+impl SortKey {
+    pub const ALL: [SortKey; 10] = [
+        SortKey::Seen,
+        SortKey::Altitude,
+        SortKey::Speed,
+        SortKey::Distance,
+        SortKey::Callsign,
+        SortKey::Registration,
+        SortKey::Type,
+        SortKey::Messages,
+        SortKey::Rssi,
+        SortKey::Hex,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            SortKey::Seen => "seen",
+            SortKey::Altitude => "alt",
+            SortKey::Speed => "gs",
+            SortKey::Distance => "distance",
+            SortKey::Callsign => "callsign",
+            SortKey::Registration => "reg",
+            SortKey::Type => "type",
+            SortKey::Messages => "messages",
+            SortKey::Rssi => "rssi",
+            SortKey::Hex => "hex",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortKey::Seen => "SEEN",
+            SortKey::Altitude => "ALT",
+            SortKey::Speed => "SPD",
+            SortKey::Distance => "DIST",
+            SortKey::Callsign => "CALLSIGN",
+            SortKey::Registration => "REG",
+            SortKey::Type => "TYPE",
+            SortKey::Messages => "MSGS",
+            SortKey::Rssi => "RSSI",
+            SortKey::Hex => "HEX",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let key = match name.to_ascii_lowercase().as_str() {
+            "seen" | "last_seen" => SortKey::Seen,
+            "alt" | "altitude" => SortKey::Altitude,
+            "gs" | "spd" | "speed" => SortKey::Speed,
+            "distance" | "dist" => SortKey::Distance,
+            "callsign" | "flight" => SortKey::Callsign,
+            "reg" | "registration" => SortKey::Registration,
+            "type" => SortKey::Type,
+            "messages" | "msgs" => SortKey::Messages,
+            "rssi" => SortKey::Rssi,
+            "hex" => SortKey::Hex,
+            _ => return None,
+        };
+        Some(key)
+    }
+
+    /// Most recent, highest, fastest, nearest and strongest come first by
+    /// default; text columns run A to Z.
+    pub fn default_descending(self) -> bool {
+        matches!(
+            self,
+            SortKey::Altitude | SortKey::Speed | SortKey::Messages | SortKey::Rssi
+        )
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortColumn {
+    pub key: SortKey,
+    pub descending: bool,
+}
+
+impl SortColumn {
+    pub fn new(key: SortKey) -> Self {
+        Self {
+            key,
+            descending: key.default_descending(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SortSpec {
+    columns: Vec<SortColumn>,
+}
+
+impl Default for SortSpec {
+    fn default() -> Self {
+        Self::single(SortKey::Seen)
+    }
+}
+
+impl SortSpec {
+    pub fn single(key: SortKey) -> Self {
+        Self {
+            columns: vec![SortColumn::new(key)],
+        }
+    }
+
+    /// Parses `"alt desc, callsign"`: comma separated columns, each with an
+    /// optional `asc` or `desc`.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let mut columns: Vec<SortColumn> = Vec::new();
+        for part in text.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let mut words = part.split_whitespace();
+            let name = words.next().unwrap_or_default();
+            let key =
+                SortKey::parse(name).ok_or_else(|| format!("unknown sort column `{name}`"))?;
+            let descending = match words.next().map(str::to_ascii_lowercase).as_deref() {
+                None => key.default_descending(),
+                Some("asc") => false,
+                Some("desc") => true,
+                Some(other) => return Err(format!("`{other}` is not asc or desc")),
+            };
+            if let Some(extra) = words.next() {
+                return Err(format!("unexpected `{extra}` after {name}"));
+            }
+            if columns.iter().any(|column| column.key == key) {
+                return Err(format!("sort column `{name}` is listed twice"));
+            }
+            columns.push(SortColumn { key, descending });
+        }
+        if columns.is_empty() {
+            return Err("sort needs at least one column".to_string());
+        }
+        Ok(Self { columns })
+    }
+
+    pub fn columns(&self) -> &[SortColumn] {
+        &self.columns
+    }
+
+    pub fn position(&self, key: SortKey) -> Option<usize> {
+        self.columns.iter().position(|column| column.key == key)
+    }
+
+    pub fn set_primary(&mut self, key: SortKey) {
+        match self.columns.as_slice() {
+            [only] if only.key == key => self.columns[0].descending = !only.descending,
+            _ => self.columns = vec![SortColumn::new(key)],
+        }
+    }
+
+    /// Adds `key` as the last tie-breaker, or removes it if already listed
+    /// (the last remaining column stays).
+    pub fn toggle_secondary(&mut self, key: SortKey) {
+        match self.position(key) {
+            Some(_) if self.columns.len() == 1 => {}
+            Some(pos) => {
+                self.columns.remove(pos);
+            }
+            None => self.columns.push(SortColumn::new(key)),
+        }
+    }
+
+    pub fn reverse(&mut self, key: SortKey) {
+        if let Some(pos) = self.position(key) {
+            self.columns[pos].descending = !self.columns[pos].descending;
+        }
+    }
+
+    pub fn label(&self) -> String {
+        self.columns
+            .iter()
+            .map(|column| {
+                let arrow = if column.descending { '↓' } else { '↑' };
+                format!("{}{arrow}", column.key.label())
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+impl fmt::Display for SortSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = self
+            .columns
+            .iter()
+            .map(|column| {
+                let direction = if column.descending { "desc" } else { "asc" };
+                format!("{} {direction}", column.key.name())
+            })
+            .collect();
+        f.write_str(&parts.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SortColumn, SortKey, SortSpec};
+
+    #[test]
+    fn parses_columns_and_directions() {
+        let spec = SortSpec::parse("ALT, callsign desc ,rssi asc").unwrap();
+        assert_eq!(
+            spec.columns(),
+            [
+                SortColumn {
+                    key: SortKey::Altitude,
+                    descending: true
+                },
+                SortColumn {
+                    key: SortKey::Callsign,
+                    descending: true
+                },
+                SortColumn {
+                    key: SortKey::Rssi,
+                    descending: false
+                },
+            ]
+        );
+        assert_eq!(spec.to_string(), "alt desc, callsign desc, rssi asc");
+        assert_eq!(SortSpec::parse(&spec.to_string()), Ok(spec));
+        assert_eq!(SortSpec::default().label(), "SEEN↑");
+
+        assert!(SortSpec::parse("").is_err());
+        assert!(SortSpec::parse("squawk").is_err());
+        assert!(SortSpec::parse("alt up").is_err());
+        assert!(SortSpec::parse("alt, alt").is_err());
+    }
+
+    #[test]
+    fn modal_edits_keep_one_column() {
+        let mut spec = SortSpec::default();
+        spec.set_primary(SortKey::Distance);
+        assert_eq!(spec.label(), "DIST↑");
+        spec.set_primary(SortKey::Distance);
+        assert_eq!(spec.label(), "DIST↓");
+        spec.toggle_secondary(SortKey::Callsign);
+        spec.reverse(SortKey::Callsign);
+        assert_eq!(spec.label(), "DIST↓ CALLSIGN↓");
+        spec.toggle_secondary(SortKey::Distance);
+        spec.toggle_secondary(SortKey::Callsign);
+        assert_eq!(spec.label(), "CALLSIGN↓");
+    }
+}
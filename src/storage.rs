@@ -1,9 +1,21 @@
 use anyhow::{Context, Result};
-use std::collections::HashSet;
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
+use std::io::{BufReader, Read};
 use std::path::Path;
+use std::time::Duration;
 
+use crate::aircraft_db::AircraftDb;
+use crate::airports::AirportDb;
+use crate::geography::Geography;
+use crate::military::MilitaryRanges;
+use crate::photos::{PhotoCache, PhotoInfo};
+use crate::roles::RoleRules;
+use crate::traffic::{DayTotals, HourTotals, RangeOutline, TrafficHistory};
 use crate::watchlist::{WatchEntry, WatchlistFile};
+use crate::zones::{Zone, ZonesFile};
 
 fn ensure_parent_dir(path: &Path, context: &str) -> Result<()> {
     if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
@@ -62,6 +74,74 @@ pub fn save_watchlist(path: &Path, entries: &[WatchEntry]) -> Result<()> {
     Ok(())
 }
 
+#[derive(Deserialize, Serialize)]
+struct TrafficFile {
+    hours: Vec<HourTotals>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    range: Option<RangeOutline>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    days: Vec<DayTotals>,
+}
+
+pub fn load_traffic_history(path: &Path) -> Result<TrafficHistory> {
+    if !path.exists() {
+        return Ok(TrafficHistory::default());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read traffic history: {}", path.display()))?;
+    let file: TrafficFile = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse traffic history: {}", path.display()))?;
+    Ok(TrafficHistory::from_hours(file.hours)
+        .with_range(file.range)
+        .with_days(file.days))
+}
+
+pub fn save_traffic_history(path: &Path, history: &TrafficHistory) -> Result<()> {
+    let file = TrafficFile {
+        hours: history.hours().copied().collect(),
+        range: history.range().cloned(),
+        days: history.days().copied().collect(),
+    };
+    let content = serde_json::to_string(&file)
+        .with_context(|| format!("Failed to serialize traffic history: {}", path.display()))?;
+    ensure_parent_dir(path, "traffic history")?;
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write traffic history: {}", path.display()))?;
+    Ok(())
+}
+
+#[derive(Deserialize, Serialize)]
+struct PhotoFile {
+    aircraft: BTreeMap<String, PhotoInfo>,
+}
+
+pub fn load_photo_cache(path: &Path, ttl: Duration, now_secs: i64) -> Result<PhotoCache> {
+    let cache = PhotoCache::new(ttl);
+    if !path.exists() {
+        return Ok(cache);
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read photo cache: {}", path.display()))?;
+    let file: PhotoFile = serde_json::from_str(&content)
+        .with_context(|| format!("Failed to parse photo cache: {}", path.display()))?;
+    Ok(cache.with_entries(file.aircraft, now_secs))
+}
+
+pub fn save_photo_cache(path: &Path, cache: &PhotoCache) -> Result<()> {
+    let file = PhotoFile {
+        aircraft: cache
+            .entries()
+            .map(|(hex, info)| (hex.clone(), info.clone()))
+            .collect(),
+    };
+    let content = serde_json::to_string(&file)
+        .with_context(|| format!("Failed to serialize photo cache: {}", path.display()))?;
+    ensure_parent_dir(path, "photo cache")?;
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write photo cache: {}", path.display()))?;
+    Ok(())
+}
+
 pub fn ensure_watchlist_file(path: &Path) -> Result<bool> {
     if path.exists() {
         return Ok(false);
@@ -70,6 +150,8 @@ pub fn ensure_watchlist_file(path: &Path) -> Result<bool> {
     let template = r#"# ADSB-TUI Watchlist
 # Each entry is a [[watchlist]] table.
 # match: hex | callsign | reg | type | owner | category | route
+# category matches the raw code (A7) or its class (light, medium, heavy,
+# super, rotorcraft, glider, uav, ...)
 # mode: exact | prefix | contains
 # enabled/notify: true or false
 
@@ -96,16 +178,139 @@ priority = 0
     Ok(true)
 }
 
+pub fn load_aircraft_db(path: &Path) -> Result<AircraftDb> {
+    let file = fs::File::open(path)
+        .with_context(|| format!("Failed to read aircraft db: {}", path.display()))?;
+    let mut content = String::new();
+    let gzipped = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("gz"));
+    let read = if gzipped {
+        GzDecoder::new(file).read_to_string(&mut content)
+    } else {
+        BufReader::new(file).read_to_string(&mut content)
+    };
+    read.with_context(|| format!("Failed to read aircraft db: {}", path.display()))?;
+    Ok(AircraftDb::parse(&content))
+}
+
+pub fn load_military_ranges(path: &Path) -> Result<MilitaryRanges> {
+    if !path.exists() {
+        return Ok(MilitaryRanges::default());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read military ranges: {}", path.display()))?;
+    MilitaryRanges::parse(&content).map_err(|err| {
+        anyhow::anyhow!("Failed to parse military ranges: {}: {err}", path.display())
+    })
+}
+
+/// Rules from `roles_path` ahead of the built-in ones; without the file
+/// only the built-in rules, using the ranges from `military_path`.
+pub fn load_role_rules(
+    roles_path: Option<&Path>,
+    military_path: Option<&Path>,
+) -> Result<RoleRules> {
+    let military = match military_path {
+        Some(path) => load_military_ranges(path)?,
+        None => MilitaryRanges::default(),
+    };
+    let Some(path) = roles_path.filter(|path| path.exists()) else {
+        return Ok(RoleRules::builtin(&military));
+    };
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read roles: {}", path.display()))?;
+    RoleRules::parse(&content, &military)
+        .map_err(|err| anyhow::anyhow!("Failed to parse roles: {}: {err}", path.display()))
+}
+
+pub fn load_airports(path: &Path) -> Result<AirportDb> {
+    if !path.exists() {
+        return Ok(AirportDb::default());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read airports: {}", path.display()))?;
+    AirportDb::parse_csv(&content)
+        .map_err(|err| anyhow::anyhow!("Failed to parse airports: {}: {err}", path.display()))
+}
+
+pub fn load_geography(path: &Path) -> Result<Geography> {
+    if !path.exists() {
+        return Ok(Geography::default());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read geography: {}", path.display()))?;
+    Geography::parse_geojson(&content)
+        .map_err(|err| anyhow::anyhow!("Failed to parse geography: {}: {err}", path.display()))
+}
+
+pub fn load_zones(path: &Path) -> Result<Vec<Zone>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read zones: {}", path.display()))?;
+    let file: ZonesFile = toml::from_str(&content)
+        .with_context(|| format!("Failed to parse zones: {}", path.display()))?;
+    Ok(file.zones)
+}
+
+pub fn save_zones(path: &Path, zones: &[Zone]) -> Result<()> {
+    let file = ZonesFile {
+        zones: zones.to_vec(),
+    };
+    // Not `to_string_pretty`: it puts every polygon coordinate on its own line.
+    let content = toml::to_string(&file)
+        .with_context(|| format!("Failed to serialize zones: {}", path.display()))?;
+    ensure_parent_dir(path, "zones")?;
+    fs::write(path, content)
+        .with_context(|| format!("Failed to write zones: {}", path.display()))?;
+    Ok(())
+}
+
+pub fn ensure_zones_file(path: &Path) -> Result<bool> {
+    if path.exists() {
+        return Ok(false);
+    }
+    ensure_parent_dir(path, "zones")?;
+    let template = r#"# ADSB-TUI Geofence zones
+# Each zone is a [[zone]] table: a circle (lat, lon, radius_nm) or a polygon
+# (points = [[lat, lon], ...], three or more corners).
+# alert: any | watchlist | off (watchlist = watchlist matches and favorites)
+# enabled: true or false
+#
+# [[zone]]
+# name = "Example: Airport"
+# lat = 40.6413
+# lon = -73.7781
+# radius_nm = 5.0
+# alert = "any"
+#
+# [[zone]]
+# name = "Example: Restricted area"
+# points = [[40.75, -74.02], [40.75, -73.95], [40.70, -73.95], [40.70, -74.02]]
+# alert = "watchlist"
+"#;
+    fs::write(path, template)
+        .with_context(|| format!("Failed to write zones template: {}", path.display()))?;
+    Ok(true)
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        ensure_watchlist_file, load_favorites, load_watchlist, save_favorites, save_watchlist,
+        ensure_watchlist_file, ensure_zones_file, load_aircraft_db, load_favorites,
+        load_photo_cache, load_traffic_history, load_watchlist, load_zones, save_favorites,
+        save_photo_cache, save_traffic_history, save_watchlist, save_zones,
     };
+    use crate::photos::PhotoInfo;
+    use crate::traffic::{HourTotals, TrafficHistory};
     use crate::watchlist::WatchEntry;
+    use crate::zones::Zone;
     use std::collections::HashSet;
     use std::fs;
     use std::path::PathBuf;
-    use std::time::{SystemTime, UNIX_EPOCH};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
     fn temp_file(name: &str) -> PathBuf {
         let mut dir = std::env::temp_dir();
@@ -169,4 +374,109 @@ mod tests {
         let _ = fs::remove_file(&path);
         let _ = fs::remove_dir(path.parent().unwrap());
     }
+
+    #[test]
+    fn zones_template_loads_empty_and_roundtrips() {
+        let path = temp_file("zones.toml");
+        assert!(ensure_zones_file(&path).unwrap());
+        assert!(load_zones(&path).unwrap().is_empty());
+
+        let zones = vec![
+            Zone {
+                name: "Field".to_string(),
+                lat: Some(40.0),
+                lon: Some(-74.0),
+                radius_nm: Some(2.5),
+                ..Zone::default()
+            },
+            Zone {
+                name: "Box".to_string(),
+                points: vec![[41.0, -75.0], [41.0, -74.0], [42.0, -74.0]],
+                enabled: Some(false),
+                alert: Some("watchlist".to_string()),
+                ..Zone::default()
+            },
+        ];
+        save_zones(&path, &zones).unwrap();
+        assert_eq!(load_zones(&path).unwrap(), zones);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_dir(path.parent().unwrap());
+    }
+
+    #[test]
+    fn traffic_history_roundtrip() {
+        let path = temp_file("traffic.json");
+        assert_eq!(load_traffic_history(&path).unwrap().hours().count(), 0);
+        let mut history = TrafficHistory::from_hours(vec![HourTotals {
+            hour: 472_222,
+            aircraft: 41,
+            messages: 90_000,
+            peak_aircraft: 18,
+            peak_msg_rate: 812.5,
+            max_range_nm: 164.0,
+        }]);
+        history.record(472_222 * 3600 + 60, &[], Some(10), Some(900.0));
+        history.record_range((51.5, -0.1), 90.0, 120.0);
+        save_traffic_history(&path, &history).unwrap();
+        let loaded = load_traffic_history(&path).unwrap();
+        assert_eq!(
+            loaded.hours().copied().collect::<Vec<_>>(),
+            history.hours().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(loaded.range(), history.range());
+        assert_eq!(
+            loaded.days().copied().collect::<Vec<_>>(),
+            history.days().copied().collect::<Vec<_>>()
+        );
+        assert_eq!(loaded.days().count(), 1);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_dir(path.parent().unwrap());
+    }
+
+    #[test]
+    fn aircraft_db_reads_plain_and_gzipped_files() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let text = "4ca7b5;EI-DCL;B738;00;BOEING 737-800;2004;Ryanair;\n";
+        let plain = temp_file("aircraft.csv");
+        fs::write(&plain, text).unwrap();
+        assert_eq!(load_aircraft_db(&plain).unwrap().len(), 1);
+
+        let gzipped = plain.with_extension("csv.gz");
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        fs::write(&gzipped, encoder.finish().unwrap()).unwrap();
+        let db = load_aircraft_db(&gzipped).unwrap();
+        assert_eq!(
+            db.get("4ca7b5")
+                .and_then(|entry| entry.type_code.as_deref()),
+            Some("B738")
+        );
+        assert!(load_aircraft_db(&plain.with_extension("missing")).is_err());
+        let _ = fs::remove_file(&plain);
+        let _ = fs::remove_file(&gzipped);
+        let _ = fs::remove_dir(plain.parent().unwrap());
+    }
+
+    #[test]
+    fn photo_cache_roundtrip_drops_expired_entries() {
+        let path = temp_file("photos.json");
+        let ttl = Duration::from_secs(600);
+        let mut cache = load_photo_cache(&path, ttl, 1_000).unwrap();
+        let info = PhotoInfo {
+            link: Some("https://www.planespotters.net/photo/1".to_string()),
+            fetched_at: 1_000,
+            ..PhotoInfo::default()
+        };
+        cache.insert("A0B1C2", info.clone());
+        save_photo_cache(&path, &cache).unwrap();
+        let loaded = load_photo_cache(&path, ttl, 1_500).unwrap();
+        assert_eq!(loaded.get("a0b1c2", 1_500), Some(&info));
+        let expired = load_photo_cache(&path, ttl, 1_600).unwrap();
+        assert_eq!(expired.entries().count(), 0);
+        let _ = fs::remove_file(&path);
+        let _ = fs::remove_dir(path.parent().unwrap());
+    }
 }
@@ -0,0 +1,160 @@
+//! Deterministic synthetic feeds for stress tests and benchmarks.
+
+use std::time::{Duration, SystemTime};
+
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, info};
+
+use crate::model::{Aircraft, Altitude, ApiResponse};
+use crate::tasks::Tasks;
+
+pub const STRESS_AIRCRAFT: usize = 10_000;
+const SPREAD_DEG: f64 = 3.0;
+const STEP_DEG: f64 = 0.002;
+
+const TYPES: [&str; 6] = ["B738", "A320", "E75L", "C172", "B77W", "H60"];
+const CATEGORIES: [&str; 6] = ["A3", "A3", "A2", "A1", "A5", "A7"];
+const OPERATORS: [&str; 5] = ["UAL", "DAL", "AAL", "SWA", "JBU"];
+
+#[derive(Clone, Copy, Debug)]
+pub struct SyntheticFeed {
+    pub size: usize,
+    pub center: (f64, f64),
+}
+
+impl SyntheticFeed {
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            center: (40.0, -74.0),
+        }
+    }
+
+    pub fn with_center(mut self, lat: f64, lon: f64) -> Self {
+        self.center = (lat, lon);
+        self
+    }
+
+    /// Snapshot `tick` of the feed, stamped `now` (UNIX seconds). Every
+    /// aircraft advances along its track each tick; on odd ticks every third
+    /// aircraft omits its callsign and speed, as real feeds do between
+    /// full updates.
+    pub fn snapshot(&self, tick: u64, now: i64) -> ApiResponse {
+        let aircraft = (0..self.size)
+            .map(|i| self.aircraft(i, tick))
+            .collect::<Vec<_>>();
+        ApiResponse {
+            now: Some(now),
+            messages: Some(self.size as u64 * 1_000 + tick * 250),
+            aircraft,
+            ..ApiResponse::default()
+        }
+    }
+
+    fn aircraft(&self, i: usize, tick: u64) -> Aircraft {
+        let sparse = !tick.is_multiple_of(2) && i.is_multiple_of(3);
+        let ground = i.is_multiple_of(25);
+        let track = (i * 37 % 360) as f64;
+        let (lat, lon) = if i.is_multiple_of(11) {
+            (None, None)
+        } else {
+            // Golden-angle spiral keeps the positions evenly spread.
+            let radius = SPREAD_DEG * ((i as f64 + 0.5) / self.size as f64).sqrt();
+            let angle = i as f64 * 2.399_963;
+            let step = tick as f64 * if ground { 0.0 } else { STEP_DEG };
+            (
+                Some(self.center.0 + radius * angle.sin() + step * track.to_radians().cos()),
+                Some(self.center.1 + radius * angle.cos() + step * track.to_radians().sin()),
+            )
+        };
+        Aircraft {
+            hex: Some(format!("{:06x}", 0xa0_0000 + i)),
+            flight: (!sparse && !i.is_multiple_of(7))
+                .then(|| format!("{}{}", OPERATORS[i % OPERATORS.len()], 100 + i % 9_000)),
+            r: Some(format!("N{}", 10_000 + i)),
+            t: Some(TYPES[i % TYPES.len()].to_string()),
+            category: Some(CATEGORIES[i % CATEGORIES.len()].to_string()),
+            alt_baro: Some(if ground {
+                Altitude::Ground
+            } else {
+                Altitude::Feet(1_000 + (i as i64 * 137) % 40_000)
+            }),
+            gs: (!sparse).then_some(if ground {
+                12.0
+            } else {
+                140.0 + (i % 360) as f64
+            }),
+            track: Some(track),
+            baro_rate: (!ground).then_some((i as i64 % 41 - 20) * 128),
+            squawk: Some(format!("{:04}", 1_200 + i % 6_000)),
+            lat,
+            lon,
+            nic: Some(8 - i.is_multiple_of(5) as i64 * 4),
+            nac_p: Some(9),
+            version: Some((i % 3) as i64),
+            messages: Some(1_000 + i as u64 + tick * 10),
+            seen: Some((i % 15) as f64),
+            seen_pos: lat.map(|_| (i % 20) as f64),
+            rssi: Some(-30.0 + (i % 20) as f64),
+            ..Aircraft::default()
+        }
+    }
+}
+
+pub(crate) fn spawn_synthetic_feed(
+    tasks: &Tasks,
+    feed: SyntheticFeed,
+    interval: Duration,
+    tx: UnboundedSender<Result<ApiResponse, String>>,
+) {
+    let interval = interval.max(Duration::from_millis(200));
+    tasks.spawn("synthetic", async move {
+        info!("synthetic feed started: {} aircraft", feed.size);
+        let mut tick = 0;
+        loop {
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0);
+            if tx.send(Ok(feed.snapshot(tick, now))).is_err() {
+                debug!("receiver dropped, exiting synthetic feed");
+                return;
+            }
+            tick += 1;
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SyntheticFeed;
+    use crate::model::Altitude;
+
+    #[test]
+    fn snapshots_are_deterministic_and_varied() {
+        let feed = SyntheticFeed::new(1_000).with_center(51.5, -0.1);
+        let first = feed.snapshot(0, 100);
+        let again = feed.snapshot(0, 100);
+        assert_eq!(first.aircraft.len(), 1_000);
+        assert_eq!(first.aircraft[42].hex, again.aircraft[42].hex);
+        assert_eq!(first.aircraft[42].lat, again.aircraft[42].lat);
+
+        let positioned = first.aircraft.iter().filter(|ac| ac.lat.is_some()).count();
+        assert!(positioned > 850 && positioned < 1_000);
+        assert!(first
+            .aircraft
+            .iter()
+            .any(|ac| ac.alt_baro == Some(Altitude::Ground)));
+        assert!(first
+            .aircraft
+            .iter()
+            .filter_map(|ac| ac.lat)
+            .all(|lat| (lat - 51.5).abs() <= 3.0));
+
+        let next = feed.snapshot(1, 101);
+        assert!(next.aircraft[3].flight.is_none());
+        assert!(first.aircraft[3].flight.is_some());
+        assert_ne!(next.aircraft[1].lat, first.aircraft[1].lat);
+    }
+}
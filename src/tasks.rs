@@ -0,0 +1,182 @@
+//! Shared tokio runtime for background tasks under one cancellation token.
+
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::runtime::{Builder, Runtime};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, info};
+
+use crate::pool::WorkerPool;
+
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(1);
+const POOL_WORKERS: usize = 4;
+const POOL_HOST_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Tasks {
+    runtime: Option<Runtime>,
+    token: CancellationToken,
+    pool: WorkerPool,
+    flushing: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl Tasks {
+    pub fn new() -> Result<Self> {
+        let runtime = Builder::new_multi_thread()
+            .worker_threads(2)
+            .thread_name("adsb-worker")
+            .enable_io()
+            .enable_time()
+            .build()?;
+        Ok(Self {
+            runtime: Some(runtime),
+            token: CancellationToken::new(),
+            pool: WorkerPool::new(POOL_WORKERS, POOL_HOST_INTERVAL),
+            flushing: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Spawn a background task; it is dropped at its next await point once
+    /// shutdown starts.
+    pub fn spawn<F>(&self, name: &'static str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let token = self.token.clone();
+        self.runtime().spawn(async move {
+            debug!("task {name} started");
+            match token.run_until_cancelled(task).await {
+                Some(()) => debug!("task {name} finished"),
+                None => debug!("task {name} cancelled"),
+            }
+        });
+    }
+
+    /// Spawn a task that is not cancelled: shutdown waits for it, up to the
+    /// grace period, so it must finish once its input channel closes.
+    pub fn spawn_flushing<F>(&self, name: &'static str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let handle = self.runtime().spawn(async move {
+            debug!("task {name} started");
+            task.await;
+            debug!("task {name} finished");
+        });
+        self.flushing
+            .lock()
+            .unwrap_or_else(|err| err.into_inner())
+            .push(handle);
+    }
+
+    /// Block the calling thread on `future`; used by the headless loop to
+    /// wait for feed updates. Must not be called from inside a task.
+    pub fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime().block_on(future)
+    }
+
+    pub fn pool(&self) -> &WorkerPool {
+        &self.pool
+    }
+
+    fn runtime(&self) -> &Runtime {
+        self.runtime
+            .as_ref()
+            .expect("runtime is only taken when stopping")
+    }
+
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        let Some(runtime) = self.runtime.take() else {
+            return;
+        };
+        info!("stopping background tasks");
+        self.token.cancel();
+        let flushing = std::mem::take(
+            self.flushing
+                .get_mut()
+                .unwrap_or_else(|err| err.into_inner()),
+        );
+        if !flushing.is_empty() {
+            let flushed = runtime.block_on(async {
+                tokio::time::timeout(SHUTDOWN_GRACE, async {
+                    for handle in flushing {
+                        let _ = handle.await;
+                    }
+                })
+                .await
+            });
+            if flushed.is_err() {
+                info!("gave up waiting for queued writes");
+            }
+        }
+        runtime.shutdown_timeout(SHUTDOWN_GRACE);
+    }
+}
+
+impl Drop for Tasks {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Run blocking work (HTTP requests through the blocking client) on the
+/// runtime's blocking pool. `None` if the work panicked or was aborted.
+pub async fn blocking<T, F>(work: F) -> Option<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(work).await.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{blocking, Tasks};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    #[test]
+    fn shutdown_cancels_pending_tasks() {
+        let tasks = Tasks::new().unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel::<u32>();
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        tasks.spawn("echo", async move {
+            let value = blocking(|| 21 * 2).await;
+            let _ = done_tx.send(value);
+            // Parks forever unless cancelled.
+            while rx.recv().await.is_some() {}
+        });
+        assert_eq!(
+            done_rx.recv_timeout(Duration::from_secs(5)).unwrap(),
+            Some(42)
+        );
+        tasks.shutdown();
+        assert!(tx.send(1).is_err());
+    }
+
+    #[test]
+    fn shutdown_waits_for_flushing_tasks() {
+        let tasks = Tasks::new().unwrap();
+        let (tx, mut rx) = mpsc::unbounded_channel::<u32>();
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        tasks.spawn_flushing("drain", async move {
+            let mut total = 0;
+            while let Some(value) = rx.recv().await {
+                total += value;
+            }
+            let _ = done_tx.send(total);
+        });
+        tx.send(1).unwrap();
+        tx.send(2).unwrap();
+        drop(tx);
+        tasks.shutdown();
+        assert_eq!(done_rx.try_recv().unwrap(), 3);
+    }
+}
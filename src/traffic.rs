@@ -0,0 +1,437 @@
+//! Hourly and daily traffic history and the per-sector range outline.
+
+use std::collections::{HashSet, VecDeque};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::Aircraft;
+
+const HOUR_SECS: i64 = 3600;
+const DAY_SECS: i64 = 24 * HOUR_SECS;
+const KEEP_HOURS: i64 = 24 * 7;
+const KEEP_DAYS: i64 = 90;
+const SAVE_INTERVAL: Duration = Duration::from_secs(300);
+pub const RANGE_SECTORS: usize = 36;
+/// Positions farther than this are treated as bad decodes, not range.
+const MAX_PLAUSIBLE_RANGE_NM: f64 = 400.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct HourTotals {
+    pub hour: i64,
+    pub aircraft: u64,
+    pub messages: u64,
+    #[serde(default)]
+    pub peak_aircraft: u64,
+    #[serde(default)]
+    pub peak_msg_rate: f64,
+    #[serde(default)]
+    pub max_range_nm: f64,
+}
+
+/// The same totals over a UTC day; distinct aircraft are counted across the
+/// whole day, not summed from the hours.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct DayTotals {
+    pub day: i64,
+    pub aircraft: u64,
+    pub messages: u64,
+    pub peak_aircraft: u64,
+    pub peak_msg_rate: f64,
+    pub max_range_nm: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TrafficMetric {
+    Aircraft,
+    PeakAircraft,
+    Messages,
+    PeakMsgRate,
+    MaxRange,
+}
+
+impl TrafficMetric {
+    pub const ALL: [TrafficMetric; 5] = [
+        TrafficMetric::Aircraft,
+        TrafficMetric::PeakAircraft,
+        TrafficMetric::Messages,
+        TrafficMetric::PeakMsgRate,
+        TrafficMetric::MaxRange,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TrafficMetric::Aircraft => "aircraft/hour",
+            TrafficMetric::PeakAircraft => "peak concurrent",
+            TrafficMetric::Messages => "messages/hour",
+            TrafficMetric::PeakMsgRate => "peak msg/s",
+            TrafficMetric::MaxRange => "max range nm",
+        }
+    }
+
+    pub fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|metric| *metric == self)
+            .unwrap_or(0)
+    }
+
+    pub fn value(self, totals: &HourTotals) -> u64 {
+        match self {
+            TrafficMetric::Aircraft => totals.aircraft,
+            TrafficMetric::PeakAircraft => totals.peak_aircraft,
+            TrafficMetric::Messages => totals.messages,
+            TrafficMetric::PeakMsgRate => totals.peak_msg_rate.round() as u64,
+            TrafficMetric::MaxRange => totals.max_range_nm.round() as u64,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct RangeOutline {
+    pub site_lat: f64,
+    pub site_lon: f64,
+    pub max_nm: Vec<f64>,
+}
+
+impl RangeOutline {
+    fn new(site: (f64, f64)) -> Self {
+        Self {
+            site_lat: site.0,
+            site_lon: site.1,
+            max_nm: vec![0.0; RANGE_SECTORS],
+        }
+    }
+}
+
+pub fn range_sector(bearing_deg: f64) -> usize {
+    let width = 360.0 / RANGE_SECTORS as f64;
+    (bearing_deg.rem_euclid(360.0) / width) as usize % RANGE_SECTORS
+}
+
+#[derive(Debug, Default)]
+pub struct TrafficHistory {
+    hours: VecDeque<HourTotals>,
+    days: VecDeque<DayTotals>,
+    range: Option<RangeOutline>,
+    seen: HashSet<String>,
+    seen_today: HashSet<String>,
+    last_messages: Option<u64>,
+    dirty: bool,
+    saved_at: Option<Instant>,
+}
+
+impl TrafficHistory {
+    pub fn from_hours(mut hours: Vec<HourTotals>) -> Self {
+        hours.sort_by_key(|totals| totals.hour);
+        hours.dedup_by_key(|totals| totals.hour);
+        Self {
+            hours: hours.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Restore a saved range outline; one with the wrong sector count is
+    /// dropped.
+    pub fn with_range(mut self, range: Option<RangeOutline>) -> Self {
+        self.range = range.filter(|outline| outline.max_nm.len() == RANGE_SECTORS);
+        self
+    }
+
+    pub fn with_days(mut self, mut days: Vec<DayTotals>) -> Self {
+        days.sort_by_key(|totals| totals.day);
+        days.dedup_by_key(|totals| totals.day);
+        self.days = days.into();
+        self
+    }
+
+    pub fn hours(&self) -> impl Iterator<Item = &HourTotals> {
+        self.hours.iter()
+    }
+
+    pub fn days(&self) -> impl DoubleEndedIterator<Item = &DayTotals> {
+        self.days.iter()
+    }
+
+    pub fn range(&self) -> Option<&RangeOutline> {
+        self.range.as_ref()
+    }
+
+    pub fn max_range(&self, site: (f64, f64)) -> Option<&[f64]> {
+        self.range
+            .as_ref()
+            .filter(|outline| (outline.site_lat, outline.site_lon) == site)
+            .map(|outline| outline.max_nm.as_slice())
+    }
+
+    /// Extend the range outline with a position seen from `site`, and the
+    /// newest hour's and day's maximum range. Moving to another site starts a
+    /// new outline.
+    pub fn record_range(&mut self, site: (f64, f64), bearing_deg: f64, distance_nm: f64) {
+        if !distance_nm.is_finite() || distance_nm > MAX_PLAUSIBLE_RANGE_NM {
+            return;
+        }
+        if let Some(current) = self.hours.back_mut() {
+            current.max_range_nm = current.max_range_nm.max(distance_nm);
+        }
+        if let Some(today) = self.days.back_mut() {
+            today.max_range_nm = today.max_range_nm.max(distance_nm);
+        }
+        let outline = match &mut self.range {
+            Some(outline) if (outline.site_lat, outline.site_lon) == site => outline,
+            range => range.insert(RangeOutline::new(site)),
+        };
+        let max = &mut outline.max_nm[range_sector(bearing_deg)];
+        if distance_nm > *max {
+            *max = distance_nm;
+            self.dirty = true;
+        }
+    }
+
+    /// Count one feed snapshot received at `now_secs` (UNIX seconds).
+    /// `messages` is the decoder's running total; a drop means it restarted.
+    /// `msg_rate` is the smoothed rate shown in the header.
+    pub fn record(
+        &mut self,
+        now_secs: i64,
+        aircraft: &[Aircraft],
+        messages: Option<u64>,
+        msg_rate: Option<f64>,
+    ) {
+        let hour = now_secs.div_euclid(HOUR_SECS);
+        if self.hours.back().is_none_or(|last| last.hour != hour) {
+            if self.hours.back().is_some_and(|last| last.hour > hour) {
+                // Clock went backwards; keep counting into the newest hour.
+                return;
+            }
+            self.seen.clear();
+            self.hours.push_back(HourTotals {
+                hour,
+                ..HourTotals::default()
+            });
+            while self
+                .hours
+                .front()
+                .is_some_and(|first| first.hour <= hour - KEEP_HOURS)
+            {
+                self.hours.pop_front();
+            }
+        }
+        let day = now_secs.div_euclid(DAY_SECS);
+        if self.days.back().is_none_or(|last| last.day < day) {
+            self.seen_today.clear();
+            self.days.push_back(DayTotals {
+                day,
+                ..DayTotals::default()
+            });
+            while self
+                .days
+                .front()
+                .is_some_and(|first| first.day <= day - KEEP_DAYS)
+            {
+                self.days.pop_front();
+            }
+        }
+        let (Some(current), Some(today)) = (self.hours.back_mut(), self.days.back_mut()) else {
+            return;
+        };
+
+        for hex in aircraft.iter().filter_map(|ac| ac.hex.as_deref()) {
+            if !self.seen.contains(hex) {
+                self.seen.insert(hex.to_ascii_lowercase());
+            }
+            if !self.seen_today.contains(hex) {
+                self.seen_today.insert(hex.to_ascii_lowercase());
+            }
+        }
+        // A total loaded from the file may predate this run's distinct set.
+        current.aircraft = current.aircraft.max(self.seen.len() as u64);
+        today.aircraft = today.aircraft.max(self.seen_today.len() as u64);
+        let concurrent = aircraft.len() as u64;
+        current.peak_aircraft = current.peak_aircraft.max(concurrent);
+        today.peak_aircraft = today.peak_aircraft.max(concurrent);
+        if let Some(rate) = msg_rate.filter(|rate| rate.is_finite()) {
+            current.peak_msg_rate = current.peak_msg_rate.max(rate);
+            today.peak_msg_rate = today.peak_msg_rate.max(rate);
+        }
+
+        if let Some(total) = messages {
+            let delta = match self.last_messages {
+                Some(last) if total >= last => total - last,
+                _ => 0,
+            };
+            current.messages += delta;
+            today.messages += delta;
+            self.last_messages = Some(total);
+        }
+        self.dirty = true;
+    }
+
+    pub fn last_day(&self, now_secs: i64) -> [HourTotals; 24] {
+        let newest = now_secs.div_euclid(HOUR_SECS);
+        std::array::from_fn(|slot| {
+            let hour = newest - 23 + slot as i64;
+            self.hours
+                .iter()
+                .find(|totals| totals.hour == hour)
+                .copied()
+                .unwrap_or(HourTotals {
+                    hour,
+                    ..HourTotals::default()
+                })
+        })
+    }
+
+    /// Whether there are changes and the last save is `SAVE_INTERVAL` old;
+    /// with `flush`, whether there are any changes at all.
+    pub fn save_due(&mut self, now: Instant, flush: bool) -> bool {
+        if !self.dirty {
+            return false;
+        }
+        let saved_at = *self.saved_at.get_or_insert(now);
+        flush || now.duration_since(saved_at) >= SAVE_INTERVAL
+    }
+
+    pub fn mark_saved(&mut self, now: Instant) {
+        self.dirty = false;
+        self.saved_at = Some(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        range_sector, DayTotals, HourTotals, TrafficHistory, TrafficMetric, KEEP_HOURS,
+        RANGE_SECTORS,
+    };
+    use crate::model::Aircraft;
+    use std::time::{Duration, Instant};
+
+    fn aircraft(hexes: &[&str]) -> Vec<Aircraft> {
+        hexes
+            .iter()
+            .map(|hex| Aircraft {
+                hex: Some(hex.to_string()),
+                ..Aircraft::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn counts_distinct_aircraft_and_messages_per_hour() {
+        let mut history = TrafficHistory::default();
+        let base = 1_700_000_000 - 1_700_000_000 % 3600;
+        history.record(base + 10, &aircraft(&["a1", "a2"]), Some(1_000), None);
+        history.record(base + 20, &aircraft(&["a2", "A3"]), Some(1_400), None);
+        history.record(base + 3_610, &aircraft(&["a1"]), Some(1_500), None);
+        // Decoder restart: the total drops and counting resumes from there.
+        history.record(base + 3_620, &aircraft(&["a1"]), Some(50), None);
+        history.record(base + 3_630, &aircraft(&["a1"]), Some(90), None);
+
+        let hours: Vec<HourTotals> = history.hours().copied().collect();
+        assert_eq!(hours.len(), 2);
+        assert_eq!((hours[0].aircraft, hours[0].messages), (3, 400));
+        assert_eq!((hours[1].aircraft, hours[1].messages), (1, 140));
+
+        let day = history.last_day(base + 3_700);
+        assert_eq!(day[23].hour, hours[1].hour);
+        assert_eq!(day[22].messages, 400);
+        assert_eq!(day[0].aircraft, 0);
+    }
+
+    #[test]
+    fn drops_hours_older_than_a_week_and_resumes_loaded_hour() {
+        let hour = 500_000;
+        let mut history = TrafficHistory::from_hours(vec![
+            HourTotals {
+                hour: hour - KEEP_HOURS,
+                aircraft: 9,
+                messages: 9,
+                ..HourTotals::default()
+            },
+            HourTotals {
+                hour,
+                aircraft: 7,
+                messages: 100,
+                ..HourTotals::default()
+            },
+        ]);
+        history.record(hour * 3600 + 5, &aircraft(&["a1"]), Some(10), None);
+        let hours: Vec<HourTotals> = history.hours().copied().collect();
+        assert_eq!(hours.len(), 2);
+        assert_eq!((hours[1].aircraft, hours[1].messages), (7, 100));
+
+        history.record((hour + 1) * 3600, &aircraft(&["a1"]), Some(20), None);
+        let hours: Vec<HourTotals> = history.hours().copied().collect();
+        assert_eq!(hours.first().map(|totals| totals.hour), Some(hour));
+    }
+
+    #[test]
+    fn tracks_hourly_peaks_and_daily_totals() {
+        let day_start = 1_700_000_000 - 1_700_000_000 % 86_400;
+        let mut history = TrafficHistory::default().with_days(vec![DayTotals {
+            day: day_start / 86_400,
+            aircraft: 2,
+            ..DayTotals::default()
+        }]);
+        history.record(
+            day_start + 10,
+            &aircraft(&["a1", "a2", "a3"]),
+            Some(0),
+            Some(42.4),
+        );
+        history.record(day_start + 20, &aircraft(&["a1"]), Some(600), Some(75.6));
+        history.record_range((0.0, 0.0), 90.0, 120.0);
+        history.record_range((0.0, 0.0), 90.0, 900.0);
+        history.record(day_start + 3_610, &aircraft(&["a4"]), Some(900), Some(20.0));
+
+        let hours: Vec<HourTotals> = history.hours().copied().collect();
+        assert_eq!((hours[0].peak_aircraft, hours[0].max_range_nm), (3, 120.0));
+        assert_eq!(TrafficMetric::PeakMsgRate.value(&hours[0]), 76);
+        assert_eq!((hours[1].peak_aircraft, hours[1].max_range_nm), (1, 0.0));
+
+        let today = history.days().last().copied().unwrap();
+        assert_eq!((today.aircraft, today.peak_aircraft), (4, 3));
+        assert_eq!((today.messages, today.max_range_nm), (900, 120.0));
+        assert_eq!(today.peak_msg_rate, 75.6);
+
+        history.record(day_start + 86_400, &aircraft(&["a1"]), Some(950), None);
+        assert_eq!(history.days().count(), 2);
+        assert_eq!(history.days().last().map(|day| day.aircraft), Some(1));
+    }
+
+    #[test]
+    fn range_outline_keeps_sector_maximum_per_site() {
+        assert_eq!(range_sector(0.0), 0);
+        assert_eq!(range_sector(359.9), RANGE_SECTORS - 1);
+        assert_eq!(range_sector(-5.0), RANGE_SECTORS - 1);
+
+        let mut history = TrafficHistory::default();
+        let site = (51.5, -0.1);
+        history.record_range(site, 45.0, 80.0);
+        history.record_range(site, 47.0, 60.0);
+        history.record_range(site, 48.0, 900.0);
+        let max = history.max_range(site).unwrap();
+        assert_eq!(max[4], 80.0);
+        assert_eq!(max.iter().filter(|nm| **nm > 0.0).count(), 1);
+        assert!(history.max_range((51.0, -0.1)).is_none());
+
+        history.record_range((51.0, -0.1), 200.0, 30.0);
+        assert!(history.max_range(site).is_none());
+        assert_eq!(history.max_range((51.0, -0.1)).unwrap()[20], 30.0);
+    }
+
+    #[test]
+    fn saves_at_most_every_interval() {
+        let mut history = TrafficHistory::default();
+        let start = Instant::now();
+        assert!(!history.save_due(start, true));
+        history.record(3_600, &aircraft(&["a1"]), None, None);
+        assert!(!history.save_due(start, false));
+        assert!(history.save_due(start, true));
+        let later = start + Duration::from_secs(301);
+        assert!(history.save_due(later, false));
+        history.mark_saved(later);
+        assert!(!history.save_due(later + Duration::from_secs(1), true));
+    }
+}
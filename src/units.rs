@@ -0,0 +1,89 @@
+pub const FT_PER_M: f64 = 3.280_84;
+pub const MI_PER_NM: f64 = 1.150_78;
+pub const KM_PER_NM: f64 = 1.852;
+pub const EARTH_RADIUS_NM: f64 = 3440.065;
+/// Bits in a long (112-bit) Mode S message, used to turn message rates into
+/// link throughput.
+pub const BITS_PER_MESSAGE: f64 = 112.0;
+
+pub fn ft_to_m(ft: f64) -> f64 {
+    ft / FT_PER_M
+}
+
+pub fn m_to_ft(m: f64) -> f64 {
+    m * FT_PER_M
+}
+
+pub fn kt_to_kmh(kt: f64) -> f64 {
+    kt * KM_PER_NM
+}
+
+pub fn kt_to_mph(kt: f64) -> f64 {
+    kt * MI_PER_NM
+}
+
+pub fn nm_to_km(nm: f64) -> f64 {
+    nm * KM_PER_NM
+}
+
+pub fn nm_to_mi(nm: f64) -> f64 {
+    nm * MI_PER_NM
+}
+
+pub fn fpm_to_ms(fpm: f64) -> f64 {
+    ft_to_m(fpm) / 60.0
+}
+
+pub fn msg_rate_to_kbps(rate: f64) -> f64 {
+    rate * BITS_PER_MESSAGE / 1000.0
+}
+
+pub fn distance_nm(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let dlat = (lat2 - lat1).to_radians();
+    let dlon = (lon2 - lon1).to_radians();
+    let lat1 = lat1.to_radians();
+    let lat2 = lat2.to_radians();
+    let a = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * a.sqrt().atan2((1.0 - a).sqrt()) * EARTH_RADIUS_NM
+}
+
+pub fn distance_mi(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    nm_to_mi(distance_nm(lat1, lon1, lat2, lon2))
+}
+
+pub fn bearing_deg(lat1: f64, lon1: f64, lat2: f64, lon2: f64) -> f64 {
+    let lat1 = lat1.to_radians();
+    let lat2 = lat2.to_radians();
+    let dlon = (lon2 - lon1).to_radians();
+    let y = dlon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+    let mut deg = y.atan2(x).to_degrees();
+    if deg < 0.0 {
+        deg += 360.0;
+    }
+    deg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversions_round_trip() {
+        assert!((m_to_ft(ft_to_m(35_000.0)) - 35_000.0).abs() < 1e-9);
+        assert!((nm_to_mi(10.0) - 11.5078).abs() < 1e-9);
+        assert!((kt_to_kmh(100.0) - 185.2).abs() < 1e-9);
+        assert!((kt_to_mph(100.0) - 115.078).abs() < 1e-9);
+        assert!((nm_to_km(1.0) - 1.852).abs() < 1e-9);
+        assert!((fpm_to_ms(1_000.0) - 5.08).abs() < 1e-3);
+        assert!((msg_rate_to_kbps(1_000.0) - 112.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn one_degree_of_latitude_is_sixty_nm() {
+        assert!((distance_nm(0.0, 0.0, 1.0, 0.0) - 60.04).abs() < 0.05);
+        assert_eq!(distance_mi(26.0, -80.0, 26.0, -80.0), 0.0);
+        assert!((bearing_deg(0.0, 0.0, 0.0, 1.0) - 90.0).abs() < 1e-9);
+        assert!((bearing_deg(0.0, 0.0, -1.0, 0.0) - 180.0).abs() < 1e-9);
+    }
+}
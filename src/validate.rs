@@ -0,0 +1,617 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing_subscriber::EnvFilter;
+
+use crate::airports::RadarAirports;
+use crate::app::{
+    parse_column_layout, AltitudeSource, ColorBy, FlagStyle, GroundFilter, LayoutMode,
+    PositionQuality, RadarBlip, RadarRenderer, RouteLabels, ThemeMode,
+};
+use crate::beast;
+use crate::cli::ConfigArgs;
+use crate::commands::api_credentials;
+use crate::config::{self, Config};
+use crate::filter::Filter;
+use crate::keys::KeyMap;
+use crate::mqtt::{check_publish_topic, MqttTarget};
+use crate::net;
+use crate::notifier::AlertKinds;
+use crate::quiet::QuietHours;
+use crate::sort::SortSpec;
+use crate::storage;
+use crate::ui::ThemeOverrides;
+use crate::zones::Zone;
+
+// Keys accepted in the file that have no editable ConfigSpec entry.
+const EXTRA_KEYS: &[&str] = &[
+    "urls",
+    "url_templates",
+    "favorites",
+    "theme_overrides",
+    "sites",
+];
+
+#[derive(Debug, Default)]
+pub struct Report {
+    pub path: PathBuf,
+    pub found: bool,
+    pub errors: Vec<String>,
+    pub warnings: Vec<String>,
+    pub notes: Vec<String>,
+}
+
+pub fn validate(args: &ConfigArgs, check_feeds: bool) -> Report {
+    let mut report = Report {
+        path: config::config_file_path(args),
+        ..Report::default()
+    };
+
+    if report.path.exists() {
+        report.found = true;
+        let table = fs::read_to_string(&report.path)
+            .map_err(|err| err.to_string())
+            .and_then(|content| {
+                content
+                    .parse::<toml::Table>()
+                    .map_err(|err| err.to_string())
+            });
+        match table {
+            Ok(table) => report.errors.extend(
+                unknown_keys(&table)
+                    .into_iter()
+                    .map(|key| format!("unknown key `{key}`")),
+            ),
+            Err(err) => {
+                report.errors.push(format!("invalid TOML: {}", err.trim()));
+                return report;
+            }
+        }
+    }
+
+    let config = match config::load_config(args) {
+        Ok(config) => config,
+        Err(err) => {
+            report.errors.push(format!("{err:#}"));
+            return report;
+        }
+    };
+
+    report.errors.extend(bad_values(&config));
+    report.errors.extend(zone_problems(&config));
+    check_airports(&config, &mut report);
+    check_geography(&config, &mut report);
+    check_aircraft_db(&config, &mut report);
+    check_military_ranges(&config, &mut report);
+    check_roles(&config, &mut report);
+    report.warnings.extend(conflicts(&config));
+
+    match config::initial_fetch_urls(&config) {
+        Ok(urls) if check_feeds => check_feed_urls(&config, &urls, &mut report),
+        Ok(urls) => report
+            .notes
+            .extend(urls.iter().map(|url| format!("feed {url} (not checked)"))),
+        Err(err) => report.errors.push(format!("{err:#}")),
+    }
+    report
+}
+
+fn unknown_keys(table: &toml::Table) -> Vec<String> {
+    let mut unknown = Vec::new();
+    for (key, value) in table {
+        if key == "profile" {
+            let Some(profiles) = value.as_table() else {
+                unknown.push(key.clone());
+                continue;
+            };
+            for (name, profile) in profiles {
+                let Some(profile) = profile.as_table() else {
+                    unknown.push(format!("profile.{name}"));
+                    continue;
+                };
+                unknown.extend(
+                    profile
+                        .keys()
+                        .filter(|key| *key != "keys" && !is_known_key(key))
+                        .map(|key| format!("profile.{name}.{key}")),
+                );
+            }
+        } else if key == "keys" {
+            let Some(keys) = value.as_table() else {
+                unknown.push(key.clone());
+                continue;
+            };
+            unknown.extend(
+                keys.keys()
+                    .map(|name| format!("keys.{name}"))
+                    .filter(|key| !is_known_key(key)),
+            );
+        } else if !is_known_key(key) {
+            unknown.push(key.clone());
+        }
+    }
+    unknown
+}
+
+fn is_known_key(key: &str) -> bool {
+    EXTRA_KEYS.contains(&key) || config::config_specs().iter().any(|spec| spec.key == key)
+}
+
+fn bad_values(config: &Config) -> Vec<String> {
+    let level = config.log_level.trim();
+    let checks = [
+        (
+            "layout",
+            &config.layout,
+            LayoutMode::parse(&config.layout).is_some(),
+        ),
+        (
+            "theme",
+            &config.theme,
+            ThemeMode::parse(&config.theme).is_some(),
+        ),
+        (
+            "radar_renderer",
+            &config.radar_renderer,
+            RadarRenderer::parse(&config.radar_renderer).is_some(),
+        ),
+        (
+            "radar_blip",
+            &config.radar_blip,
+            RadarBlip::parse(&config.radar_blip).is_some(),
+        ),
+        (
+            "radar_airports",
+            &config.radar_airports,
+            RadarAirports::parse(&config.radar_airports).is_some(),
+        ),
+        (
+            "ground_filter",
+            &config.ground_filter,
+            GroundFilter::parse(&config.ground_filter).is_some(),
+        ),
+        (
+            "altitude_source",
+            &config.altitude_source,
+            AltitudeSource::parse(&config.altitude_source).is_some(),
+        ),
+        (
+            "low_quality_positions",
+            &config.low_quality_positions,
+            PositionQuality::parse(&config.low_quality_positions).is_some(),
+        ),
+        (
+            "flag_style",
+            &config.flag_style,
+            FlagStyle::parse(&config.flag_style).is_some(),
+        ),
+        (
+            "color_by",
+            &config.color_by,
+            ColorBy::parse(&config.color_by).is_some(),
+        ),
+        (
+            "route_labels",
+            &config.route_labels,
+            RouteLabels::parse(&config.route_labels).is_some(),
+        ),
+        (
+            "route_mode",
+            &config.route_mode,
+            is_choice("route_mode", &config.route_mode),
+        ),
+        (
+            "log_level",
+            &config.log_level,
+            level.is_empty() || EnvFilter::try_new(level).is_ok(),
+        ),
+        (
+            "stats_metric_1",
+            &config.stats_metric_1,
+            is_choice("stats_metric_1", &config.stats_metric_1),
+        ),
+        (
+            "stats_metric_2",
+            &config.stats_metric_2,
+            is_choice("stats_metric_2", &config.stats_metric_2),
+        ),
+        (
+            "stats_metric_3",
+            &config.stats_metric_3,
+            is_choice("stats_metric_3", &config.stats_metric_3),
+        ),
+        (
+            "perf_panel_1",
+            &config.perf_panel_1,
+            is_choice("perf_panel_1", &config.perf_panel_1),
+        ),
+        (
+            "perf_panel_2",
+            &config.perf_panel_2,
+            is_choice("perf_panel_2", &config.perf_panel_2),
+        ),
+        (
+            "perf_panel_3",
+            &config.perf_panel_3,
+            is_choice("perf_panel_3", &config.perf_panel_3),
+        ),
+        (
+            "perf_panel_4",
+            &config.perf_panel_4,
+            is_choice("perf_panel_4", &config.perf_panel_4),
+        ),
+        (
+            "perf_panel_5",
+            &config.perf_panel_5,
+            is_choice("perf_panel_5", &config.perf_panel_5),
+        ),
+        (
+            "perf_panel_6",
+            &config.perf_panel_6,
+            is_choice("perf_panel_6", &config.perf_panel_6),
+        ),
+        (
+            "perf_window",
+            &config.perf_window,
+            is_choice("perf_window", &config.perf_window),
+        ),
+    ];
+    let mut errors: Vec<String> = checks
+        .into_iter()
+        .filter(|(_, _, ok)| !ok)
+        .map(|(key, value, _)| {
+            let expected = config::config_choices(key)
+                .map(|choices| choices.join(", "))
+                .unwrap_or_else(|| "trace, debug, info, warn, error or a tracing filter".into());
+            format!("{key} = \"{value}\" is not valid (expected {expected})")
+        })
+        .collect();
+
+    errors.extend(ThemeOverrides::parse(&config.theme_overrides).1);
+    for (key, value) in [
+        ("notify_desktop", &config.notify_desktop),
+        ("hook_events", &config.hook_events),
+    ] {
+        if let Err(err) = AlertKinds::parse(value) {
+            errors.push(format!("{key} = \"{value}\": {err}"));
+        }
+    }
+    if let Err(err) = QuietHours::parse(&config.quiet_hours) {
+        errors.push(format!("quiet_hours = \"{}\": {err}", config.quiet_hours));
+    }
+    if let Err(err) = Filter::parse(&config.filter) {
+        errors.push(format!("filter = \"{}\": {err}", config.filter));
+    }
+    if let Err(err) = parse_column_layout(&config.table_columns) {
+        errors.push(format!(
+            "table_columns = \"{}\": {err}",
+            config.table_columns
+        ));
+    }
+    if let Err(err) = SortSpec::parse(&config.sort_order) {
+        errors.push(format!("sort_order = \"{}\": {err}", config.sort_order));
+    }
+    if !config.hook_mqtt.trim().is_empty() {
+        let target = MqttTarget::parse(&config.hook_mqtt);
+        if let Err(err) = target.and_then(|target| check_publish_topic(&target)) {
+            errors.push(format!("hook_mqtt = \"{}\": {err}", config.hook_mqtt));
+        }
+    }
+    let feeds: BTreeSet<&str> = std::iter::once(&config.url)
+        .chain(&config.urls)
+        .map(|url| url.trim())
+        .filter(|url| MqttTarget::is_mqtt_url(url))
+        .collect();
+    for url in feeds {
+        if let Err(err) = MqttTarget::parse(url) {
+            errors.push(format!("feed {url}: {err}"));
+        }
+    }
+
+    if let Err(key_errors) = KeyMap::from_overrides(&config.keys) {
+        errors.extend(key_errors);
+    }
+    errors
+}
+
+fn zone_problems(config: &Config) -> Vec<String> {
+    let path = config.zones_file.trim();
+    if path.is_empty() {
+        return Vec::new();
+    }
+    match storage::load_zones(Path::new(path)) {
+        Ok(zones) => zones.iter().filter_map(Zone::problem).collect(),
+        Err(err) => vec![format!("{err:#}")],
+    }
+}
+
+fn check_airports(config: &Config, report: &mut Report) {
+    let path = Path::new(config.airports_file.trim());
+    if path.as_os_str().is_empty() || !path.exists() {
+        return;
+    }
+    match storage::load_airports(path) {
+        Ok(airports) if airports.is_empty() => report
+            .warnings
+            .push(format!("no airports found in {}", path.display())),
+        Ok(airports) => report.notes.push(format!(
+            "{} airports from {}",
+            airports.len(),
+            path.display()
+        )),
+        Err(err) => report.errors.push(format!("{err:#}")),
+    }
+}
+
+fn check_aircraft_db(config: &Config, report: &mut Report) {
+    let path = Path::new(config.aircraft_db_file.trim());
+    if path.as_os_str().is_empty() || !path.exists() {
+        return;
+    }
+    match storage::load_aircraft_db(path) {
+        Ok(db) if db.is_empty() => report
+            .warnings
+            .push(format!("no aircraft found in {}", path.display())),
+        Ok(db) => report
+            .notes
+            .push(format!("{} aircraft from {}", db.len(), path.display())),
+        Err(err) => report.errors.push(format!("{err:#}")),
+    }
+}
+
+fn check_military_ranges(config: &Config, report: &mut Report) {
+    let path = Path::new(config.military_ranges_file.trim());
+    if path.as_os_str().is_empty() || !path.exists() {
+        return;
+    }
+    match storage::load_military_ranges(path) {
+        Ok(ranges) if ranges.is_empty() => report.warnings.push(format!(
+            "no military ranges found in {}; hex ranges will match nothing",
+            path.display()
+        )),
+        Ok(ranges) => report.notes.push(format!(
+            "{} military ranges from {}",
+            ranges.len(),
+            path.display()
+        )),
+        Err(err) => report.errors.push(format!("{err:#}")),
+    }
+}
+
+fn check_roles(config: &Config, report: &mut Report) {
+    let path = Path::new(config.roles_file.trim());
+    if path.as_os_str().is_empty() || !path.exists() {
+        return;
+    }
+    // Ranges file problems are reported by `check_military_ranges`.
+    match storage::load_role_rules(Some(path), None) {
+        Ok(rules) => report.notes.push(format!(
+            "{} role rules from {}",
+            rules.len(),
+            path.display()
+        )),
+        Err(err) => report.errors.push(format!("{err:#}")),
+    }
+}
+
+fn check_geography(config: &Config, report: &mut Report) {
+    let path = Path::new(config.geography_file.trim());
+    if path.as_os_str().is_empty() || !path.exists() {
+        return;
+    }
+    match storage::load_geography(path) {
+        Ok(geography) if geography.is_empty() => report
+            .warnings
+            .push(format!("no lines or polygons found in {}", path.display())),
+        Ok(geography) => report.notes.push(format!(
+            "{} outlines from {}",
+            geography.len(),
+            path.display()
+        )),
+        Err(err) => report.errors.push(format!("{err:#}")),
+    }
+}
+
+fn is_choice(key: &str, value: &str) -> bool {
+    config::config_choices(key)
+        .is_some_and(|choices| choices.iter().any(|c| c.eq_ignore_ascii_case(value.trim())))
+}
+
+fn conflicts(config: &Config) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let has_site = config.site_lat.is_some() && config.site_lon.is_some();
+
+    if config.site_lat.is_some() != config.site_lon.is_some() {
+        warnings.push("site_lat and site_lon must be set together; site is ignored".to_string());
+    }
+    if config.url_template.is_some() && config.url != config::DEFAULT_URL {
+        warnings.push("url is ignored while url_template is set".to_string());
+    }
+    let feed_count = config::initial_fetch_urls(config).map_or(0, |urls| urls.len());
+    if config.merge_receivers && feed_count < 2 {
+        warnings.push("merge_receivers is set but only one feed URL is configured".to_string());
+    }
+    let alerts_customized = config.notify_radius_mi != config::DEFAULT_NOTIFY_RADIUS_MI
+        || config.overpass_mi != config::DEFAULT_OVERPASS_MI;
+    if !has_site && alerts_customized {
+        warnings.push(
+            "notify_radius_mi/overpass_mi need site_lat and site_lon; proximity alerts are off"
+                .to_string(),
+        );
+    }
+    if !config.hook_payload.trim().is_empty()
+        && config.hook_url.trim().is_empty()
+        && config.hook_mqtt.trim().is_empty()
+        && config.hook_command.trim().is_empty()
+    {
+        warnings.push(
+            "hook_payload is set but none of hook_url, hook_mqtt and hook_command is".to_string(),
+        );
+    }
+    if config.watchlist_enabled && config.watchlist_file.trim().is_empty() {
+        warnings.push("watchlist_enabled is set but watchlist_file is empty".to_string());
+    }
+    if config.route_enabled && config.route_base.trim().is_empty() {
+        warnings.push("route_enabled is set but route_base is empty".to_string());
+    }
+    if config.route_enabled
+        && config.route_mode.eq_ignore_ascii_case("tar1090")
+        && config.route_path.trim().is_empty()
+    {
+        warnings.push("route_mode = \"tar1090\" needs a route_path".to_string());
+    }
+    let mut site_names = std::collections::HashSet::new();
+    for site in &config.sites {
+        if !site_names.insert(site.name.to_ascii_lowercase()) {
+            warnings.push(format!(
+                "site {} is defined more than once; the first entry is used",
+                site.name
+            ));
+        }
+    }
+    warnings
+}
+
+fn check_feed_urls(config: &Config, urls: &[String], report: &mut Report) {
+    let client = match net::build_client(config.insecure) {
+        Ok(client) => client,
+        Err(err) => {
+            report.errors.push(format!("HTTP client error: {err}"));
+            return;
+        }
+    };
+    let (api_key, api_key_header) = api_credentials(config);
+    for url in urls {
+        if let Some(address) = beast::beast_address(url) {
+            match beast::probe(&address) {
+                Ok(()) => report
+                    .notes
+                    .push(format!("feed {url} OK (Beast port open)")),
+                Err(err) => report.errors.push(format!("feed {url} unreachable: {err}")),
+            }
+            continue;
+        }
+        if MqttTarget::is_mqtt_url(url) {
+            // Messages only arrive when something publishes, so there is
+            // nothing to fetch; bad URLs are already reported as values.
+            if let Ok(target) = MqttTarget::parse(url) {
+                report.notes.push(format!(
+                    "feed {url} is MQTT input from {} topic {} (not probed)",
+                    target.address(),
+                    target.topic
+                ));
+            }
+            continue;
+        }
+        match net::fetch_first(
+            &client,
+            std::slice::from_ref(url),
+            api_key.as_deref(),
+            api_key_header.as_deref(),
+        ) {
+            Ok(data) => report
+                .notes
+                .push(format!("feed {url} OK ({} aircraft)", data.aircraft.len())),
+            Err(err) => report.errors.push(format!("feed {url} unreachable: {err}")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bad_values, conflicts, unknown_keys, validate};
+    use crate::cli::ConfigArgs;
+    use crate::config::load_config;
+    use std::fs;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn temp_config(content: &str) -> std::path::PathBuf {
+        let suffix = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        let path = std::env::temp_dir().join(format!("adsb-tui-validate-{suffix}.toml"));
+        fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn unknown_keys_include_profile_sections() {
+        let table: toml::Table = r#"
+stale_secs = 30
+stael_secs = 30
+urls = ["http://a.test"]
+
+[keys]
+quit = "x"
+teleport = "z"
+
+[profile.field]
+theme = "amber"
+radar_rnage_nm = 10
+"#
+        .parse()
+        .unwrap();
+        assert_eq!(
+            unknown_keys(&table),
+            vec![
+                "keys.teleport",
+                "profile.field.radar_rnage_nm",
+                "stael_secs"
+            ]
+        );
+    }
+
+    #[test]
+    fn bad_enums_and_conflicts_are_reported() {
+        let path = temp_config(
+            "layout = \"grid\"\ntheme = \"amber\"\nradar_renderer = \"svg\"\nstats_metric_2 = \"bogus\"\nsite_lat = 26.0\nroute_enabled = true\nroute_mode = \"tar1090\"\nroute_path = \"\"\nmerge_receivers = true\nnotify_desktop = \"near loud\"\nhook_payload = \"{hex}\"\nhook_events = \"watch sos\"\nfilter = \"alt<low\"\n[keys]\ntheme = \"f\"\nsort = \"ctrl-s\"\n",
+        );
+        let args = ConfigArgs {
+            config: Some(path.clone()),
+            ..ConfigArgs::default()
+        };
+        let config = load_config(&args).unwrap();
+
+        let errors = bad_values(&config);
+        assert_eq!(errors.len(), 8);
+        assert!(errors[0].starts_with("layout = \"grid\""));
+        assert!(errors[1].starts_with("radar_renderer = \"svg\""));
+        assert!(errors[2].starts_with("stats_metric_2 = \"bogus\""));
+        assert!(errors[3].starts_with("notify_desktop = \"near loud\""));
+        assert!(errors[4].starts_with("hook_events = \"watch sos\""));
+        assert_eq!(
+            errors[5],
+            "filter = \"alt<low\": \"alt\" needs a number, not \"low\""
+        );
+        assert!(errors[6].starts_with("keys.sort = \"ctrl-s\""));
+        assert_eq!(
+            errors[7],
+            "key `f` is bound to both keys.favorite and keys.theme"
+        );
+
+        let warnings = conflicts(&config);
+        assert!(warnings
+            .iter()
+            .any(|w| w.starts_with("site_lat and site_lon")));
+        assert!(warnings.iter().any(|w| w.starts_with("route_mode")));
+        assert!(warnings.iter().any(|w| w.starts_with("merge_receivers")));
+        assert!(warnings.iter().any(|w| w.starts_with("hook_payload")));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn invalid_toml_stops_the_report_early() {
+        let path = temp_config("stale_secs = = 3\n");
+        let args = ConfigArgs {
+            config: Some(path.clone()),
+            ..ConfigArgs::default()
+        };
+        let report = validate(&args, false);
+        assert!(report.found);
+        assert_eq!(report.errors.len(), 1);
+        assert!(report.errors[0].starts_with("invalid TOML"));
+        assert!(report.notes.is_empty());
+        let _ = fs::remove_file(&path);
+    }
+}
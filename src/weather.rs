@@ -0,0 +1,330 @@
+//! METAR/TAF from the NOAA text service.
+
+use std::time::{Duration, SystemTime};
+
+use reqwest::Client;
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, error, info, warn};
+
+use crate::net::build_async_client;
+use crate::tasks::Tasks;
+
+const HPA_PER_INHG: f64 = 33.8639;
+const WEATHER_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Wind {
+    /// Degrees true; `None` for variable (VRB).
+    pub direction: Option<u16>,
+    pub speed_kt: u16,
+    pub gust_kt: Option<u16>,
+}
+
+impl Wind {
+    pub fn describe(&self) -> String {
+        if self.speed_kt == 0 && self.gust_kt.is_none() {
+            return "calm".to_string();
+        }
+        let mut text = match self.direction {
+            Some(deg) => format!("{deg:03}° {}", self.speed_kt),
+            None => format!("VRB {}", self.speed_kt),
+        };
+        if let Some(gust) = self.gust_kt {
+            text.push_str(&format!("G{gust}"));
+        }
+        text.push_str(" kt");
+        text
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metar {
+    pub raw: String,
+    pub station: String,
+    pub time: Option<String>,
+    pub wind: Option<Wind>,
+    pub visibility: Option<String>,
+    pub temp_c: Option<i32>,
+    pub dew_c: Option<i32>,
+    pub qnh_hpa: Option<f64>,
+}
+
+impl Metar {
+    pub fn parse(raw: &str) -> Option<Self> {
+        let raw = raw.trim();
+        let mut tokens = raw
+            .split_whitespace()
+            .take_while(|token| *token != "RMK")
+            .skip_while(|token| matches!(*token, "METAR" | "SPECI" | "COR"));
+        let station = tokens.next()?.to_string();
+        let mut metar = Metar {
+            raw: raw.to_string(),
+            station,
+            ..Metar::default()
+        };
+        // "1 1/2SM" splits the whole miles from the fraction.
+        let mut whole_miles: Option<&str> = None;
+        for token in tokens {
+            if metar.time.is_none() && is_time(token) {
+                metar.time = Some(token.to_string());
+                continue;
+            }
+            if metar.wind.is_none() {
+                if let Some(wind) = parse_wind(token) {
+                    metar.wind = Some(wind);
+                    continue;
+                }
+            }
+            if metar.visibility.is_none() {
+                if is_visibility(token) {
+                    metar.visibility = Some(match whole_miles.take() {
+                        Some(whole) => format!("{whole} {token}"),
+                        None => token.to_string(),
+                    });
+                    continue;
+                }
+                if token.len() == 1 && token.chars().all(|c| c.is_ascii_digit()) {
+                    whole_miles = Some(token);
+                    continue;
+                }
+            }
+            if metar.temp_c.is_none() {
+                if let Some((temp, dew)) = parse_temps(token) {
+                    (metar.temp_c, metar.dew_c) = (temp, dew);
+                    continue;
+                }
+            }
+            if metar.qnh_hpa.is_none() {
+                metar.qnh_hpa = parse_altimeter(token);
+            }
+        }
+        Some(metar)
+    }
+
+    pub fn qnh_inhg(&self) -> Option<f64> {
+        self.qnh_hpa.map(|hpa| hpa / HPA_PER_INHG)
+    }
+}
+
+fn is_time(token: &str) -> bool {
+    token.len() == 7 && token.ends_with('Z') && token[..6].chars().all(|c| c.is_ascii_digit())
+}
+
+/// `27012G20KT`, `VRB03KT`, `00000KT` or the same in MPS.
+fn parse_wind(token: &str) -> Option<Wind> {
+    let (body, to_kt) = match token.strip_suffix("KT") {
+        Some(body) => (body, 1.0),
+        None => (token.strip_suffix("MPS")?, 1.943_844),
+    };
+    if body.len() < 5 || !body.is_char_boundary(3) {
+        return None;
+    }
+    let (dir, rest) = body.split_at(3);
+    let direction = match dir {
+        "VRB" => None,
+        _ => Some(dir.parse::<u16>().ok()?),
+    };
+    let (speed, gust) = match rest.split_once('G') {
+        Some((speed, gust)) => (speed, Some(gust)),
+        None => (rest, None),
+    };
+    let knots = |value: &str| -> Option<u16> {
+        let value: f64 = value.parse().ok()?;
+        Some((value * to_kt).round() as u16)
+    };
+    Some(Wind {
+        direction,
+        speed_kt: knots(speed)?,
+        gust_kt: match gust {
+            Some(gust) => Some(knots(gust)?),
+            None => None,
+        },
+    })
+}
+
+fn is_visibility(token: &str) -> bool {
+    if token == "CAVOK" {
+        return true;
+    }
+    if let Some(miles) = token.strip_suffix("SM") {
+        let miles = miles.trim_start_matches(['P', 'M']);
+        return !miles.is_empty() && miles.chars().all(|c| c.is_ascii_digit() || c == '/');
+    }
+    token.len() == 4 && token.chars().all(|c| c.is_ascii_digit())
+}
+
+fn parse_temps(token: &str) -> Option<(Option<i32>, Option<i32>)> {
+    let (temp, dew) = token.split_once('/')?;
+    let value = |text: &str| -> Option<i32> {
+        let (sign, digits) = match text.strip_prefix('M') {
+            Some(digits) => (-1, digits),
+            None => (1, text),
+        };
+        (digits.len() == 2 && digits.chars().all(|c| c.is_ascii_digit()))
+            .then(|| sign * digits.parse::<i32>().unwrap_or(0))
+    };
+    let temp = value(temp)?;
+    Some((Some(temp), value(dew)))
+}
+
+/// `A2992` (hundredths of inHg) or `Q1013` (hPa), as hPa.
+fn parse_altimeter(token: &str) -> Option<f64> {
+    if token.len() != 5 || !token[1..].chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let value: f64 = token[1..].parse().ok()?;
+    match &token[..1] {
+        "A" => Some(value / 100.0 * HPA_PER_INHG),
+        "Q" => Some(value),
+        _ => None,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WeatherReport {
+    pub metar: Option<Metar>,
+    pub taf: Option<String>,
+    pub fetched_at: SystemTime,
+}
+
+pub fn spawn_weather_fetcher(
+    tasks: &Tasks,
+    base_url: String,
+    station: String,
+    interval: Duration,
+    insecure: bool,
+    tx: UnboundedSender<Result<WeatherReport, String>>,
+) {
+    tasks.spawn("weather", async move {
+        info!("weather fetcher started: {station}");
+        let client = match build_async_client(insecure, WEATHER_TIMEOUT) {
+            Ok(client) => client,
+            Err(err) => {
+                error!("weather client error: {err}");
+                return;
+            }
+        };
+        let base = base_url.trim_end_matches('/').to_string();
+        loop {
+            let result = fetch_weather(&client, &base, &station).await;
+            if let Err(err) = &result {
+                warn!("weather fetch for {station} failed: {err}");
+            }
+            if tx.send(result).is_err() {
+                debug!("receiver dropped, exiting weather fetcher");
+                return;
+            }
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+/// NOAA serves one text file per station: a timestamp line, then the report.
+async fn fetch_weather(
+    client: &Client,
+    base: &str,
+    station: &str,
+) -> Result<WeatherReport, String> {
+    let station = station.trim().to_ascii_uppercase();
+    let metar = fetch_text(
+        client,
+        &format!("{base}/observations/metar/stations/{station}.TXT"),
+    )
+    .await?;
+    // Many stations have no TAF; that is not an error.
+    let taf = fetch_text(
+        client,
+        &format!("{base}/forecasts/taf/stations/{station}.TXT"),
+    )
+    .await
+    .ok();
+    Ok(WeatherReport {
+        metar: report_body(&metar).and_then(|body| Metar::parse(&body)),
+        taf: taf.as_deref().and_then(report_body).map(|body| {
+            body.strip_prefix("TAF ")
+                .map(str::to_string)
+                .unwrap_or(body)
+        }),
+        fetched_at: SystemTime::now(),
+    })
+}
+
+async fn fetch_text(client: &Client, url: &str) -> Result<String, String> {
+    let resp = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|err| err.to_string())?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("HTTP {status}"));
+    }
+    resp.text().await.map_err(|err| err.to_string())
+}
+
+fn report_body(text: &str) -> Option<String> {
+    let body = text
+        .lines()
+        .skip(1)
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!body.is_empty()).then_some(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{report_body, Metar, Wind};
+
+    #[test]
+    fn decodes_us_metar() {
+        let metar =
+            Metar::parse("KJFK 151851Z 27012G20KT 1 1/2SM BR FEW050 M02/M05 A2992 RMK AO2 T0022")
+                .unwrap();
+        assert_eq!(metar.station, "KJFK");
+        assert_eq!(metar.time.as_deref(), Some("151851Z"));
+        assert_eq!(
+            metar.wind,
+            Some(Wind {
+                direction: Some(270),
+                speed_kt: 12,
+                gust_kt: Some(20)
+            })
+        );
+        assert_eq!(metar.wind.unwrap().describe(), "270° 12G20 kt");
+        assert_eq!(metar.visibility.as_deref(), Some("1 1/2SM"));
+        assert_eq!((metar.temp_c, metar.dew_c), (Some(-2), Some(-5)));
+        assert!((metar.qnh_inhg().unwrap() - 29.92).abs() < 0.005);
+    }
+
+    #[test]
+    fn decodes_icao_metar() {
+        let metar = Metar::parse("METAR EGLL 151850Z VRB03MPS 9999 SCT030 12/ Q1013").unwrap();
+        assert_eq!(metar.station, "EGLL");
+        assert_eq!(metar.wind.unwrap().describe(), "VRB 6 kt");
+        assert_eq!(metar.visibility.as_deref(), Some("9999"));
+        assert_eq!((metar.temp_c, metar.dew_c), (Some(12), None));
+        assert_eq!(metar.qnh_hpa, Some(1013.0));
+        assert_eq!(
+            Metar::parse("EGLL 151850Z 00000KT CAVOK 12/08 Q1013")
+                .unwrap()
+                .wind
+                .unwrap()
+                .describe(),
+            "calm"
+        );
+    }
+
+    #[test]
+    fn report_body_drops_the_timestamp_line() {
+        assert_eq!(
+            report_body(
+                "2024/01/15 18:51\nTAF KJFK 151720Z 1518/1624 27012KT\n  FM152200 28008KT\n"
+            )
+            .as_deref(),
+            Some("TAF KJFK 151720Z 1518/1624 27012KT FM152200 28008KT")
+        );
+        assert_eq!(report_body("2024/01/15 18:51\n"), None);
+    }
+}
@@ -0,0 +1,307 @@
+//! Geofence zones: circles or polygons.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::units::distance_nm;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ZoneAlert {
+    #[default]
+    Any,
+    Watchlist,
+    Off,
+}
+
+impl ZoneAlert {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "any" | "all" => Some(ZoneAlert::Any),
+            "watchlist" | "watch" => Some(ZoneAlert::Watchlist),
+            "off" | "none" => Some(ZoneAlert::Off),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ZoneAlert::Any => "any",
+            ZoneAlert::Watchlist => "watchlist",
+            ZoneAlert::Off => "off",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        match self {
+            ZoneAlert::Any => ZoneAlert::Watchlist,
+            ZoneAlert::Watchlist => ZoneAlert::Off,
+            ZoneAlert::Off => ZoneAlert::Any,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Zone {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lat: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lon: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub radius_nm: Option<f64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub points: Vec<[f64; 2]>,
+    pub enabled: Option<bool>,
+    pub alert: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ZonesFile {
+    #[serde(default, rename = "zone")]
+    pub zones: Vec<Zone>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ZoneShape<'a> {
+    Circle { lat: f64, lon: f64, radius_nm: f64 },
+    Polygon(&'a [[f64; 2]]),
+}
+
+impl Zone {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+
+    pub fn alert_mode(&self) -> ZoneAlert {
+        self.alert
+            .as_deref()
+            .and_then(ZoneAlert::parse)
+            .unwrap_or_default()
+    }
+
+    /// The zone's outline; `None` when it has neither a full circle nor at
+    /// least three corners.
+    pub fn shape(&self) -> Option<ZoneShape<'_>> {
+        if self.points.len() >= 3 {
+            return Some(ZoneShape::Polygon(&self.points));
+        }
+        match (self.lat, self.lon, self.radius_nm) {
+            (Some(lat), Some(lon), Some(radius_nm)) if radius_nm > 0.0 => Some(ZoneShape::Circle {
+                lat,
+                lon,
+                radius_nm,
+            }),
+            _ => None,
+        }
+    }
+
+    pub fn problem(&self) -> Option<String> {
+        if self.shape().is_none() {
+            return Some(format!(
+                "zone \"{}\" needs lat, lon and radius_nm, or three or more points",
+                self.name
+            ));
+        }
+        let alert = self.alert.as_deref().unwrap_or_default();
+        if ZoneAlert::parse(alert).is_none() {
+            return Some(format!(
+                "zone \"{}\": alert = \"{alert}\" is not valid (expected any, watchlist or off)",
+                self.name
+            ));
+        }
+        None
+    }
+
+    pub fn contains(&self, lat: f64, lon: f64) -> bool {
+        match self.shape() {
+            Some(ZoneShape::Circle {
+                lat: center_lat,
+                lon: center_lon,
+                radius_nm,
+            }) => distance_nm(center_lat, center_lon, lat, lon) <= radius_nm,
+            Some(ZoneShape::Polygon(points)) => polygon_contains(points, lat, lon),
+            None => false,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self.shape() {
+            Some(ZoneShape::Circle { radius_nm, .. }) => format!("circle {radius_nm:.1} nm"),
+            Some(ZoneShape::Polygon(points)) => format!("polygon {} pts", points.len()),
+            None => "invalid".to_string(),
+        }
+    }
+}
+
+/// Even-odd ray casting on raw lat/lon, fine for zones that do not span the
+/// antimeridian or a pole.
+fn polygon_contains(points: &[[f64; 2]], lat: f64, lon: f64) -> bool {
+    let mut inside = false;
+    let mut prev = points[points.len() - 1];
+    for &point in points {
+        let ([lat_a, lon_a], [lat_b, lon_b]) = (point, prev);
+        if (lat_a > lat) != (lat_b > lat) {
+            let cross_lon = lon_a + (lat - lat_a) / (lat_b - lat_a) * (lon_b - lon_a);
+            if lon < cross_lon {
+                inside = !inside;
+            }
+        }
+        prev = point;
+    }
+    inside
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ZoneCrossing {
+    pub zone: usize,
+    pub hex: String,
+    pub entered: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct ZoneTracker {
+    inside: HashMap<String, HashSet<usize>>,
+    primed: bool,
+}
+
+impl ZoneTracker {
+    pub fn reset(&mut self) {
+        self.inside.clear();
+        self.primed = false;
+    }
+
+    /// Compare one snapshot of `(hex, lat, lon)` positions with the last and
+    /// return the crossings. Aircraft without a position keep their state;
+    /// aircraft gone from the snapshot are forgotten without an exit.
+    pub fn update<'a>(
+        &mut self,
+        zones: &[Zone],
+        positions: impl IntoIterator<Item = (&'a str, Option<(f64, f64)>)>,
+    ) -> Vec<ZoneCrossing> {
+        let mut crossings = Vec::new();
+        let mut present = HashSet::new();
+        for (hex, position) in positions {
+            let hex = hex.trim().to_ascii_lowercase();
+            if hex.is_empty() {
+                continue;
+            }
+            present.insert(hex.clone());
+            let Some((lat, lon)) = position else {
+                continue;
+            };
+            let now_inside: HashSet<usize> = zones
+                .iter()
+                .enumerate()
+                .filter(|(_, zone)| zone.is_enabled() && zone.contains(lat, lon))
+                .map(|(idx, _)| idx)
+                .collect();
+            let before = self.inside.entry(hex.clone()).or_default();
+            if self.primed {
+                for &zone in now_inside.difference(before) {
+                    crossings.push(ZoneCrossing {
+                        zone,
+                        hex: hex.clone(),
+                        entered: true,
+                    });
+                }
+                for &zone in before.difference(&now_inside) {
+                    crossings.push(ZoneCrossing {
+                        zone,
+                        hex: hex.clone(),
+                        entered: false,
+                    });
+                }
+            }
+            *before = now_inside;
+        }
+        self.inside.retain(|hex, _| present.contains(hex));
+        self.primed = true;
+        crossings.sort_by(|a, b| (a.zone, &a.hex).cmp(&(b.zone, &b.hex)));
+        crossings
+    }
+
+    pub fn count_inside(&self, idx: usize) -> usize {
+        self.inside
+            .values()
+            .filter(|zones| zones.contains(&idx))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Zone, ZoneAlert, ZoneCrossing, ZoneTracker};
+
+    fn circle() -> Zone {
+        Zone {
+            name: "Field".to_string(),
+            lat: Some(40.0),
+            lon: Some(-74.0),
+            radius_nm: Some(5.0),
+            ..Zone::default()
+        }
+    }
+
+    fn square() -> Zone {
+        Zone {
+            name: "Box".to_string(),
+            points: vec![[41.0, -75.0], [41.0, -74.0], [42.0, -74.0], [42.0, -75.0]],
+            alert: Some("watchlist".to_string()),
+            ..Zone::default()
+        }
+    }
+
+    #[test]
+    fn circles_and_polygons_contain_points() {
+        assert!(circle().contains(40.05, -74.0));
+        assert!(!circle().contains(40.2, -74.0));
+        assert!(square().contains(41.5, -74.5));
+        assert!(!square().contains(41.5, -73.9));
+        assert!(!square().contains(42.1, -74.5));
+        assert_eq!(square().alert_mode(), ZoneAlert::Watchlist);
+        assert_eq!(circle().describe(), "circle 5.0 nm");
+
+        let broken = Zone {
+            name: "Half".to_string(),
+            lat: Some(40.0),
+            ..Zone::default()
+        };
+        assert!(broken.problem().is_some());
+        assert!(!broken.contains(40.0, -74.0));
+        assert!(circle().problem().is_none());
+    }
+
+    #[test]
+    fn tracker_reports_entries_and_exits_after_first_snapshot() {
+        let zones = [circle(), square()];
+        let mut tracker = ZoneTracker::default();
+        assert!(tracker
+            .update(&zones, [("A1", Some((40.0, -74.0)))])
+            .is_empty());
+
+        let crossings = tracker.update(
+            &zones,
+            [("a1", Some((41.5, -74.5))), ("b2", Some((40.01, -74.0)))],
+        );
+        let entered = |zone, hex: &str, entered| ZoneCrossing {
+            zone,
+            hex: hex.to_string(),
+            entered,
+        };
+        assert_eq!(
+            crossings,
+            [
+                entered(0, "a1", false),
+                entered(0, "b2", true),
+                entered(1, "a1", true)
+            ]
+        );
+        assert_eq!(tracker.count_inside(1), 1);
+
+        // No position: state kept. Gone from the feed: forgotten quietly.
+        assert!(tracker.update(&zones, [("a1", None)]).is_empty());
+        assert_eq!(tracker.count_inside(1), 1);
+        assert_eq!(tracker.count_inside(0), 0);
+    }
+}
@@ -0,0 +1,80 @@
+// tests/stress.rs
+//
+// Runs a 10,000-aircraft synthetic feed through filtering, smooth-mode
+// merging and a full frame, so scalability regressions that break or hang
+// under a busy aggregator feed show up in `cargo test`.
+
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+
+use adsb_tui::cli::ConfigArgs;
+use adsb_tui::config::{self, Config};
+use adsb_tui::synthetic::{SyntheticFeed, STRESS_AIRCRAFT};
+use adsb_tui::{commands, ui};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+fn stress_config() -> Config {
+    let path = std::env::temp_dir().join(format!("adsb-tui-stress-{}.toml", std::process::id()));
+    std::fs::write(
+        &path,
+        "url = \"http://127.0.0.1:1/data/aircraft.json\"\nsite_lat = 40.0\nsite_lon = -74.0\n",
+    )
+    .expect("write stress config");
+    let args = ConfigArgs {
+        config: Some(PathBuf::from(&path)),
+        ..ConfigArgs::default()
+    };
+    let mut config = config::load_config(&args).expect("load stress config");
+    let _ = std::fs::remove_file(&path);
+    config.layout = "full".to_string();
+    config.smooth_mode = true;
+    config.smooth_merge = true;
+    config.route_enabled = false;
+    // Keep every aircraft's history; the default cap is below the feed size.
+    config.max_tracked_aircraft = STRESS_AIRCRAFT as u64;
+    config.favorites_file = String::new();
+    config.traffic_history_file = String::new();
+    config.watchlist_file = String::new();
+    config
+}
+
+#[test]
+fn stress_feed_filters_merges_and_renders() {
+    let config = stress_config();
+    let feed = SyntheticFeed::new(STRESS_AIRCRAFT);
+    let mut app = commands::build_app(&config, "synthetic".to_string()).unwrap();
+    let start = SystemTime::now();
+
+    app.apply_update(feed.snapshot(0, 1_000));
+    assert!(app.maybe_swap_snapshot(start));
+    app.apply_update(feed.snapshot(1, 1_001));
+    assert!(app.maybe_swap_snapshot(start + Duration::from_secs(60)));
+
+    // Odd ticks drop every third callsign; the merge keeps the previous one.
+    let aircraft = app.aircraft();
+    assert_eq!(aircraft.len(), STRESS_AIRCRAFT);
+    assert!(aircraft[3].flight.is_some());
+    assert!(aircraft[3].gs.is_some());
+    assert!(app
+        .trail_for(&aircraft[1])
+        .is_some_and(|trail| trail.len() == 2));
+
+    let indices = app.visible_indices();
+    assert_eq!(indices.len(), STRESS_AIRCRAFT);
+
+    let mut terminal = Terminal::new(TestBackend::new(200, 60)).expect("test terminal");
+    for _ in 0..3 {
+        terminal
+            .draw(|f| ui::ui(f, &mut app, &indices))
+            .expect("draw");
+    }
+    let screen: String = terminal
+        .backend()
+        .buffer()
+        .content()
+        .iter()
+        .map(|cell| cell.symbol())
+        .collect();
+    assert!(screen.contains("N10"), "table rows rendered");
+}